@@ -5,20 +5,31 @@ use crate::mastodon::{MastodonClient, MastodonStream, TootEvent};
 use crate::media::MediaProcessor;
 use crate::openrouter::OpenRouterClient;
 use crate::toot_handler::processor;
+use crate::toot_handler::state::ProcessedStore;
+use chrono::{DateTime, Utc};
+use std::collections::HashSet;
+use std::sync::{Arc, Mutex};
 use std::time::Duration;
 use tracing::{debug, info, warn};
 
+/// Log backfill progress every this many toots
+const PROGRESS_LOG_INTERVAL: usize = 10;
+
 /// Backfill processor for handling recent toots on startup
 pub struct BackfillProcessor;
 
 impl BackfillProcessor {
     /// Process recent toots for backfill if enabled in configuration
+    ///
+    /// `progress` is the handler's state file and the toot IDs a previous backfill handled
+    /// (see `TootStreamHandler::take_backfill_progress`), used to resume an interrupted backfill.
     pub async fn process_backfill(
         config: &RuntimeConfig,
         mastodon_client: &MastodonClient,
         openrouter_client: &OpenRouterClient,
         media_processor: &MediaProcessor,
         language_detector: &LanguageDetector,
+        progress: Option<(Arc<Mutex<ProcessedStore>>, Vec<String>)>,
     ) -> Result<(), AlternatorError> {
         let backfill_count = config.config().mastodon.backfill_count.unwrap_or(25);
         let backfill_pause = config.config().mastodon.backfill_pause.unwrap_or(60);
@@ -43,6 +54,33 @@ impl BackfillProcessor {
             }
         };
 
+        let fetched = toots.len();
+        let toots = Self::filter_by_date_range(
+            toots,
+            Self::parse_bound(config.config().mastodon.backfill_since.as_deref()),
+            Self::parse_bound(config.config().mastodon.backfill_until.as_deref()),
+        );
+        if toots.len() < fetched {
+            info!(
+                "Skipping {} of {} fetched toots outside the backfill date range",
+                fetched - toots.len(),
+                fetched
+            );
+        }
+
+        let (store, done) = match progress {
+            Some((store, done)) => (Some(store), done),
+            None => (None, Vec::new()),
+        };
+        let fetched = toots.len();
+        let toots = Self::skip_processed(toots, &done);
+        if toots.len() < fetched {
+            info!(
+                "Resuming backfill: skipping {} toots processed before restart",
+                fetched - toots.len()
+            );
+        }
+
         if toots.is_empty() {
             info!("No toots found for backfill processing");
             return Ok(());
@@ -61,7 +99,7 @@ impl BackfillProcessor {
             );
 
             // Process the toot
-            if let Err(e) = Self::process_backfill_toot(
+            match Self::process_backfill_toot(
                 toot,
                 mastodon_client,
                 openrouter_client,
@@ -71,8 +109,24 @@ impl BackfillProcessor {
             )
             .await
             {
-                warn!("Failed to process backfill toot {}: {}", toot.id, e);
-                // Continue with next toot instead of failing completely
+                Ok(()) => {
+                    if let Some(store) = &store {
+                        store.lock().unwrap().record_backfill(&toot.id);
+                    }
+                }
+                Err(e) => {
+                    // Left unrecorded so the next backfill retries it; continue with the next toot
+                    warn!("Failed to process backfill toot {}: {}", toot.id, e);
+                }
+            }
+
+            let processed = index + 1;
+            if processed % PROGRESS_LOG_INTERVAL == 0 || processed == toots.len() {
+                info!(
+                    "Backfill progress: {} of {} processed",
+                    processed,
+                    toots.len()
+                );
             }
 
             // Pause between toots (except for the last one)
@@ -89,6 +143,37 @@ impl BackfillProcessor {
         Ok(())
     }
 
+    /// Drop toots a previous backfill run already handled
+    fn skip_processed(toots: Vec<TootEvent>, done: &[String]) -> Vec<TootEvent> {
+        let done: HashSet<&str> = done.iter().map(String::as_str).collect();
+        toots
+            .into_iter()
+            .filter(|toot| !done.contains(toot.id.as_str()))
+            .collect()
+    }
+
+    /// Parse an RFC3339 backfill bound (validated when the config was loaded)
+    fn parse_bound(value: Option<&str>) -> Option<DateTime<Utc>> {
+        value
+            .and_then(|value| DateTime::parse_from_rfc3339(value).ok())
+            .map(|bound| bound.with_timezone(&Utc))
+    }
+
+    /// Keep toots created within `since..=until`; a missing bound is open-ended
+    fn filter_by_date_range(
+        toots: Vec<TootEvent>,
+        since: Option<DateTime<Utc>>,
+        until: Option<DateTime<Utc>>,
+    ) -> Vec<TootEvent> {
+        toots
+            .into_iter()
+            .filter(|toot| {
+                since.map_or(true, |since| toot.created_at >= since)
+                    && until.map_or(true, |until| toot.created_at <= until)
+            })
+            .collect()
+    }
+
     /// Process a single toot during backfill
     async fn process_backfill_toot(
         toot: &TootEvent,
@@ -104,9 +189,11 @@ impl BackfillProcessor {
             return Ok(());
         }
 
-        // Check if any media attachments lack descriptions
+        // Check if any media attachments lack descriptions (or have a replaceable AI one)
         let needs_processing = toot.media_attachments.iter().any(|media| {
-            media.description.is_none() || media.description.as_ref().unwrap().trim().is_empty()
+            media.description.is_none()
+                || media.description.as_ref().unwrap().trim().is_empty()
+                || media_processor.has_replaceable_description(media)
         });
 
         if !needs_processing {
@@ -141,7 +228,8 @@ impl BackfillProcessor {
 
 #[cfg(test)]
 mod tests {
-    use crate::config::{Config, MastodonConfig, OpenRouterConfig};
+    use super::BackfillProcessor;
+    use crate::config::{Config, MastodonConfig, OpenRouterConfig, RuntimeConfig};
     use crate::mastodon::{Account, MediaAttachment, TootEvent};
     use chrono::Utc;
 
@@ -152,24 +240,83 @@ mod tests {
             mastodon: MastodonConfig {
                 instance_url: "https://test.social".to_string(),
                 access_token: "test_token".to_string(),
+                access_token_file: None,
                 user_stream: Some(true),
                 backfill_count: Some(backfill_count),
                 backfill_pause: Some(backfill_pause),
+                stream: None,
+                stream_tag: None,
+                stream_list: None,
+                own_toots_only: None,
+                state_file: None,
+                failed_toots_file: None,
+                processed_cache_size: None,
+                skip_sensitive: None,
+                skip_spoiler: None,
+                skip_replies: None,
+                respect_filters: None,
+                prefer_inplace_edit: None,
+                reconnect_catchup_count: None,
+                poll_handling: None,
+                processing_timeout_secs: None,
+                streaming_auth: None,
+                process_visibilities: None,
+                shutdown_grace_secs: None,
+                inter_toot_delay_ms: None,
+                new_toot_delay_secs: None,
+                max_inflight: None,
+                queue_capacity: None,
+                queue_overflow: None,
+                workers: None,
+                backfill_since: None,
+                backfill_until: None,
+                user_agent_contact: None,
+                delivery_mode: None,
+                allow_empty_text_edit: None,
             },
             openrouter: OpenRouterConfig {
                 api_key: "test_key".to_string(),
+                api_key_file: None,
                 model: "test_model".to_string(),
                 vision_model: "test_vision_model".to_string(),
                 vision_fallback_model: "test_vision_fallback".to_string(),
                 text_model: "test_text_model".to_string(),
                 text_fallback_model: "test_text_fallback".to_string(),
                 base_url: None,
+                provider: None,
                 max_tokens: Some(1500),
+                daily_spend_cap: None,
+                max_concurrent_requests: None,
+                min_request_interval_ms: None,
+                description_cache_size: None,
+                description_cache_ttl_secs: None,
+                max_description_length: None,
+                min_description_length: None,
+                refusal_patterns: None,
+                circuit_breaker_threshold: None,
+                circuit_breaker_cooldown_secs: None,
+                retry_base_delay_ms: None,
+                retry_backoff_multiplier: None,
+                retry_max_delay_ms: None,
+                image_url_passthrough: None,
+                use_toot_text_as_context: None,
+                chart_hashtags: None,
+                chart_prompt: None,
+                batch_image_descriptions: None,
             },
             media: None,
             balance: None,
             logging: None,
             whisper: None,
+            ocr: None,
+            attribution: None,
+            health: None,
+            notifications: None,
+            systemd: None,
+            tracing: None,
+            language: None,
+            prompts: None,
+            events: None,
         }
     }
 
@@ -209,6 +356,8 @@ mod tests {
             tags: Vec::new(),
             emojis: Vec::new(),
             poll: None,
+            filtered: Vec::new(),
+            edited_at: None,
             is_edit: false,
         }
     }
@@ -270,4 +419,189 @@ mod tests {
         assert_eq!(config_enabled.mastodon.backfill_count, Some(10));
         assert_eq!(config_enabled.mastodon.backfill_pause, Some(30));
     }
+
+    #[test]
+    fn test_backfill_date_range_excludes_toots_outside_range() {
+        let toot_at = |id: &str, created_at: &str| TootEvent {
+            created_at: created_at.parse().unwrap(),
+            ..create_test_toot_with_media(id, false)
+        };
+        let toots = || {
+            vec![
+                toot_at("before", "2022-12-31T23:59:59Z"),
+                toot_at("start", "2023-01-01T00:00:00Z"),
+                toot_at("middle", "2023-03-15T12:00:00Z"),
+                toot_at("end", "2023-06-30T23:59:59Z"),
+                toot_at("after", "2023-07-01T00:00:00Z"),
+            ]
+        };
+        let ids = |toots: Vec<TootEvent>| toots.into_iter().map(|t| t.id).collect::<Vec<_>>();
+
+        let since = BackfillProcessor::parse_bound(Some("2023-01-01T00:00:00Z"));
+        // Offsets other than UTC are honored
+        let until = BackfillProcessor::parse_bound(Some("2023-07-01T01:59:59+02:00"));
+        assert_eq!(
+            ids(BackfillProcessor::filter_by_date_range(
+                toots(),
+                since,
+                until
+            )),
+            ["start", "middle", "end"]
+        );
+
+        // Open-ended ranges
+        assert_eq!(
+            ids(BackfillProcessor::filter_by_date_range(
+                toots(),
+                since,
+                None
+            )),
+            ["start", "middle", "end", "after"]
+        );
+        assert_eq!(
+            ids(BackfillProcessor::filter_by_date_range(
+                toots(),
+                None,
+                until
+            )),
+            ["before", "start", "middle", "end"]
+        );
+        assert_eq!(
+            BackfillProcessor::filter_by_date_range(toots(), None, None).len(),
+            5
+        );
+    }
+
+    #[test]
+    fn test_backfill_resume_skips_processed_ids() {
+        use crate::toot_handler::state::ProcessedStore;
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("state.txt");
+        let toots = || {
+            ["3", "2", "1"]
+                .into_iter()
+                .map(|id| create_test_toot_with_media(id, false))
+                .collect::<Vec<_>>()
+        };
+
+        // First run is interrupted after handling the newest toot
+        {
+            let (mut store, entries) = ProcessedStore::open(&path, 100).unwrap();
+            let pending = BackfillProcessor::skip_processed(toots(), &entries.backfill);
+            assert_eq!(pending.len(), 3);
+            store.record_backfill(&pending[0].id);
+        }
+
+        let (_store, entries) = ProcessedStore::open(&path, 100).unwrap();
+        let pending = BackfillProcessor::skip_processed(toots(), &entries.backfill);
+        let ids: Vec<_> = pending.into_iter().map(|t| t.id).collect();
+        assert_eq!(ids, ["2", "1"]);
+    }
+
+    #[tokio::test]
+    async fn test_backfill_retries_failed_toot_on_resume() {
+        use crate::language::LanguageDetector;
+        use crate::mastodon::{MastodonClient, MastodonStream};
+        use crate::media::{MediaConfig, MediaProcessor};
+        use crate::openrouter::OpenRouterClient;
+        use crate::test_support::{local_media_config, MockHttpServer, MockResponse};
+        use crate::toot_handler::state::ProcessedStore;
+        use crate::toot_handler::TootStreamHandler;
+        use std::sync::{Arc, Mutex};
+
+        // The Mastodon server is reachable but the media of toot "broken" is not
+        let toots: Arc<Mutex<Vec<TootEvent>>> = Arc::default();
+        let server_toots = toots.clone();
+        let server = MockHttpServer::start(move |request| {
+            let toots = server_toots.lock().unwrap();
+            match request.path.as_str() {
+                "/api/v1/accounts/verify_credentials" => MockResponse::json(
+                    200,
+                    r#"{"id":"test_user","username":"testuser","acct":"testuser","display_name":"Test User","url":"https://example.com"}"#,
+                ),
+                path if path.starts_with("/api/v1/accounts/test_user/statuses") => {
+                    MockResponse::json(200, &serde_json::to_string(&*toots).unwrap())
+                }
+                path => match toots
+                    .iter()
+                    .find(|toot| path == format!("/api/v1/statuses/{}", toot.id))
+                {
+                    Some(toot) => MockResponse::json(200, &serde_json::to_string(toot).unwrap()),
+                    None => MockResponse::json(503, r#"{"error":"Service Unavailable"}"#),
+                },
+            }
+        })
+        .await;
+
+        let mut broken = create_test_toot_with_media("broken", false);
+        broken.media_attachments[0].url = format!("{}/media/broken.png", server.url());
+        broken.media_attachments[0].preview_url = None;
+        let mut text_only = create_test_toot_with_media("text_only", false);
+        text_only.media_attachments.clear();
+        *toots.lock().unwrap() = vec![broken, text_only];
+
+        let dir = tempfile::tempdir().unwrap();
+        let state_file = dir.path().join("state.txt");
+        let mut config: Config = toml::from_str(&format!(
+            r#"
+[mastodon]
+instance_url = "{url}"
+access_token = "token"
+backfill_count = 2
+backfill_pause = 0
+
+[openrouter]
+api_key = "key"
+base_url = "{url}/openrouter"
+"#,
+            url = server.url()
+        ))
+        .unwrap();
+        config.mastodon.state_file = Some(state_file.to_string_lossy().to_string());
+        let config = RuntimeConfig::new(config);
+
+        let mut mastodon_client = MastodonClient::new(config.config().mastodon.clone());
+        mastodon_client.verify_credentials().await.unwrap();
+        let openrouter_client = OpenRouterClient::new(config.config().openrouter.clone());
+        let media_processor = MediaProcessor::with_unified_transformer(MediaConfig {
+            download_retries: 0,
+            ..local_media_config()
+        });
+        let media_requests = || {
+            server
+                .requests()
+                .iter()
+                .filter(|r| r.path == "/media/broken.png")
+                .count()
+        };
+
+        for _ in 0..2 {
+            // Each run starts like the application does, with the handler owning the state file
+            let mut handler = TootStreamHandler::new(
+                mastodon_client.clone(),
+                openrouter_client.clone(),
+                media_processor.clone(),
+                LanguageDetector::new(),
+                config.clone(),
+            );
+            let media_requests_before = media_requests();
+            BackfillProcessor::process_backfill(
+                &config,
+                &mastodon_client,
+                &openrouter_client,
+                &media_processor,
+                &LanguageDetector::new(),
+                handler.take_backfill_progress(),
+            )
+            .await
+            .unwrap();
+            drop(handler);
+
+            // Only the toot that succeeded is skipped on the next run
+            let (_store, entries) = ProcessedStore::open(&state_file, 100).unwrap();
+            assert_eq!(entries.backfill, ["text_only"]);
+            assert!(media_requests() > media_requests_before);
+        }
+    }
 }
@@ -2,6 +2,7 @@ use crate::config::BalanceConfig;
 use crate::error::BalanceError;
 #[allow(unused_imports)]
 use crate::mastodon::{MastodonStream, MediaRecreation};
+use crate::notifications::{NotificationEvent, Notifier};
 use crate::openrouter::OpenRouterClient;
 use chrono::{DateTime, Local, NaiveTime, Timelike, Utc};
 use std::time::Duration;
@@ -14,6 +15,7 @@ pub struct BalanceMonitor {
     openrouter_client: OpenRouterClient,
     last_check: Option<DateTime<Utc>>,
     last_notification: Option<DateTime<Utc>>,
+    notifier: Notifier,
 }
 
 impl BalanceMonitor {
@@ -24,12 +26,21 @@ impl BalanceMonitor {
             openrouter_client,
             last_check: None,
             last_notification: None,
+            notifier: Notifier::disabled(),
         }
     }
 
+    /// Also send low balance alerts to the notification webhook
+    pub fn with_notifier(mut self, notifier: Notifier) -> Self {
+        self.notifier = notifier;
+        self
+    }
+
     /// Check if balance monitoring is enabled
+    ///
+    /// Always disabled for generic OpenAI-compatible providers, which have no balance endpoint.
     pub fn is_enabled(&self) -> bool {
-        self.config.enabled.unwrap_or(true)
+        self.config.enabled.unwrap_or(true) && self.openrouter_client.is_openrouter_provider()
     }
 
     /// Get the configured balance threshold
@@ -164,6 +175,11 @@ impl BalanceMonitor {
 
         self.last_check = Some(Utc::now());
 
+        let Some(balance) = balance else {
+            info!("OpenRouter key has no credit limit, nothing to compare against the threshold");
+            return Ok(());
+        };
+
         let threshold = self.threshold();
         info!(
             "Current balance: ${:.2}, threshold: ${:.2}",
@@ -207,7 +223,7 @@ impl BalanceMonitor {
         true
     }
 
-    /// Send low balance notification via direct message
+    /// Send low balance notification via webhook and direct message
     async fn send_low_balance_notification<M>(
         &self,
         mastodon_client: &M,
@@ -226,6 +242,15 @@ impl BalanceMonitor {
 
         info!("Sending low balance notification");
 
+        // A failing webhook shouldn't prevent the DM
+        if let Err(e) = self
+            .notifier
+            .notify(&NotificationEvent::LowBalance { balance, threshold })
+            .await
+        {
+            warn!("Failed to send low balance webhook notification: {}", e);
+        }
+
         mastodon_client
             .send_dm(&message)
             .await
@@ -279,8 +304,10 @@ impl BalanceMonitor {
     }
 
     /// Perform an immediate balance check (for testing or manual triggers)
+    ///
+    /// Returns the remaining credit, `None` if the key has no credit limit.
     #[allow(dead_code)] // Public API for manual balance checks
-    pub async fn check_now<M>(&mut self, mastodon_client: &M) -> Result<f64, BalanceError>
+    pub async fn check_now<M>(&mut self, mastodon_client: &M) -> Result<Option<f64>, BalanceError>
     where
         M: MastodonStream,
     {
@@ -294,18 +321,23 @@ impl BalanceMonitor {
 
         self.last_check = Some(Utc::now());
 
+        let Some(remaining) = balance else {
+            info!("OpenRouter key has no credit limit, nothing to compare against the threshold");
+            return Ok(None);
+        };
+
         let threshold = self.threshold();
         info!(
             "Current balance: ${:.2}, threshold: ${:.2}",
-            balance, threshold
+            remaining, threshold
         );
 
-        if balance < threshold {
+        if remaining < threshold {
             warn!(
                 "Balance ${:.2} is below threshold ${:.2}",
-                balance, threshold
+                remaining, threshold
             );
-            self.send_low_balance_notification(mastodon_client, balance, threshold)
+            self.send_low_balance_notification(mastodon_client, remaining, threshold)
                 .await?;
             self.last_notification = Some(Utc::now());
         }
@@ -420,6 +452,15 @@ mod tests {
             Ok(())
         }
 
+        async fn post_reply(
+            &self,
+            _in_reply_to_id: &str,
+            _status: &str,
+            _visibility: &str,
+        ) -> Result<String, MastodonError> {
+            Ok("reply".to_string())
+        }
+
         async fn verify_credentials(&mut self) -> Result<Account, MastodonError> {
             Ok(Account {
                 id: "test_user".to_string(),
@@ -446,13 +487,33 @@ mod tests {
     fn create_openrouter_config() -> OpenRouterConfig {
         OpenRouterConfig {
             api_key: "test_key".to_string(),
+            api_key_file: None,
             model: "test_model".to_string(),
             vision_model: "test_vision_model".to_string(),
             vision_fallback_model: "test_vision_fallback_model".to_string(),
             text_model: "test_text_model".to_string(),
             text_fallback_model: "test_text_fallback_model".to_string(),
             base_url: Some("https://test.openrouter.ai".to_string()),
+            provider: None,
             max_tokens: Some(150),
+            daily_spend_cap: None,
+            max_concurrent_requests: None,
+            min_request_interval_ms: None,
+            description_cache_size: None,
+            description_cache_ttl_secs: None,
+            max_description_length: None,
+            min_description_length: None,
+            refusal_patterns: None,
+            circuit_breaker_threshold: None,
+            circuit_breaker_cooldown_secs: None,
+            retry_base_delay_ms: None,
+            retry_backoff_multiplier: None,
+            retry_max_delay_ms: None,
+            image_url_passthrough: None,
+            use_toot_text_as_context: None,
+            chart_hashtags: None,
+            chart_prompt: None,
+            batch_image_descriptions: None,
         }
     }
 
@@ -480,6 +541,16 @@ mod tests {
         assert!(!monitor.is_enabled());
     }
 
+    #[test]
+    fn test_balance_monitor_disabled_for_openai_provider() {
+        let mut openrouter_config = create_openrouter_config();
+        openrouter_config.provider = Some("openai".to_string());
+        let openrouter_client = crate::openrouter::OpenRouterClient::new(openrouter_config);
+        let monitor = BalanceMonitor::new(create_test_config(), openrouter_client);
+
+        assert!(!monitor.is_enabled());
+    }
+
     #[test]
     fn test_balance_monitor_default_values() {
         let config = BalanceConfig {
@@ -656,6 +727,98 @@ mod tests {
         assert!(messages.is_empty());
     }
 
+    #[tokio::test]
+    async fn test_low_balance_sends_webhook_and_dm() {
+        use crate::test_support::{MockHttpServer, MockResponse};
+
+        let server = MockHttpServer::start(|request| {
+            if request.path == "/auth/key" {
+                MockResponse::json(200, r#"{"data":{"usage":8.75,"limit":10.0}}"#)
+            } else {
+                MockResponse::json(204, "")
+            }
+        })
+        .await;
+        let mut openrouter_config = create_openrouter_config();
+        openrouter_config.base_url = Some(server.url());
+        let notifier = Notifier::from_config(Some(&crate::config::NotificationsConfig {
+            webhook_url: Some(format!("{}/webhook", server.url())),
+            events: Some(vec!["low_balance".to_string()]),
+        }));
+        let mut monitor = BalanceMonitor::new(
+            create_test_config(),
+            crate::openrouter::OpenRouterClient::new(openrouter_config),
+        )
+        .with_notifier(notifier);
+        let mastodon_client = MockMastodonClient::new();
+
+        let balance = monitor.check_now(&mastodon_client).await.unwrap();
+        assert_eq!(balance, Some(1.25));
+
+        let webhook_requests: Vec<_> = server
+            .requests()
+            .into_iter()
+            .filter(|request| request.path == "/webhook")
+            .collect();
+        assert_eq!(webhook_requests.len(), 1);
+        let body: serde_json::Value = serde_json::from_slice(&webhook_requests[0].body).unwrap();
+        assert_eq!(body["event"], "low_balance");
+        assert_eq!(body["details"]["balance"], 1.25);
+        assert_eq!(body["details"]["threshold"], 5.0);
+
+        assert_eq!(mastodon_client.get_sent_messages().await.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_unlimited_key_never_reports_low_balance() {
+        use crate::test_support::{MockHttpServer, MockResponse};
+
+        // High spend on a key without a credit limit is not a low balance
+        let server = MockHttpServer::start(|request| {
+            if request.path == "/auth/key" {
+                MockResponse::json(200, r#"{"data":{"usage":480.0,"limit":null}}"#)
+            } else {
+                MockResponse::json(204, "")
+            }
+        })
+        .await;
+        let mut openrouter_config = create_openrouter_config();
+        openrouter_config.base_url = Some(server.url());
+        let mut monitor = BalanceMonitor::new(
+            create_test_config(),
+            crate::openrouter::OpenRouterClient::new(openrouter_config),
+        );
+        let mastodon_client = MockMastodonClient::new();
+
+        assert_eq!(monitor.check_now(&mastodon_client).await.unwrap(), None);
+        monitor.check_balance(&mastodon_client).await.unwrap();
+        assert!(mastodon_client.get_sent_messages().await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_low_spend_with_ample_limit_is_not_low_balance() {
+        use crate::test_support::{MockHttpServer, MockResponse};
+
+        // Low spend used to be mistaken for a low balance
+        let server = MockHttpServer::start(|_| {
+            MockResponse::json(200, r#"{"data":{"usage":0.5,"limit":50.0}}"#)
+        })
+        .await;
+        let mut openrouter_config = create_openrouter_config();
+        openrouter_config.base_url = Some(server.url());
+        let mut monitor = BalanceMonitor::new(
+            create_test_config(),
+            crate::openrouter::OpenRouterClient::new(openrouter_config),
+        );
+        let mastodon_client = MockMastodonClient::new();
+
+        assert_eq!(
+            monitor.check_now(&mastodon_client).await.unwrap(),
+            Some(49.5)
+        );
+        assert!(mastodon_client.get_sent_messages().await.is_empty());
+    }
+
     #[test]
     fn test_balance_error_display() {
         let check_error = BalanceError::CheckFailed("network timeout".to_string());
@@ -55,6 +55,9 @@ pub enum AlternatorError {
 
     #[error("Invalid data: {0}")]
     InvalidData(String),
+
+    #[error("Processing toot {toot_id} timed out after {timeout_secs} seconds")]
+    ProcessingTimeout { toot_id: String, timeout_secs: u64 },
 }
 
 #[derive(Error, Debug, Clone)]
@@ -92,6 +95,9 @@ pub enum MastodonError {
     #[error("Race condition detected: toot was modified")]
     RaceConditionDetected,
 
+    #[error("Status text is {length} characters, over the instance limit of {limit}")]
+    StatusTooLong { length: usize, limit: usize },
+
     #[error("Blacklisted server: {server} - {reason}")]
     BlacklistedServer { server: String, reason: String },
 }
@@ -105,7 +111,7 @@ pub enum OpenRouterError {
     #[error("Authentication failed: invalid API key")]
     AuthenticationFailed,
 
-    #[error("Model not available: {model}")]
+    #[error("Model not available: {model} (check the configured model names, `alternator --list-models` lists available models)")]
     ModelNotAvailable { model: String },
 
     #[error("Token limit exceeded: {tokens_used}/{max_tokens}")]
@@ -120,6 +126,12 @@ pub enum OpenRouterError {
     #[error("Invalid response format: {0}")]
     InvalidResponse(String),
 
+    #[error("Empty description returned by {model}")]
+    EmptyDescription { model: String },
+
+    #[error("Unusable description returned by {model}: {reason}")]
+    LowQualityDescription { model: String, reason: String },
+
     #[error("Image too large: {size_mb}MB (max: {max_mb}MB)")]
     ImageTooLarge { size_mb: f64, max_mb: f64 },
 
@@ -128,6 +140,16 @@ pub enum OpenRouterError {
 
     #[error("Provider failure: {provider} - {message}")]
     ProviderFailure { provider: String, message: String },
+
+    #[error("Circuit breaker open after {failures} consecutive provider failures, retrying in {retry_in} seconds")]
+    CircuitOpen { failures: u32, retry_in: u64 },
+
+    #[error("Daily spend cap reached: ${spent:.4} of ${cap:.2}, paused for {resets_in} seconds")]
+    SpendCapReached {
+        spent: f64,
+        cap: f64,
+        resets_in: u64,
+    },
 }
 
 #[allow(dead_code)] // Comprehensive error enum with some unused variants for completeness
@@ -155,8 +177,14 @@ pub enum MediaError {
     #[error("Invalid image data")]
     InvalidImageData,
 
-    #[error("Media download failed: {url}")]
+    #[error("Media download failed: {}", crate::redact::log_url(.url))]
     DownloadFailed { url: String },
+
+    #[error("Media host not allowed for {}: {reason}", crate::redact::log_url(.url))]
+    HostNotAllowed { url: String, reason: String },
+
+    #[error("Media duration {duration_secs:.1}s is below the minimum of {min_secs}s")]
+    TooShort { duration_secs: f64, min_secs: u32 },
 }
 
 #[allow(dead_code)] // Comprehensive error enum with some unused variants for completeness
@@ -191,6 +219,15 @@ pub enum BalanceError {
     NotificationFailed(String),
 }
 
+#[derive(Error, Debug, Clone)]
+pub enum NotificationError {
+    #[error("Webhook request failed: {0}")]
+    RequestFailed(String),
+
+    #[error("Webhook returned status {status}")]
+    UnexpectedStatus { status: u16 },
+}
+
 /// Error recovery strategies for different failure scenarios
 pub struct ErrorRecovery;
 
@@ -213,6 +250,7 @@ impl ErrorRecovery {
                 MastodonError::AuthenticationFailed(_) => false, // Not recoverable
                 MastodonError::UserVerificationFailed => false,  // Not recoverable
                 MastodonError::BlacklistedServer { .. } => false, // Not recoverable
+                MastodonError::StatusTooLong { .. } => false,    // Same text fails again
                 _ => false,
             },
 
@@ -223,6 +261,8 @@ impl ErrorRecovery {
                 OpenRouterError::TokenLimitExceeded { .. } => false, // Skip this media
                 OpenRouterError::AuthenticationFailed => false,      // Not recoverable
                 OpenRouterError::InsufficientBalance { .. } => false, // Not recoverable
+                OpenRouterError::SpendCapReached { .. } => true,     // Resets after the window
+                OpenRouterError::CircuitOpen { .. } => true,         // Probed after the cooldown
                 _ => false,
             },
 
@@ -242,6 +282,7 @@ impl ErrorRecovery {
             AlternatorError::Authentication(_) => false,
             AlternatorError::InvalidData(_) => false,
             AlternatorError::Language(_) => false, // Skip this toot
+            AlternatorError::ProcessingTimeout { .. } => true, // Retried on a later edit
             AlternatorError::Balance(_) => true,   // May be temporary
         }
     }
@@ -269,6 +310,14 @@ impl ErrorRecovery {
                 // Rate limit errors should respect the exact retry_after value
                 *retry_after
             }
+            AlternatorError::OpenRouter(OpenRouterError::SpendCapReached { resets_in, .. }) => {
+                // Wait until the spend window resets
+                *resets_in
+            }
+            AlternatorError::OpenRouter(OpenRouterError::CircuitOpen { retry_in, .. }) => {
+                // Wait until the circuit breaker lets a probe through
+                *retry_in
+            }
             AlternatorError::OpenRouter(_) => {
                 // Apply exponential backoff, max 60 seconds
                 let base_delay = 5;
@@ -0,0 +1,143 @@
+//! JSON-lines result events for external consumers
+
+use crate::config::EventsConfig;
+use serde::Serialize;
+use std::fs::OpenOptions;
+use std::io::Write;
+use tracing::warn;
+
+/// `events.output` value that writes events to standard output
+pub const STDOUT_OUTPUT: &str = "stdout";
+
+/// Description generated for a single media attachment
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct DescribedMedia {
+    pub media_id: String,
+    pub description: String,
+    /// Vision model that wrote the description, `None` for Whisper transcripts
+    pub model: Option<String>,
+}
+
+/// Result of running the pipeline for one toot, written as a single JSON line
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TootResultEvent {
+    pub event: &'static str,
+    pub timestamp: String,
+    pub toot_id: String,
+    /// The toot was processed because it was edited
+    pub edit: bool,
+    /// Descriptions were generated but not applied
+    pub dry_run: bool,
+    pub success: bool,
+    /// IDs of all media attachments of the toot
+    pub media_ids: Vec<String>,
+    /// Descriptions generated for the toot, in attachment order
+    pub media: Vec<DescribedMedia>,
+    /// Error that stopped processing, if any
+    pub error: Option<String>,
+}
+
+impl TootResultEvent {
+    /// Event for a toot whose media were described
+    pub fn success(toot_id: &str, media_ids: Vec<String>, media: Vec<DescribedMedia>) -> Self {
+        Self {
+            event: "toot_processed",
+            timestamp: chrono::Utc::now().to_rfc3339(),
+            toot_id: toot_id.to_string(),
+            edit: false,
+            dry_run: false,
+            success: true,
+            media_ids,
+            media,
+            error: None,
+        }
+    }
+
+    /// Event for a toot that failed to process
+    pub fn failure(toot_id: &str, media_ids: Vec<String>, error: String) -> Self {
+        Self {
+            success: false,
+            error: Some(error),
+            ..Self::success(toot_id, media_ids, Vec::new())
+        }
+    }
+
+    /// Mark the event as coming from an edited toot and/or a dry run
+    pub fn with_flags(mut self, edit: bool, dry_run: bool) -> Self {
+        self.edit = edit;
+        self.dry_run = dry_run;
+        self
+    }
+}
+
+/// Append `event` as one JSON line to the configured output; a no-op without `events.output`
+///
+/// Write failures are logged, never returned, so consumers can't break processing.
+pub fn emit(config: Option<&EventsConfig>, event: &TootResultEvent) {
+    let Some(output) = config.and_then(|events| events.output.as_deref()) else {
+        return;
+    };
+
+    let line = match serde_json::to_string(event) {
+        Ok(line) => line + "\n",
+        Err(e) => {
+            warn!(
+                "Failed to serialize result event for {}: {}",
+                event.toot_id, e
+            );
+            return;
+        }
+    };
+
+    let result = if output == STDOUT_OUTPUT {
+        let mut stdout = std::io::stdout().lock();
+        stdout
+            .write_all(line.as_bytes())
+            .and_then(|()| stdout.flush())
+    } else {
+        OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(output)
+            .and_then(|mut file| file.write_all(line.as_bytes()))
+    };
+
+    if let Err(e) = result {
+        warn!("Failed to write result event to {}: {}", output, e);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_events_appended_as_json_lines() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("events.jsonl");
+        let config = EventsConfig {
+            output: Some(path.to_string_lossy().to_string()),
+        };
+
+        emit(
+            Some(&config),
+            &TootResultEvent::failure("1", vec!["m1".to_string()], "boom".to_string()),
+        );
+        emit(
+            Some(&config),
+            &TootResultEvent::success("2", Vec::new(), Vec::new()).with_flags(true, false),
+        );
+
+        let contents = std::fs::read_to_string(&path).unwrap();
+        let lines: Vec<serde_json::Value> = contents
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0]["toot_id"], "1");
+        assert_eq!(lines[0]["success"], false);
+        assert_eq!(lines[0]["error"], "boom");
+        assert_eq!(lines[1]["edit"], true);
+        assert_eq!(lines[1]["error"], serde_json::Value::Null);
+    }
+}
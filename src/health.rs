@@ -0,0 +1,159 @@
+//! Liveness and readiness endpoints for container orchestration
+
+use std::net::SocketAddr;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::task::JoinHandle;
+use tracing::{debug, info, warn};
+
+/// Maximum number of bytes read from a probe request
+const MAX_REQUEST_SIZE: usize = 4096;
+
+/// Connection state shared between the Mastodon client and the health server
+#[derive(Debug, Clone, Default)]
+pub struct HealthState {
+    websocket_connected: Arc<AtomicBool>,
+    credentials_verified: Arc<AtomicBool>,
+}
+
+impl HealthState {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record whether the streaming WebSocket is currently open
+    pub fn set_websocket_connected(&self, connected: bool) {
+        self.websocket_connected.store(connected, Ordering::Relaxed);
+    }
+
+    /// Record that the access token was accepted by the instance
+    pub fn set_credentials_verified(&self, verified: bool) {
+        self.credentials_verified.store(verified, Ordering::Relaxed);
+    }
+
+    /// Ready once credentials are verified and the stream is connected
+    pub fn is_ready(&self) -> bool {
+        self.websocket_connected.load(Ordering::Relaxed)
+            && self.credentials_verified.load(Ordering::Relaxed)
+    }
+}
+
+/// Bind the health server and serve probes in the background
+///
+/// Binding happens before spawning so an unusable address fails startup.
+pub async fn spawn_health_server(
+    bind_address: &str,
+    state: HealthState,
+) -> Result<(SocketAddr, JoinHandle<()>), std::io::Error> {
+    let listener = TcpListener::bind(bind_address).await?;
+    let local_addr = listener.local_addr()?;
+    info!("Health check endpoints listening on http://{}", local_addr);
+
+    let handle = tokio::spawn(async move {
+        loop {
+            match listener.accept().await {
+                Ok((stream, _)) => {
+                    let state = state.clone();
+                    tokio::spawn(async move {
+                        if let Err(e) = handle_connection(stream, &state).await {
+                            debug!("Health check connection error: {}", e);
+                        }
+                    });
+                }
+                Err(e) => warn!("Failed to accept health check connection: {}", e),
+            }
+        }
+    });
+
+    Ok((local_addr, handle))
+}
+
+async fn handle_connection(mut stream: TcpStream, state: &HealthState) -> std::io::Result<()> {
+    let mut buffer = vec![0u8; MAX_REQUEST_SIZE];
+    let mut len = 0;
+
+    // Only the request line matters, so stop once the headers are complete
+    while len < buffer.len() {
+        let read = stream.read(&mut buffer[len..]).await?;
+        if read == 0 {
+            break;
+        }
+        len += read;
+        if buffer[..len].windows(4).any(|window| window == b"\r\n\r\n") {
+            break;
+        }
+    }
+
+    let request = String::from_utf8_lossy(&buffer[..len]);
+    let mut request_line = request.lines().next().unwrap_or("").split_whitespace();
+    let method = request_line.next().unwrap_or("");
+    let path = request_line.next().unwrap_or("");
+
+    let (status, body) = route(method, path, state);
+    let response = format!(
+        "HTTP/1.1 {status}\r\nContent-Type: text/plain\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+        body.len()
+    );
+
+    stream.write_all(response.as_bytes()).await?;
+    stream.shutdown().await
+}
+
+fn route(method: &str, path: &str, state: &HealthState) -> (&'static str, &'static str) {
+    if method != "GET" {
+        return ("405 Method Not Allowed", "method not allowed\n");
+    }
+
+    // Ignore query strings probes may append
+    match path.split('?').next().unwrap_or("") {
+        // Answering at all proves the runtime is alive
+        "/healthz" => ("200 OK", "ok\n"),
+        "/readyz" if state.is_ready() => ("200 OK", "ready\n"),
+        "/readyz" => ("503 Service Unavailable", "not ready\n"),
+        _ => ("404 Not Found", "not found\n"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn get(addr: SocketAddr, path: &str) -> String {
+        let mut stream = TcpStream::connect(addr).await.unwrap();
+        stream
+            .write_all(format!("GET {path} HTTP/1.1\r\nHost: localhost\r\n\r\n").as_bytes())
+            .await
+            .unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).await.unwrap();
+        response
+    }
+
+    #[tokio::test]
+    async fn test_readyz_reflects_connection_state() {
+        let state = HealthState::new();
+        let (addr, handle) = spawn_health_server("127.0.0.1:0", state.clone())
+            .await
+            .unwrap();
+
+        assert!(get(addr, "/healthz").await.starts_with("HTTP/1.1 200"));
+        assert!(get(addr, "/readyz").await.starts_with("HTTP/1.1 503"));
+
+        // Credentials alone are not enough without a live stream
+        state.set_credentials_verified(true);
+        assert!(get(addr, "/readyz").await.starts_with("HTTP/1.1 503"));
+
+        state.set_websocket_connected(true);
+        let response = get(addr, "/readyz?verbose").await;
+        assert!(response.starts_with("HTTP/1.1 200"));
+        assert!(response.ends_with("ready\n"));
+
+        state.set_websocket_connected(false);
+        assert!(get(addr, "/readyz").await.starts_with("HTTP/1.1 503"));
+        assert!(get(addr, "/metrics").await.starts_with("HTTP/1.1 404"));
+
+        handle.abort();
+    }
+}
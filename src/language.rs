@@ -1,7 +1,276 @@
 use crate::error::LanguageError;
+use crate::mastodon::TootEvent;
 use std::collections::HashMap;
 use tracing::{debug, warn};
 
+/// Placeholders that may appear in prompt templates (substituted before sending the prompt)
+pub const PROMPT_PLACEHOLDERS: &[&str] = &["model", "max_length"];
+
+/// Placeholders that may appear in attribution templates
+pub const ATTRIBUTION_PLACEHOLDERS: &[&str] = &["model"];
+
+/// Return the `{...}` placeholders in a template that are not in `allowed`
+pub fn unknown_placeholders(template: &str, allowed: &[&str]) -> Vec<String> {
+    let mut unknown = Vec::new();
+    let mut rest = template;
+
+    while let Some(start) = rest.find('{') {
+        let after = &rest[start + 1..];
+        let Some(end) = after.find('}') else {
+            break;
+        };
+        let name = &after[..end];
+        if !allowed.contains(&name) {
+            unknown.push(format!("{{{name}}}"));
+        }
+        rest = &after[end + 1..];
+    }
+
+    unknown
+}
+
+/// Built-in attribution appended to AI-generated descriptions, by language
+const ATTRIBUTION_TEMPLATES: &[(&str, &str)] = &[
+    ("en", "this image description was made by AI: {model}"),
+    (
+        "de",
+        "diese Bildbeschreibung wurde von KI erstellt: {model}",
+    ),
+    (
+        "fr",
+        "cette description d'image a été créée par IA : {model}",
+    ),
+    (
+        "es",
+        "esta descripción de imagen fue creada por IA: {model}",
+    ),
+    (
+        "it",
+        "questa descrizione dell'immagine è stata creata dall'IA: {model}",
+    ),
+    ("pt", "esta descrição de imagem foi criada por IA: {model}"),
+    (
+        "nl",
+        "deze afbeeldingsbeschrijving is gemaakt door AI: {model}",
+    ),
+    ("ja", "この画像説明はAIによって作成されました：{model}"),
+    ("da", "denne billedbeskrivelse blev lavet af AI: {model}"),
+    ("sv", "denna bildbeskrivning skapades av AI: {model}"),
+    ("no", "denne billebeskrivelsen ble laget av AI: {model}"),
+    (
+        "is",
+        "þessi myndlýsing var búin til af gervigreind: {model}",
+    ),
+    (
+        "gd",
+        "chaidh an tuairisgeul deilbh seo a chruthachadh le AI: {model}",
+    ),
+    ("pl", "ten opis obrazu został stworzony przez AI: {model}"),
+    (
+        "cs",
+        "tento popis obrázku byl vytvořen umělou inteligencí: {model}",
+    ),
+    (
+        "hu",
+        "ezt a képleírást mesterséges intelligencia készítette: {model}",
+    ),
+    (
+        "bg",
+        "това описание на изображението беше създадено от изкуствен интелект: {model}",
+    ),
+    (
+        "la",
+        "haec imaginis descriptio ab intelligentia artificiali facta est: {model}",
+    ),
+    ("ru", "это описание изображения было создано ИИ: {model}"),
+    (
+        "pt-br",
+        "esta descrição de imagem foi criada por IA: {model}",
+    ),
+    ("id", "deskripsi gambar ini dibuat oleh AI: {model}"),
+    ("zh-cn", "此图片描述由AI生成：{model}"),
+    ("zh-tw", "此圖片描述由AI生成：{model}"),
+    ("hi", "यह छवि विवरण AI द्वारा बनाया गया था: {model}"),
+    ("gsw", "die Bildbeschribig isch vo KI gmacht worde: {model}"),
+    (
+        "nds",
+        "disse Bildbeschrievung is von KI maakt worrn: {model}",
+    ),
+    (
+        "sk",
+        "tento popis obrázka bol vytvorený umelou inteligenciou: {model}",
+    ),
+    (
+        "sl",
+        "ta opis slike je ustvarila umetna inteligenca: {model}",
+    ),
+    (
+        "hr",
+        "ovaj opis slike je stvoren umjetnom inteligencijom: {model}",
+    ),
+    (
+        "bs",
+        "ovaj opis slike je napravljen umjetnom inteligencijom: {model}",
+    ),
+    (
+        "sr",
+        "овај опис слике је направљен вештачком интелигенцијом: {model}",
+    ),
+    (
+        "el",
+        "αυτή η περιγραφή εικόνας δημιουργήθηκε από τεχνητή νοημοσύνη: {model}",
+    ),
+    (
+        "lt",
+        "šis vaizdo aprašymas buvo sukurtas dirbtinio intelekto: {model}",
+    ),
+    (
+        "et",
+        "see pildikirjeldus on loodud tehisintellekti poolt: {model}",
+    ),
+    (
+        "lv",
+        "šis attēla apraksts ir izveidots ar mākslīgo intelektu: {model}",
+    ),
+    (
+        "uk",
+        "цей опис зображення було створено штучним інтелектом: {model}",
+    ),
+    (
+        "yi",
+        "דער דאָזיקער בילד־באַשרײַבונג איז געמאַכט געוואָרן דורך קינסטלעכער אינטעליגענץ: {model}",
+    ),
+    ("he", "תיאור התמונה הזה נוצר על ידי בינה מלאכותית: {model}"),
+    ("fi", "tämän kuvan kuvauksen on luonut tekoäly: {model}"),
+    (
+        "ga",
+        "rinne hintleacht shaorga an tuairisc íomhá seo: {model}",
+    ),
+    (
+        "cy",
+        "cafodd y disgrifiad delwedd hwn ei greu gan ddeallusrwydd artiffisial: {model}",
+    ),
+    (
+        "ro",
+        "această descriere a imaginii a fost creată de inteligența artificială: {model}",
+    ),
+    (
+        "rm",
+        "questa descripziun da maletg è vegnida creada dad intelligenza artifiziala: {model}",
+    ),
+];
+
+/// Separator between a description and its attribution
+pub const ATTRIBUTION_SEPARATOR: &str = " — ";
+
+/// Whether a description ends with an AI attribution, built-in or `custom_template`
+///
+/// Used to recognize Alternator's own descriptions; anything else counts as human-written.
+pub fn has_ai_attribution(description: &str, custom_template: Option<&str>) -> bool {
+    let Some((_, attribution)) = description.trim().rsplit_once(ATTRIBUTION_SEPARATOR) else {
+        return false;
+    };
+
+    ATTRIBUTION_TEMPLATES
+        .iter()
+        .map(|(_, template)| *template)
+        .chain(custom_template)
+        .any(|template| match template.split_once("{model}") {
+            Some((prefix, suffix)) => {
+                attribution.len() > prefix.len() + suffix.len()
+                    && attribution.starts_with(prefix)
+                    && attribution.ends_with(suffix)
+            }
+            None => attribution == template,
+        })
+}
+
+/// Kind of media a prompt template is written for
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MediaKind {
+    Image,
+    /// Frames an audio transcript
+    Audio,
+    /// Frames a video clip summary from a representative frame
+    Video,
+}
+
+/// Built-in audio and video prompt templates; image templates are built in `LanguageDetector::new`
+const MEDIA_PROMPT_TEMPLATES: &[(&str, MediaKind, &str)] = &[
+    (
+        "en",
+        MediaKind::Audio,
+        "Summarize this audio transcript as alt-text for people who cannot hear it. Say what kind of audio it is (for example speech, interview, podcast or song) and capture the main points. Keep the TOTAL response under {max_length} characters and note briefly that it is a summary. Respond with ONLY the summary.",
+    ),
+    (
+        "en",
+        MediaKind::Video,
+        "Create a concise alt-text for this video clip from its representative frame. Describe the setting, the people or objects shown and the action taking place, so visually impaired users know what the clip is about. Be specific and objective. Keep the TOTAL response under {max_length} characters. Respond with ONLY the description text.",
+    ),
+    (
+        "de",
+        MediaKind::Audio,
+        "Fasse dieses Audio-Transkript als Alt-Text für Menschen zusammen, die es nicht hören können. Nenne die Art des Audios (zum Beispiel Rede, Interview, Podcast oder Lied) und gib die wichtigsten Punkte wieder. Halte die GESAMTE Antwort unter {max_length} Zeichen und weise kurz darauf hin, dass es eine Zusammenfassung ist. Antworte NUR mit der Zusammenfassung.",
+    ),
+    (
+        "de",
+        MediaKind::Video,
+        "Erstelle eine prägnante Alt-Text-Beschreibung für diesen Videoclip anhand seines repräsentativen Bildes. Beschreibe die Umgebung, die gezeigten Personen oder Objekte und die Handlung, damit sehbehinderte Nutzer wissen, worum es im Clip geht. Sei spezifisch und objektiv. Halte die GESAMTE Antwort unter {max_length} Zeichen. Antworte NUR mit der Beschreibung.",
+    ),
+    (
+        "fr",
+        MediaKind::Audio,
+        "Résumez cette transcription audio sous forme de texte alternatif pour les personnes qui ne peuvent pas l'entendre. Indiquez le type d'audio (par exemple discours, interview, podcast ou chanson) et les points principaux. Gardez la réponse TOTALE sous {max_length} caractères et précisez brièvement qu'il s'agit d'un résumé. Répondez SEULEMENT avec le résumé.",
+    ),
+    (
+        "fr",
+        MediaKind::Video,
+        "Créez un texte alternatif concis pour ce clip vidéo à partir de son image représentative. Décrivez le décor, les personnes ou objets montrés et l'action, afin que les utilisateurs malvoyants sachent de quoi parle le clip. Soyez spécifique et objectif. Gardez la réponse TOTALE sous {max_length} caractères. Répondez SEULEMENT avec le texte de description.",
+    ),
+    (
+        "es",
+        MediaKind::Audio,
+        "Resume esta transcripción de audio como texto alternativo para personas que no pueden oírla. Indica el tipo de audio (por ejemplo discurso, entrevista, pódcast o canción) y los puntos principales. Mantén la respuesta TOTAL por debajo de {max_length} caracteres e indica brevemente que es un resumen. Responde SOLO con el resumen.",
+    ),
+    (
+        "es",
+        MediaKind::Video,
+        "Crea un texto alternativo conciso para este videoclip a partir de su fotograma representativo. Describe el entorno, las personas u objetos que aparecen y la acción, para que los usuarios con discapacidad visual sepan de qué trata el clip. Sé específico y objetivo. Mantén la respuesta TOTAL por debajo de {max_length} caracteres. Responde SOLO con el texto descriptivo.",
+    ),
+];
+
+/// Note added to the image prompt when the image is a montage of animation frames, by language
+const ANIMATION_NOTES: &[(&str, &str)] = &[
+    (
+        "en",
+        "This image is a grid of frames sampled in order (left to right, top to bottom) from an animation. Describe it as one animated image, including the motion or change across the frames; don't describe it as a grid or collage.",
+    ),
+    (
+        "de",
+        "Dieses Bild ist ein Raster aus Einzelbildern, die der Reihe nach (von links nach rechts, von oben nach unten) aus einer Animation entnommen wurden. Beschreibe es als ein animiertes Bild, einschließlich der Bewegung oder Veränderung zwischen den Einzelbildern; beschreibe es nicht als Raster oder Collage.",
+    ),
+    (
+        "fr",
+        "Cette image est une grille d'images extraites dans l'ordre (de gauche à droite, de haut en bas) d'une animation. Décrivez-la comme une seule image animée, y compris le mouvement ou le changement d'une image à l'autre ; ne la décrivez pas comme une grille ou un collage.",
+    ),
+    (
+        "es",
+        "Esta imagen es una cuadrícula de fotogramas tomados en orden (de izquierda a derecha, de arriba abajo) de una animación. Descríbela como una sola imagen animada, incluido el movimiento o el cambio entre los fotogramas; no la describas como una cuadrícula o un collage.",
+    ),
+    (
+        "it",
+        "Questa immagine è una griglia di fotogrammi estratti in ordine (da sinistra a destra, dall'alto in basso) da un'animazione. Descrivila come un'unica immagine animata, incluso il movimento o il cambiamento tra i fotogrammi; non descriverla come una griglia o un collage.",
+    ),
+    (
+        "pt",
+        "Esta imagem é uma grade de quadros extraídos em ordem (da esquerda para a direita, de cima para baixo) de uma animação. Descreva-a como uma única imagem animada, incluindo o movimento ou a mudança entre os quadros; não a descreva como uma grade ou colagem.",
+    ),
+    (
+        "nl",
+        "Deze afbeelding is een raster van frames die op volgorde (van links naar rechts, van boven naar beneden) uit een animatie zijn genomen. Beschrijf het als één geanimeerde afbeelding, inclusief de beweging of verandering tussen de frames; beschrijf het niet als raster of collage.",
+    ),
+];
+
 /// Convenient function to detect the language of the given text
 pub fn detect_text_language(text: &str) -> String {
     let detector = LanguageDetector::new();
@@ -13,273 +282,309 @@ pub fn detect_text_language(text: &str) -> String {
 /// Language detector with prompt template management
 #[derive(Clone)]
 pub struct LanguageDetector {
-    prompt_templates: HashMap<String, String>,
+    prompt_templates: HashMap<(String, MediaKind), String>,
+    /// Language used when the detected one has no prompt template
+    default_language: String,
 }
 
+/// Language used when none is configured and the detected one has no prompt template
+pub const DEFAULT_LANGUAGE: &str = "en";
+
 impl LanguageDetector {
     /// Create a new language detector with built-in prompt templates
     pub fn new() -> Self {
-        let mut prompt_templates = HashMap::new();
+        let mut image_templates = HashMap::new();
 
         // English template (default)
-        prompt_templates.insert(
+        image_templates.insert(
             "en".to_string(),
-            "Create a concise, descriptive alt-text for this image. Focus on key visual elements, actions, and context that would help visually impaired users understand the content. Be specific and objective. End your description with ' — this image description was made by AI: {model}' where {model} is the AI model name. Keep the TOTAL response (description + attribution) under 1500 characters. Respond with ONLY the description text including the attribution.".to_string()
+            "Create a concise, descriptive alt-text for this image. Focus on key visual elements, actions, and context that would help visually impaired users understand the content. Be specific and objective. Keep the TOTAL response under {max_length} characters. Respond with ONLY the description text.".to_string()
         );
 
         // German template
-        prompt_templates.insert(
+        image_templates.insert(
             "de".to_string(),
-            "Erstelle eine prägnante, beschreibende Alt-Text-Beschreibung für dieses Bild. Konzentriere dich auf wichtige visuelle Elemente, Handlungen und Kontext, die sehbehinderten Nutzern helfen würden. Sei spezifisch und objektiv. Beende deine Beschreibung mit ' — diese Bildbeschreibung wurde von KI erstellt: {model}' wobei {model} der Name des KI-Modells ist. Halte die GESAMTE Antwort (Beschreibung + Quellenangabe) unter 1500 Zeichen. Antworte NUR mit der Beschreibung inklusive Quellenangabe.".to_string()
+            "Erstelle eine prägnante, beschreibende Alt-Text-Beschreibung für dieses Bild. Konzentriere dich auf wichtige visuelle Elemente, Handlungen und Kontext, die sehbehinderten Nutzern helfen würden. Sei spezifisch und objektiv. Halte die GESAMTE Antwort unter {max_length} Zeichen. Antworte NUR mit der Beschreibung.".to_string()
         );
 
         // French template
-        prompt_templates.insert(
+        image_templates.insert(
             "fr".to_string(),
-            "Créez un texte alternatif concis et descriptif pour cette image. Concentrez-vous sur les éléments visuels clés, les actions et le contexte qui aideraient les utilisateurs malvoyants. Soyez spécifique et objectif. Terminez votre description par ' — cette description d'image a été créée par IA : {model}' où {model} est le nom du modèle IA. Gardez la réponse TOTALE (description + attribution) sous 1500 caractères. Répondez SEULEMENT avec le texte de description incluant l'attribution.".to_string()
+            "Créez un texte alternatif concis et descriptif pour cette image. Concentrez-vous sur les éléments visuels clés, les actions et le contexte qui aideraient les utilisateurs malvoyants. Soyez spécifique et objectif. Gardez la réponse TOTALE sous {max_length} caractères. Répondez SEULEMENT avec le texte de description.".to_string()
         );
 
         // Spanish template
-        prompt_templates.insert(
+        image_templates.insert(
             "es".to_string(),
-            "Crea un texto alternativo conciso y descriptivo para esta imagen. Enfócate en elementos visuales clave, acciones y contexto que ayudarían a usuarios con discapacidad visual. Sé específico y objetivo. Termina tu descripción con ' — esta descripción de imagen fue creada por IA: {model}' donde {model} es el nombre del modelo de IA. Mantén la respuesta TOTAL (descripción + atribución) bajo 1500 caracteres. Responde SOLO con el texto de descripción incluyendo la atribución.".to_string()
+            "Crea un texto alternativo conciso y descriptivo para esta imagen. Enfócate en elementos visuales clave, acciones y contexto que ayudarían a usuarios con discapacidad visual. Sé específico y objetivo. Mantén la respuesta TOTAL bajo {max_length} caracteres. Responde SOLO con el texto de descripción.".to_string()
         );
 
         // Italian template
-        prompt_templates.insert(
+        image_templates.insert(
             "it".to_string(),
-            "Crea un testo alternativo conciso e descrittivo per questa immagine. Concentrati su elementi visivi chiave, azioni e contesto che aiuterebbero gli utenti ipovedenti. Sii specifico e obiettivo. Termina la tua descrizione con ' — questa descrizione dell'immagine è stata creata dall'IA: {model}' dove {model} è il nome del modello IA. Mantieni la risposta TOTALE (descrizione + attribuzione) sotto 1500 caratteri. Rispondi SOLO con il testo di descrizione inclusa l'attribuzione.".to_string()
+            "Crea un testo alternativo conciso e descrittivo per questa immagine. Concentrati su elementi visivi chiave, azioni e contesto che aiuterebbero gli utenti ipovedenti. Sii specifico e obiettivo. Mantieni la risposta TOTALE sotto {max_length} caratteri. Rispondi SOLO con il testo di descrizione.".to_string()
         );
 
         // Portuguese template
-        prompt_templates.insert(
+        image_templates.insert(
             "pt".to_string(),
-            "Crie um texto alternativo conciso e descritivo para esta imagem. Foque em elementos visuais chave, ações e contexto que ajudariam usuários com deficiência visual. Seja específico e objetivo. Termine sua descrição com ' — esta descrição de imagem foi criada por IA: {model}' onde {model} é o nome do modelo de IA. Mantenha a resposta TOTAL (descrição + atribuição) abaixo de 1500 caracteres. Responda APENAS com o texto de descrição incluindo a atribuição.".to_string()
+            "Crie um texto alternativo conciso e descritivo para esta imagem. Foque em elementos visuais chave, ações e contexto que ajudariam usuários com deficiência visual. Seja específico e objetivo. Mantenha a resposta TOTAL abaixo de {max_length} caracteres. Responda APENAS com o texto de descrição.".to_string()
         );
 
         // Dutch template
-        prompt_templates.insert(
+        image_templates.insert(
             "nl".to_string(),
-            "Maak een beknopte, beschrijvende alt-tekst voor deze afbeelding. Focus op belangrijke visuele elementen, acties en context die visueel gehandicapte gebruikers zouden helpen. Wees specifiek en objectief. Eindig je beschrijving met ' — deze afbeeldingsbeschrijving is gemaakt door AI: {model}' waarbij {model} de naam van het AI-model is. Houd het TOTALE antwoord (beschrijving + vermelding) onder 1500 tekens. Antwoord ALLEEN met de beschrijvingstekst inclusief de vermelding.".to_string()
+            "Maak een beknopte, beschrijvende alt-tekst voor deze afbeelding. Focus op belangrijke visuele elementen, acties en context die visueel gehandicapte gebruikers zouden helpen. Wees specifiek en objectief. Houd het TOTALE antwoord onder {max_length} tekens. Antwoord ALLEEN met de beschrijvingstekst.".to_string()
         );
 
         // Japanese template
-        prompt_templates.insert(
+        image_templates.insert(
             "ja".to_string(),
-            "この画像の簡潔で説明的な代替テキストを作成してください。視覚障害者の方に役立つよう、重要な視覚要素、行動、文脈に焦点を当ててください。具体的で客観的に記述し、説明の最後に「 — この画像説明はAIによって作成されました：{model}」を追加してください（{model}はAIモデル名）。全体の回答（説明＋出典表示）を1500文字以内に収めてください。説明テキストと出典表示のみで回答してください。".to_string()
+            "この画像の簡潔で説明的な代替テキストを作成してください。視覚障害者の方に役立つよう、重要な視覚要素、行動、文脈に焦点を当ててください。具体的で客観的に記述してください。全体の回答を{max_length}文字以内に収めてください。説明テキストのみで回答してください。".to_string()
         );
 
         // Danish template
-        prompt_templates.insert(
+        image_templates.insert(
             "da".to_string(),
-            "Lav en kortfattet, beskrivende alt-tekst for dette billede. Fokuser på vigtige visuelle elementer, handlinger og kontekst, der ville hjælpe synshandicappede brugere. Vær specifik og objektiv. Afslut din beskrivelse med ' — denne billedbeskrivelse blev lavet af AI: {model}' hvor {model} er AI-modellens navn. Hold det SAMLEDE svar (beskrivelse + attribution) under 1500 tegn. Svar KUN med beskrivelsesteksten inklusive attributionen.".to_string()
+            "Lav en kortfattet, beskrivende alt-tekst for dette billede. Fokuser på vigtige visuelle elementer, handlinger og kontekst, der ville hjælpe synshandicappede brugere. Vær specifik og objektiv. Hold det SAMLEDE svar under {max_length} tegn. Svar KUN med beskrivelsesteksten.".to_string()
         );
 
         // Swedish template
-        prompt_templates.insert(
+        image_templates.insert(
             "sv".to_string(),
-            "Skapa en kortfattad, beskrivande alt-text för denna bild. Fokusera på viktiga visuella element, handlingar och sammanhang som skulle hjälpa synskadade användare. Var specifik och objektiv. Avsluta din beskrivning med ' — denna bildbeskrivning skapades av AI: {model}' där {model} är AI-modellens namn. Håll det TOTALA svaret (beskrivning + attribution) under 1500 tecken. Svara ENDAST med beskrivningstexten inklusive attributionen.".to_string()
+            "Skapa en kortfattad, beskrivande alt-text för denna bild. Fokusera på viktiga visuella element, handlingar och sammanhang som skulle hjälpa synskadade användare. Var specifik och objektiv. Håll det TOTALA svaret under {max_length} tecken. Svara ENDAST med beskrivningstexten.".to_string()
         );
 
         // Norwegian template
-        prompt_templates.insert(
+        image_templates.insert(
             "no".to_string(),
-            "Lag en kortfattet, beskrivende alt-tekst for dette bildet. Fokuser på viktige visuelle elementer, handlinger og kontekst som ville hjelpe synshemmede brukere. Vær spesifikk og objektiv. Avslutt beskrivelsen din med ' — denne billebeskrivelsen ble laget av AI: {model}' der {model} er AI-modellens navn. Hold det TOTALE svaret (beskrivelse + attribusjon) under 1500 tegn. Svar KUN med beskrivelsesteksten inkludert attribusjonen.".to_string()
+            "Lag en kortfattet, beskrivende alt-tekst for dette bildet. Fokuser på viktige visuelle elementer, handlinger og kontekst som ville hjelpe synshemmede brukere. Vær spesifikk og objektiv. Hold det TOTALE svaret under {max_length} tegn. Svar KUN med beskrivelsesteksten.".to_string()
         );
 
         // Icelandic template
-        prompt_templates.insert(
+        image_templates.insert(
             "is".to_string(),
-            "Búðu til stutta, lýsandi alt-texta fyrir þessa mynd. Einbeittu þér að mikilvægum sjónrænum þáttum, aðgerðum og samhengi sem myndi hjálpa sjónskertum notendum. Vertu nákvæm/ur og hlutlæg/ur. Endaðu lýsinguna þína með ' — þessi myndlýsing var búin til af gervigreind: {model}' þar sem {model} er nafn gervigreindarinnar. Haltu HEILDARSVARI (lýsing + tilvísun) undir 1500 stöfum. Svaraðu AÐEINS með lýsingartextanum ásamt tilvísuninni.".to_string()
+            "Búðu til stutta, lýsandi alt-texta fyrir þessa mynd. Einbeittu þér að mikilvægum sjónrænum þáttum, aðgerðum og samhengi sem myndi hjálpa sjónskertum notendum. Vertu nákvæm/ur og hlutlæg/ur. Haltu HEILDARSVARI undir {max_length} stöfum. Svaraðu AÐEINS með lýsingartextanum.".to_string()
         );
 
         // Scottish Gaelic template
-        prompt_templates.insert(
+        image_templates.insert(
             "gd".to_string(),
-            "Cruthaich alt-teacsa goirid, tuairisgeulach airson an deilbh seo. Cuir fòcas air feartan lèirsinneach cudromach, gnìomhan agus co-theacsa a chuidicheadh luchd-cleachdaidh le cion-lèirsinn. Bi sònraichte agus oibheachail. Crìochnaich an tuairisgeul agad le ' — chaidh an tuairisgeul deilbh seo a chruthachadh le AI: {model}' far a bheil {model} ainm a' mhodail AI. Cum am FREAGAIRT IOMLAN (tuairisgeul + buaidh) fo 1500 caractar. Freagair le DÌREACH an teacsa tuairisgeul a' gabhail a-steach a' bhuaidh.".to_string()
+            "Cruthaich alt-teacsa goirid, tuairisgeulach airson an deilbh seo. Cuir fòcas air feartan lèirsinneach cudromach, gnìomhan agus co-theacsa a chuidicheadh luchd-cleachdaidh le cion-lèirsinn. Bi sònraichte agus oibheachail. Cum am FREAGAIRT IOMLAN fo {max_length} caractar. Freagair le DÌREACH an teacsa tuairisgeul.".to_string()
         );
 
         // Polish template
-        prompt_templates.insert(
+        image_templates.insert(
             "pl".to_string(),
-            "Stwórz zwięzły, opisowy tekst alternatywny dla tego obrazu. Skup się na kluczowych elementach wizualnych, działaniach i kontekście, które pomogłyby użytkownikom z wadami wzroku. Bądź konkretny i obiektywny. Zakończ swój opis ' — ten opis obrazu został stworzony przez AI: {model}' gdzie {model} to nazwa modelu AI. Utrzymaj CAŁKOWITĄ odpowiedź (opis + atrybucja) poniżej 1500 znaków. Odpowiedz TYLKO tekstem opisu wraz z atrybucją.".to_string()
+            "Stwórz zwięzły, opisowy tekst alternatywny dla tego obrazu. Skup się na kluczowych elementach wizualnych, działaniach i kontekście, które pomogłyby użytkownikom z wadami wzroku. Bądź konkretny i obiektywny. Utrzymaj CAŁKOWITĄ odpowiedź poniżej {max_length} znaków. Odpowiedz TYLKO tekstem opisu.".to_string()
         );
 
         // Czech template
-        prompt_templates.insert(
+        image_templates.insert(
             "cs".to_string(),
-            "Vytvořte stručný, popisný alt-text pro tento obrázek. Zaměřte se na klíčové vizuální prvky, akce a kontext, které by pomohly uživatelům se zrakovým postižením. Buďte konkrétní a objektivní. Ukončete svůj popis ' — tento popis obrázku byl vytvořen umělou inteligencí: {model}' kde {model} je název AI modelu. Udržte CELKOVOU odpověď (popis + atribuce) pod 1500 znaky. Odpovězte POUZE textem popisu včetně atribuce.".to_string()
+            "Vytvořte stručný, popisný alt-text pro tento obrázek. Zaměřte se na klíčové vizuální prvky, akce a kontext, které by pomohly uživatelům se zrakovým postižením. Buďte konkrétní a objektivní. Udržte CELKOVOU odpověď pod {max_length} znaky. Odpovězte POUZE textem popisu.".to_string()
         );
 
         // Hungarian template
-        prompt_templates.insert(
+        image_templates.insert(
             "hu".to_string(),
-            "Készítsen tömör, leíró alt-szöveget ehhez a képhez. Összpontosítson a kulcsfontosságú vizuális elemekre, cselekvésekre és kontextusra, amelyek segítenének a látássérült felhasználóknak. Legyen konkrét és objektív. Fejezze be a leírását ezzel: ' — ezt a képleírást mesterséges intelligencia készítette: {model}' ahol a {model} az AI modell neve. Tartsa a TELJES választ (leírás + forrásmegjelölés) 1500 karakter alatt. Válaszoljon CSAK a leíró szöveggel a forrásmegjelöléssel együtt.".to_string()
+            "Készítsen tömör, leíró alt-szöveget ehhez a képhez. Összpontosítson a kulcsfontosságú vizuális elemekre, cselekvésekre és kontextusra, amelyek segítenének a látássérült felhasználóknak. Legyen konkrét és objektív. Tartsa a TELJES választ {max_length} karakter alatt. Válaszoljon CSAK a leíró szöveggel.".to_string()
         );
 
         // Bulgarian template
-        prompt_templates.insert(
+        image_templates.insert(
             "bg".to_string(),
-            "Създайте кратък, описателен alt-текст за това изображение. Фокусирайте се върху ключови визуални елементи, действия и контекст, които биха помогнали на потребители със зрителни увреждания. Бъдете конкретни и обективни. Завършете описанието си с ' — това описание на изображението беше създадено от изкуствен интелект: {model}' където {model} е името на AI модела. Поддържайте ОБЩИЯ отговор (описание + атрибуция) под 1500 знака. Отговорете САМО с описателния текст заедно с атрибуцията.".to_string()
+            "Създайте кратък, описателен alt-текст за това изображение. Фокусирайте се върху ключови визуални елементи, действия и контекст, които биха помогнали на потребители със зрителни увреждания. Бъдете конкретни и обективни. Поддържайте ОБЩИЯ отговор под {max_length} знака. Отговорете САМО с описателния текст.".to_string()
         );
 
         // Latin template
-        prompt_templates.insert(
+        image_templates.insert(
             "la".to_string(),
-            "Crea brevem, descriptivum alt-textum huic imagini. Attende ad elementa visualia principalia, actiones et contextum qui hominibus visu carentes adiuvent. Esto specificus et obiectivus. Fini descriptionem tuam cum ' — haec imaginis descriptio ab intelligentia artificiali facta est: {model}' ubi {model} est nomen exemplaris AI. Tene TOTAM responsionem (descriptionem + attributionem) sub 1500 characteribus. Responde SOLUM cum textu descriptivo una cum attributione.".to_string()
+            "Crea brevem, descriptivum alt-textum huic imagini. Attende ad elementa visualia principalia, actiones et contextum qui hominibus visu carentes adiuvent. Esto specificus et obiectivus. Tene TOTAM responsionem sub {max_length} characteribus. Responde SOLUM cum textu descriptivo.".to_string()
         );
 
         // Russian template
-        prompt_templates.insert(
+        image_templates.insert(
             "ru".to_string(),
-            "Создайте краткий, описательный alt-текст для этого изображения. Сосредоточьтесь на ключевых визуальных элементах, действиях и контексте, которые помогли бы пользователям с нарушениями зрения. Будьте конкретными и объективными. Завершите описание словами ' — это описание изображения было создано ИИ: {model}' где {model} - название модели ИИ. Держите ОБЩИЙ ответ (описание + атрибуция) менее 1500 символов. Отвечайте ТОЛЬКО описательным текстом вместе с атрибуцией.".to_string()
+            "Создайте краткий, описательный alt-текст для этого изображения. Сосредоточьтесь на ключевых визуальных элементах, действиях и контексте, которые помогли бы пользователям с нарушениями зрения. Будьте конкретными и объективными. Держите ОБЩИЙ ответ менее {max_length} символов. Отвечайте ТОЛЬКО описательным текстом.".to_string()
         );
 
         // Brazilian Portuguese template
-        prompt_templates.insert(
+        image_templates.insert(
             "pt-br".to_string(),
-            "Crie um texto alternativo conciso e descritivo para esta imagem. Foque em elementos visuais-chave, ações e contexto que ajudariam usuários com deficiência visual. Seja específico e objetivo. Termine sua descrição com ' — esta descrição de imagem foi criada por IA: {model}' onde {model} é o nome do modelo de IA. Mantenha a resposta TOTAL (descrição + atribuição) abaixo de 1500 caracteres. Responda APENAS com o texto descritivo incluindo a atribuição.".to_string()
+            "Crie um texto alternativo conciso e descritivo para esta imagem. Foque em elementos visuais-chave, ações e contexto que ajudariam usuários com deficiência visual. Seja específico e objetivo. Mantenha a resposta TOTAL abaixo de {max_length} caracteres. Responda APENAS com o texto descritivo.".to_string()
         );
 
         // Indonesian template
-        prompt_templates.insert(
+        image_templates.insert(
             "id".to_string(),
-            "Buat teks alt yang ringkas dan deskriptif untuk gambar ini. Fokus pada elemen visual utama, tindakan, dan konteks yang akan membantu pengguna dengan gangguan penglihatan. Jadilah spesifik dan objektif. Akhiri deskripsi Anda dengan ' — deskripsi gambar ini dibuat oleh AI: {model}' di mana {model} adalah nama model AI. Jaga TOTAL respons (deskripsi + atribusi) di bawah 1500 karakter. Jawab HANYA dengan teks deskriptif termasuk atribusi.".to_string()
+            "Buat teks alt yang ringkas dan deskriptif untuk gambar ini. Fokus pada elemen visual utama, tindakan, dan konteks yang akan membantu pengguna dengan gangguan penglihatan. Jadilah spesifik dan objektif. Jaga TOTAL respons di bawah {max_length} karakter. Jawab HANYA dengan teks deskriptif.".to_string()
         );
 
         // Chinese Simplified template
-        prompt_templates.insert(
+        image_templates.insert(
             "zh-cn".to_string(),
-            "为这张图片创建简洁、描述性的替代文本。专注于关键的视觉元素、动作和背景，这些将帮助视觉障碍用户理解内容。要具体和客观。用' — 此图片描述由AI生成：{model}'结束您的描述，其中{model}是AI模型名称。保持总回复（描述+署名）在1500字符以下。仅回复描述文本包括署名。".to_string()
+            "为这张图片创建简洁、描述性的替代文本。专注于关键的视觉元素、动作和背景，这些将帮助视觉障碍用户理解内容。要具体和客观。保持总回复在{max_length}字符以下。仅回复描述文本。".to_string()
         );
 
         // Chinese Traditional template
-        prompt_templates.insert(
+        image_templates.insert(
             "zh-tw".to_string(),
-            "為這張圖片創建簡潔、描述性的替代文字。專注於關鍵的視覺元素、動作和背景，這些將幫助視覺障礙用戶理解內容。要具體和客觀。用' — 此圖片描述由AI生成：{model}'結束您的描述，其中{model}是AI模型名稱。保持總回覆（描述+署名）在1500字符以下。僅回覆描述文字包括署名。".to_string()
+            "為這張圖片創建簡潔、描述性的替代文字。專注於關鍵的視覺元素、動作和背景，這些將幫助視覺障礙用戶理解內容。要具體和客觀。保持總回覆在{max_length}字符以下。僅回覆描述文字。".to_string()
         );
 
         // Hindi template
-        prompt_templates.insert(
+        image_templates.insert(
             "hi".to_string(),
-            "इस छवि के लिए एक संक्षिप्त, वर्णनात्मक alt-text बनाएं। मुख्य दृश्य तत्वों, क्रियाओं और संदर्भ पर ध्यान दें जो दृष्टिबाधित उपयोगकर्ताओं की मदद करेगा। विशिष्ट और वस्तुनिष्ठ रहें। अपने विवरण को ' — यह छवि विवरण AI द्वारा बनाया गया था: {model}' के साथ समाप्त करें जहाँ {model} AI मॉडल का नाम है। कुल उत्तर (विवरण + श्रेय) को 1500 वर्णों के अंतर्गत रखें। केवल विवरणात्मक पाठ के साथ श्रेय सहित उत्तर दें।".to_string()
+            "इस छवि के लिए एक संक्षिप्त, वर्णनात्मक alt-text बनाएं। मुख्य दृश्य तत्वों, क्रियाओं और संदर्भ पर ध्यान दें जो दृष्टिबाधित उपयोगकर्ताओं की मदद करेगा। विशिष्ट और वस्तुनिष्ठ रहें। कुल उत्तर को {max_length} वर्णों के अंतर्गत रखें। केवल विवरणात्मक पाठ के साथ उत्तर दें।".to_string()
         );
 
         // Swiss German template
-        prompt_templates.insert(
+        image_templates.insert(
             "gsw".to_string(),
-            "Mach en churze, beschribendi Alt-Text für das Bild. Konzentriere di uf wichtigi visuelli Element, Handlige und Kontext, wo sehbehinderte Nutzer würde hälfe. Sig spezifisch und objektiv. Beende dini Beschribig mit ' — die Bildbeschribig isch vo KI gmacht worde: {model}' wo {model} de Name vom KI-Modell isch. Halt d GSAMTI Antwort (Beschribig + Quälleaagab) under 1500 Zeiche. Antworte NUR mit em Beschribigtext inklusive Quälleaagab.".to_string()
+            "Mach en churze, beschribendi Alt-Text für das Bild. Konzentriere di uf wichtigi visuelli Element, Handlige und Kontext, wo sehbehinderte Nutzer würde hälfe. Sig spezifisch und objektiv. Halt d GSAMTI Antwort under {max_length} Zeiche. Antworte NUR mit em Beschribigtext.".to_string()
         );
 
         // Low German (Niederdeutsch) template
-        prompt_templates.insert(
+        image_templates.insert(
             "nds".to_string(),
-            "Maak en korte, beschrievende Alt-Text för dit Bild. Konzentreert ju op wichtige visuelle Elementen, Handlungen un Kontext, de blinde un sehbehinderte Lüüd helpen deit. Weest spezifisch un objektiv. Beendt jue Beschrievung mit ' — disse Bildbeschrievung is von KI maakt worrn: {model}' wo {model} de Name von't KI-Modell is. Holt de HELE Antwoort (Beschrievung + Toschrieven) ünner 1500 Teken. Antwoordt BLOTS mit den beschrievenden Text inklusive Toschrieven.".to_string()
+            "Maak en korte, beschrievende Alt-Text för dit Bild. Konzentreert ju op wichtige visuelle Elementen, Handlungen un Kontext, de blinde un sehbehinderte Lüüd helpen deit. Weest spezifisch un objektiv. Holt de HELE Antwoort ünner {max_length} Teken. Antwoordt BLOTS mit den beschrievenden Text.".to_string()
         );
 
         // Slovak template
-        prompt_templates.insert(
+        image_templates.insert(
             "sk".to_string(),
-            "Vytvorte stručný, popisný alt-text pre tento obrázok. Zamerajte sa na kľúčové vizuálne prvky, akcie a kontext, ktoré by pomohli používateľom so zrakovým postihnutím. Buďte konkrétni a objektívni. Ukončite svoj popis ' — tento popis obrázka bol vytvorený umelou inteligenciou: {model}' kde {model} je názov AI modelu. Udržte CELKOVÚ odpoveď (popis + atribúcia) pod 1500 znakmi. Odpovedzte LEN textom popisu vrátane atribúcie.".to_string()
+            "Vytvorte stručný, popisný alt-text pre tento obrázok. Zamerajte sa na kľúčové vizuálne prvky, akcie a kontext, ktoré by pomohli používateľom so zrakovým postihnutím. Buďte konkrétni a objektívni. Udržte CELKOVÚ odpoveď pod {max_length} znakmi. Odpovedzte LEN textom popisu.".to_string()
         );
 
         // Slovenian template
-        prompt_templates.insert(
+        image_templates.insert(
             "sl".to_string(),
-            "Ustvarite jedrnat, opisni alt-besedilo za to sliko. Osredotočite se na ključne vizualne elemente, dejanja in kontekst, ki bi pomagali uporabnikom z okvaro vida. Bodite specifični in objektivni. Končajte svoj opis z ' — ta opis slike je ustvarila umetna inteligenca: {model}' kjer je {model} ime AI modela. Ohranite CELOTEN odgovor (opis + atribucija) pod 1500 znaki. Odgovorite SAMO z opisnim besedilom vključno z atribucijo.".to_string()
+            "Ustvarite jedrnat, opisni alt-besedilo za to sliko. Osredotočite se na ključne vizualne elemente, dejanja in kontekst, ki bi pomagali uporabnikom z okvaro vida. Bodite specifični in objektivni. Ohranite CELOTEN odgovor pod {max_length} znaki. Odgovorite SAMO z opisnim besedilom.".to_string()
         );
 
         // Croatian template
-        prompt_templates.insert(
+        image_templates.insert(
             "hr".to_string(),
-            "Stvorite sažet, opisni alt-tekst za ovu sliku. Usredotočite se na ključne vizualne elemente, radnje i kontekst koji bi pomogli korisnicima s oštećenjem vida. Budite specifični i objektivni. Završite svoj opis s ' — ovaj opis slike je stvoren umjetnom inteligencijom: {model}' gdje je {model} naziv AI modela. Držite UKUPAN odgovor (opis + atribucija) ispod 1500 znakova. Odgovorite SAMO opisnim tekstom uključujući atribuciju.".to_string()
+            "Stvorite sažet, opisni alt-tekst za ovu sliku. Usredotočite se na ključne vizualne elemente, radnje i kontekst koji bi pomogli korisnicima s oštećenjem vida. Budite specifični i objektivni. Držite UKUPAN odgovor ispod {max_length} znakova. Odgovorite SAMO opisnim tekstom.".to_string()
         );
 
         // Bosnian template
-        prompt_templates.insert(
+        image_templates.insert(
             "bs".to_string(),
-            "Napravite sažet, opisni alt-tekst za ovu sliku. Fokusirajte se na ključne vizuelne elemente, radnje i kontekst koji bi pomogli korisnicima sa oštećenjem vida. Budite specifični i objektivni. Završite svoj opis sa ' — ovaj opis slike je napravljen umjetnom inteligencijom: {model}' gdje je {model} naziv AI modela. Držite UKUPAN odgovor (opis + atribucija) ispod 1500 znakova. Odgovorite SAMO opisnim tekstom uključujući atribuciju.".to_string()
+            "Napravite sažet, opisni alt-tekst za ovu sliku. Fokusirajte se na ključne vizuelne elemente, radnje i kontekst koji bi pomogli korisnicima sa oštećenjem vida. Budite specifični i objektivni. Držite UKUPAN odgovor ispod {max_length} znakova. Odgovorite SAMO opisnim tekstom.".to_string()
         );
 
         // Serbian template
-        prompt_templates.insert(
+        image_templates.insert(
             "sr".to_string(),
-            "Направите сажет, описни алт-текст за ову слику. Фокусирајте се на кључне визуелне елементе, радње и контекст који би помогли корисницима са оштећењем вида. Будите специфични и објективни. Завршите свој опис са ' — овај опис слике је направљен вештачком интелигенцијом: {model}' где је {model} назив АИ модела. Држите УКУПАН одговор (опис + атрибуција) испод 1500 знакова. Одговорите САМО описним текстом укључујући атрибуцију.".to_string()
+            "Направите сажет, описни алт-текст за ову слику. Фокусирајте се на кључне визуелне елементе, радње и контекст који би помогли корисницима са оштећењем вида. Будите специфични и објективни. Држите УКУПАН одговор испод {max_length} знакова. Одговорите САМО описним текстом.".to_string()
         );
 
         // Greek template
-        prompt_templates.insert(
+        image_templates.insert(
             "el".to_string(),
-            "Δημιουργήστε ένα συνοπτικό, περιγραφικό alt-κείμενο για αυτή την εικόνα. Εστιάστε σε βασικά οπτικά στοιχεία, ενέργειες και πλαίσιο που θα βοηθούσαν χρήστες με προβλήματα όρασης. Να είστε συγκεκριμένοι και αντικειμενικοί. Τελειώστε την περιγραφή σας με ' — αυτή η περιγραφή εικόνας δημιουργήθηκε από τεχνητή νοημοσύνη: {model}' όπου {model} είναι το όνομα του μοντέλου AI. Κρατήστε τη ΣΥΝΟΛΙΚΗ απάντηση (περιγραφή + απόδοση) κάτω από 1500 χαρακτήρες. Απαντήστε ΜΟΝΟ με το περιγραφικό κείμενο συμπεριλαμβανομένης της απόδοσης.".to_string()
+            "Δημιουργήστε ένα συνοπτικό, περιγραφικό alt-κείμενο για αυτή την εικόνα. Εστιάστε σε βασικά οπτικά στοιχεία, ενέργειες και πλαίσιο που θα βοηθούσαν χρήστες με προβλήματα όρασης. Να είστε συγκεκριμένοι και αντικειμενικοί. Κρατήστε τη ΣΥΝΟΛΙΚΗ απάντηση κάτω από {max_length} χαρακτήρες. Απαντήστε ΜΟΝΟ με το περιγραφικό κείμενο.".to_string()
         );
 
         // Lithuanian template
-        prompt_templates.insert(
+        image_templates.insert(
             "lt".to_string(),
-            "Sukurkite glaustą, aprašomąjį alt-tekstą šiam vaizdui. Sutelkite dėmesį į pagrindinius vizualinius elementus, veiksmus ir kontekstą, kurie padėtų naudotojams su regos sutrikimais. Būkite konkretūs ir objektyvūs. Užbaikite savo aprašymą ' — šis vaizdo aprašymas buvo sukurtas dirbtinio intelekto: {model}' kur {model} yra AI modelio pavadinimas. Išlaikykite BENDRĄ atsakymą (aprašymas + priskyrimas) žemiau 1500 simbolių. Atsakykite TIK aprašomuoju tekstu įskaitant priskyrimą.".to_string()
+            "Sukurkite glaustą, aprašomąjį alt-tekstą šiam vaizdui. Sutelkite dėmesį į pagrindinius vizualinius elementus, veiksmus ir kontekstą, kurie padėtų naudotojams su regos sutrikimais. Būkite konkretūs ir objektyvūs. Išlaikykite BENDRĄ atsakymą žemiau {max_length} simbolių. Atsakykite TIK aprašomuoju tekstu.".to_string()
         );
 
         // Estonian template
-        prompt_templates.insert(
+        image_templates.insert(
             "et".to_string(),
-            "Looge lühike, kirjeldav alt-tekst sellele pildile. Keskenduge olulistele visuaalsetele elementidele, tegevustele ja kontekstile, mis aitaksid nägemispuudega kasutajaid. Olge konkreetne ja objektiivne. Lõpetage oma kirjeldus ' — see pildikirjeldus on loodud tehisintellekti poolt: {model}' kus {model} on AI mudeli nimi. Hoidke KOGU vastus (kirjeldus + omistamine) alla 1500 märgi. Vastake AINULT kirjeldava tekstiga koos omistamisega.".to_string()
+            "Looge lühike, kirjeldav alt-tekst sellele pildile. Keskenduge olulistele visuaalsetele elementidele, tegevustele ja kontekstile, mis aitaksid nägemispuudega kasutajaid. Olge konkreetne ja objektiivne. Hoidke KOGU vastus alla {max_length} märgi. Vastake AINULT kirjeldava tekstiga.".to_string()
         );
 
         // Latvian template
-        prompt_templates.insert(
+        image_templates.insert(
             "lv".to_string(),
-            "Izveidojiet īsu, aprakstošu alt-tekstu šim attēlam. Koncentrējieties uz galvenajiem vizuālajiem elementiem, darbībām un kontekstu, kas palīdzētu lietotājiem ar redzes traucējumiem. Esiet konkrēti un objektīvi. Beidziet savu aprakstu ar ' — šis attēla apraksts ir izveidots ar mākslīgo intelektu: {model}' kur {model} ir AI modeļa nosaukums. Saglabājiet KOPĒJO atbildi (apraksts + piešķiršana) zem 1500 rakstzīmēm. Atbildiet TIKAI ar aprakstošo tekstu, ieskaitot piešķiršanu.".to_string()
+            "Izveidojiet īsu, aprakstošu alt-tekstu šim attēlam. Koncentrējieties uz galvenajiem vizuālajiem elementiem, darbībām un kontekstu, kas palīdzētu lietotājiem ar redzes traucējumiem. Esiet konkrēti un objektīvi. Saglabājiet KOPĒJO atbildi zem {max_length} rakstzīmēm. Atbildiet TIKAI ar aprakstošo tekstu.".to_string()
         );
 
         // Ukrainian template
-        prompt_templates.insert(
+        image_templates.insert(
             "uk".to_string(),
-            "Створіть стислий, описовий alt-текст для цього зображення. Зосередьтеся на ключових візуальних елементах, діях та контексті, які допомогли б користувачам з порушеннями зору. Будьте конкретними та об'єктивними. Завершіть свій опис ' — цей опис зображення було створено штучним інтелектом: {model}' де {model} - назва моделі ШІ. Тримайте ЗАГАЛЬНУ відповідь (опис + атрибуція) менше 1500 символів. Відповідайте ЛИШЕ описовим текстом разом з атрибуцією.".to_string()
+            "Створіть стислий, описовий alt-текст для цього зображення. Зосередьтеся на ключових візуальних елементах, діях та контексті, які допомогли б користувачам з порушеннями зору. Будьте конкретними та об'єктивними. Тримайте ЗАГАЛЬНУ відповідь менше {max_length} символів. Відповідайте ЛИШЕ описовим текстом.".to_string()
         );
 
         // Yiddish template
-        prompt_templates.insert(
+        image_templates.insert(
             "yi".to_string(),
-            "שאַפֿט אַ קורצן, באַשרײַבנדיקן אַלט־טעקסט פֿאַר דעם בילד. קאָנצענטרירט זיך אויף הויפּט־זעיק עלעמענטן, אַקציעס און קאָנטעקסט וואָס וואָלט געהאָלפֿן ניצער מיט זעיק־פּראָבלעמען. זײַט ספּעציפֿיש און אָביעקטיוו. ענדיקט אײַער באַשרײַבונג מיט ' — דער דאָזיקער בילד־באַשרײַבונג איז געמאַכט געוואָרן דורך קינסטלעכער אינטעליגענץ: {model}' וווּ {model} איז דער נאָמען פֿון קי־מאָדעל. האַלט די גאַנצע ענטפֿער (באַשרײַבונג + צושרײַבונג) אונטער 1500 צייכנס. ענטפֿערט נאָר מיט דעם באַשרײַבנדיקן טעקסט צוזאַמען מיט דער צושרײַבונג.".to_string()
+            "שאַפֿט אַ קורצן, באַשרײַבנדיקן אַלט־טעקסט פֿאַר דעם בילד. קאָנצענטרירט זיך אויף הויפּט־זעיק עלעמענטן, אַקציעס און קאָנטעקסט וואָס וואָלט געהאָלפֿן ניצער מיט זעיק־פּראָבלעמען. זײַט ספּעציפֿיש און אָביעקטיוו. האַלט די גאַנצע ענטפֿער אונטער {max_length} צייכנס. ענטפֿערט נאָר מיט דעם באַשרײַבנדיקן טעקסט.".to_string()
         );
 
         // Hebrew template
-        prompt_templates.insert(
+        image_templates.insert(
             "he".to_string(),
-            "צרו טקסט alt קצר ותיאורי עבור התמונה הזו. התמקדו באלמנטים חזותיים מרכזיים, פעולות והקשר שיעזרו למשתמשים עם לקויות ראייה. היו ספציפיים ואובייקטיביים. סיימו את התיאור שלכם עם ' — תיאור התמונה הזה נוצר על ידי בינה מלאכותית: {model}' כאשר {model} הוא שם מודל הבינה המלאכותית. שמרו על התשובה הכוללת (תיאור + ייחוס) מתחת ל-1500 תווים. ענו רק עם הטקסט התיאורי כולל הייחוס.".to_string()
+            "צרו טקסט alt קצר ותיאורי עבור התמונה הזו. התמקדו באלמנטים חזותיים מרכזיים, פעולות והקשר שיעזרו למשתמשים עם לקויות ראייה. היו ספציפיים ואובייקטיביים. שמרו על התשובה הכוללת מתחת ל-{max_length} תווים. ענו רק עם הטקסט התיאורי.".to_string()
         );
 
         // Finnish template
-        prompt_templates.insert(
+        image_templates.insert(
             "fi".to_string(),
-            "Luo tiivis, kuvaava alt-teksti tälle kuvalle. Keskity tärkeisiin visuaalisiin elementteihin, toimintoihin ja kontekstiin, jotka auttaisivat näkövammaisia käyttäjiä. Ole tarkka ja objektiivinen. Lopeta kuvauksesi tekstillä ' — tämän kuvan kuvauksen on luonut tekoäly: {model}' missä {model} on tekoälymallin nimi. Pidä KOKONAISVASTAUS (kuvaus + lähdemerkintä) alle 1500 merkkiä. Vastaa VAIN kuvaavalla tekstillä lähdemerkintä mukaan lukien.".to_string()
+            "Luo tiivis, kuvaava alt-teksti tälle kuvalle. Keskity tärkeisiin visuaalisiin elementteihin, toimintoihin ja kontekstiin, jotka auttaisivat näkövammaisia käyttäjiä. Ole tarkka ja objektiivinen. Pidä KOKONAISVASTAUS alle {max_length} merkkiä. Vastaa VAIN kuvaavalla tekstillä.".to_string()
         );
 
         // Irish template
-        prompt_templates.insert(
+        image_templates.insert(
             "ga".to_string(),
-            "Cruthaigh téacs alt gearr, tuairisciúil don íomhá seo. Dírigh ar eilimintí amhairc tábhachtacha, gníomhartha agus comhthéacs a chabhródh le húsáideoirí a bhfuil lagú radhairc orthu. Bí sonrach agus oibiachtúil. Críochnaigh do thuairisc le ' — rinne hintleacht shaorga an tuairisc íomhá seo: {model}' áit a bhfuil {model} ainm an tsamhail AI. Coinnigh an FREAGRA IOMLÁN (tuairisc + sannadh) faoi bhun 1500 carachtar. Freagair le téacs tuairisciúil AMHÁIN lena n-áirítear an sannadh.".to_string()
+            "Cruthaigh téacs alt gearr, tuairisciúil don íomhá seo. Dírigh ar eilimintí amhairc tábhachtacha, gníomhartha agus comhthéacs a chabhródh le húsáideoirí a bhfuil lagú radhairc orthu. Bí sonrach agus oibiachtúil. Coinnigh an FREAGRA IOMLÁN faoi bhun {max_length} carachtar. Freagair le téacs tuairisciúil AMHÁIN.".to_string()
         );
 
         // Welsh template
-        prompt_templates.insert(
+        image_templates.insert(
             "cy".to_string(),
-            "Crëwch destun alt cryno, disgrifiadol ar gyfer y ddelwedd hon. Canolbwyntiwch ar elfennau gweledol allweddol, gweithredoedd a chyd-destun a fyddai'n helpu defnyddwyr â nam ar eu golwg. Byddwch yn benodol ac yn wrthrychol. Gorffennwch eich disgrifiad gyda ' — cafodd y disgrifiad delwedd hwn ei greu gan ddeallusrwydd artiffisial: {model}' lle mae {model} yn enw'r model AI. Cadwch yr YMATEB CYFAN (disgrifiad + priodoli) o dan 1500 nod. Atebwch gyda'r testun disgrifiadol YN UNIG gan gynnwys y priodoli.".to_string()
+            "Crëwch destun alt cryno, disgrifiadol ar gyfer y ddelwedd hon. Canolbwyntiwch ar elfennau gweledol allweddol, gweithredoedd a chyd-destun a fyddai'n helpu defnyddwyr â nam ar eu golwg. Byddwch yn benodol ac yn wrthrychol. Cadwch yr YMATEB CYFAN o dan {max_length} nod. Atebwch gyda'r testun disgrifiadol YN UNIG.".to_string()
         );
 
         // Romanian template
-        prompt_templates.insert(
+        image_templates.insert(
             "ro".to_string(),
-            "Creați un text alternativ concis și descriptiv pentru această imagine. Concentrați-vă pe elementele vizuale cheie, acțiuni și context care ar ajuta utilizatorii cu deficiențe de vedere. Fiți specifici și obiectivi. Terminați descrierea cu ' — această descriere a imaginii a fost creată de inteligența artificială: {model}' unde {model} este numele modelului AI. Păstrați RĂSPUNSUL TOTAL (descriere + atribuire) sub 1500 de caractere. Răspundeți DOAR cu textul descriptiv incluzând atribuirea.".to_string()
+            "Creați un text alternativ concis și descriptiv pentru această imagine. Concentrați-vă pe elementele vizuale cheie, acțiuni și context care ar ajuta utilizatorii cu deficiențe de vedere. Fiți specifici și obiectivi. Păstrați RĂSPUNSUL TOTAL sub {max_length} de caractere. Răspundeți DOAR cu textul descriptiv.".to_string()
         );
 
         // Romansh template
-        prompt_templates.insert(
+        image_templates.insert(
             "rm".to_string(),
-            "Creai in text alternativ concis e descriptiv per questa maletg. Concentrai vus sin elements visuals impurtants, acziuns e context che gidass utilisaders cun impediments da vista. Siai specifics ed objectivs. Finiai vossa descripziun cun ' — questa descripziun da maletg è vegnida creada dad intelligenza artifiziala: {model}' nua che {model} è il num dal model AI. Tegniai la RESPOSTA TOTALA (descripziun + attribuziun) sut 1500 caracters. Respundiai SULETTAMAIN cul text descriptiv inclusiv l'attribuziun.".to_string()
+            "Creai in text alternativ concis e descriptiv per questa maletg. Concentrai vus sin elements visuals impurtants, acziuns e context che gidass utilisaders cun impediments da vista. Siai specifics ed objectivs. Tegniai la RESPOSTA TOTALA sut {max_length} caracters. Respundiai SULETTAMAIN cul text descriptiv.".to_string()
         );
 
-        Self { prompt_templates }
+        let mut prompt_templates: HashMap<(String, MediaKind), String> = image_templates
+            .into_iter()
+            .map(|(language, template)| ((language, MediaKind::Image), template))
+            .collect();
+        for (language, kind, template) in MEDIA_PROMPT_TEMPLATES {
+            prompt_templates.insert((language.to_string(), *kind), template.to_string());
+        }
+
+        Self {
+            prompt_templates,
+            default_language: DEFAULT_LANGUAGE.to_string(),
+        }
+    }
+
+    /// Create a language detector whose built-in templates are overridden or extended
+    /// by the configured `[prompts]` templates
+    pub fn with_prompt_overrides(prompts: Option<&HashMap<String, String>>) -> Self {
+        let mut detector = Self::new();
+        for (language, template) in prompts.into_iter().flatten() {
+            detector.add_prompt_template(language.trim().to_lowercase(), template.clone());
+        }
+        detector
+    }
+
+    /// Use `language` instead of English when the detected language has no prompt template
+    pub fn with_default_language(mut self, language: Option<&str>) -> Self {
+        if let Some(language) = language.map(|l| l.trim().to_lowercase()) {
+            if !language.is_empty() {
+                self.default_language = language;
+            }
+        }
+        self
     }
 
     /// Detect the language of the given text
@@ -288,16 +593,22 @@ impl LanguageDetector {
     /// For production use, consider using a proper language detection library.
     pub fn detect_language(&self, text: &str) -> Result<String, LanguageError> {
         if text.trim().is_empty() {
-            debug!("Empty text provided for language detection, defaulting to English");
-            return Ok("en".to_string());
+            debug!(
+                "Empty text provided for language detection, using default language '{}'",
+                self.default_language
+            );
+            return Ok(self.default_language.clone());
         }
 
         let text_lower = text.to_lowercase();
         let words: Vec<&str> = text_lower.split_whitespace().collect();
 
         if words.is_empty() {
-            debug!("No words found in text, defaulting to English");
-            return Ok("en".to_string());
+            debug!(
+                "No words found in text, using default language '{}'",
+                self.default_language
+            );
+            return Ok(self.default_language.clone());
         }
 
         debug!("Detecting language for text with {} words", words.len());
@@ -308,19 +619,68 @@ impl LanguageDetector {
         // Find the language with the highest score
         let detected_language = language_scores
             .iter()
+            .filter(|(_, score)| **score > 0.0)
             .max_by(|a, b| a.1.partial_cmp(b.1).unwrap_or(std::cmp::Ordering::Equal))
             .map(|(lang, score)| {
                 debug!("Detected language: {} (score: {:.2})", lang, score);
                 lang.clone()
             })
             .unwrap_or_else(|| {
-                debug!("No clear language detected, defaulting to English");
-                "en".to_string()
+                debug!(
+                    "No clear language detected, using default language '{}'",
+                    self.default_language
+                );
+                self.default_language.clone()
             });
 
         Ok(detected_language)
     }
 
+    /// Resolve the prompt language for a toot
+    ///
+    /// Prefers the author-selected `toot.language` when a prompt template exists for it,
+    /// and only falls back to content-based detection when the field is absent or unsupported.
+    pub fn resolve_language(&self, toot: &TootEvent) -> String {
+        if let Some(lang) = toot
+            .language
+            .as_deref()
+            .map(str::trim)
+            .filter(|lang| !lang.is_empty())
+        {
+            if self.is_language_supported(lang) {
+                debug!("Using toot language attribute: {}", lang);
+                return lang.to_string();
+            }
+            debug!(
+                "Toot language attribute '{}' has no prompt template, detecting from content",
+                lang
+            );
+        } else {
+            debug!("No toot language attribute found, detecting from content");
+        }
+
+        match self.detect_language(&toot.content) {
+            Ok(lang) if self.is_language_supported(&lang) => {
+                debug!("Detected language from content: {}", lang);
+                lang
+            }
+            Ok(lang) => {
+                debug!(
+                    "Detected language '{}' has no prompt template, using default language '{}'",
+                    lang, self.default_language
+                );
+                self.default_language.clone()
+            }
+            Err(e) => {
+                warn!(
+                    "Language detection failed: {}, using default language '{}'",
+                    e, self.default_language
+                );
+                self.default_language.clone()
+            }
+        }
+    }
+
     /// Calculate language scores based on common words
     fn calculate_language_scores(&self, words: &[&str]) -> HashMap<String, f64> {
         let mut scores = HashMap::new();
@@ -506,8 +866,19 @@ impl LanguageDetector {
         indicators
     }
 
-    /// Get the appropriate prompt template for the detected language
+    /// Get the appropriate image prompt template for the detected language
     pub fn get_prompt_template(&self, language: &str) -> Result<&str, LanguageError> {
+        self.get_media_prompt_template(language, MediaKind::Image)
+    }
+
+    /// Get the prompt template for a media kind in the detected language
+    ///
+    /// Falls back to the template of the same kind in the default language, then English.
+    pub fn get_media_prompt_template(
+        &self,
+        language: &str,
+        kind: MediaKind,
+    ) -> Result<&str, LanguageError> {
         // Normalize language code (handle cases like "en-US" -> "en")
         let normalized_lang = language
             .split('-')
@@ -516,52 +887,96 @@ impl LanguageDetector {
             .to_lowercase();
 
         debug!(
-            "Getting prompt template for language: {} (normalized: {})",
-            language, normalized_lang
+            "Getting {:?} prompt template for language: {} (normalized: {})",
+            kind, language, normalized_lang
         );
 
-        match self.prompt_templates.get(&normalized_lang) {
+        match self.prompt_templates.get(&(normalized_lang.clone(), kind)) {
             Some(template) => {
                 debug!("Found prompt template for language: {}", normalized_lang);
                 Ok(template.as_str())
             }
             None => {
                 warn!(
-                    "No prompt template found for language: {}, falling back to English",
-                    normalized_lang
+                    "No {:?} prompt template found for language: {}, falling back to '{}'",
+                    kind, normalized_lang, self.default_language
                 );
-                // Fall back to English template
-                self.prompt_templates.get("en").map(|s| s.as_str()).ok_or(
-                    LanguageError::PromptTemplateNotFound {
+                [self.default_language.as_str(), DEFAULT_LANGUAGE]
+                    .into_iter()
+                    .find_map(|fallback| self.prompt_templates.get(&(fallback.to_string(), kind)))
+                    .map(|s| s.as_str())
+                    .ok_or(LanguageError::PromptTemplateNotFound {
                         language: normalized_lang,
-                    },
-                )
+                    })
             }
         }
     }
 
+    /// Get the built-in attribution template for a language, falling back to English
+    pub fn get_attribution_template(&self, language: &str) -> &'static str {
+        let normalized_lang = language.to_lowercase();
+        let base_lang = normalized_lang.split('-').next().unwrap_or_default();
+
+        // Prefer regional variants like "pt-br" and "zh-tw" before the base language
+        let template = [normalized_lang.as_str(), base_lang, "en"]
+            .into_iter()
+            .find_map(|candidate| {
+                ATTRIBUTION_TEMPLATES
+                    .iter()
+                    .find(|(lang, _)| *lang == candidate)
+                    .map(|(_, template)| *template)
+            });
+        template.unwrap_or(ATTRIBUTION_TEMPLATES[0].1)
+    }
+
+    /// Get the built-in note for animation montages in a language, falling back to English
+    pub fn get_animation_note(&self, language: &str) -> &'static str {
+        let normalized_lang = language.to_lowercase();
+        let base_lang = normalized_lang.split('-').next().unwrap_or_default();
+        ANIMATION_NOTES
+            .iter()
+            .find(|(lang, _)| *lang == base_lang)
+            .map_or(ANIMATION_NOTES[0].1, |(_, note)| *note)
+    }
+
     /// Get all supported languages
     #[allow(dead_code)] // Public API method, may be used in future
     pub fn supported_languages(&self) -> Vec<&String> {
-        self.prompt_templates.keys().collect()
+        self.prompt_templates
+            .keys()
+            .filter(|(_, kind)| *kind == MediaKind::Image)
+            .map(|(language, _)| language)
+            .collect()
     }
 
-    /// Add or update a prompt template for a specific language
-    #[allow(dead_code)] // Public API method, may be used in future
+    /// Add or update the image prompt template for a specific language
     pub fn add_prompt_template(&mut self, language: String, template: String) {
-        debug!("Adding prompt template for language: {}", language);
-        self.prompt_templates.insert(language, template);
+        self.add_media_prompt_template(language, MediaKind::Image, template);
+    }
+
+    /// Add or update the prompt template for a media kind in a specific language
+    pub fn add_media_prompt_template(
+        &mut self,
+        language: String,
+        kind: MediaKind,
+        template: String,
+    ) {
+        debug!(
+            "Adding {:?} prompt template for language: {}",
+            kind, language
+        );
+        self.prompt_templates.insert((language, kind), template);
     }
 
     /// Check if a language is supported
-    #[allow(dead_code)]
     pub fn is_language_supported(&self, language: &str) -> bool {
         let normalized_lang = language
             .split('-')
             .next()
             .unwrap_or(language)
             .to_lowercase();
-        self.prompt_templates.contains_key(&normalized_lang)
+        self.prompt_templates
+            .contains_key(&(normalized_lang, MediaKind::Image))
     }
 }
 
@@ -601,6 +1016,39 @@ impl LanguageService for LanguageDetector {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::mastodon::Account;
+    use chrono::Utc;
+
+    fn create_test_toot(content: &str, language: Option<&str>) -> TootEvent {
+        TootEvent {
+            id: "123".to_string(),
+            uri: "https://example.com/statuses/123".to_string(),
+            account: Account {
+                id: "user".to_string(),
+                username: "user".to_string(),
+                acct: "user".to_string(),
+                display_name: "User".to_string(),
+                url: "https://example.com/@user".to_string(),
+            },
+            content: content.to_string(),
+            language: language.map(str::to_string),
+            media_attachments: Vec::new(),
+            created_at: Utc::now(),
+            url: None,
+            visibility: "public".to_string(),
+            in_reply_to_id: None,
+            in_reply_to_account_id: None,
+            mentions: Vec::new(),
+            sensitive: false,
+            spoiler_text: String::new(),
+            tags: Vec::new(),
+            emojis: Vec::new(),
+            poll: None,
+            filtered: Vec::new(),
+            edited_at: None,
+            is_edit: false,
+        }
+    }
 
     #[test]
     fn test_language_detector_creation() {
@@ -703,17 +1151,101 @@ mod tests {
 
         let template = detector.get_prompt_template("en").unwrap();
         assert!(template.contains("alt-text"));
-        assert!(template.contains("1500 characters"));
+        assert!(template.contains("{max_length} characters"));
         assert!(template.contains("description"));
     }
 
+    #[test]
+    fn test_prompt_templates_leave_attribution_to_processor() {
+        let detector = LanguageDetector::new();
+
+        for language in detector.supported_languages() {
+            let template = detector.get_prompt_template(language).unwrap();
+            assert!(!template.contains("{model}"), "{language}: {template}");
+            assert!(!template.contains(" — "), "{language}: {template}");
+        }
+    }
+
+    #[test]
+    fn test_get_attribution_template() {
+        let detector = LanguageDetector::new();
+
+        assert_eq!(
+            detector.get_attribution_template("en"),
+            "this image description was made by AI: {model}"
+        );
+        assert_eq!(
+            detector.get_attribution_template("de-AT"),
+            "diese Bildbeschreibung wurde von KI erstellt: {model}"
+        );
+        assert_eq!(
+            detector.get_attribution_template("zh-TW"),
+            "此圖片描述由AI生成：{model}"
+        );
+        assert_eq!(
+            detector.get_attribution_template("xx"),
+            detector.get_attribution_template("en")
+        );
+
+        // Every built-in language has a localized attribution
+        for language in detector.supported_languages() {
+            assert!(ATTRIBUTION_TEMPLATES
+                .iter()
+                .any(|(lang, _)| lang == language));
+        }
+    }
+
+    #[test]
+    fn test_get_animation_note() {
+        let detector = LanguageDetector::new();
+
+        assert!(detector.get_animation_note("en").contains("grid of frames"));
+        assert!(detector.get_animation_note("de-AT").contains("Raster"));
+        // Languages without a note fall back to English
+        assert_eq!(
+            detector.get_animation_note("ja"),
+            detector.get_animation_note("en")
+        );
+    }
+
+    #[test]
+    fn test_has_ai_attribution() {
+        assert!(has_ai_attribution(
+            "A red square — this image description was made by AI: google/gemma-3-27b-it:free",
+            None
+        ));
+        assert!(has_ai_attribution(
+            "Ein rotes Quadrat — diese Bildbeschreibung wurde von KI erstellt: some/model\n",
+            None
+        ));
+        assert!(has_ai_attribution(
+            "A red square — described by some/model for you",
+            Some("described by {model} for you")
+        ));
+
+        // Human-written descriptions
+        assert!(!has_ai_attribution("A red square", None));
+        assert!(!has_ai_attribution(
+            "A red square — drawn by me",
+            Some("described by {model} for you")
+        ));
+        assert!(!has_ai_attribution(
+            "this image description was made by AI: some/model",
+            None
+        ));
+        assert!(!has_ai_attribution(
+            "A red square — this image description was made by AI: ",
+            None
+        ));
+    }
+
     #[test]
     fn test_get_prompt_template_german() {
         let detector = LanguageDetector::new();
 
         let template = detector.get_prompt_template("de").unwrap();
         assert!(template.contains("Alt-Text"));
-        assert!(template.contains("1500 Zeichen"));
+        assert!(template.contains("{max_length} Zeichen"));
         assert!(template.contains("NUR"));
     }
 
@@ -723,7 +1255,7 @@ mod tests {
 
         let template = detector.get_prompt_template("fr").unwrap();
         assert!(template.contains("texte alternatif"));
-        assert!(template.contains("1500 caractères"));
+        assert!(template.contains("{max_length} caractères"));
         assert!(template.contains("SEULEMENT"));
     }
 
@@ -734,7 +1266,7 @@ mod tests {
         // Unsupported language should fall back to English
         let template = detector.get_prompt_template("xyz").unwrap();
         assert!(template.contains("alt-text"));
-        assert!(template.contains("1500 characters"));
+        assert!(template.contains("{max_length} characters"));
     }
 
     #[test]
@@ -779,6 +1311,60 @@ mod tests {
         assert_eq!(template, custom_template);
     }
 
+    #[test]
+    fn test_get_media_prompt_template_by_kind() {
+        let detector = LanguageDetector::new();
+
+        let image = detector
+            .get_media_prompt_template("de", MediaKind::Image)
+            .unwrap();
+        let audio = detector
+            .get_media_prompt_template("de", MediaKind::Audio)
+            .unwrap();
+        let video = detector
+            .get_media_prompt_template("de-AT", MediaKind::Video)
+            .unwrap();
+
+        assert_eq!(image, detector.get_prompt_template("de").unwrap());
+        assert!(image.contains("Bild"));
+        assert!(audio.contains("Audio-Transkript"));
+        assert!(video.contains("Videoclip"));
+        for template in [audio, video] {
+            assert!(template.contains("{max_length}"));
+            assert!(unknown_placeholders(template, PROMPT_PLACEHOLDERS).is_empty());
+        }
+    }
+
+    #[test]
+    fn test_get_media_prompt_template_falls_back_to_english_of_same_kind() {
+        let mut detector = LanguageDetector::new();
+        detector.add_media_prompt_template(
+            "ja".to_string(),
+            MediaKind::Video,
+            "Custom video template".to_string(),
+        );
+
+        assert_eq!(
+            detector
+                .get_media_prompt_template("ja", MediaKind::Video)
+                .unwrap(),
+            "Custom video template"
+        );
+        assert_eq!(
+            detector
+                .get_media_prompt_template("ja", MediaKind::Audio)
+                .unwrap(),
+            detector
+                .get_media_prompt_template("en", MediaKind::Audio)
+                .unwrap()
+        );
+        // Adding a video template doesn't change the image template
+        assert_ne!(
+            detector.get_prompt_template("ja").unwrap(),
+            "Custom video template"
+        );
+    }
+
     #[test]
     fn test_language_service_trait() {
         let detector = LanguageDetector::new();
@@ -893,4 +1479,142 @@ mod tests {
         let result2 = detector.detect_language(mixed_case_text).unwrap();
         assert_eq!(result2, "de");
     }
+
+    #[test]
+    fn test_resolve_language_prefers_supported_toot_language() {
+        let detector = LanguageDetector::new();
+
+        // Media-only toot with English-looking content but German language attribute
+        let toot = create_test_toot("the", Some("de"));
+        assert_eq!(detector.resolve_language(&toot), "de");
+
+        // Regional variants map to the base template
+        let toot = create_test_toot("", Some("de-AT"));
+        assert_eq!(detector.resolve_language(&toot), "de-AT");
+        assert!(detector
+            .get_prompt_template(&detector.resolve_language(&toot))
+            .unwrap()
+            .contains("Alt-Text"));
+    }
+
+    #[test]
+    fn test_resolve_language_unsupported_toot_language_falls_back_to_detection() {
+        let detector = LanguageDetector::new();
+
+        let toot = create_test_toot("Das ist ein schönes Bild und ich mag es sehr", Some("xx"));
+        assert_eq!(detector.resolve_language(&toot), "de");
+    }
+
+    #[test]
+    fn test_resolve_language_absent_toot_language_uses_detection() {
+        let detector = LanguageDetector::new();
+
+        let toot = create_test_toot("C'est une très belle photo et je l'aime", None);
+        assert_eq!(detector.resolve_language(&toot), "fr");
+
+        let toot = create_test_toot("", Some("  "));
+        assert_eq!(detector.resolve_language(&toot), "en");
+    }
+
+    #[test]
+    fn test_configured_default_language_used_for_unknowns() {
+        let detector = LanguageDetector::new().with_default_language(Some("DE"));
+        assert_eq!(detector.default_language, "de");
+
+        // No template for the toot language and nothing to detect from
+        let toot = create_test_toot("", Some("xx"));
+        assert_eq!(detector.resolve_language(&toot), "de");
+
+        // Content without any known words
+        let toot = create_test_toot("🙂 📷 #fediphoto", None);
+        assert_eq!(detector.resolve_language(&toot), "de");
+
+        // Detected languages with a template still win
+        let toot = create_test_toot("C'est une très belle photo et je l'aime", None);
+        assert_eq!(detector.resolve_language(&toot), "fr");
+    }
+
+    #[test]
+    fn test_prompt_template_falls_back_to_configured_default_language() {
+        let detector = LanguageDetector::new().with_default_language(Some("de"));
+
+        assert_eq!(
+            detector.get_prompt_template("xx").unwrap(),
+            detector.get_prompt_template("de").unwrap()
+        );
+        assert_eq!(
+            detector
+                .get_media_prompt_template("xx", MediaKind::Video)
+                .unwrap(),
+            detector
+                .get_media_prompt_template("de", MediaKind::Video)
+                .unwrap()
+        );
+
+        // A default without a template of its own falls back to English
+        let detector = LanguageDetector::new().with_default_language(Some("eo"));
+        assert_eq!(
+            detector.get_prompt_template("xx").unwrap(),
+            detector.get_prompt_template("en").unwrap()
+        );
+
+        // Blank values keep English
+        let detector = LanguageDetector::new().with_default_language(Some("  "));
+        assert_eq!(detector.default_language, "en");
+    }
+
+    #[test]
+    fn test_prompt_overrides_replace_builtin_template() {
+        let mut prompts = HashMap::new();
+        prompts.insert(
+            "EN".to_string(),
+            "Describe this image in one short sentence. Model: {model}".to_string(),
+        );
+        prompts.insert("eo".to_string(), "Priskribu ĉi tiun bildon.".to_string());
+
+        let detector = LanguageDetector::with_prompt_overrides(Some(&prompts));
+
+        assert_eq!(
+            detector.get_prompt_template("en").unwrap(),
+            "Describe this image in one short sentence. Model: {model}"
+        );
+        assert_eq!(
+            detector.get_prompt_template("eo").unwrap(),
+            "Priskribu ĉi tiun bildon."
+        );
+        // Built-in templates that are not overridden stay untouched
+        assert!(detector
+            .get_prompt_template("de")
+            .unwrap()
+            .contains("Alt-Text"));
+    }
+
+    #[test]
+    fn test_prompt_overrides_none_keeps_builtin_templates() {
+        let detector = LanguageDetector::with_prompt_overrides(None);
+        let builtin = LanguageDetector::new();
+
+        assert_eq!(
+            detector.get_prompt_template("en").unwrap(),
+            builtin.get_prompt_template("en").unwrap()
+        );
+    }
+
+    #[test]
+    fn test_unknown_placeholders() {
+        assert!(unknown_placeholders("Made by {model}", PROMPT_PLACEHOLDERS).is_empty());
+        assert!(unknown_placeholders("No placeholders at all", PROMPT_PLACEHOLDERS).is_empty());
+        assert_eq!(
+            unknown_placeholders("By {model} for {user} in {lang}", PROMPT_PLACEHOLDERS),
+            vec!["{user}".to_string(), "{lang}".to_string()]
+        );
+        assert!(unknown_placeholders("Unclosed {model", PROMPT_PLACEHOLDERS).is_empty());
+
+        // The length limit only makes sense in prompts
+        assert!(unknown_placeholders("Under {max_length} chars", PROMPT_PLACEHOLDERS).is_empty());
+        assert_eq!(
+            unknown_placeholders("By {model}, max {max_length}", ATTRIBUTION_PLACEHOLDERS),
+            vec!["{max_length}".to_string()]
+        );
+    }
 }
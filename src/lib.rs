@@ -2,9 +2,18 @@ pub mod backfill;
 pub mod balance;
 pub mod config;
 pub mod error;
+pub mod events;
+pub mod health;
 pub mod language;
 pub mod mastodon;
 pub mod media;
+pub mod notifications;
 pub mod openrouter;
+pub mod redact;
+pub mod systemd;
+pub mod telemetry;
 pub mod toot_handler;
 pub mod whisper_cli;
+
+#[cfg(test)]
+pub(crate) mod test_support;
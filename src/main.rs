@@ -1,16 +1,28 @@
 use clap::Parser;
 use std::path::PathBuf;
+use std::sync::Arc;
 use tracing::{debug, error, info, warn, Level};
-use tracing_subscriber::{self, EnvFilter};
+use tracing_subscriber::filter::Targets;
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::{self, EnvFilter, Layer, Registry};
 
 mod backfill;
 mod balance;
 mod config;
 mod error;
+mod events;
+mod health;
 mod language;
 mod mastodon;
 mod media;
+mod notifications;
 mod openrouter;
+mod redact;
+mod systemd;
+mod telemetry;
+#[cfg(test)]
+mod test_support;
 mod toot_handler;
 mod whisper_cli;
 
@@ -32,9 +44,57 @@ struct Cli {
     #[arg(long, value_name = "LEVEL")]
     log_level: Option<String>,
 
+    /// Set log output format (text, json)
+    #[arg(long, value_name = "FORMAT")]
+    log_format: Option<String>,
+
     /// Enable verbose logging (equivalent to --log-level debug)
     #[arg(short, long)]
     verbose: bool,
+
+    /// Generate descriptions but only log them instead of editing toots (can also be set via ALTERNATOR_DRY_RUN env var)
+    #[arg(long)]
+    dry_run: bool,
+
+    /// Process a single toot by ID and exit instead of listening to the stream
+    #[arg(long, value_name = "ID")]
+    toot_id: Option<String>,
+
+    /// Validate the configuration, print a report and exit without connecting to any service
+    #[arg(long)]
+    validate_config: bool,
+
+    /// With --validate-config, also verify Mastodon and OpenRouter connectivity
+    #[arg(long, requires = "validate_config")]
+    check_connectivity: bool,
+
+    /// List available OpenRouter models with pricing, flag the configured ones and exit
+    #[arg(long, conflicts_with = "validate_config")]
+    list_models: bool,
+
+    /// Transcribe a short generated test clip with FFmpeg and Whisper, print the transcript and exit
+    #[arg(long, conflicts_with_all = ["validate_config", "list_models"])]
+    check_whisper: bool,
+
+    /// Re-run the toots recorded in mastodon.failed_toots_file, remove those that succeed and exit
+    #[arg(long, conflicts_with_all = ["toot_id", "validate_config", "list_models", "check_whisper"])]
+    reprocess_failed: bool,
+
+    /// Upload and delete a tiny test image to confirm the access token may write media, then exit
+    #[arg(long, conflicts_with_all = ["validate_config", "list_models", "check_whisper", "reprocess_failed"])]
+    self_test: bool,
+
+    /// Print the effective configuration (file, environment overrides and defaults) with
+    /// secrets redacted, as "toml" (default) or "json", and exit
+    #[arg(
+        long,
+        value_name = "FORMAT",
+        num_args = 0..=1,
+        default_missing_value = "toml",
+        value_parser = ["toml", "json"],
+        conflicts_with_all = ["validate_config", "list_models", "check_whisper", "reprocess_failed", "self_test"]
+    )]
+    print_config: Option<String>,
 }
 
 impl Cli {
@@ -44,19 +104,65 @@ impl Cli {
             .clone()
             .or_else(|| std::env::var("ALTERNATOR_CONFIG").ok().map(PathBuf::from))
     }
+
+    /// Check dry-run mode from CLI flag or ALTERNATOR_DRY_RUN environment variable
+    fn dry_run(&self) -> bool {
+        self.dry_run
+            || std::env::var("ALTERNATOR_DRY_RUN")
+                .map(|value| matches!(value.to_lowercase().as_str(), "1" | "true" | "yes"))
+                .unwrap_or(false)
+    }
 }
 
 /// Initialize structured logging with proper error handling
 #[allow(clippy::result_large_err)] // AlternatorError is large but needed for comprehensive error handling
 fn init_logging(config: &RuntimeConfig, cli: &Cli) -> Result<(), AlternatorError> {
-    // Determine log level from CLI args, config, or environment
-    let log_level = if cli.verbose {
+    build_subscriber(config, cli)?.init();
+    redact::set_redact_urls(config.config().logging().redact_urls.unwrap_or(false));
+
+    debug!("Logging initialized with level: {}", log_level(config, cli));
+    Ok(())
+}
+
+/// Determine log level from CLI args, config, or environment
+fn log_level<'a>(config: &'a RuntimeConfig, cli: &'a Cli) -> &'a str {
+    if cli.verbose {
         "debug"
     } else if let Some(ref level) = cli.log_level {
         level.as_str()
     } else {
         config.config().logging().level.as_deref().unwrap_or("info")
-    };
+    }
+}
+
+/// Build the subscriber for the log output and, when `[tracing]` is configured, span export
+#[allow(clippy::result_large_err)]
+fn build_subscriber(
+    config: &RuntimeConfig,
+    cli: &Cli,
+) -> Result<Box<dyn tracing::Subscriber + Send + Sync>, AlternatorError> {
+    let log_layer = log_layer(config, cli)?;
+
+    // Span export has its own filter so the log level doesn't decide which spans are exported
+    let otlp_layer =
+        telemetry::OtlpLayer::from_config(config.config().tracing.as_ref()).map(|layer| {
+            layer.with_filter(Targets::new().with_target(env!("CARGO_CRATE_NAME"), Level::INFO))
+        });
+
+    Ok(Box::new(
+        tracing_subscriber::registry()
+            .with(log_layer)
+            .with(otlp_layer),
+    ))
+}
+
+/// Build the log output for the configured level and output format
+#[allow(clippy::result_large_err)]
+fn log_layer(
+    config: &RuntimeConfig,
+    cli: &Cli,
+) -> Result<Box<dyn Layer<Registry> + Send + Sync>, AlternatorError> {
+    let log_level = log_level(config, cli);
 
     // Validate log level
     let _level = match log_level.to_lowercase().as_str() {
@@ -72,27 +178,406 @@ fn init_logging(config: &RuntimeConfig, cli: &Cli) -> Result<(), AlternatorError
         }
     };
 
+    // Determine log format from CLI args or config
+    let log_format = cli
+        .log_format
+        .as_deref()
+        .or(config.config().logging().format.as_deref())
+        .unwrap_or("text");
+
     // Create environment filter with fallback
     let env_filter = EnvFilter::try_from_default_env()
         .or_else(|_| EnvFilter::try_new(log_level))
         .map_err(|e| AlternatorError::InvalidData(format!("Failed to create log filter: {e}")))?;
 
-    // Initialize structured logging with timestamps and target information
-    tracing_subscriber::fmt()
-        .with_env_filter(env_filter)
+    // Structured logging with timestamps and target information
+    let builder = tracing_subscriber::fmt::layer()
         .with_target(true)
         .with_thread_ids(true)
         .with_file(true)
         .with_line_number(true)
-        .with_level(true)
-        .init();
+        .with_level(true);
 
-    debug!("Logging initialized with level: {}", log_level);
-    Ok(())
+    let layer = match log_format.to_lowercase().as_str() {
+        "text" => builder.boxed(),
+        "json" => builder.json().boxed(),
+        _ => {
+            return Err(AlternatorError::InvalidData(format!(
+                "Invalid log format: {log_format}. Valid formats are: text, json"
+            )))
+        }
+    };
+    Ok(layer.with_filter(env_filter).boxed())
+}
+
+/// Human-readable result of `--validate-config`
+#[derive(Debug, Default)]
+struct ValidationReport {
+    lines: Vec<String>,
+    failures: usize,
+}
+
+impl ValidationReport {
+    fn pass(&mut self, message: impl Into<String>) {
+        self.lines.push(format!("✓ {}", message.into()));
+    }
+
+    fn warn(&mut self, message: impl Into<String>) {
+        self.lines.push(format!("⚠ {}", message.into()));
+    }
+
+    fn fail(&mut self, message: impl Into<String>) {
+        self.lines.push(format!("✗ {}", message.into()));
+        self.failures += 1;
+    }
+
+    fn is_valid(&self) -> bool {
+        self.failures == 0
+    }
+}
+
+/// Check configuration and local tooling without touching the network
+fn validate_config(cli: &Cli) -> (ValidationReport, Option<RuntimeConfig>) {
+    let mut report = ValidationReport::default();
+
+    let config = match Config::load(cli.config_path()) {
+        Ok(config) => {
+            report.pass("Configuration parsed and required fields present");
+            RuntimeConfig::new(config).with_dry_run(cli.dry_run())
+        }
+        Err(e) => {
+            report.fail(format!("Configuration invalid: {e}"));
+            return (report, None);
+        }
+    };
+
+    // Only the log output is checked, so no span exporter is started
+    match log_layer(&config, cli) {
+        Ok(_) => report.pass(format!("Log level '{}' is valid", log_level(&config, cli))),
+        Err(e) => report.fail(e.to_string()),
+    }
+
+    let whisper_config = config.config().whisper();
+    let whisper_enabled = whisper_config.enabled.unwrap_or(false);
+
+    let ffmpeg = crate::media::audio::ffmpeg_path().display();
+    if crate::media::is_ffmpeg_available() {
+        report.pass(format!("FFmpeg found ({ffmpeg})"));
+    } else if whisper_enabled {
+        report.warn(format!(
+            "FFmpeg not found ({ffmpeg}) - audio and video transcription will be disabled"
+        ));
+    } else {
+        report.warn(format!(
+            "FFmpeg not found ({ffmpeg}) - only needed for audio and video"
+        ));
+    }
+
+    if whisper_enabled {
+        let python = whisper_config
+            .python_executable
+            .as_deref()
+            .unwrap_or("python3");
+        let whisper_available = std::process::Command::new(python)
+            .args(["-c", "import whisper"])
+            .output()
+            .map(|output| output.status.success())
+            .unwrap_or(false);
+
+        if whisper_available {
+            report.pass(format!("Whisper importable with {python}"));
+        } else {
+            report.warn(format!(
+                "Whisper not importable with {python} - audio transcription will fail"
+            ));
+        }
+    }
+
+    if config.config().ocr().enabled.unwrap_or(false) {
+        if crate::media::is_tesseract_available() {
+            report.pass("Tesseract found in PATH");
+        } else {
+            report.warn("Tesseract not found in PATH - image OCR will be skipped");
+        }
+    }
+
+    (report, Some(config))
+}
+
+/// Run `--validate-config`, print the report and return whether everything passed
+async fn run_config_validation(cli: &Cli) -> bool {
+    let (mut report, config) = validate_config(cli);
+
+    if let (true, Some(config)) = (cli.check_connectivity, config.as_ref()) {
+        let mut mastodon_client =
+            crate::mastodon::MastodonClient::new(config.config().mastodon.clone());
+        let openrouter_client =
+            crate::openrouter::OpenRouterClient::new(config.config().openrouter.clone());
+
+        match startup_validation(&mut mastodon_client, &openrouter_client).await {
+            Ok(()) => report.pass("Mastodon and OpenRouter connectivity verified"),
+            Err(e) => report.fail(format!("Connectivity check failed: {e}")),
+        }
+    }
+
+    for line in &report.lines {
+        println!("{line}");
+    }
+
+    if report.is_valid() {
+        println!("Configuration is valid");
+    } else {
+        println!("Configuration has {} error(s)", report.failures);
+    }
+
+    report.is_valid()
+}
+
+/// Run `--list-models`, print the model table and return whether the listing succeeded
+async fn run_list_models(cli: &Cli) -> bool {
+    let config = match Config::load(cli.config_path()) {
+        Ok(config) => config,
+        Err(e) => {
+            eprintln!("Configuration invalid: {e}");
+            return false;
+        }
+    };
+
+    let openrouter_client = crate::openrouter::OpenRouterClient::new(config.openrouter.clone());
+    match model_listing(&openrouter_client, &config.openrouter).await {
+        Ok(listing) => {
+            print!("{listing}");
+            true
+        }
+        Err(e) => {
+            eprintln!("Failed to list OpenRouter models: {e}");
+            false
+        }
+    }
+}
+
+/// Length of the generated clip transcribed by `--check-whisper`, in seconds
+const WHISPER_CHECK_CLIP_SECS: u32 = 2;
+
+/// Run `--check-whisper`, print the transcript of a test clip and return whether it worked
+async fn run_whisper_check(cli: &Cli) -> bool {
+    let config = match Config::load(cli.config_path()) {
+        Ok(config) => RuntimeConfig::new(config),
+        Err(e) => {
+            eprintln!("Configuration invalid: {e}");
+            return false;
+        }
+    };
+
+    let whisper_config = config.config().whisper();
+    if !whisper_config.enabled.unwrap_or(false) {
+        println!("Whisper is disabled in the configuration, checking the setup anyway");
+    }
+    println!(
+        "Transcribing a {WHISPER_CHECK_CLIP_SECS}s test clip with FFmpeg ({}) and Whisper model '{}'",
+        crate::media::audio::ffmpeg_path().display(),
+        whisper_config.model.as_deref().unwrap_or("base")
+    );
+
+    let started = std::time::Instant::now();
+    // The test clip is short, so a configured minimum duration must not reject it
+    let whisper_config = &crate::config::WhisperConfig {
+        min_audio_duration_secs: None,
+        ..whisper_config.clone()
+    };
+    match whisper_check(|clip| async move {
+        let summary = crate::media::audio::TranscriptSummary::default();
+        crate::media::audio::transcribe_audio_data(&clip, whisper_config, &summary).await
+    })
+    .await
+    {
+        Ok(transcript) => {
+            println!(
+                "✓ Transcription succeeded in {:.1}s: {:?}",
+                started.elapsed().as_secs_f64(),
+                transcript
+            );
+            println!(
+                "The test clip is a plain tone, so an empty or nonsensical transcript is expected"
+            );
+            true
+        }
+        Err(e) => {
+            println!("✗ Transcription failed: {e}");
+            false
+        }
+    }
+}
+
+/// Run a generated test clip through `transcribe`, the audio transcription path
+async fn whisper_check<F, Fut>(transcribe: F) -> Result<String, crate::error::MediaError>
+where
+    F: FnOnce(Vec<u8>) -> Fut,
+    Fut: std::future::Future<Output = Result<String, crate::error::MediaError>>,
+{
+    transcribe(crate::media::audio::test_tone_wav(WHISPER_CHECK_CLIP_SECS)).await
+}
+
+/// Print the effective configuration with secrets redacted
+fn run_print_config(cli: &Cli, format: &str) -> bool {
+    let config = match Config::load(cli.config_path()) {
+        Ok(config) => config,
+        Err(e) => {
+            eprintln!("Configuration invalid: {e}");
+            return false;
+        }
+    };
+
+    let output = match format {
+        "json" => config.to_redacted_json(),
+        _ => config.to_redacted_toml(),
+    };
+    match output {
+        Ok(output) => {
+            println!("{output}");
+            true
+        }
+        Err(e) => {
+            eprintln!("{e}");
+            false
+        }
+    }
+}
+
+/// Run the edit permission self-test against the configured Mastodon account and print the result
+async fn run_self_test(cli: &Cli) -> bool {
+    let config = match Config::load(cli.config_path()) {
+        Ok(config) => config,
+        Err(e) => {
+            eprintln!("Configuration invalid: {e}");
+            return false;
+        }
+    };
+
+    use crate::mastodon::MastodonStream;
+    let mut client = crate::mastodon::MastodonClient::new(config.mastodon);
+    match client.verify_credentials().await {
+        Ok(account) => println!("✓ Authenticated as @{}", account.acct),
+        Err(e) => {
+            println!("✗ Mastodon credentials rejected: {e}");
+            return false;
+        }
+    }
+
+    match edit_permission_self_test(&client).await {
+        Ok(media_id) => {
+            println!("✓ Uploaded and deleted test media {media_id}: the token can write media");
+            true
+        }
+        Err(e) => {
+            println!("✗ {e}");
+            false
+        }
+    }
+}
+
+/// Upload a 1x1 image and delete it again, confirming the `write:media` scope that editing
+/// descriptions needs; returns the ID of the deleted test media
+async fn edit_permission_self_test(
+    client: &crate::mastodon::MastodonClient,
+) -> Result<String, String> {
+    use crate::mastodon::MastodonStream;
+
+    let mut png = Vec::new();
+    image::DynamicImage::ImageRgb8(image::RgbImage::new(1, 1))
+        .write_to(&mut std::io::Cursor::new(&mut png), image::ImageFormat::Png)
+        .map_err(|e| format!("Failed to create test image: {e}"))?;
+
+    let media_id = client
+        .create_media_attachment(png, "Alternator self-test", "self-test.png", "image/png")
+        .await
+        .map_err(|e| {
+            format!(
+                "Test media upload failed: {e}. The access token needs the write:media and write:statuses scopes"
+            )
+        })?;
+
+    client
+        .delete_media_attachment(&media_id)
+        .await
+        .map_err(|e| format!("Uploaded test media {media_id} but could not delete it: {e}"))?;
+
+    Ok(media_id)
+}
+
+/// Table of available models followed by the availability of each configured model
+async fn model_listing(
+    client: &impl crate::openrouter::OpenRouterApi,
+    config: &crate::config::OpenRouterConfig,
+) -> Result<String, crate::error::OpenRouterError> {
+    use std::fmt::Write;
+
+    let models = client.list_models().await?;
+    let id_width = models.iter().map(|m| m.id.len()).max().unwrap_or(0).max(2);
+    let name_width = models
+        .iter()
+        .map(|m| m.name.len())
+        .max()
+        .unwrap_or(0)
+        .max(4);
+
+    let mut listing = String::new();
+    let _ = writeln!(
+        listing,
+        "{:id_width$}  {:name_width$}  {:>9}  {:>10}  {:>10}",
+        "ID", "NAME", "CONTEXT", "PROMPT/M", "OUTPUT/M"
+    );
+    for model in &models {
+        let (prompt, completion) = match &model.pricing {
+            Some(pricing) => (
+                format_price_per_million(&pricing.prompt),
+                format_price_per_million(&pricing.completion),
+            ),
+            None => ("-".to_string(), "-".to_string()),
+        };
+        let context = model
+            .context_length
+            .map_or_else(|| "-".to_string(), |length| length.to_string());
+        let _ = writeln!(
+            listing,
+            "{:id_width$}  {:name_width$}  {:>9}  {:>10}  {:>10}",
+            model.id, model.name, context, prompt, completion
+        );
+    }
+
+    let _ = writeln!(listing, "\n{} models available\n", models.len());
+    for (key, model) in [
+        ("model", &config.model),
+        ("vision_model", &config.vision_model),
+        ("vision_fallback_model", &config.vision_fallback_model),
+        ("text_model", &config.text_model),
+        ("text_fallback_model", &config.text_fallback_model),
+    ] {
+        let (mark, status) = if models.iter().any(|m| m.id == *model) {
+            ("✓", "available")
+        } else {
+            ("✗", "NOT available")
+        };
+        let _ = writeln!(listing, "{mark} {key} {model}: {status}");
+    }
+
+    Ok(listing)
 }
 
-/// Handle application errors with appropriate logging and recovery
-async fn handle_error(error: AlternatorError) -> Result<(), AlternatorError> {
+/// OpenRouter per-token USD price as dollars per million tokens
+fn format_price_per_million(per_token: &str) -> String {
+    match per_token.parse::<f64>() {
+        Ok(0.0) => "free".to_string(),
+        Ok(price) if price > 0.0 => format!("${:.2}", price * 1_000_000.0),
+        _ => "-".to_string(),
+    }
+}
+
+/// Handle application errors with appropriate logging, notification and recovery
+async fn handle_error(
+    error: AlternatorError,
+    notifier: Option<&crate::notifications::Notifier>,
+) -> Result<(), AlternatorError> {
     match &error {
         // Log different error types at appropriate levels
         AlternatorError::Config(_) => {
@@ -137,6 +622,15 @@ async fn handle_error(error: AlternatorError) -> Result<(), AlternatorError> {
         }
     }
 
+    if let Some(notifier) = notifier {
+        let event = crate::notifications::NotificationEvent::ProcessingError {
+            error: error.to_string(),
+        };
+        if let Err(e) = notifier.notify(&event).await {
+            warn!("Failed to send error notification: {}", e);
+        }
+    }
+
     // Determine if we should shutdown
     if ErrorRecovery::should_shutdown(&error) {
         error!("Fatal error encountered, shutting down application");
@@ -151,6 +645,31 @@ async fn handle_error(error: AlternatorError) -> Result<(), AlternatorError> {
 async fn main() -> Result<(), AlternatorError> {
     let cli = Cli::parse();
 
+    if let Some(format) = cli.print_config.as_deref() {
+        let printed = run_print_config(&cli, format);
+        std::process::exit(if printed { 0 } else { 1 });
+    }
+
+    if cli.validate_config {
+        let valid = run_config_validation(&cli).await;
+        std::process::exit(if valid { 0 } else { 1 });
+    }
+
+    if cli.list_models {
+        let listed = run_list_models(&cli).await;
+        std::process::exit(if listed { 0 } else { 1 });
+    }
+
+    if cli.check_whisper {
+        let transcribed = run_whisper_check(&cli).await;
+        std::process::exit(if transcribed { 0 } else { 1 });
+    }
+
+    if cli.self_test {
+        let passed = run_self_test(&cli).await;
+        std::process::exit(if passed { 0 } else { 1 });
+    }
+
     // Load configuration first
     let base_config = match Config::load(cli.config_path()) {
         Ok(config) => config,
@@ -158,13 +677,13 @@ async fn main() -> Result<(), AlternatorError> {
             // Initialize basic logging for configuration errors
             tracing_subscriber::fmt().init();
             let error = AlternatorError::Config(e);
-            handle_error(error).await?;
+            handle_error(error, None).await?;
             return Err(AlternatorError::Shutdown);
         }
     };
 
     // Create runtime config with FFmpeg/audio availability check
-    let config = RuntimeConfig::new(base_config);
+    let config = RuntimeConfig::new(base_config).with_dry_run(cli.dry_run());
 
     // Initialize structured logging
     if let Err(e) = init_logging(&config, &cli) {
@@ -195,24 +714,48 @@ async fn main() -> Result<(), AlternatorError> {
         }
     );
 
+    if config.is_dry_run() {
+        warn!("Dry-run mode: descriptions will be generated and logged, toots will NOT be edited");
+    }
+
     // Log audio/FFmpeg status
     if config.is_audio_enabled() {
         info!("✓ Audio transcription: enabled (FFmpeg available)");
     } else if crate::media::is_ffmpeg_available() {
         info!("Audio transcription: disabled (Whisper disabled in config)");
     } else {
-        warn!("Audio transcription: disabled (FFmpeg not found in PATH)");
+        warn!(
+            "Audio transcription: disabled (FFmpeg not found: {})",
+            crate::media::audio::ffmpeg_path().display()
+        );
         info!("To enable audio transcription, install FFmpeg and enable Whisper in config");
     }
 
+    // Log OCR status
+    if config.is_ocr_enabled() {
+        info!("✓ Image OCR: enabled (tesseract available)");
+    } else if config.config().ocr().enabled.unwrap_or(false) {
+        warn!("Image OCR: disabled (tesseract not found in PATH)");
+    }
+
+    let notifier =
+        crate::notifications::Notifier::from_config(config.config().notifications.as_ref());
+
     // Initialize and start main application loop
-    match run_application(config).await {
+    match run_application(
+        config,
+        cli.toot_id.as_deref(),
+        cli.reprocess_failed,
+        &notifier,
+    )
+    .await
+    {
         Ok(()) => {
             info!("Application shutdown complete");
             Ok(())
         }
         Err(e) => {
-            handle_error(e).await?;
+            handle_error(e, Some(&notifier)).await?;
             Err(AlternatorError::Shutdown)
         }
     }
@@ -221,6 +764,7 @@ async fn main() -> Result<(), AlternatorError> {
 /// Initialize all application components with proper configuration
 async fn initialize_components(
     config: &RuntimeConfig,
+    notifier: &crate::notifications::Notifier,
 ) -> Result<(ApplicationComponents, crate::balance::BalanceMonitor), AlternatorError> {
     info!("Initializing application components");
 
@@ -242,14 +786,62 @@ async fn initialize_components(
                 .as_ref()
                 .map(|formats| formats.iter().cloned().collect())
                 .unwrap_or_else(|| crate::media::MediaConfig::default().supported_formats),
+            max_download_size_mb: config
+                .config()
+                .media()
+                .max_download_size_mb
+                .unwrap_or(crate::media::DEFAULT_MAX_DOWNLOAD_SIZE_MB),
+            download_retries: config
+                .config()
+                .media()
+                .download_retries
+                .unwrap_or(crate::media::DEFAULT_DOWNLOAD_RETRIES),
+            proxy_url: config.config().media().proxy_url.clone(),
+            host_policy: crate::media::hosts::MediaHostPolicy::from_config(config.config()),
+            analysis_budget_mb: config
+                .config()
+                .media()
+                .analysis_budget_mb
+                .unwrap_or(crate::media::image::DEFAULT_ANALYSIS_BUDGET_MB),
+            analysis_output_format: config
+                .config()
+                .media()
+                .analysis_output_format
+                .as_deref()
+                .and_then(crate::media::image::analysis_output_format)
+                .unwrap_or(crate::media::image::ImageFormat::Jpeg),
+            min_image_dimension: config
+                .config()
+                .media()
+                .min_image_dimension
+                .unwrap_or(crate::media::DEFAULT_MIN_IMAGE_DIMENSION),
+            reprocess_existing_descriptions: config
+                .config()
+                .media()
+                .reprocess_existing_descriptions
+                .unwrap_or(false),
+            attribution_template: config
+                .config()
+                .attribution
+                .as_ref()
+                .and_then(|attribution| attribution.template.clone()),
         });
 
     // Initialize supporting components
-    let language_detector = crate::language::LanguageDetector::new();
+    let language_detector =
+        crate::language::LanguageDetector::with_prompt_overrides(config.config().prompts.as_ref())
+            .with_default_language(
+                config
+                    .config()
+                    .language
+                    .as_ref()
+                    .and_then(|language| language.default_language.as_deref()),
+            );
     let balance_monitor = crate::balance::BalanceMonitor::new(
         config.config().balance().clone(),
-        crate::openrouter::OpenRouterClient::new(config.config().openrouter.clone()),
-    );
+        openrouter_client.clone(),
+    )
+    .with_notifier(notifier.clone());
 
     // Perform startup validation
     info!("Performing startup validation");
@@ -261,30 +853,20 @@ async fn initialize_components(
         check_whisper_model(config).await?;
     }
 
+    // The backfill shares the handler's media processor so it recognizes descriptions the
+    // handler wrote and vice versa
+    let backfill_media_processor = media_processor.clone();
+    let backfill_language_detector = language_detector.clone();
+
     // Create toot handler for processing
     let toot_handler = TootStreamHandler::new(
         mastodon_client.clone(),
-        crate::openrouter::OpenRouterClient::new(config.config().openrouter.clone()),
+        openrouter_client.clone(),
         media_processor,
         language_detector,
         config.clone(),
     );
 
-    // Create fresh instances for ApplicationComponents since they were moved to TootStreamHandler
-    let backfill_media_processor =
-        crate::media::MediaProcessor::with_image_transformer(crate::media::MediaConfig {
-            max_size_mb: config.config().media().max_size_mb.unwrap_or(10) as f64,
-            max_dimension: config.config().media().resize_max_dimension.unwrap_or(2048),
-            supported_formats: config
-                .config()
-                .media()
-                .supported_formats
-                .as_ref()
-                .map(|formats| formats.iter().cloned().collect())
-                .unwrap_or_else(|| crate::media::MediaConfig::default().supported_formats),
-        });
-    let backfill_language_detector = crate::language::LanguageDetector::new();
-
     let components = ApplicationComponents {
         mastodon_client,
         openrouter_client,
@@ -345,6 +927,7 @@ async fn run_main_loop(
         let backfill_openrouter_client = components.openrouter_client.clone();
         let backfill_media_processor = components.media_processor.clone();
         let backfill_language_detector = components.language_detector.clone();
+        let backfill_progress = components.toot_handler.take_backfill_progress();
 
         tokio::spawn(async move {
             info!("Starting backfill processing in background");
@@ -354,6 +937,7 @@ async fn run_main_loop(
                 &backfill_openrouter_client,
                 &backfill_media_processor,
                 &backfill_language_detector,
+                backfill_progress,
             )
             .await
             {
@@ -367,17 +951,29 @@ async fn run_main_loop(
     // Set up graceful shutdown handling
     let shutdown_signal = setup_shutdown_signal();
 
+    let (shutdown_tx, shutdown_rx) = tokio::sync::watch::channel(false);
+    components.toot_handler.set_shutdown_receiver(shutdown_rx);
+    let shutdown_grace = std::time::Duration::from_secs(
+        config
+            .config()
+            .mastodon
+            .shutdown_grace_secs
+            .unwrap_or(crate::toot_handler::handler::DEFAULT_SHUTDOWN_GRACE_SECS),
+    );
+
     // Start main toot processing loop
     info!("Starting main toot processing loop");
-    let processing_task =
+    let mut processing_task =
         tokio::spawn(async move { components.toot_handler.start_processing().await });
 
     // Wait for shutdown signal or task completion
     tokio::select! {
         _ = shutdown_signal => {
             info!("Shutdown signal received, stopping application");
+            let _ = shutdown_tx.send(true);
+            drain_processing_task(processing_task, shutdown_grace).await;
         }
-        result = processing_task => {
+        result = &mut processing_task => {
             match result {
                 Ok(Ok(())) => {
                     info!("Toot processing loop completed successfully");
@@ -405,16 +1001,159 @@ async fn run_main_loop(
     Ok(())
 }
 
+/// Give the processing loop up to `grace` to finish the toot it is working on
+///
+/// Returns `false` if the task had to be aborted.
+async fn drain_processing_task<T>(
+    mut task: tokio::task::JoinHandle<T>,
+    grace: std::time::Duration,
+) -> bool {
+    match tokio::time::timeout(grace, &mut task).await {
+        Ok(_) => {
+            info!("In-flight processing finished");
+            true
+        }
+        Err(_) => {
+            warn!(
+                "In-flight processing did not finish within {:?}, aborting",
+                grace
+            );
+            task.abort();
+            false
+        }
+    }
+}
+
 /// Main application orchestration - coordinates all components
-async fn run_application(config: RuntimeConfig) -> Result<(), AlternatorError> {
+async fn run_application(
+    config: RuntimeConfig,
+    toot_id: Option<&str>,
+    reprocess_failed: bool,
+    notifier: &crate::notifications::Notifier,
+) -> Result<(), AlternatorError> {
     // Initialize all components
-    let (components, balance_monitor) = initialize_components(&config).await?;
+    let (mut components, balance_monitor) = initialize_components(&config, notifier).await?;
+
+    // One-shot mode: process the requested toot and exit without streaming or backfill
+    if let Some(toot_id) = toot_id {
+        info!("Processing single toot: {}", toot_id);
+        return components.toot_handler.process_single(toot_id).await;
+    }
+    if reprocess_failed {
+        return components.toot_handler.reprocess_failed().await;
+    }
+
+    let started = crate::notifications::NotificationEvent::Started {
+        version: env!("CARGO_PKG_VERSION").to_string(),
+    };
+    if let Err(e) = notifier.notify(&started).await {
+        warn!("Failed to send startup notification: {}", e);
+    }
+
+    // Serve liveness/readiness probes if configured
+    let health_task = start_health_server(&config, &components.mastodon_client).await?;
+
+    // Tell systemd we're up and keep its watchdog fed while the stream is healthy
+    let (systemd_notifier, watchdog_task) =
+        start_systemd_notifications(&config, &components.mastodon_client);
 
     // Set up background tasks
     let balance_task = setup_background_tasks(&config, balance_monitor);
 
+    // Clients share their stats with the clones used by the processing loop
+    let openrouter_client = components.openrouter_client.clone();
+
     // Run main processing loop
-    run_main_loop(&config, components, balance_task).await
+    let result = run_main_loop(&config, components, balance_task).await;
+
+    info!(
+        "Session summary: {}",
+        openrouter_client.processing_stats().summary()
+    );
+
+    if let Some(task) = health_task {
+        task.abort();
+    }
+    if let Some(task) = watchdog_task {
+        task.abort();
+    }
+    if let Some(notifier) = systemd_notifier {
+        crate::systemd::notify_stopping(notifier.as_ref());
+    }
+
+    // Export the spans of the last processed toots before exiting
+    let flushed =
+        tokio::task::spawn_blocking(|| telemetry::flush(std::time::Duration::from_secs(5)))
+            .await
+            .unwrap_or(false);
+    if !flushed {
+        warn!("Timed out exporting remaining spans");
+    }
+
+    result
+}
+
+/// Start the health check server when `health.bind_address` is set
+async fn start_health_server(
+    config: &RuntimeConfig,
+    mastodon_client: &crate::mastodon::MastodonClient,
+) -> Result<Option<tokio::task::JoinHandle<()>>, AlternatorError> {
+    let Some(bind_address) = config
+        .config()
+        .health
+        .as_ref()
+        .and_then(|health| health.bind_address.as_deref())
+    else {
+        debug!("Health check endpoints are disabled");
+        return Ok(None);
+    };
+
+    let (_, handle) =
+        crate::health::spawn_health_server(bind_address, mastodon_client.health_state())
+            .await
+            .map_err(|e| {
+                error!(
+                    "Failed to bind health check server to {}: {}",
+                    bind_address, e
+                );
+                AlternatorError::Io(e)
+            })?;
+
+    Ok(Some(handle))
+}
+
+/// Send READY=1 to systemd and start the watchdog when running under a notify unit
+fn start_systemd_notifications(
+    config: &RuntimeConfig,
+    mastodon_client: &crate::mastodon::MastodonClient,
+) -> (
+    Option<Arc<dyn crate::systemd::SystemdNotifier>>,
+    Option<tokio::task::JoinHandle<()>>,
+) {
+    let socket = crate::systemd::NotifySocket::from_env();
+    let enabled = config
+        .config()
+        .systemd
+        .clone()
+        .unwrap_or_default()
+        .is_enabled(socket.is_some());
+    if !enabled {
+        debug!("systemd notifications are disabled");
+        return (None, None);
+    }
+    let Some(socket) = socket else {
+        warn!("systemd notifications are enabled but NOTIFY_SOCKET is not set");
+        return (None, None);
+    };
+
+    let notifier: Arc<dyn crate::systemd::SystemdNotifier> = Arc::new(socket);
+    crate::systemd::notify_ready(notifier.as_ref());
+
+    let watchdog_task = crate::systemd::watchdog_interval_from_env().map(|interval| {
+        crate::systemd::spawn_watchdog(notifier.clone(), interval, mastodon_client.health_state())
+    });
+
+    (Some(notifier), watchdog_task)
 }
 
 /// Check Whisper model availability and preload if configured
@@ -473,15 +1212,32 @@ async fn startup_validation(
         account.display_name, account.acct
     );
 
-    info!("Validating OpenRouter connectivity");
+    // Fetched once and shared with the clients used for processing
+    match mastodon_client.description_limit().await {
+        Some(limit) => info!("✓ Instance alt text limit: {} characters", limit),
+        None => info!(
+            "Instance doesn't report an alt text limit, using {} characters",
+            openrouter_client.max_description_length()
+        ),
+    }
 
-    // Check OpenRouter account balance
-    let balance = openrouter_client
-        .get_account_balance()
-        .await
-        .map_err(AlternatorError::OpenRouter)?;
+    info!("Validating OpenRouter connectivity");
 
-    info!("✓ OpenRouter account balance: ${:.2}", balance);
+    // Check OpenRouter account balance; generic OpenAI-compatible servers have none
+    let balance = if openrouter_client.is_openrouter_provider() {
+        let balance = openrouter_client
+            .get_account_balance()
+            .await
+            .map_err(AlternatorError::OpenRouter)?;
+        match balance {
+            Some(remaining) => info!("✓ OpenRouter credit remaining: ${:.2}", remaining),
+            None => info!("✓ OpenRouter key has no credit limit"),
+        }
+        balance
+    } else {
+        info!("Skipping balance check - provider has no balance endpoint");
+        None
+    };
 
     // Verify configured model is available
     let models = openrouter_client
@@ -495,9 +1251,9 @@ async fn startup_validation(
     );
 
     // Warn if balance is low
-    if balance < 1.0 {
+    if let Some(balance) = balance.filter(|balance| *balance < 1.0) {
         warn!(
-            "⚠️  OpenRouter balance is low (${:.2}) - consider topping up your account",
+            "⚠️  OpenRouter credit is low (${:.2} remaining) - consider topping up your account",
             balance
         );
     }
@@ -546,29 +1302,120 @@ mod tests {
             mastodon: MastodonConfig {
                 instance_url: "https://mastodon.example".to_string(),
                 access_token: "test_token".to_string(),
+                access_token_file: None,
                 user_stream: Some(true),
                 backfill_count: Some(25),
                 backfill_pause: Some(60),
+                stream: None,
+                stream_tag: None,
+                stream_list: None,
+                own_toots_only: None,
+                state_file: None,
+                failed_toots_file: None,
+                processed_cache_size: None,
+                skip_sensitive: None,
+                skip_spoiler: None,
+                skip_replies: None,
+                respect_filters: None,
+                prefer_inplace_edit: None,
+                reconnect_catchup_count: None,
+                poll_handling: None,
+                processing_timeout_secs: None,
+                streaming_auth: None,
+                process_visibilities: None,
+                shutdown_grace_secs: None,
+                inter_toot_delay_ms: None,
+                new_toot_delay_secs: None,
+                max_inflight: None,
+                queue_capacity: None,
+                queue_overflow: None,
+                workers: None,
+                backfill_since: None,
+                backfill_until: None,
+                user_agent_contact: None,
+                delivery_mode: None,
+                allow_empty_text_edit: None,
             },
             openrouter: OpenRouterConfig {
                 api_key: "test_key".to_string(),
+                api_key_file: None,
                 model: "mistralai/mistral-small-3.2-24b-instruct:free".to_string(),
                 vision_model: "mistralai/mistral-small-3.2-24b-instruct:free".to_string(),
                 vision_fallback_model: "google/gemma-3-27b-it:free".to_string(),
                 text_model: "mistralai/mistral-small-3.2-24b-instruct:free".to_string(),
                 text_fallback_model: "moonshotai/kimi-k2:free".to_string(),
                 base_url: Some("https://openrouter.ai/api/v1".to_string()),
+                provider: None,
                 max_tokens: Some(150),
+                daily_spend_cap: None,
+                max_concurrent_requests: None,
+                min_request_interval_ms: None,
+                description_cache_size: None,
+                description_cache_ttl_secs: None,
+                max_description_length: None,
+                min_description_length: None,
+                refusal_patterns: None,
+                circuit_breaker_threshold: None,
+                circuit_breaker_cooldown_secs: None,
+                retry_base_delay_ms: None,
+                retry_backoff_multiplier: None,
+                retry_max_delay_ms: None,
+                image_url_passthrough: None,
+                use_toot_text_as_context: None,
+                chart_hashtags: None,
+                chart_prompt: None,
+                batch_image_descriptions: None,
             },
             media: None,
             balance: None,
             logging: Some(LoggingConfig {
                 level: Some("info".to_string()),
+                format: None,
+                redact_urls: None,
             }),
             whisper: None,
+            ocr: None,
+            attribution: None,
+            health: None,
+            notifications: None,
+            systemd: None,
+            tracing: None,
+            language: None,
+            prompts: None,
+            events: None,
         }
     }
 
+    #[tokio::test]
+    async fn test_model_listing_flags_configured_models() {
+        let client = crate::openrouter::MockOpenRouterClient::new();
+        let mut config = create_test_config().openrouter;
+        config.text_model = "missing/model".to_string();
+
+        let listing = model_listing(&client, &config).await.unwrap();
+        let lines: Vec<&str> = listing.lines().collect();
+
+        assert!(lines[0].starts_with("ID"));
+        let haiku = lines
+            .iter()
+            .find(|line| line.starts_with("anthropic/claude-3-haiku"))
+            .unwrap();
+        assert!(haiku.contains("Claude 3 Haiku"));
+        assert!(haiku.contains("200000"));
+        assert!(haiku.contains("$250.00") && haiku.contains("$1250.00"));
+        let mistral = lines
+            .iter()
+            .find(|line| line.starts_with("mistralai/mistral-small-3.2-24b-instruct:free"))
+            .unwrap();
+        assert!(mistral.contains("32768") && mistral.contains("free"));
+
+        assert!(listing.contains("2 models available"));
+        assert!(listing
+            .contains("✓ vision_model mistralai/mistral-small-3.2-24b-instruct:free: available"));
+        assert!(listing.contains("✗ text_model missing/model: NOT available"));
+        assert!(listing.contains("✗ vision_fallback_model google/gemma-3-27b-it:free"));
+    }
+
     #[test]
     fn test_cli_parsing() {
         let cli = Cli::parse_from(["alternator"]);
@@ -584,6 +1431,243 @@ mod tests {
 
         let cli = Cli::parse_from(["alternator", "--verbose"]);
         assert!(cli.verbose);
+
+        let cli = Cli::parse_from(["alternator", "--dry-run"]);
+        assert!(cli.dry_run);
+        assert!(cli.dry_run());
+    }
+
+    #[test]
+    fn test_json_log_format() {
+        let mut config = create_test_config();
+        config.logging.as_mut().unwrap().format = Some("json".to_string());
+        let config = RuntimeConfig::new(config);
+
+        let subscriber = build_subscriber(&config, &Cli::parse_from(["alternator"])).unwrap();
+        tracing::subscriber::with_default(subscriber, || {
+            info!(toot_id = "123", "JSON logging initialized");
+        });
+
+        let cli = Cli::parse_from(["alternator", "--log-format", "xml"]);
+        assert!(build_subscriber(&config, &cli).is_err());
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_subscriber_with_span_export() {
+        use crate::test_support::{MockHttpServer, MockResponse};
+
+        #[tracing::instrument(fields(media_count = 2))]
+        fn instrumented(toot_id: &str) -> usize {
+            info!("Inside instrumented span");
+            toot_id.len()
+        }
+
+        let server = MockHttpServer::start(|_| MockResponse::json(200, "{}")).await;
+        let mut config = create_test_config();
+        config.logging.as_mut().unwrap().level = Some("warn".to_string());
+        config.tracing = Some(crate::config::TracingConfig {
+            endpoint: Some(server.url()),
+            service_name: Some("alternator-test".to_string()),
+        });
+        let config = RuntimeConfig::new(config);
+
+        for format in ["text", "json"] {
+            let cli = Cli::parse_from(["alternator", "--log-format", format]);
+            let subscriber = build_subscriber(&config, &cli).unwrap();
+            tracing::subscriber::with_default(subscriber, || {
+                assert_eq!(instrumented("123"), 3);
+            });
+        }
+
+        // Info spans are exported even though only warnings are logged
+        let exported = || {
+            server
+                .requests()
+                .iter()
+                .map(|request| {
+                    String::from_utf8_lossy(&request.body)
+                        .matches("\"instrumented\"")
+                        .count()
+                })
+                .sum::<usize>()
+        };
+        let deadline = std::time::Instant::now() + std::time::Duration::from_secs(15);
+        while exported() < 2 && std::time::Instant::now() < deadline {
+            tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+        }
+        assert_eq!(exported(), 2);
+    }
+
+    #[test]
+    fn test_validate_config_report() {
+        let dir = tempfile::tempdir().unwrap();
+
+        let good_path = dir.path().join("good.toml");
+        std::fs::write(
+            &good_path,
+            r#"
+[mastodon]
+instance_url = "https://mastodon.example"
+access_token = "test_token"
+
+[openrouter]
+api_key = "test_key"
+
+[logging]
+level = "debug"
+"#,
+        )
+        .unwrap();
+
+        let cli = Cli::parse_from([
+            "alternator",
+            "--validate-config",
+            "--config",
+            good_path.to_str().unwrap(),
+        ]);
+        let (report, config) = validate_config(&cli);
+        assert!(report.is_valid(), "{:?}", report.lines);
+        assert!(config.is_some());
+
+        // Missing API key and an unknown log level
+        let bad_path = dir.path().join("bad.toml");
+        std::fs::write(
+            &bad_path,
+            r#"
+[mastodon]
+instance_url = "https://mastodon.example"
+access_token = "test_token"
+
+[openrouter]
+model = "mistralai/mistral-small-3.2-24b-instruct:free"
+"#,
+        )
+        .unwrap();
+
+        let cli = Cli::parse_from([
+            "alternator",
+            "--validate-config",
+            "--config",
+            bad_path.to_str().unwrap(),
+        ]);
+        let (report, config) = validate_config(&cli);
+        assert!(!report.is_valid());
+        assert!(config.is_none());
+        assert!(report.lines[0].contains("api_key"));
+
+        let cli = Cli::parse_from([
+            "alternator",
+            "--validate-config",
+            "--log-level",
+            "loud",
+            "--config",
+            good_path.to_str().unwrap(),
+        ]);
+        let (report, _) = validate_config(&cli);
+        assert_eq!(report.failures, 1);
+        assert!(report.lines.iter().any(|line| line.contains("loud")));
+    }
+
+    #[test]
+    fn test_check_connectivity_requires_validate_config() {
+        assert!(Cli::try_parse_from(["alternator", "--check-connectivity"]).is_err());
+
+        let cli = Cli::try_parse_from(["alternator", "--validate-config", "--check-connectivity"])
+            .unwrap();
+        assert!(cli.validate_config && cli.check_connectivity);
+    }
+
+    #[test]
+    fn test_print_config_format() {
+        let format = |args: &[&str]| {
+            Cli::try_parse_from([&["alternator"], args].concat()).map(|cli| cli.print_config)
+        };
+        assert_eq!(format(&[]).unwrap(), None);
+        assert_eq!(
+            format(&["--print-config"]).unwrap().as_deref(),
+            Some("toml")
+        );
+        assert_eq!(
+            format(&["--print-config", "json"]).unwrap().as_deref(),
+            Some("json")
+        );
+        assert!(format(&["--print-config", "yaml"]).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_whisper_check_transcribes_test_clip() {
+        let cli = Cli::try_parse_from(["alternator", "--check-whisper"]).unwrap();
+        assert!(cli.check_whisper);
+        assert!(Cli::try_parse_from(["alternator", "--check-whisper", "--list-models"]).is_err());
+
+        let mut transcribed = None;
+        let transcript = whisper_check(|clip| {
+            transcribed = Some(clip);
+            async { Ok("beep".to_string()) }
+        })
+        .await
+        .unwrap();
+        assert_eq!(transcript, "beep");
+
+        // The transcription path received a WAV clip of the expected length
+        let clip = transcribed.expect("transcription should be invoked");
+        assert_eq!(&clip[..4], b"RIFF");
+        assert_eq!(clip.len(), 44 + 32_000 * WHISPER_CHECK_CLIP_SECS as usize);
+
+        let failed = whisper_check(|_| async {
+            Err(crate::error::MediaError::ProcessingFailed(
+                "whisper not installed".to_string(),
+            ))
+        })
+        .await;
+        assert!(failed.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_self_test_uploads_and_deletes_media() {
+        use crate::test_support::{MockHttpServer, MockResponse};
+
+        let client_for = |url: &str| {
+            let config: Config = toml::from_str(&format!(
+                "[mastodon]\ninstance_url = \"{url}\"\naccess_token = \"token\"\n\n[openrouter]\napi_key = \"key\"\n"
+            ))
+            .unwrap();
+            crate::mastodon::MastodonClient::new(config.mastodon)
+        };
+
+        let server = MockHttpServer::start(|request| {
+            match (request.method.as_str(), request.path.as_str()) {
+                ("POST", "/api/v2/media") => MockResponse::json(200, r#"{"id":"selftest_1"}"#),
+                ("DELETE", "/api/v1/media/selftest_1") => MockResponse::json(200, "{}"),
+                _ => MockResponse::json(404, r#"{"error":"Record not found"}"#),
+            }
+        })
+        .await;
+        let client = client_for(&server.url());
+
+        assert_eq!(
+            edit_permission_self_test(&client).await.unwrap(),
+            "selftest_1"
+        );
+        let requests = server.requests();
+        assert_eq!(requests.len(), 2);
+        assert_eq!(requests[1].method, "DELETE");
+
+        // A token without write scope fails with a hint
+        let forbidden = MockHttpServer::start(|_| {
+            MockResponse::json(
+                403,
+                r#"{"error":"This action is outside the authorized scopes"}"#,
+            )
+        })
+        .await;
+        let client = client_for(&forbidden.url());
+        let error = edit_permission_self_test(&client).await.unwrap_err();
+        assert!(error.contains("write:media"));
+        assert_eq!(forbidden.requests().len(), 1);
+
+        let cli = Cli::try_parse_from(["alternator", "--self-test"]).unwrap();
+        assert!(cli.self_test);
     }
 
     #[test]
@@ -616,4 +1700,32 @@ mod tests {
         let cli = Cli::parse_from(["alternator"]);
         assert_eq!(cli.config_path(), None);
     }
+
+    #[tokio::test]
+    async fn test_shutdown_drain_lets_in_flight_processing_finish() {
+        use std::sync::atomic::{AtomicBool, Ordering};
+        use std::sync::Arc;
+        use std::time::Duration;
+
+        let spawn_edit = |duration: Duration| {
+            let finished = Arc::new(AtomicBool::new(false));
+            let task_finished = finished.clone();
+            let task = tokio::spawn(async move {
+                tokio::time::sleep(duration).await;
+                task_finished.store(true, Ordering::SeqCst);
+            });
+            (task, finished)
+        };
+
+        // An edit that completes inside the grace window is not cut off
+        let (task, finished) = spawn_edit(Duration::from_millis(100));
+        assert!(drain_processing_task(task, Duration::from_secs(5)).await);
+        assert!(finished.load(Ordering::SeqCst));
+
+        // One that outlives it is aborted
+        let (task, finished) = spawn_edit(Duration::from_secs(5));
+        assert!(!drain_processing_task(task, Duration::from_millis(50)).await);
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert!(!finished.load(Ordering::SeqCst));
+    }
 }
@@ -1,22 +1,125 @@
 use crate::config::MastodonConfig;
 use crate::error::{AlternatorError, ErrorRecovery, MastodonError};
+use crate::health::HealthState;
+use crate::redact::{log_text, log_url};
 use chrono::{DateTime, Utc};
 use futures_util::{SinkExt, StreamExt};
 use serde::{Deserialize, Serialize};
+use std::collections::{HashSet, VecDeque};
+use std::sync::{Arc, Mutex};
 use std::time::Duration;
 use tokio::net::TcpStream;
 use tokio::time::sleep;
+use tokio_tungstenite::tungstenite::client::IntoClientRequest;
+use tokio_tungstenite::tungstenite::handshake::client::Request;
+use tokio_tungstenite::tungstenite::http::header::{
+    HeaderValue, AUTHORIZATION, SEC_WEBSOCKET_PROTOCOL, USER_AGENT,
+};
 use tokio_tungstenite::{connect_async, tungstenite::Message, MaybeTlsStream, WebSocketStream};
-use tracing::{debug, error, info, warn};
+use tracing::{debug, error, info, warn, Instrument};
 use url::Url;
 
 /// Zero-width space character used as invisible placeholder for empty text content
 /// This allows media descriptions to be updated on posts that originally had no text
 const ZERO_WIDTH_SPACE: &str = "\u{200B}";
 
+/// Status length limit assumed when the instance doesn't report one
+const DEFAULT_MAX_CHARACTERS: usize = 500;
+
+/// Characters a link counts towards the status length, regardless of its actual length
+const URL_CHARACTER_COUNT: usize = 23;
+
+/// Shortest poll duration accepted by the Mastodon API, in seconds
+const MIN_POLL_EXPIRES_IN_SECS: i64 = 300;
+
+/// Status fields fixed at creation; the edit API rejects them with a 422
+const IMMUTABLE_STATUS_FIELDS: &[&str] = &["in_reply_to_id", "visibility"];
+
+/// Status visibilities, as accepted in `mastodon.process_visibilities`
+pub const VISIBILITIES: &[&str] = &["public", "unlisted", "private", "direct"];
+
 /// Blacklisted Mastodon servers that Alternator will refuse to run on
 const BLACKLISTED_SERVERS: &[(&str, &str)] = &[("mastodon.social", "toxic moderation")];
 
+/// Backoff in seconds between retries of requests that hit a transient gateway error (502/503/504)
+const GATEWAY_RETRY_DELAYS: [u64; 3] = [1, 2, 4];
+
+/// Remaining request budget at or below which writes wait for the rate limit to reset
+const RATE_LIMIT_LOW_WATERMARK: u32 = 5;
+
+/// Upper bound for a single rate limit wait, guarding against clock skew
+const MAX_RATE_LIMIT_WAIT: Duration = Duration::from_secs(5 * 60);
+
+/// Rate limit budget last reported by the Mastodon API
+#[derive(Debug, Default)]
+struct RateLimitState {
+    remaining: Option<u32>,
+    reset_at: Option<DateTime<Utc>>,
+}
+
+/// Length limits reported by `/api/v1/instance`
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct InstanceLimits {
+    max_characters: usize,
+    /// Alt text limit, if the server software reports one
+    description_limit: Option<usize>,
+}
+
+impl Default for InstanceLimits {
+    fn default() -> Self {
+        Self {
+            max_characters: DEFAULT_MAX_CHARACTERS,
+            description_limit: None,
+        }
+    }
+}
+
+impl InstanceLimits {
+    fn from_instance(instance: &serde_json::Value) -> Self {
+        let limit = |value: Option<&serde_json::Value>| {
+            value
+                .and_then(serde_json::Value::as_u64)
+                .filter(|limit| *limit > 0)
+                .map(|limit| limit as usize)
+        };
+
+        Self {
+            // Mastodon 3.x+ reports `configuration.statuses.max_characters`, forks `max_toot_chars`
+            max_characters: limit(
+                instance
+                    .pointer("/configuration/statuses/max_characters")
+                    .or_else(|| instance.get("max_toot_chars")),
+            )
+            .unwrap_or(DEFAULT_MAX_CHARACTERS),
+            // Mastodon 4.4+ reports `configuration.media_attachments.description_limit`,
+            // Pleroma and Akkoma a top-level `description_limit`
+            description_limit: limit(
+                instance
+                    .pointer("/configuration/media_attachments/description_limit")
+                    .or_else(|| instance.get("description_limit")),
+            ),
+        }
+    }
+}
+
+/// Default upper bound on media processing and background tasks in flight at once
+pub const DEFAULT_MAX_INFLIGHT: usize = 16;
+
+/// Delayed media cleanups running at once; further ones wait for a free slot
+const MAX_CLEANUP_TASKS: usize = 4;
+
+/// Default number of own toots fetched after a reconnect to cover the gap
+const DEFAULT_RECONNECT_CATCHUP_COUNT: u32 = 20;
+
+/// Maximum fraction by which a reconnect delay is randomly shortened or lengthened
+const RECONNECT_JITTER: f64 = 0.2;
+
+/// Spread a reconnect delay by +/- `RECONNECT_JITTER` so clients don't reconnect in lockstep
+fn jittered_delay(base_secs: u64) -> Duration {
+    let factor = 1.0 + RECONNECT_JITTER * (fastrand::f64() * 2.0 - 1.0);
+    Duration::from_secs_f64(base_secs as f64 * factor)
+}
+
 /// Mastodon toot event from WebSocket stream
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TootEvent {
@@ -27,6 +130,9 @@ pub struct TootEvent {
     pub language: Option<String>,
     pub media_attachments: Vec<MediaAttachment>,
     pub created_at: DateTime<Utc>,
+    /// When the status was last edited, `None` for statuses that were never edited
+    #[serde(default)]
+    pub edited_at: Option<DateTime<Utc>>,
     pub url: Option<String>,
     pub visibility: String,
     pub sensitive: bool,
@@ -37,6 +143,9 @@ pub struct TootEvent {
     pub tags: Vec<Tag>,
     pub emojis: Vec<CustomEmoji>,
     pub poll: Option<Poll>,
+    /// Keyword filters of the authenticated account that match this status
+    #[serde(default)]
+    pub filtered: Vec<FilterResult>,
     /// Indicates if this toot event represents an edit (from status.update)
     /// This field is not part of the Mastodon API but added by Alternator
     #[serde(skip)]
@@ -70,6 +179,23 @@ pub struct MediaAttachment {
 pub struct MediaMeta {
     pub original: Option<MediaDimensions>,
     pub small: Option<MediaDimensions>,
+    /// Crop focus point set by the author
+    #[serde(default)]
+    pub focus: Option<MediaFocus>,
+}
+
+/// Media focus point, both coordinates in the range -1.0..=1.0
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct MediaFocus {
+    pub x: f64,
+    pub y: f64,
+}
+
+impl MediaFocus {
+    /// Format as the `x,y` string expected by the media API
+    pub fn to_param(self) -> String {
+        format!("{},{}", self.x.clamp(-1.0, 1.0), self.y.clamp(-1.0, 1.0))
+    }
 }
 
 /// Media dimensions
@@ -79,16 +205,30 @@ pub struct MediaDimensions {
     pub height: Option<u32>,
     pub size: Option<String>,
     pub aspect: Option<f64>,
+    /// Length in seconds, reported for audio and video
+    #[serde(default)]
+    pub duration: Option<f64>,
 }
 
 /// Media recreation data for uploading with descriptions
 #[derive(Debug, Clone)]
 #[allow(dead_code)]
 pub struct MediaRecreation {
+    /// ID of the attachment this recreation describes (and replaces when recreated)
+    pub original_media_id: String,
     pub data: Vec<u8>,
     pub description: String,
     pub media_type: String,
     pub filename: String,
+    /// Focus point of the original attachment, re-applied on upload
+    pub focus: Option<MediaFocus>,
+    /// Description the attachment had when processing started, e.g. an earlier AI
+    /// description being regenerated; race checks accept it unchanged
+    pub replaced_description: Option<String>,
+    /// Vision model that wrote the description, `None` for Whisper transcripts
+    pub model: Option<String>,
+    /// Full transcript when the description only holds a summary of it, posted as a reply thread
+    pub full_transcript: Option<String>,
 }
 
 /// Mentioned user in a status
@@ -116,6 +256,34 @@ pub struct CustomEmoji {
     pub visible_in_picker: bool,
 }
 
+/// A keyword filter matching a status, as returned in `Status.filtered`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FilterResult {
+    pub filter: Filter,
+    pub keyword_matches: Option<Vec<String>>,
+}
+
+/// User-defined keyword filter
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Filter {
+    pub id: String,
+    pub title: String,
+    #[serde(default)]
+    pub context: Vec<String>,
+    /// "warn", "hide" or "blur"
+    pub filter_action: String,
+}
+
+impl TootEvent {
+    /// First matching filter that warns about or hides this status
+    pub fn hiding_filter(&self) -> Option<&Filter> {
+        self.filtered
+            .iter()
+            .map(|result| &result.filter)
+            .find(|filter| matches!(filter.filter_action.as_str(), "warn" | "hide"))
+    }
+}
+
 /// Poll attached to a status
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Poll {
@@ -131,6 +299,33 @@ pub struct Poll {
     pub emojis: Vec<CustomEmoji>,
 }
 
+impl Poll {
+    /// Form parameters that re-apply this poll when editing its status
+    ///
+    /// Status edits without poll parameters remove the poll. Returns `None` if the
+    /// poll can't be re-applied unchanged: it has ended, has no end date, or ends
+    /// sooner than the API's minimum duration.
+    pub fn edit_params(&self, now: DateTime<Utc>) -> Option<Vec<(String, String)>> {
+        if self.expired {
+            return None;
+        }
+        let expires_in = (self.expires_at? - now).num_seconds();
+        if expires_in < MIN_POLL_EXPIRES_IN_SECS {
+            return None;
+        }
+
+        // Unchanged options and `multiple` keep the existing votes
+        let mut params: Vec<(String, String)> = self
+            .options
+            .iter()
+            .map(|option| ("poll[options][]".to_string(), option.title.clone()))
+            .collect();
+        params.push(("poll[expires_in]".to_string(), expires_in.to_string()));
+        params.push(("poll[multiple]".to_string(), self.multiple.to_string()));
+        Some(params)
+    }
+}
+
 /// Poll option
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PollOption {
@@ -160,6 +355,20 @@ pub struct MastodonClient {
     websocket: Option<WebSocketStream<MaybeTlsStream<TcpStream>>>,
     reconnect_attempts: u32,
     authenticated_user_id: Option<String>,
+    /// ID of the newest toot delivered by `listen`, used to catch up after a reconnect
+    last_seen_id: Option<String>,
+    /// Toots fetched after a reconnect, delivered by `listen` before the live stream
+    missed_toots: VecDeque<TootEvent>,
+    /// Shared between clones since they use the same access token
+    rate_limit: Arc<Mutex<RateLimitState>>,
+    /// Connection state reported by the health check endpoints
+    health: HealthState,
+    /// Instance length limits, fetched once and shared between clones
+    instance_limits: Arc<Mutex<Option<InstanceLimits>>>,
+    /// Slots for media processing and background tasks (`max_inflight`), shared between clones
+    inflight: Arc<tokio::sync::Semaphore>,
+    /// Slots for delayed media cleanup tasks, so a burst of recreations doesn't pile up
+    cleanup_tasks: Arc<tokio::sync::Semaphore>,
 }
 
 impl Clone for MastodonClient {
@@ -170,6 +379,13 @@ impl Clone for MastodonClient {
             websocket: None, // WebSocket connections can't be cloned
             reconnect_attempts: self.reconnect_attempts,
             authenticated_user_id: self.authenticated_user_id.clone(),
+            last_seen_id: self.last_seen_id.clone(),
+            missed_toots: VecDeque::new(),
+            rate_limit: Arc::clone(&self.rate_limit),
+            health: self.health.clone(),
+            instance_limits: Arc::clone(&self.instance_limits),
+            inflight: Arc::clone(&self.inflight),
+            cleanup_tasks: Arc::clone(&self.cleanup_tasks),
         }
     }
 }
@@ -188,7 +404,6 @@ pub trait MastodonStream {
         media_id: &str,
         description: &str,
     ) -> Result<(), MastodonError>;
-    #[allow(dead_code)]
     async fn update_multiple_media(
         &self,
         toot_id: &str,
@@ -208,6 +423,13 @@ pub trait MastodonStream {
         original_media_ids: Vec<String>,
     ) -> Result<(), MastodonError>;
     async fn send_dm(&self, message: &str) -> Result<(), MastodonError>;
+    /// Post `status` as a reply with the given visibility, returning the new status ID
+    async fn post_reply(
+        &self,
+        in_reply_to_id: &str,
+        status: &str,
+        visibility: &str,
+    ) -> Result<String, MastodonError>;
     async fn verify_credentials(&mut self) -> Result<Account, MastodonError>;
     async fn get_user_toots(&self, limit: u32) -> Result<Vec<TootEvent>, MastodonError>;
 }
@@ -217,9 +439,10 @@ impl MastodonClient {
     pub fn new(config: MastodonConfig) -> Self {
         let http_client = reqwest::Client::builder()
             .timeout(Duration::from_secs(30))
-            .user_agent(format!("Alternator/{}", env!("CARGO_PKG_VERSION")))
+            .user_agent(Self::user_agent(&config))
             .build()
             .expect("Failed to create HTTP client");
+        let max_inflight = config.max_inflight.unwrap_or(DEFAULT_MAX_INFLIGHT).max(1);
 
         Self {
             config,
@@ -227,7 +450,291 @@ impl MastodonClient {
             websocket: None,
             reconnect_attempts: 0,
             authenticated_user_id: None,
+            last_seen_id: None,
+            missed_toots: VecDeque::new(),
+            rate_limit: Arc::new(Mutex::new(RateLimitState::default())),
+            health: HealthState::new(),
+            instance_limits: Arc::new(Mutex::new(None)),
+            inflight: Arc::new(tokio::sync::Semaphore::new(max_inflight)),
+            cleanup_tasks: Arc::new(tokio::sync::Semaphore::new(MAX_CLEANUP_TASKS)),
+        }
+    }
+
+    /// Wait for one of the `max_inflight` slots shared by all clones of this client
+    ///
+    /// Held while processing a media attachment or sending a background request, so load
+    /// beyond the limit queues up instead of running all at once.
+    pub async fn acquire_inflight(&self) -> tokio::sync::OwnedSemaphorePermit {
+        Arc::clone(&self.inflight)
+            .acquire_owned()
+            .await
+            .expect("in-flight semaphore is never closed")
+    }
+
+    /// User-Agent for REST and streaming requests, with the configured contact if any
+    fn user_agent(config: &MastodonConfig) -> String {
+        let version = env!("CARGO_PKG_VERSION");
+        match config.user_agent_contact.as_deref().map(str::trim) {
+            Some(contact) if !contact.is_empty() => format!("Alternator/{version} (+{contact})"),
+            _ => format!("Alternator/{version}"),
+        }
+    }
+
+    /// Move the open WebSocket connection and pending missed toots into a clone
+    ///
+    /// The clone listens on the stream while this client keeps serving REST calls.
+    pub fn take_stream(&mut self) -> Self {
+        let mut stream = self.clone();
+        stream.websocket = self.websocket.take();
+        stream.missed_toots = std::mem::take(&mut self.missed_toots);
+        stream
+    }
+
+    /// Attach an already open WebSocket stream for the given authenticated user (for testing)
+    #[cfg(test)]
+    pub(crate) fn attach_websocket(
+        &mut self,
+        user_id: &str,
+        websocket: WebSocketStream<MaybeTlsStream<TcpStream>>,
+    ) {
+        self.authenticated_user_id = Some(user_id.to_string());
+        self.set_websocket(Some(websocket));
+    }
+
+    /// Connection state shared with clones, for the health check server
+    pub fn health_state(&self) -> HealthState {
+        self.health.clone()
+    }
+
+    /// Replace the WebSocket stream and publish the connection state
+    fn set_websocket(&mut self, websocket: Option<WebSocketStream<MaybeTlsStream<TcpStream>>>) {
+        self.health.set_websocket_connected(websocket.is_some());
+        self.websocket = websocket;
+    }
+
+    /// Maximum status length of the instance, fetched from `/api/v1/instance` on first use
+    pub async fn max_characters(&self) -> usize {
+        self.instance_limits().await.max_characters
+    }
+
+    /// Alt text length limit reported by the instance, `None` if it doesn't report one
+    pub async fn description_limit(&self) -> Option<usize> {
+        self.instance_limits().await.description_limit
+    }
+
+    /// Post `text` as a thread of replies under `in_reply_to_id`, split to fit the status limit
+    ///
+    /// Each reply answers the previous one; returns the number of replies posted.
+    pub async fn post_reply_thread(
+        &self,
+        in_reply_to_id: &str,
+        text: &str,
+        visibility: &str,
+    ) -> Result<usize, MastodonError> {
+        let parts = Self::split_into_posts(text, self.max_characters().await);
+        let mut parent = in_reply_to_id.to_string();
+        for part in &parts {
+            parent = self.post_reply(&parent, part, visibility).await?;
+        }
+        Ok(parts.len())
+    }
+
+    /// Split `text` at whitespace into posts of at most `max_characters`, numbered "(1/3)"
+    /// when there is more than one
+    pub(crate) fn split_into_posts(text: &str, max_characters: usize) -> Vec<String> {
+        // Room for the " (12/34)" counter
+        let limit = max_characters.saturating_sub(10).max(1);
+
+        let mut posts: Vec<String> = Vec::new();
+        let mut current = String::new();
+        for word in text.split_whitespace() {
+            let word_len = word.chars().count();
+            let current_len = current.chars().count();
+            if !current.is_empty() && current_len + 1 + word_len > limit {
+                posts.push(std::mem::take(&mut current));
+            }
+            if word_len > limit {
+                // A single over-long "word" (e.g. CJK text without spaces) is hard-cut
+                let chars: Vec<char> = word.chars().collect();
+                for chunk in chars.chunks(limit) {
+                    if !current.is_empty() {
+                        posts.push(std::mem::take(&mut current));
+                    }
+                    current = chunk.iter().collect();
+                }
+                continue;
+            }
+            if !current.is_empty() {
+                current.push(' ');
+            }
+            current.push_str(word);
+        }
+        if !current.is_empty() {
+            posts.push(current);
+        }
+
+        let total = posts.len();
+        if total > 1 {
+            for (index, post) in posts.iter_mut().enumerate() {
+                post.push_str(&format!(" ({}/{total})", index + 1));
+            }
+        }
+        posts
+    }
+
+    /// Length limits of the instance, fetched from `/api/v1/instance` on first use
+    async fn instance_limits(&self) -> InstanceLimits {
+        if let Some(limits) = *self.instance_limits.lock().unwrap() {
+            return limits;
+        }
+
+        let url = format!(
+            "{}/api/v1/instance",
+            self.config.instance_url.trim_end_matches('/')
+        );
+        let instance = match self.http_client.get(&url).send().await {
+            Ok(response) if response.status().is_success() => {
+                response.json::<serde_json::Value>().await.ok()
+            }
+            Ok(response) => {
+                warn!(
+                    "Failed to fetch instance limits: HTTP {}",
+                    response.status()
+                );
+                None
+            }
+            Err(e) => {
+                warn!("Failed to fetch instance limits: {}", e);
+                None
+            }
+        };
+        // Retry on the next edit if the instance couldn't be reached
+        let Some(instance) = instance else {
+            return InstanceLimits::default();
+        };
+
+        let limits = InstanceLimits::from_instance(&instance);
+        debug!(
+            "Instance limits: {} status characters, alt text {:?}",
+            limits.max_characters, limits.description_limit
+        );
+
+        *self.instance_limits.lock().unwrap() = Some(limits);
+        limits
+    }
+
+    /// Status length as counted by Mastodon: links count as 23 characters and
+    /// remote mentions only count the username
+    fn status_length(text: &str) -> usize {
+        let mut length = 0;
+        for segment in text.split_inclusive(char::is_whitespace) {
+            let word = segment.trim_end_matches(char::is_whitespace);
+            let whitespace = segment[word.len()..].chars().count();
+            length += Self::word_length(word) + whitespace;
+        }
+        length
+    }
+
+    /// Counted length of a single whitespace-free word, see [`Self::status_length`]
+    fn word_length(word: &str) -> usize {
+        if let Some(start) = Self::url_start(word) {
+            let (prefix, url) = word.split_at(start);
+            let url_length = Self::trim_url_punctuation(url).len();
+            return prefix.chars().count()
+                + URL_CHARACTER_COUNT
+                + url[url_length..].chars().count();
+        }
+        if let Some((username, _domain)) = word
+            .strip_prefix('@')
+            .and_then(|mention| mention.split_once('@'))
+        {
+            return username.chars().count() + 1;
+        }
+        word.chars().count()
+    }
+
+    /// Byte offset of a link in `word`, also when it follows punctuation like `(`
+    fn url_start(word: &str) -> Option<usize> {
+        ["https://", "http://"]
+            .iter()
+            .filter_map(|scheme| word.find(scheme))
+            .min()
+            .filter(|&start| {
+                let linked = word[..start]
+                    .chars()
+                    .next_back()
+                    .map_or(true, |c| !c.is_alphanumeric());
+                let has_host = word[start..]
+                    .split_once("://")
+                    .is_some_and(|(_, rest)| rest.starts_with(|c: char| c.is_alphanumeric()));
+                linked && has_host
+            })
+    }
+
+    /// `url` without the trailing punctuation Mastodon leaves outside the link, keeping a
+    /// closing parenthesis that belongs to the URL itself (e.g. Wikipedia links)
+    fn trim_url_punctuation(url: &str) -> &str {
+        let mut url = url;
+        loop {
+            let Some(last) = url.chars().next_back() else {
+                return url;
+            };
+            let unbalanced_paren =
+                last == ')' && url.matches('(').count() < url.matches(')').count();
+            if matches!(
+                last,
+                '.' | ',' | ';' | ':' | '!' | '?' | '\'' | '"' | ']' | '}' | '>'
+            ) || unbalanced_paren
+            {
+                url = &url[..url.len() - last.len_utf8()];
+            } else {
+                return url;
+            }
+        }
+    }
+
+    /// Refuse to edit a media-only status when `allow_empty_text_edit` is disabled, since
+    /// the edit would need the zero-width space as its text
+    fn check_empty_text_edit(&self, toot_id: &str, source_text: &str) -> Result<(), MastodonError> {
+        if source_text.trim().is_empty() && !self.config.allow_empty_text_edit.unwrap_or(true) {
+            return Err(MastodonError::InvalidTootData(format!(
+                "Toot {toot_id} has no text and allow_empty_text_edit is disabled"
+            )));
+        }
+        Ok(())
+    }
+
+    /// Status text to send with an edit: the zero-width space placeholder for media-only
+    /// posts, otherwise the source text, trimmed if only trailing whitespace exceeds `limit`
+    ///
+    /// Mastodon counts the content warning towards the same limit as the text.
+    fn edit_status_text(
+        source_text: String,
+        spoiler_text: &str,
+        limit: usize,
+    ) -> Result<String, MastodonError> {
+        // Mastodon requires text content when updating a status, but we want to support
+        // adding descriptions to media-only posts
+        if source_text.trim().is_empty() {
+            debug!("Using zero-width space for empty content to enable media description update");
+            return Ok(ZERO_WIDTH_SPACE.to_string());
+        }
+
+        let spoiler_length = spoiler_text.chars().count();
+        if spoiler_length + Self::status_length(&source_text) <= limit {
+            debug!("Using original status text exactly as-is");
+            return Ok(source_text);
         }
+
+        let trimmed = source_text.trim_end();
+        let length = spoiler_length + Self::status_length(trimmed);
+        if length <= limit {
+            debug!("Trimmed trailing whitespace to fit the {limit} character limit");
+            return Ok(trimmed.to_string());
+        }
+
+        // Cutting the author's text would change the toot, so the edit is refused
+        Err(MastodonError::StatusTooLong { length, limit })
     }
 
     /// Check if the configured server is blacklisted
@@ -267,41 +774,123 @@ impl MastodonClient {
         })?;
 
         let final_url = response.url().to_string();
-        debug!("Resolved HTTP URL: {} -> {}", http_url, final_url);
-
-        // Convert the final HTTP URL to WebSocket URL and add authentication
-        let streaming_url = format!(
-            "{}?access_token={}&stream=user",
-            final_url
-                .replace("https://", "wss://")
-                .replace("http://", "ws://"),
-            self.config.access_token
+        debug!(
+            "Resolved HTTP URL: {} -> {}",
+            log_url(&http_url),
+            log_url(&final_url)
         );
 
-        Url::parse(&streaming_url)
-            .map_err(|e| MastodonError::ConnectionFailed(format!("Invalid streaming URL: {e}")))
+        self.websocket_url(&final_url)
     }
 
     /// Get the WebSocket streaming URL (for testing)
     #[cfg(test)]
     fn get_streaming_url(&self) -> Result<Url, MastodonError> {
         let base_url = self.config.instance_url.trim_end_matches('/');
-        let streaming_url = format!(
-            "{}/api/v1/streaming?access_token={}&stream=user",
-            base_url
-                .replace("https://", "wss://")
-                .replace("http://", "ws://"),
-            self.config.access_token
-        );
+        self.websocket_url(&format!("{base_url}/api/v1/streaming"))
+    }
+
+    /// Convert the HTTP streaming endpoint to a WebSocket URL with the stream selection
+    ///
+    /// The access token is only added to the query in `"query"` streaming auth mode.
+    fn websocket_url(&self, http_url: &str) -> Result<Url, MastodonError> {
+        let ws_url = http_url
+            .replace("https://", "wss://")
+            .replace("http://", "ws://");
+        let streaming_url = if self.uses_header_streaming_auth() {
+            format!("{ws_url}?{}", self.stream_query())
+        } else {
+            format!(
+                "{ws_url}?access_token={}&{}",
+                self.config.access_token,
+                self.stream_query()
+            )
+        };
 
         Url::parse(&streaming_url)
             .map_err(|e| MastodonError::ConnectionFailed(format!("Invalid streaming URL: {e}")))
     }
 
+    /// Whether the access token is sent in the WebSocket handshake headers instead of the URL
+    fn uses_header_streaming_auth(&self) -> bool {
+        self.config.streaming_auth.as_deref() == Some("header")
+    }
+
+    /// Build the WebSocket handshake request, adding the token headers in `"header"` mode
+    ///
+    /// Mastodon, GoToSocial and Pleroma accept the token as `Sec-WebSocket-Protocol`;
+    /// `Authorization` is sent as well for servers and proxies that expect a bearer token.
+    fn streaming_request(&self, streaming_url: &Url) -> Result<Request, MastodonError> {
+        let mut request = streaming_url.as_str().into_client_request().map_err(|e| {
+            MastodonError::ConnectionFailed(format!("Invalid streaming request: {e}"))
+        })?;
+        request.headers_mut().insert(
+            USER_AGENT,
+            HeaderValue::from_str(&Self::user_agent(&self.config)).map_err(|_| {
+                MastodonError::ConnectionFailed("Invalid User-Agent contact".to_string())
+            })?,
+        );
+
+        if self.uses_header_streaming_auth() {
+            let invalid_token =
+                |_| MastodonError::AuthenticationFailed("Invalid access token".to_string());
+            let headers = request.headers_mut();
+            headers.insert(
+                AUTHORIZATION,
+                HeaderValue::from_str(&format!("Bearer {}", self.config.access_token))
+                    .map_err(invalid_token)?,
+            );
+            headers.insert(
+                SEC_WEBSOCKET_PROTOCOL,
+                HeaderValue::from_str(&self.config.access_token).map_err(invalid_token)?,
+            );
+        }
+
+        Ok(request)
+    }
+
+    /// Whether the user stream is selected (default) rather than a public, hashtag or list stream
+    fn is_user_stream(&self) -> bool {
+        self.config.user_stream.unwrap_or(true)
+    }
+
+    /// Build the `stream=...` query parameters for the configured stream type
+    fn stream_query(&self) -> String {
+        if self.is_user_stream() {
+            return "stream=user".to_string();
+        }
+
+        let encode = |value: &str| {
+            url::form_urlencoded::byte_serialize(value.as_bytes()).collect::<String>()
+        };
+
+        match self.config.stream.as_deref().unwrap_or("public") {
+            "hashtag" => {
+                let tag = self.config.stream_tag.as_deref().unwrap_or("");
+                format!(
+                    "stream=hashtag&tag={}",
+                    encode(tag.trim().trim_start_matches('#'))
+                )
+            }
+            "list" => {
+                let list = self.config.stream_list.as_deref().unwrap_or("");
+                format!("stream=list&list={}", encode(list.trim()))
+            }
+            "public:local" => "stream=public:local".to_string(),
+            _ => "stream=public".to_string(),
+        }
+    }
+
+    /// Whether incoming toots must be authored by the authenticated user to be processed.
+    /// The user stream always filters; other streams filter unless `own_toots_only = false`.
+    fn filters_own_toots(&self) -> bool {
+        self.is_user_stream() || self.config.own_toots_only.unwrap_or(true)
+    }
+
     /// Reconnect with exponential backoff
     async fn reconnect(&mut self) -> Result<(), MastodonError> {
         // Close existing connection if any
-        self.websocket = None;
+        self.set_websocket(None);
 
         loop {
             if self.reconnect_attempts > 0 {
@@ -311,13 +900,14 @@ impl MastodonClient {
                     )),
                     self.reconnect_attempts,
                 );
+                let delay = jittered_delay(delay);
 
                 warn!(
-                    "Reconnecting to Mastodon WebSocket in {} seconds (attempt {})",
-                    delay,
+                    "Reconnecting to Mastodon WebSocket in {:.1} seconds (attempt {})",
+                    delay.as_secs_f64(),
                     self.reconnect_attempts + 1
                 );
-                sleep(Duration::from_secs(delay)).await;
+                sleep(delay).await;
             }
 
             match self.connect().await {
@@ -327,6 +917,7 @@ impl MastodonClient {
                         self.reconnect_attempts + 1
                     );
                     self.reconnect_attempts = 0;
+                    self.catch_up_missed_toots().await;
                     return Ok(());
                 }
                 Err(e) => {
@@ -351,9 +942,94 @@ impl MastodonClient {
         }
     }
 
+    /// Queue own toots posted while the stream was disconnected so `listen` delivers them first
+    async fn catch_up_missed_toots(&mut self) {
+        let limit = self
+            .config
+            .reconnect_catchup_count
+            .unwrap_or(DEFAULT_RECONNECT_CATCHUP_COUNT);
+        if limit == 0 {
+            return;
+        }
+
+        // Only the user stream maps onto the account's statuses
+        if !self.is_user_stream() {
+            debug!("Skipping reconnect catch-up for non-user stream");
+            return;
+        }
+
+        let Some(since_id) = self.last_seen_id.clone() else {
+            debug!("No toot seen before the reconnect, nothing to catch up");
+            return;
+        };
+
+        match self.get_toots_since(&since_id, limit).await {
+            Ok(toots) => {
+                if !toots.is_empty() {
+                    info!(
+                        "Catching up on {} toots posted while disconnected (since {})",
+                        toots.len(),
+                        since_id
+                    );
+                }
+                // The API returns newest first, process in posting order
+                self.missed_toots.extend(toots.into_iter().rev());
+            }
+            Err(e) => {
+                warn!("Failed to fetch toots missed while disconnected: {}", e);
+            }
+        }
+    }
+
+    /// Fetch the authenticated user's toots newer than `since_id`
+    async fn get_toots_since(
+        &self,
+        since_id: &str,
+        limit: u32,
+    ) -> Result<Vec<TootEvent>, MastodonError> {
+        let user_id = self
+            .authenticated_user_id
+            .as_ref()
+            .ok_or(MastodonError::UserVerificationFailed)?;
+
+        let url = format!(
+            "{}/api/v1/accounts/{}/statuses?since_id={}&limit={}&exclude_reblogs=true",
+            self.config.instance_url.trim_end_matches('/'),
+            user_id,
+            since_id,
+            limit.min(40) // Mastodon API limit is 40
+        );
+
+        debug!("Fetching toots since {}: {}", since_id, url);
+
+        let response = self
+            .send_with_gateway_retry(|| self.http_client.get(&url), "fetch missed toots")
+            .await?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let error_text = response.text().await.unwrap_or_default();
+            return Err(MastodonError::ApiRequestFailed(format!(
+                "Missed toots API request failed with status {status}: {error_text}"
+            )));
+        }
+
+        response.json().await.map_err(|e| {
+            MastodonError::InvalidTootData(format!("Failed to parse missed toots response: {e}"))
+        })
+    }
+
+    /// Remember the newest toot delivered so a reconnect can catch up from it
+    fn track_seen(&mut self, toot: &TootEvent) {
+        // Edits refer to older toots and must not move the marker back
+        if !toot.is_edit {
+            self.last_seen_id = Some(toot.id.clone());
+        }
+    }
+
     /// Parse streaming event from WebSocket message
     fn parse_streaming_event(&self, message: &str) -> Result<Option<TootEvent>, MastodonError> {
-        debug!("Received WebSocket message: {}", message);
+        debug!("Received WebSocket message: {}", log_text(message));
 
         let stream_event: StreamEvent = serde_json::from_str(message).map_err(|e| {
             MastodonError::InvalidTootData(format!("Failed to parse stream event: {e}"))
@@ -362,7 +1038,18 @@ impl MastodonClient {
         match stream_event.event.as_str() {
             "update" | "status.update" => {
                 if let Some(payload) = stream_event.payload {
-                    let mut toot: TootEvent = serde_json::from_str(&payload).map_err(|e| {
+                    let status: serde_json::Value =
+                        serde_json::from_str(&payload).map_err(|e| {
+                            MastodonError::InvalidTootData(format!("Failed to parse toot: {e}"))
+                        })?;
+
+                    // Boosts carry someone else's status in `reblog`; never touch those
+                    if status.get("reblog").is_some_and(|reblog| !reblog.is_null()) {
+                        debug!("Received boost in {} event, ignoring", stream_event.event);
+                        return Ok(None);
+                    }
+
+                    let mut toot: TootEvent = serde_json::from_value(status).map_err(|e| {
                         MastodonError::InvalidTootData(format!("Failed to parse toot: {e}"))
                     })?;
 
@@ -370,12 +1057,13 @@ impl MastodonClient {
                     toot.is_edit = stream_event.event == "status.update";
 
                     debug!(
-                        "Parsed {} event: id={}, account={}, media_count={}, is_edit={}",
+                        "Parsed {} event: id={}, account={}, media_count={}, is_edit={}, filters={}",
                         stream_event.event,
                         toot.id,
                         toot.account.id,
                         toot.media_attachments.len(),
-                        toot.is_edit
+                        toot.is_edit,
+                        toot.filtered.len()
                     );
 
                     Ok(Some(toot))
@@ -400,7 +1088,7 @@ impl MastodonClient {
     }
 
     /// Check if toot is from authenticated user
-    fn is_own_toot(&self, toot: &TootEvent) -> Result<bool, MastodonError> {
+    pub fn is_own_toot(&self, toot: &TootEvent) -> Result<bool, MastodonError> {
         match &self.authenticated_user_id {
             Some(user_id) => Ok(toot.account.id == *user_id),
             None => Err(MastodonError::UserVerificationFailed),
@@ -416,7 +1104,11 @@ impl MastodonClient {
 
         let client = self.clone();
 
-        tokio::spawn(async move {
+        let cleanup = async move {
+            let _slot = Arc::clone(&client.cleanup_tasks)
+                .acquire_owned()
+                .await
+                .expect("cleanup semaphore is never closed");
             // Initial delay to let Mastodon process the status update (increased from 5s to 10s)
             tokio::time::sleep(tokio::time::Duration::from_secs(10)).await;
 
@@ -429,7 +1121,12 @@ impl MastodonClient {
                 let mut any_currently_used = false;
 
                 for media_id in &media_ids {
-                    match client.delete_media_attachment(media_id).await {
+                    // An in-flight slot only for the request itself, not across the retry sleeps
+                    let result = {
+                        let _permit = client.acquire_inflight().await;
+                        client.delete_media_attachment(media_id).await
+                    };
+                    match result {
                         Ok(()) => {
                             debug!("Successfully cleaned up media attachment: {}", media_id);
                         }
@@ -471,31 +1168,128 @@ impl MastodonClient {
                     MAX_RETRIES
                 );
             }
-        });
+        };
+        tokio::spawn(cleanup.in_current_span());
     }
 
-    /// Delete a single media attachment
-    async fn delete_media_attachment(&self, media_id: &str) -> Result<(), MastodonError> {
-        let url = format!(
-            "{}/api/v1/media/{}",
-            self.config.instance_url.trim_end_matches('/'),
-            media_id
-        );
+    /// Remember the rate limit budget reported in the response headers
+    fn record_rate_limit(&self, response: &reqwest::Response) {
+        let header = |name: &str| {
+            response
+                .headers()
+                .get(name)
+                .and_then(|value| value.to_str().ok())
+        };
 
-        debug!("Deleting orphaned media attachment: {}", media_id);
+        let mut state = self.rate_limit.lock().unwrap();
 
-        let response = self
-            .http_client
-            .delete(&url)
-            .header(
-                "Authorization",
-                format!("Bearer {}", self.config.access_token),
-            )
-            .send()
-            .await
+        if let Some(remaining) = header("x-ratelimit-remaining").and_then(|v| v.parse().ok()) {
+            state.remaining = Some(remaining);
+            state.reset_at = header("x-ratelimit-reset")
+                .and_then(|v| DateTime::parse_from_rfc3339(v).ok())
+                .map(|reset| reset.with_timezone(&Utc));
+        }
+
+        // A 429 exhausts the budget until Retry-After has passed
+        if response.status() == 429 {
+            if let Some(retry_after) = header("retry-after").and_then(|v| v.parse::<i64>().ok()) {
+                state.remaining = Some(0);
+                state.reset_at = Some(Utc::now() + chrono::Duration::seconds(retry_after));
+            }
+        }
+    }
+
+    /// Wait for the rate limit to reset before a write if the remaining budget is low
+    async fn wait_for_rate_limit(&self) {
+        let wait = {
+            let mut state = self.rate_limit.lock().unwrap();
+            match (state.remaining, state.reset_at) {
+                (Some(remaining), Some(reset_at)) if remaining <= RATE_LIMIT_LOW_WATERMARK => {
+                    // The budget is replenished once the reset time has passed
+                    state.remaining = None;
+                    (reset_at - Utc::now()).to_std().ok()
+                }
+                _ => None,
+            }
+        };
+
+        if let Some(wait) = wait {
+            let wait = wait.min(MAX_RATE_LIMIT_WAIT);
+            warn!(
+                "Mastodon rate limit nearly exhausted, waiting {:.1} seconds for it to reset",
+                wait.as_secs_f64()
+            );
+            sleep(wait).await;
+        }
+    }
+
+    /// Send an authenticated request, retrying transient gateway errors (502/503/504) with backoff
+    ///
+    /// The last response is returned once retries are exhausted so callers can map it as usual.
+    async fn send_with_gateway_retry(
+        &self,
+        request_fn: impl Fn() -> reqwest::RequestBuilder,
+        action: &str,
+    ) -> Result<reqwest::Response, MastodonError> {
+        let mut attempt = 0;
+
+        loop {
+            let response = request_fn()
+                .header(
+                    "Authorization",
+                    format!("Bearer {}", self.config.access_token),
+                )
+                .send()
+                .await
+                .map_err(|e| MastodonError::ApiRequestFailed(format!("Failed to {action}: {e}")))?;
+            self.record_rate_limit(&response);
+
+            let status = response.status().as_u16();
+            if !matches!(status, 502..=504) || attempt >= GATEWAY_RETRY_DELAYS.len() {
+                return Ok(response);
+            }
+
+            let delay = GATEWAY_RETRY_DELAYS[attempt];
+            warn!(
+                "Mastodon returned HTTP {} trying to {}, retrying in {} seconds (attempt {}/{})",
+                status,
+                action,
+                delay,
+                attempt + 1,
+                GATEWAY_RETRY_DELAYS.len()
+            );
+            sleep(Duration::from_secs(delay)).await;
+            attempt += 1;
+        }
+    }
+
+    /// Delete a single media attachment
+    pub(crate) async fn delete_media_attachment(
+        &self,
+        media_id: &str,
+    ) -> Result<(), MastodonError> {
+        let url = format!(
+            "{}/api/v1/media/{}",
+            self.config.instance_url.trim_end_matches('/'),
+            media_id
+        );
+
+        debug!("Deleting orphaned media attachment: {}", media_id);
+
+        self.wait_for_rate_limit().await;
+        let response = self
+            .http_client
+            .delete(&url)
+            .header(
+                "Authorization",
+                format!("Bearer {}", self.config.access_token),
+            )
+            .send()
+            .await
             .map_err(|e| {
                 MastodonError::ApiRequestFailed(format!("Failed to delete media {media_id}: {e}"))
             })?;
+        self.record_rate_limit(&response);
 
         if response.status() == 404 {
             // Media not found - could have already been deleted or never existed
@@ -574,9 +1368,13 @@ impl MastodonStream for MastodonClient {
         }
 
         let streaming_url = self.resolve_streaming_url().await?;
-        debug!("Connecting to WebSocket URL: {}", streaming_url);
+        debug!(
+            "Connecting to WebSocket URL: {}",
+            log_url(streaming_url.as_str())
+        );
 
-        let (ws_stream, response) = connect_async(streaming_url.as_str()).await.map_err(|e| {
+        let request = self.streaming_request(&streaming_url)?;
+        let (ws_stream, response) = connect_async(request).await.map_err(|e| {
             MastodonError::ConnectionFailed(format!("WebSocket connection failed: {e}"))
         })?;
 
@@ -585,7 +1383,7 @@ impl MastodonStream for MastodonClient {
             response.status()
         );
 
-        self.websocket = Some(ws_stream);
+        self.set_websocket(Some(ws_stream));
         self.reconnect_attempts = 0;
 
         info!("Successfully connected to Mastodon WebSocket streaming API");
@@ -593,7 +1391,15 @@ impl MastodonStream for MastodonClient {
     }
 
     /// Listen for toot events from WebSocket stream
+    #[tracing::instrument(skip_all)]
     async fn listen(&mut self) -> Result<Option<TootEvent>, MastodonError> {
+        // Deliver toots missed during a disconnect before resuming the live stream
+        if let Some(toot) = self.missed_toots.pop_front() {
+            debug!("Delivering missed toot: {}", toot.id);
+            self.track_seen(&toot);
+            return Ok(Some(toot));
+        }
+
         loop {
             let websocket = match &mut self.websocket {
                 Some(ws) => ws,
@@ -619,9 +1425,17 @@ impl MastodonStream for MastodonClient {
                             Ok(Message::Text(text)) => {
                             match self.parse_streaming_event(&text) {
                                 Ok(Some(toot)) => {
+                                    // Non-user streams may opt out of the ownership filter
+                                    if !self.filters_own_toots() {
+                                        debug!("Received stream toot: {}", toot.id);
+                                        self.track_seen(&toot);
+                                        return Ok(Some(toot));
+                                    }
+
                                     // Check if this is the authenticated user's toot
                                     if self.is_own_toot(&toot)? {
                                         debug!("Received own toot: {}", toot.id);
+                                        self.track_seen(&toot);
                                         return Ok(Some(toot));
                                     }
                                     debug!("Ignoring toot from other user: {}", toot.account.acct);
@@ -636,7 +1450,7 @@ impl MastodonStream for MastodonClient {
                         }
                             Ok(Message::Close(_)) => {
                             warn!("WebSocket connection closed by server");
-                            self.websocket = None;
+                            self.set_websocket(None);
                             self.reconnect().await?;
                             continue;
                         }
@@ -644,7 +1458,7 @@ impl MastodonStream for MastodonClient {
                             debug!("Received WebSocket ping, sending pong");
                             if let Err(e) = websocket.send(Message::Pong(data)).await {
                                 warn!("Failed to send pong: {}", e);
-                                self.websocket = None;
+                                self.set_websocket(None);
                                 self.reconnect().await?;
                             }
                         }
@@ -660,20 +1474,20 @@ impl MastodonStream for MastodonClient {
                         }
                             Err(e) => {
                             error!("WebSocket error: {}", e);
-                            self.websocket = None;
+                            self.set_websocket(None);
                             return Err(MastodonError::Disconnected(format!("WebSocket error: {e}")));
                         }
                         }
                         Ok(None) => {
                             warn!("WebSocket stream ended unexpectedly");
-                            self.websocket = None;
+                            self.set_websocket(None);
                             self.reconnect().await?;
                             continue;
                         }
                         Err(_timeout) => {
                             warn!("WebSocket operation timed out after {} seconds - connection may be dead",
                                   timeout_duration.as_secs());
-                            self.websocket = None;
+                            self.set_websocket(None);
                             self.reconnect().await?;
                             continue;
                         }
@@ -685,7 +1499,7 @@ impl MastodonStream for MastodonClient {
                     debug!("Sending periodic ping to detect dead connections");
                     if let Err(e) = websocket.send(Message::Ping(vec![].into())).await {
                         warn!("Failed to send periodic ping: {}", e);
-                        self.websocket = None;
+                        self.set_websocket(None);
                         self.reconnect().await?;
                         continue;
                     }
@@ -705,15 +1519,8 @@ impl MastodonStream for MastodonClient {
         debug!("Fetching toot state: {}", url);
 
         let response = self
-            .http_client
-            .get(&url)
-            .header(
-                "Authorization",
-                format!("Bearer {}", self.config.access_token),
-            )
-            .send()
-            .await
-            .map_err(|e| MastodonError::ApiRequestFailed(format!("Failed to fetch toot: {e}")))?;
+            .send_with_gateway_retry(|| self.http_client.get(&url), "fetch toot")
+            .await?;
 
         if response.status() == 404 {
             return Err(MastodonError::TootNotFound {
@@ -762,6 +1569,7 @@ impl MastodonStream for MastodonClient {
             .map_err(|e| {
                 MastodonError::ApiRequestFailed(format!("Failed to fetch status source: {e}"))
             })?;
+        self.record_rate_limit(&response);
 
         if response.status() == 404 {
             return Err(MastodonError::TootNotFound {
@@ -801,6 +1609,7 @@ impl MastodonStream for MastodonClient {
     }
 
     /// Update multiple media attachment descriptions by editing the status
+    #[tracing::instrument(skip_all, fields(toot_id = %toot_id, media_count = media_updates.len()))]
     async fn update_multiple_media(
         &self,
         toot_id: &str,
@@ -816,18 +1625,21 @@ impl MastodonStream for MastodonClient {
             toot_id
         );
 
+        // Get current status to preserve its poll
+        let current_status = self.get_toot(toot_id).await?;
+        let poll_params = Self::poll_edit_params(&current_status)?;
+
         // Get original status text from source API to preserve exact original text
         let status_source = self.get_status_source(toot_id).await?;
+        self.check_empty_text_edit(toot_id, &status_source.text)?;
 
-        // Use zero-width space for empty content to allow media description updates
-        // Otherwise use original text exactly as-is without any HTML processing
-        let status_text = if status_source.text.trim().is_empty() {
-            debug!("Using zero-width space for empty content to enable media description update");
-            ZERO_WIDTH_SPACE.to_string()
-        } else {
-            debug!("Using original status text exactly as-is");
-            status_source.text
-        };
+        // Original text exactly as-is without any HTML processing, checked against the
+        // instance limit so the edit isn't rejected with a 422
+        let status_text = Self::edit_status_text(
+            status_source.text,
+            &status_source.spoiler_text,
+            self.max_characters().await,
+        )?;
 
         let url = format!(
             "{}/api/v1/statuses/{}",
@@ -835,66 +1647,85 @@ impl MastodonStream for MastodonClient {
             toot_id
         );
 
-        // Prepare form data with the current status text and media attributes
-        let mut form_data = std::collections::HashMap::new();
-        form_data.insert("status".to_string(), status_text);
+        // A batch containing a media ID the instance rejects is retried without it, so the
+        // remaining descriptions still land
+        let mut media_updates = media_updates;
+        let response = loop {
+            // Prepare form data with the current status text, media attributes and poll
+            let mut form_data = vec![("status".to_string(), status_text.clone())];
+
+            for (index, (media_id, description)) in media_updates.iter().enumerate() {
+                form_data.push((format!("media_attributes[{index}][id]"), media_id.clone()));
+                form_data.push((
+                    format!("media_attributes[{index}][description]"),
+                    description.clone(),
+                ));
+                debug!(
+                    "  - media[{index}]: id={media_id}, description_length={}",
+                    description.len()
+                );
+            }
+            form_data.extend(poll_params.iter().cloned());
+            Self::check_editable_fields(form_data.iter().map(|(key, _)| key.as_str()))?;
+
+            self.wait_for_rate_limit().await;
+            let response = self
+                .send_with_gateway_retry(
+                    || self.http_client.put(&url).form(&form_data),
+                    "update status",
+                )
+                .await?;
+
+            if response.status() == 404 {
+                return Err(MastodonError::MediaNotFound {
+                    media_id: format!(
+                        "one of: {:?}",
+                        media_updates.iter().map(|(id, _)| id).collect::<Vec<_>>()
+                    ),
+                });
+            }
 
-        for (index, (media_id, description)) in media_updates.iter().enumerate() {
-            form_data.insert(format!("media_attributes[{index}][id]"), media_id.clone());
-            form_data.insert(
-                format!("media_attributes[{index}][description]"),
-                description.clone(),
-            );
-            debug!(
-                "  - media[{index}]: id={media_id}, description_length={}",
-                description.len()
-            );
-        }
+            if response.status() == 429 {
+                let retry_after = response
+                    .headers()
+                    .get("retry-after")
+                    .and_then(|h| h.to_str().ok())
+                    .and_then(|s| s.parse().ok())
+                    .unwrap_or(60);
 
-        let response = self
-            .http_client
-            .put(&url)
-            .header(
-                "Authorization",
-                format!("Bearer {}", self.config.access_token),
-            )
-            .form(&form_data)
-            .send()
-            .await
-            .map_err(|e| {
-                MastodonError::ApiRequestFailed(format!("Failed to update status: {e}"))
-            })?;
+                return Err(MastodonError::RateLimitExceeded { retry_after });
+            }
 
-        if response.status() == 404 {
-            return Err(MastodonError::MediaNotFound {
-                media_id: format!(
-                    "one of: {:?}",
-                    media_updates.iter().map(|(id, _)| id).collect::<Vec<_>>()
-                ),
-            });
-        }
+            if !response.status().is_success() {
+                let status = response.status();
+                let error_text = response.text().await.unwrap_or_default();
 
-        if response.status() == 429 {
-            let retry_after = response
-                .headers()
-                .get("retry-after")
-                .and_then(|h| h.to_str().ok())
-                .and_then(|s| s.parse().ok())
-                .unwrap_or(60);
+                if status == 422 {
+                    let rejected = Self::rejected_media_ids(&error_text, &media_updates);
+                    if !rejected.is_empty() && rejected.len() < media_updates.len() {
+                        for media_id in &rejected {
+                            warn!(
+                                "Instance rejected media {media_id} of toot {toot_id}, retrying without it: {error_text}"
+                            );
+                        }
+                        media_updates.retain(|(media_id, _)| !rejected.contains(media_id));
+                        continue;
+                    }
+                }
 
-            return Err(MastodonError::RateLimitExceeded { retry_after });
-        }
+                error!(
+                    "Failed to update media descriptions via status edit: HTTP {status}: {error_text}"
+                );
+                return Err(MastodonError::ApiRequestFailed(format!(
+                    "Media update failed with status {status}: {error_text}"
+                )));
+            }
 
-        if !response.status().is_success() {
-            let status = response.status();
-            let error_text = response.text().await.unwrap_or_default();
-            error!(
-                "Failed to update media descriptions via status edit: HTTP {status}: {error_text}"
-            );
-            return Err(MastodonError::ApiRequestFailed(format!(
-                "Media update failed with status {status}: {error_text}"
-            )));
-        }
+            break response;
+        };
+
+        let edited = response.text().await.unwrap_or_default();
+        Self::check_reply_context(&current_status, &edited);
 
         info!(
             "Successfully updated {} media descriptions for toot: {toot_id}",
@@ -920,6 +1751,7 @@ impl MastodonStream for MastodonClient {
         params.insert("visibility", "direct");
         params.insert("in_reply_to_id", user_id);
 
+        self.wait_for_rate_limit().await;
         let response = self
             .http_client
             .post(&url)
@@ -931,6 +1763,7 @@ impl MastodonStream for MastodonClient {
             .send()
             .await
             .map_err(|e| MastodonError::ApiRequestFailed(format!("Failed to send DM: {e}")))?;
+        self.record_rate_limit(&response);
 
         if !response.status().is_success() {
             let status = response.status();
@@ -944,6 +1777,55 @@ impl MastodonStream for MastodonClient {
         Ok(())
     }
 
+    /// Post a reply status and return its ID
+    async fn post_reply(
+        &self,
+        in_reply_to_id: &str,
+        status: &str,
+        visibility: &str,
+    ) -> Result<String, MastodonError> {
+        let url = format!(
+            "{}/api/v1/statuses",
+            self.config.instance_url.trim_end_matches('/')
+        );
+
+        let params = [
+            ("status", status),
+            ("in_reply_to_id", in_reply_to_id),
+            ("visibility", visibility),
+        ];
+
+        self.wait_for_rate_limit().await;
+        let response = self
+            .http_client
+            .post(&url)
+            .header(
+                "Authorization",
+                format!("Bearer {}", self.config.access_token),
+            )
+            .form(&params)
+            .send()
+            .await
+            .map_err(|e| MastodonError::ApiRequestFailed(format!("Failed to post reply: {e}")))?;
+        self.record_rate_limit(&response);
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let error_text = response.text().await.unwrap_or_default();
+            return Err(MastodonError::ApiRequestFailed(format!(
+                "Reply failed with status {status}: {error_text}"
+            )));
+        }
+
+        let reply: serde_json::Value = response.json().await.map_err(|e| {
+            MastodonError::InvalidTootData(format!("Failed to parse reply status: {e}"))
+        })?;
+        reply["id"]
+            .as_str()
+            .map(str::to_string)
+            .ok_or_else(|| MastodonError::InvalidTootData("Reply status has no ID".to_string()))
+    }
+
     /// Verify user credentials and store user ID for ownership checks
     async fn verify_credentials(&mut self) -> Result<Account, MastodonError> {
         let url = format!(
@@ -963,10 +1845,12 @@ impl MastodonStream for MastodonClient {
             .map_err(|e| {
                 MastodonError::ApiRequestFailed(format!("Failed to verify credentials: {e}"))
             })?;
+        self.record_rate_limit(&response);
 
         if !response.status().is_success() {
             let status = response.status();
             let error_text = response.text().await.unwrap_or_default();
+            self.health.set_credentials_verified(false);
             return Err(MastodonError::ApiRequestFailed(format!(
                 "Credential verification failed with status {status}: {error_text}"
             )));
@@ -978,6 +1862,7 @@ impl MastodonStream for MastodonClient {
 
         // Store the authenticated user ID for future ownership checks
         self.authenticated_user_id = Some(account.id.clone());
+        self.health.set_credentials_verified(true);
 
         // Check if the server is blacklisted
         if let Err(e) = self.check_server_blacklist() {
@@ -1020,6 +1905,7 @@ impl MastodonStream for MastodonClient {
             .map_err(|e| {
                 MastodonError::ApiRequestFailed(format!("Failed to fetch user toots: {e}"))
             })?;
+        self.record_rate_limit(&response);
 
         if !response.status().is_success() {
             let status = response.status();
@@ -1045,67 +1931,8 @@ impl MastodonStream for MastodonClient {
         filename: &str,
         media_type: &str,
     ) -> Result<String, MastodonError> {
-        let url = format!(
-            "{}/api/v2/media",
-            self.config.instance_url.trim_end_matches('/')
-        );
-
-        // Validate and sanitize the MIME type
-        let mime_type = Self::validate_and_sanitize_mime_type(media_type, filename)?;
-
-        tracing::debug!(
-            "Creating media attachment with MIME type: '{mime_type}' for file: '{filename}'"
-        );
-
-        // Create multipart form with media data and description
-        let form = reqwest::multipart::Form::new()
-            .part(
-                "file",
-                reqwest::multipart::Part::bytes(media_data)
-                    .file_name(filename.to_string())
-                    .mime_str(&mime_type)
-                    .map_err(|e| {
-                        tracing::error!("Failed to set MIME type '{mime_type}': {e}");
-                        MastodonError::ApiRequestFailed(format!(
-                            "Failed to set MIME type '{mime_type}': {e}"
-                        ))
-                    })?,
-            )
-            .text("description", description.to_string());
-
-        let response = self
-            .http_client
-            .post(&url)
-            .header(
-                "Authorization",
-                format!("Bearer {}", self.config.access_token),
-            )
-            .multipart(form)
-            .send()
+        self.upload_media(media_data, description, filename, media_type, None)
             .await
-            .map_err(|e| {
-                MastodonError::ApiRequestFailed(format!("Failed to create media attachment: {e}"))
-            })?;
-
-        if !response.status().is_success() {
-            let status = response.status();
-            let error_text = response.text().await.unwrap_or_default();
-            return Err(MastodonError::ApiRequestFailed(format!(
-                "Media creation failed with status {status}: {error_text}"
-            )));
-        }
-
-        #[derive(Deserialize)]
-        struct MediaResponse {
-            id: String,
-        }
-
-        let media_response: MediaResponse = response.json().await.map_err(|e| {
-            MastodonError::ApiRequestFailed(format!("Failed to parse media response: {e}"))
-        })?;
-
-        info!("Created new media attachment: id={}", media_response.id);
-        Ok(media_response.id)
     }
 
     /// Recreate media attachments with descriptions and update the status
@@ -1155,11 +1982,12 @@ impl MastodonStream for MastodonClient {
         let mut new_media_ids = Vec::new();
         for (index, recreation) in media_recreations.iter().enumerate() {
             match self
-                .create_media_attachment(
+                .upload_media(
                     recreation.data.clone(),
                     &recreation.description,
                     &recreation.filename,
                     &recreation.media_type,
+                    recreation.focus.as_ref(),
                 )
                 .await
             {
@@ -1217,49 +2045,195 @@ impl MastodonStream for MastodonClient {
 }
 
 impl MastodonClient {
-    /// Update status with new media IDs, handling Mastodon processing delays with retries
-    async fn update_status_with_media_retry(
+    /// Upload a new media attachment with description and optional focus point
+    async fn upload_media(
         &self,
-        toot_id: &str,
-        new_media_ids: Vec<String>,
-        media_count: usize,
-    ) -> Result<(), MastodonError> {
-        const MAX_RETRIES: u32 = 4;
-        // Initial wait + retry delays: 2s, 5s, 10s, 20s
-        const RETRY_DELAYS: [u64; 4] = [2, 5, 10, 20];
-
-        let mut retry_count = 0;
-
-        // Initial delay to let Mastodon process the uploaded media
-        debug!(
-            "Waiting 2 seconds for Mastodon to process {} media attachments",
-            media_count
+        media_data: Vec<u8>,
+        description: &str,
+        filename: &str,
+        media_type: &str,
+        focus: Option<&MediaFocus>,
+    ) -> Result<String, MastodonError> {
+        let url = format!(
+            "{}/api/v2/media",
+            self.config.instance_url.trim_end_matches('/')
         );
-        tokio::time::sleep(tokio::time::Duration::from_secs(2)).await;
 
-        loop {
-            match self.update_status_with_media(toot_id, &new_media_ids).await {
-                Ok(()) => {
-                    info!(
-                        "Successfully recreated {} media attachments for toot: {}",
-                        media_count, toot_id
-                    );
-                    return Ok(());
-                }
-                Err(MastodonError::ApiRequestFailed(ref error_text))
-                    if retry_count < MAX_RETRIES
-                        && (error_text.contains("422")
-                            && (error_text.contains("nicht verarbeitet wurden")  // German
-                            || error_text.contains("not yet been processed")  // English
-                            || error_text.contains("cannot be attached"))) =>
-                {
-                    let delay = RETRY_DELAYS[retry_count as usize];
-                    retry_count += 1;
+        // Validate and sanitize the MIME type, preferring the content's real type over a generic one
+        let media_type = crate::media::resolve_media_type(media_type, &media_data);
+        let mime_type = Self::validate_and_sanitize_mime_type(&media_type, filename)?;
 
-                    warn!(
-                        "Media attachments not yet processed by Mastodon (attempt {}), retrying in {} seconds: {}",
-                        retry_count, delay, error_text
-                    );
+        tracing::debug!(
+            "Creating media attachment with MIME type: '{mime_type}' for file: '{filename}'"
+        );
+
+        // Create multipart form with media data, description and focus
+        let mut form = reqwest::multipart::Form::new()
+            .part(
+                "file",
+                reqwest::multipart::Part::bytes(media_data)
+                    .file_name(filename.to_string())
+                    .mime_str(&mime_type)
+                    .map_err(|e| {
+                        tracing::error!("Failed to set MIME type '{mime_type}': {e}");
+                        MastodonError::ApiRequestFailed(format!(
+                            "Failed to set MIME type '{mime_type}': {e}"
+                        ))
+                    })?,
+            )
+            .text("description", description.to_string());
+        for (name, value) in Self::media_upload_fields(focus) {
+            form = form.text(name, value);
+        }
+
+        self.wait_for_rate_limit().await;
+        let response = self
+            .http_client
+            .post(&url)
+            .header(
+                "Authorization",
+                format!("Bearer {}", self.config.access_token),
+            )
+            .multipart(form)
+            .send()
+            .await
+            .map_err(|e| {
+                MastodonError::ApiRequestFailed(format!("Failed to create media attachment: {e}"))
+            })?;
+        self.record_rate_limit(&response);
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let error_text = response.text().await.unwrap_or_default();
+            return Err(MastodonError::ApiRequestFailed(format!(
+                "Media creation failed with status {status}: {error_text}"
+            )));
+        }
+
+        #[derive(Deserialize)]
+        struct MediaResponse {
+            id: String,
+        }
+
+        let media_response: MediaResponse = response.json().await.map_err(|e| {
+            MastodonError::ApiRequestFailed(format!("Failed to parse media response: {e}"))
+        })?;
+
+        info!("Created new media attachment: id={}", media_response.id);
+        Ok(media_response.id)
+    }
+
+    /// Poll parameters for editing `status`, failing if an edit would remove its poll
+    fn poll_edit_params(status: &TootEvent) -> Result<Vec<(String, String)>, MastodonError> {
+        let Some(poll) = status.poll.as_ref() else {
+            return Ok(Vec::new());
+        };
+
+        let params = poll.edit_params(Utc::now()).ok_or_else(|| {
+            MastodonError::InvalidTootData(format!(
+                "status {} has a poll that would be removed by editing",
+                status.id
+            ))
+        })?;
+        debug!("Re-applying poll {} in status edit", poll.id);
+        Ok(params)
+    }
+
+    /// Media IDs of the batch that a 422 error names, matched as whole words
+    fn rejected_media_ids(error_text: &str, media_updates: &[(String, String)]) -> Vec<String> {
+        let words: HashSet<&str> = error_text
+            .split(|c: char| !c.is_alphanumeric() && c != '_')
+            .collect();
+        media_updates
+            .iter()
+            .map(|(media_id, _)| media_id)
+            .filter(|media_id| words.contains(media_id.as_str()))
+            .cloned()
+            .collect()
+    }
+
+    /// Refuse to send an edit form with fields the edit API rejects with a 422
+    fn check_editable_fields<'a>(
+        mut keys: impl Iterator<Item = &'a str>,
+    ) -> Result<(), MastodonError> {
+        match keys.find(|key| IMMUTABLE_STATUS_FIELDS.contains(key)) {
+            Some(key) => Err(MastodonError::InvalidTootData(format!(
+                "Status edits can't change '{key}'"
+            ))),
+            None => Ok(()),
+        }
+    }
+
+    /// Whether the edited status returned by the instance still replies to the same toot
+    ///
+    /// A mismatch is logged; responses that aren't a full status are assumed intact.
+    fn check_reply_context(before: &TootEvent, response_body: &str) -> bool {
+        let Ok(after) = serde_json::from_str::<TootEvent>(response_body) else {
+            debug!("Edit response for {} is not a full status", before.id);
+            return true;
+        };
+
+        let intact = after.in_reply_to_id == before.in_reply_to_id
+            && after.in_reply_to_account_id == before.in_reply_to_account_id;
+        if !intact {
+            error!(
+                "Edit of toot {} changed its reply context from {:?} to {:?}",
+                before.id, before.in_reply_to_id, after.in_reply_to_id
+            );
+        }
+        intact
+    }
+
+    /// Additional form fields for a media upload that preserve original attachment metadata
+    fn media_upload_fields(focus: Option<&MediaFocus>) -> Vec<(&'static str, String)> {
+        focus
+            .map(|focus| vec![("focus", focus.to_param())])
+            .unwrap_or_default()
+    }
+
+    /// Update status with new media IDs, handling Mastodon processing delays with retries
+    async fn update_status_with_media_retry(
+        &self,
+        toot_id: &str,
+        new_media_ids: Vec<String>,
+        media_count: usize,
+    ) -> Result<(), MastodonError> {
+        const MAX_RETRIES: u32 = 4;
+        // Initial wait + retry delays: 2s, 5s, 10s, 20s
+        const RETRY_DELAYS: [u64; 4] = [2, 5, 10, 20];
+
+        let mut retry_count = 0;
+
+        // Initial delay to let Mastodon process the uploaded media
+        debug!(
+            "Waiting 2 seconds for Mastodon to process {} media attachments",
+            media_count
+        );
+        tokio::time::sleep(tokio::time::Duration::from_secs(2)).await;
+
+        loop {
+            match self.update_status_with_media(toot_id, &new_media_ids).await {
+                Ok(()) => {
+                    info!(
+                        "Successfully recreated {} media attachments for toot: {}",
+                        media_count, toot_id
+                    );
+                    return Ok(());
+                }
+                Err(MastodonError::ApiRequestFailed(ref error_text))
+                    if retry_count < MAX_RETRIES
+                        && (error_text.contains("422")
+                            && (error_text.contains("nicht verarbeitet wurden")  // German
+                            || error_text.contains("not yet been processed")  // English
+                            || error_text.contains("cannot be attached"))) =>
+                {
+                    let delay = RETRY_DELAYS[retry_count as usize];
+                    retry_count += 1;
+
+                    warn!(
+                        "Media attachments not yet processed by Mastodon (attempt {}), retrying in {} seconds: {}",
+                        retry_count, delay, error_text
+                    );
 
                     tokio::time::sleep(tokio::time::Duration::from_secs(delay)).await;
                 }
@@ -1309,17 +2283,13 @@ impl MastodonClient {
 
         debug!("Original content HTML: {}", current_status.content);
         debug!("Source text: '{}'", status_source.text);
+        self.check_empty_text_edit(toot_id, &status_source.text)?;
 
-        // Use zero-width space for empty content to allow media description updates
-        // Mastodon requires text content when updating a status, but we want to support
-        // adding descriptions to media-only posts
-        let status_content = if status_source.text.trim().is_empty() {
-            debug!("Using zero-width space for empty content to enable media description update");
-            ZERO_WIDTH_SPACE.to_string()
-        } else {
-            debug!("Including original status text from source in update");
-            status_source.text
-        };
+        let status_content = Self::edit_status_text(
+            status_source.text,
+            &status_source.spoiler_text,
+            self.max_characters().await,
+        )?;
 
         // Create form data as a vector of tuples to properly handle array parameters
         let mut form_data = Vec::new();
@@ -1338,14 +2308,22 @@ impl MastodonClient {
             form_data.push(("language", lang.as_str()));
         }
 
-        // Note: visibility and in_reply_to_id are NOT supported by the edit status API
-        // These fields are immutable after status creation and sending them causes 422 errors
+        // Visibility and in_reply_to_id are immutable and left out; the status keeps its ID,
+        // so replies to it and its place in a thread are unaffected by swapping the media
 
         // Add new media IDs as array parameters
         for media_id in new_media_ids.iter() {
             form_data.push(("media_ids[]", media_id.as_str()));
         }
 
+        // Edits without poll parameters would delete the poll
+        let poll_params = Self::poll_edit_params(&current_status)?;
+        for (key, value) in &poll_params {
+            form_data.push((key.as_str(), value.as_str()));
+        }
+        Self::check_editable_fields(form_data.iter().map(|(key, _)| *key))?;
+
+        self.wait_for_rate_limit().await;
         let response = self
             .http_client
             .put(&url)
@@ -1359,6 +2337,7 @@ impl MastodonClient {
             .map_err(|e| {
                 MastodonError::ApiRequestFailed(format!("Failed to update status: {e}"))
             })?;
+        self.record_rate_limit(&response);
 
         if !response.status().is_success() {
             let status = response.status();
@@ -1368,6 +2347,9 @@ impl MastodonClient {
             )));
         }
 
+        let edited = response.text().await.unwrap_or_default();
+        Self::check_reply_context(&current_status, &edited);
+
         Ok(())
     }
 }
@@ -1424,6 +2406,7 @@ impl MastodonClient {
             "video/mp4" => "video/mp4".to_string(),
             "video/webm" => "video/webm".to_string(),
             "video" => "video/mp4".to_string(), // Default to MP4
+            "gifv" => "video/mp4".to_string(),  // Uploaded GIFs are silent MP4s
             // Fallback for unknown types
             _ => {
                 // Try to determine from filename extension as last resort
@@ -1468,7 +2451,7 @@ impl MastodonClient {
         // Check for basic MIME type format (type/subtype)
         if !trimmed_type.contains('/') {
             // This is a common case for Mastodon media types like "audio", "image", "video"
-            if matches!(trimmed_type, "audio" | "image" | "video") {
+            if matches!(trimmed_type, "audio" | "image" | "video" | "gifv") {
                 tracing::debug!("Generic media type '{trimmed_type}' detected, using filename detection for: {filename}");
             } else {
                 tracing::warn!("Invalid MIME type format '{trimmed_type}', falling back to filename detection for: {filename}");
@@ -1495,7 +2478,6 @@ impl MastodonClient {
     }
 
     /// Extract plain text from HTML content
-    #[allow(dead_code)] // Used in tests and integration tests
     pub fn extract_text_from_html(html: &str) -> String {
         // Simple HTML tag removal - this is basic but should work for our needs
         let mut text = html.to_string();
@@ -1590,9 +2572,39 @@ mod tests {
         MastodonConfig {
             instance_url: "https://mastodon.social".to_string(),
             access_token: "test_token".to_string(),
+            access_token_file: None,
             user_stream: Some(true),
             backfill_count: Some(25),
             backfill_pause: Some(60),
+            stream: None,
+            stream_tag: None,
+            stream_list: None,
+            own_toots_only: None,
+            state_file: None,
+            failed_toots_file: None,
+            processed_cache_size: None,
+            skip_sensitive: None,
+            skip_spoiler: None,
+            skip_replies: None,
+            respect_filters: None,
+            prefer_inplace_edit: None,
+            reconnect_catchup_count: None,
+            poll_handling: None,
+            processing_timeout_secs: None,
+            streaming_auth: None,
+            process_visibilities: None,
+            shutdown_grace_secs: None,
+            inter_toot_delay_ms: None,
+            new_toot_delay_secs: None,
+            max_inflight: None,
+            queue_capacity: None,
+            queue_overflow: None,
+            workers: None,
+            backfill_since: None,
+            backfill_until: None,
+            user_agent_contact: None,
+            delivery_mode: None,
+            allow_empty_text_edit: None,
         }
     }
 
@@ -1621,13 +2633,16 @@ mod tests {
                         height: Some(1080),
                         size: Some("1920x1080".to_string()),
                         aspect: Some(1.777),
+                        duration: None,
                     }),
                     small: Some(MediaDimensions {
                         width: Some(400),
                         height: Some(225),
                         size: Some("400x225".to_string()),
                         aspect: Some(1.777),
+                        duration: None,
                     }),
+                    focus: None,
                 }),
             }],
             created_at: Utc::now(),
@@ -1641,6 +2656,8 @@ mod tests {
             tags: Vec::new(),
             emojis: Vec::new(),
             poll: None,
+            filtered: Vec::new(),
+            edited_at: None,
             is_edit: false,
         };
 
@@ -1652,6 +2669,26 @@ mod tests {
         serde_json::to_string(&stream_event).unwrap()
     }
 
+    #[test]
+    fn test_split_into_posts_numbers_thread_parts() {
+        assert_eq!(
+            MastodonClient::split_into_posts("A short transcript.", 500),
+            vec!["A short transcript."]
+        );
+
+        let posts = MastodonClient::split_into_posts("one two three four five six", 20);
+        assert_eq!(
+            posts,
+            vec!["one two (1/3)", "three four (2/3)", "five six (3/3)"]
+        );
+        assert!(posts.iter().all(|post| post.chars().count() <= 20));
+
+        // Text without spaces is cut at the limit
+        let posts = MastodonClient::split_into_posts(&"字".repeat(25), 20);
+        assert_eq!(posts.len(), 3);
+        assert!(posts[0].starts_with(&"字".repeat(10)));
+    }
+
     #[test]
     fn test_is_own_toot_matching_user() {
         let config = create_test_config();
@@ -1682,6 +2719,8 @@ mod tests {
             tags: Vec::new(),
             emojis: Vec::new(),
             poll: None,
+            filtered: Vec::new(),
+            edited_at: None,
             is_edit: false,
         };
 
@@ -1719,6 +2758,8 @@ mod tests {
             tags: Vec::new(),
             emojis: Vec::new(),
             poll: None,
+            filtered: Vec::new(),
+            edited_at: None,
             is_edit: false,
         };
 
@@ -1755,6 +2796,8 @@ mod tests {
             tags: Vec::new(),
             emojis: Vec::new(),
             poll: None,
+            filtered: Vec::new(),
+            edited_at: None,
             is_edit: false,
         };
 
@@ -1792,6 +2835,8 @@ mod tests {
             tags: Vec::new(),
             emojis: Vec::new(),
             poll: None,
+            filtered: Vec::new(),
+            edited_at: None,
             is_edit: false,
         };
 
@@ -1820,8 +2865,10 @@ mod tests {
                     height: Some(1080),
                     size: Some("1920x1080".to_string()),
                     aspect: Some(1.777),
+                    duration: None,
                 }),
                 small: None,
+                focus: None,
             }),
         };
 
@@ -1890,6 +2937,84 @@ mod tests {
         assert_eq!(toot.media_attachments.len(), 1);
     }
 
+    #[tokio::test]
+    async fn test_health_state_follows_websocket_and_credentials() {
+        use crate::test_support::{MockHttpServer, MockResponse};
+
+        let server = MockWebSocketServer::new().await;
+        let addr = server.local_addr();
+        let server_handle = tokio::spawn(async move {
+            server.run().await;
+        });
+        tokio::time::sleep(Duration::from_millis(100)).await;
+
+        let mut client = MastodonClient::new(create_test_config());
+        let health = client.clone().health_state();
+        client.health.set_credentials_verified(true);
+        assert!(!health.is_ready());
+
+        let (ws_stream, _) =
+            tokio_tungstenite::connect_async(format!("ws://127.0.0.1:{}/", addr.port()))
+                .await
+                .unwrap();
+        client.set_websocket(Some(ws_stream));
+        assert!(health.is_ready());
+
+        client.set_websocket(None);
+        assert!(!health.is_ready());
+        server_handle.abort();
+
+        // A rejected token clears the verified flag
+        let api =
+            MockHttpServer::start(|_| MockResponse::json(401, r#"{"error":"invalid"}"#)).await;
+        let mut config = create_test_config();
+        config.instance_url = api.url();
+        let mut client = MastodonClient::new(config);
+        let health = client.health_state();
+        client.health.set_websocket_connected(true);
+        client.health.set_credentials_verified(true);
+        assert!(client.verify_credentials().await.is_err());
+        assert!(!health.is_ready());
+    }
+
+    #[tokio::test]
+    async fn test_user_agent_includes_configured_contact() {
+        use crate::test_support::{MockHttpServer, MockResponse};
+
+        let version = env!("CARGO_PKG_VERSION");
+        let client = MastodonClient::new(create_test_config());
+        let request = client
+            .streaming_request(&client.get_streaming_url().unwrap())
+            .unwrap();
+        assert_eq!(
+            request.headers().get(USER_AGENT).unwrap(),
+            format!("Alternator/{version}").as_str()
+        );
+
+        let api = MockHttpServer::start(|_| {
+            MockResponse::json(200, r#"{"id":"1","username":"bot","acct":"bot"}"#)
+        })
+        .await;
+        let mut config = create_test_config();
+        config.instance_url = api.url();
+        config.user_agent_contact = Some("admin@example.org".to_string());
+        let expected = format!("Alternator/{version} (+admin@example.org)");
+        let mut client = MastodonClient::new(config);
+
+        let request = client
+            .streaming_request(&client.get_streaming_url().unwrap())
+            .unwrap();
+        assert_eq!(
+            request.headers().get(USER_AGENT).unwrap(),
+            expected.as_str()
+        );
+
+        let _ = client.verify_credentials().await;
+        let requests = api.requests();
+        assert!(!requests.is_empty());
+        assert_eq!(requests[0].header("user-agent"), Some(expected.as_str()));
+    }
+
     #[test]
     fn test_extract_text_from_html_empty_content() {
         // Test the HTML text extraction with empty content
@@ -2064,12 +3189,8 @@ mod tests {
         ];
 
         for (source_text, expected_status_content) in test_cases {
-            // Simulate the logic from both update functions
-            let status_content = if source_text.trim().is_empty() {
-                ZERO_WIDTH_SPACE.to_string()
-            } else {
-                source_text.to_string()
-            };
+            let status_content =
+                MastodonClient::edit_status_text(source_text.to_string(), "", 500).unwrap();
 
             assert_eq!(
                 status_content, expected_status_content,
@@ -2084,6 +3205,147 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_status_length_counts_like_mastodon() {
+        assert_eq!(MastodonClient::status_length("Hello world"), 11);
+        assert_eq!(MastodonClient::status_length("🎉 ok\n"), 5);
+        assert_eq!(
+            MastodonClient::status_length(
+                "See https://example.com/a/very/long/path/that/goes/on/and/on?query=1 now"
+            ),
+            4 + URL_CHARACTER_COUNT + 4
+        );
+        assert_eq!(
+            MastodonClient::status_length("Hi @alice@social.example.org and @bob"),
+            "Hi @alice and @bob".len()
+        );
+        // Punctuation around a link counts as text, the link itself as 23 characters
+        assert_eq!(
+            MastodonClient::status_length("(https://example.com/a/very/long/path), and"),
+            1 + URL_CHARACTER_COUNT + 2 + 4
+        );
+        assert_eq!(
+            MastodonClient::status_length("https://en.wikipedia.org/wiki/Rust_(language)."),
+            URL_CHARACTER_COUNT + 1
+        );
+        // Not a link without a host, or glued to a word
+        assert_eq!(MastodonClient::status_length("https://"), 8);
+        assert_eq!(
+            MastodonClient::status_length("xhttps://example.com"),
+            "xhttps://example.com".len()
+        );
+    }
+
+    #[test]
+    fn test_instance_limits_parsed_from_instance_response() {
+        let mastodon: serde_json::Value = serde_json::from_str(
+            r#"{"configuration":{"statuses":{"max_characters":500},"media_attachments":{"description_limit":1500}}}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            InstanceLimits::from_instance(&mastodon),
+            InstanceLimits {
+                max_characters: 500,
+                description_limit: Some(1500),
+            }
+        );
+
+        let akkoma: serde_json::Value =
+            serde_json::from_str(r#"{"max_toot_chars":5000,"description_limit":5000}"#).unwrap();
+        assert_eq!(
+            InstanceLimits::from_instance(&akkoma).description_limit,
+            Some(5000)
+        );
+
+        // Older servers don't report an alt text limit
+        let older: serde_json::Value =
+            serde_json::from_str(r#"{"configuration":{"statuses":{"max_characters":500}}}"#)
+                .unwrap();
+        assert_eq!(
+            InstanceLimits::from_instance(&older).description_limit,
+            None
+        );
+        assert_eq!(
+            InstanceLimits::from_instance(&serde_json::json!({})),
+            InstanceLimits::default()
+        );
+    }
+
+    #[test]
+    fn test_edit_status_text_respects_limit() {
+        // Trailing whitespace is dropped when only it exceeds the limit
+        let text = MastodonClient::edit_status_text("0123456789\n\n".to_string(), "", 10).unwrap();
+        assert_eq!(text, "0123456789");
+
+        // The author's text itself is never cut
+        let result = MastodonClient::edit_status_text("0123456789ab".to_string(), "", 10);
+        assert!(matches!(
+            result,
+            Err(MastodonError::StatusTooLong {
+                length: 12,
+                limit: 10
+            })
+        ));
+
+        // The content warning counts towards the same limit
+        let result = MastodonClient::edit_status_text("0123456".to_string(), "CW: food", 10);
+        assert!(matches!(
+            result,
+            Err(MastodonError::StatusTooLong {
+                length: 15,
+                limit: 10
+            })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_over_limit_status_not_edited() {
+        use crate::test_support::{MockHttpServer, MockResponse};
+
+        let server = MockHttpServer::start(|request| {
+            if request.path == "/api/v1/instance" {
+                MockResponse::json(
+                    200,
+                    r#"{"configuration":{"statuses":{"max_characters":20}}}"#,
+                )
+            } else if request.path.ends_with("/source") {
+                MockResponse::json(
+                    200,
+                    r#"{"id":"123","text":"This toot is longer than twenty characters","spoiler_text":""}"#,
+                )
+            } else if request.method == "GET" {
+                MockResponse::json(200, &status_json("123"))
+            } else {
+                MockResponse::json(422, r#"{"error":"Text character limit of 20 exceeded"}"#)
+            }
+        })
+        .await;
+        let mut config = create_test_config();
+        config.instance_url = server.url();
+        let client = MastodonClient::new(config);
+        let updates = vec![("media_1".to_string(), "desc".to_string())];
+
+        for _ in 0..2 {
+            let result = client
+                .clone()
+                .update_multiple_media("123", updates.clone())
+                .await;
+            assert!(matches!(
+                result,
+                Err(MastodonError::StatusTooLong { limit: 20, .. })
+            ));
+        }
+
+        // The limit is fetched once and shared between clones; no edit is attempted
+        let requests = server.requests();
+        let instance_requests = requests
+            .iter()
+            .filter(|r| r.path == "/api/v1/instance")
+            .count();
+        assert_eq!(instance_requests, 1);
+        assert!(requests.iter().all(|r| r.method == "GET"));
+    }
+
     #[test]
     fn test_error_recovery_integration() {
         // Test that MastodonError variants work with ErrorRecovery
@@ -2105,6 +3367,21 @@ mod tests {
         assert!(ErrorRecovery::should_shutdown(&auth_error));
     }
 
+    #[test]
+    fn test_jittered_delay_within_bounds() {
+        for base in [1, 8, 60] {
+            let min = base as f64 * (1.0 - RECONNECT_JITTER);
+            let max = base as f64 * (1.0 + RECONNECT_JITTER);
+            let samples: Vec<f64> = (0..1000)
+                .map(|_| jittered_delay(base).as_secs_f64())
+                .collect();
+
+            assert!(samples.iter().all(|delay| *delay >= min && *delay <= max));
+            // Delays should actually be spread out rather than constant
+            assert!(samples.iter().any(|delay| *delay != samples[0]));
+        }
+    }
+
     #[test]
     fn test_client_clone() {
         let config = create_test_config();
@@ -2162,6 +3439,7 @@ mod tests {
             height: Some(1080),
             size: Some("1920x1080".to_string()),
             aspect: Some(1.777),
+            duration: None,
         };
 
         let json = serde_json::to_string(&dimensions).unwrap();
@@ -2183,13 +3461,16 @@ mod tests {
                 height: Some(1080),
                 size: Some("1920x1080".to_string()),
                 aspect: Some(1.777),
+                duration: None,
             }),
             small: Some(MediaDimensions {
                 width: Some(400),
                 height: Some(225),
                 size: Some("400x225".to_string()),
                 aspect: Some(1.777),
+                duration: None,
             }),
+            focus: None,
         };
 
         let json = serde_json::to_string(&meta).unwrap();
@@ -2201,6 +3482,625 @@ mod tests {
         assert!(deserialized.small.is_some());
     }
 
+    #[test]
+    fn test_media_meta_focus_parsing() {
+        let json = r#"{
+            "focus": {"x": -0.42, "y": 0.69},
+            "original": {"width": 640, "height": 480}
+        }"#;
+        let meta: MediaMeta = serde_json::from_str(json).unwrap();
+        assert_eq!(meta.focus, Some(MediaFocus { x: -0.42, y: 0.69 }));
+        assert!(meta.small.is_none());
+
+        // Focus is optional
+        let meta: MediaMeta = serde_json::from_str(r#"{"original": null}"#).unwrap();
+        assert!(meta.focus.is_none());
+    }
+
+    #[test]
+    fn test_media_focus_param() {
+        assert_eq!(MediaFocus { x: -0.42, y: 0.69 }.to_param(), "-0.42,0.69");
+        assert_eq!(MediaFocus { x: 0.0, y: 0.0 }.to_param(), "0,0");
+        // Out-of-range values are clamped
+        assert_eq!(MediaFocus { x: 1.5, y: -2.0 }.to_param(), "1,-1");
+
+        assert!(MastodonClient::media_upload_fields(None).is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_upload_media_round_trips_focus_point() {
+        use crate::test_support::{MockHttpServer, MockResponse};
+
+        let server =
+            MockHttpServer::start(|_| MockResponse::json(200, r#"{"id":"new_media"}"#)).await;
+        let mut config = create_test_config();
+        config.instance_url = server.url();
+        let client = MastodonClient::new(config);
+
+        // Attachment as received from the API, with an author-set focus point
+        let attachment: MediaAttachment = serde_json::from_str(
+            r#"{
+                "id": "media_1",
+                "type": "image",
+                "url": "https://example.com/image.png",
+                "preview_url": null,
+                "description": null,
+                "meta": {"focus": {"x": -0.42, "y": 0.69}}
+            }"#,
+        )
+        .unwrap();
+        let recreation = MediaRecreation {
+            original_media_id: attachment.id.clone(),
+            data: vec![1, 2, 3],
+            description: "A description".to_string(),
+            media_type: "image/png".to_string(),
+            filename: "image_media_1.png".to_string(),
+            focus: attachment.meta.as_ref().and_then(|meta| meta.focus),
+            replaced_description: None,
+            model: None,
+            full_transcript: None,
+        };
+
+        let media_id = client
+            .upload_media(
+                recreation.data.clone(),
+                &recreation.description,
+                &recreation.filename,
+                &recreation.media_type,
+                recreation.focus.as_ref(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(media_id, "new_media");
+
+        let requests = server.requests();
+        assert_eq!(requests.len(), 1);
+        assert_eq!(requests[0].method, "POST");
+        assert_eq!(requests[0].path, "/api/v2/media");
+        let body = String::from_utf8_lossy(&requests[0].body);
+        assert!(body.contains("name=\"focus\"\r\n\r\n-0.42,0.69\r\n"));
+        assert!(body.contains("name=\"description\"\r\n\r\nA description\r\n"));
+    }
+
+    /// Mock server answering the first `failures` requests to `failing_path` with 503
+    async fn start_flaky_server(
+        failing_path: &'static str,
+        failures: usize,
+        success: &'static str,
+    ) -> crate::test_support::MockHttpServer {
+        use crate::test_support::{MockHttpServer, MockResponse};
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let attempts = AtomicUsize::new(0);
+        MockHttpServer::start(move |request| {
+            if request.path == failing_path {
+                if attempts.fetch_add(1, Ordering::SeqCst) < failures {
+                    MockResponse::json(503, r#"{"error":"Service Unavailable"}"#)
+                } else {
+                    MockResponse::json(200, success)
+                }
+            } else if request.path.ends_with("/source") {
+                MockResponse::json(200, r#"{"id":"123","text":"Hello","spoiler_text":""}"#)
+            } else {
+                MockResponse::json(404, r#"{"error":"Record not found"}"#)
+            }
+        })
+        .await
+    }
+
+    #[tokio::test]
+    async fn test_update_multiple_media_retries_service_unavailable() {
+        use crate::test_support::{MockHttpServer, MockResponse};
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let attempts = AtomicUsize::new(0);
+        let server = MockHttpServer::start(move |request| {
+            if request.path.ends_with("/source") {
+                MockResponse::json(200, r#"{"id":"123","text":"Hello","spoiler_text":""}"#)
+            } else if request.method == "GET" {
+                MockResponse::json(200, &status_json("123"))
+            } else if attempts.fetch_add(1, Ordering::SeqCst) < 2 {
+                MockResponse::json(503, r#"{"error":"Service Unavailable"}"#)
+            } else {
+                MockResponse::json(200, r#"{"id":"123"}"#)
+            }
+        })
+        .await;
+        let mut config = create_test_config();
+        config.instance_url = server.url();
+        let client = MastodonClient::new(config);
+
+        client
+            .update_multiple_media("123", vec![("media_1".to_string(), "desc".to_string())])
+            .await
+            .unwrap();
+
+        let updates: Vec<_> = server
+            .requests()
+            .into_iter()
+            .filter(|r| r.method == "PUT")
+            .collect();
+        assert_eq!(updates.len(), 3);
+    }
+
+    fn status_json(id: &str) -> String {
+        format!(
+            r#"{{
+                "id": "{id}",
+                "uri": "https://example.com/statuses/{id}",
+                "account": {{"id": "user123", "username": "u", "acct": "u", "display_name": "U", "url": "https://example.com/@u"}},
+                "content": "Posted while disconnected",
+                "language": null,
+                "media_attachments": [],
+                "created_at": "2024-01-01T00:00:00Z",
+                "url": null,
+                "visibility": "public",
+                "sensitive": false,
+                "spoiler_text": "",
+                "in_reply_to_id": null,
+                "in_reply_to_account_id": null,
+                "mentions": [],
+                "tags": [],
+                "emojis": [],
+                "poll": null
+            }}"#
+        )
+    }
+
+    #[tokio::test]
+    async fn test_reconnect_catches_up_on_missed_toots() {
+        use crate::test_support::{MockHttpServer, MockResponse};
+
+        let missed = format!("[{}, {}]", status_json("102"), status_json("101"));
+        let server = MockHttpServer::start(move |request| {
+            if request
+                .path
+                .starts_with("/api/v1/accounts/user123/statuses")
+            {
+                MockResponse::json(200, &missed)
+            } else {
+                MockResponse::json(404, r#"{"error":"Record not found"}"#)
+            }
+        })
+        .await;
+
+        let mut config = create_test_config();
+        config.instance_url = server.url();
+        config.reconnect_catchup_count = Some(5);
+        let mut client = MastodonClient::new(config);
+        client.authenticated_user_id = Some("user123".to_string());
+
+        // Toot 100 was the last one seen before the connection dropped
+        let last_seen: TootEvent = serde_json::from_str(&status_json("100")).unwrap();
+        client.track_seen(&last_seen);
+
+        client.catch_up_missed_toots().await;
+
+        let requests = server.requests();
+        assert_eq!(requests.len(), 1);
+        assert!(requests[0].path.contains("since_id=100"));
+        assert!(requests[0].path.contains("limit=5"));
+
+        // Missed toots are delivered oldest first before the live stream resumes
+        assert_eq!(client.listen().await.unwrap().unwrap().id, "101");
+        assert_eq!(client.listen().await.unwrap().unwrap().id, "102");
+        assert_eq!(client.last_seen_id.as_deref(), Some("102"));
+    }
+
+    #[tokio::test]
+    async fn test_reconnect_catch_up_skipped_without_last_seen() {
+        use crate::test_support::{MockHttpServer, MockResponse};
+
+        let server = MockHttpServer::start(|_| MockResponse::json(200, "[]")).await;
+        let mut config = create_test_config();
+        config.instance_url = server.url();
+        let mut client = MastodonClient::new(config);
+        client.authenticated_user_id = Some("user123".to_string());
+
+        client.catch_up_missed_toots().await;
+
+        assert!(server.requests().is_empty());
+        assert!(client.missed_toots.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_low_rate_limit_remaining_delays_next_write() {
+        use crate::test_support::{MockHttpServer, MockResponse};
+
+        let server = MockHttpServer::start(|request| {
+            if request.path.ends_with("/source") {
+                MockResponse::json(200, r#"{"id":"123","text":"Hello","spoiler_text":""}"#)
+            } else if request.method == "GET" {
+                MockResponse::json(200, &status_json("123"))
+            } else {
+                let reset = (Utc::now() + chrono::Duration::seconds(1)).to_rfc3339();
+                MockResponse::json(200, r#"{"id":"123"}"#)
+                    .with_header("X-RateLimit-Remaining", "1")
+                    .with_header("X-RateLimit-Reset", &reset)
+            }
+        })
+        .await;
+        let mut config = create_test_config();
+        config.instance_url = server.url();
+        let client = MastodonClient::new(config);
+        let updates = vec![("media_1".to_string(), "desc".to_string())];
+
+        client
+            .update_multiple_media("123", updates.clone())
+            .await
+            .unwrap();
+
+        // The next write waits for the reported reset instead of running into a 429
+        let start = std::time::Instant::now();
+        client.update_multiple_media("123", updates).await.unwrap();
+        assert!(start.elapsed() >= Duration::from_millis(800));
+    }
+
+    fn open_poll_json(expires_at: DateTime<Utc>) -> String {
+        format!(
+            r#"{{
+                "id": "poll_1",
+                "expires_at": "{}",
+                "expired": false,
+                "multiple": true,
+                "votes_count": 4,
+                "voters_count": 3,
+                "voted": null,
+                "own_votes": null,
+                "options": [
+                    {{"title": "Cats", "votes_count": 3}},
+                    {{"title": "Dogs", "votes_count": 1}}
+                ],
+                "emojis": []
+            }}"#,
+            expires_at.to_rfc3339()
+        )
+    }
+
+    #[test]
+    fn test_poll_edit_params() {
+        let now = Utc::now();
+        let poll: Poll =
+            serde_json::from_str(&open_poll_json(now + chrono::Duration::hours(2))).unwrap();
+
+        let params = poll.edit_params(now).unwrap();
+        assert_eq!(
+            params,
+            vec![
+                ("poll[options][]".to_string(), "Cats".to_string()),
+                ("poll[options][]".to_string(), "Dogs".to_string()),
+                ("poll[expires_in]".to_string(), "7200".to_string()),
+                ("poll[multiple]".to_string(), "true".to_string()),
+            ]
+        );
+
+        // Polls that ended, end too soon or never end can't be re-applied unchanged
+        let mut ended = poll.clone();
+        ended.expired = true;
+        assert!(ended.edit_params(now).is_none());
+        assert!(poll
+            .edit_params(now + chrono::Duration::minutes(118))
+            .is_none());
+        let mut open_ended = poll;
+        open_ended.expires_at = None;
+        assert!(open_ended.edit_params(now).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_update_multiple_media_reapplies_open_poll() {
+        use crate::test_support::{MockHttpServer, MockResponse};
+
+        let expires_at = Utc::now() + chrono::Duration::hours(1);
+        let status = status_json("123").replace(
+            r#""poll": null"#,
+            &format!(r#""poll": {}"#, open_poll_json(expires_at)),
+        );
+        let server = MockHttpServer::start(move |request| {
+            if request.path.ends_with("/source") {
+                MockResponse::json(200, r#"{"id":"123","text":"Vote!","spoiler_text":""}"#)
+            } else {
+                MockResponse::json(200, &status)
+            }
+        })
+        .await;
+        let mut config = create_test_config();
+        config.instance_url = server.url();
+        let client = MastodonClient::new(config);
+
+        client
+            .update_multiple_media("123", vec![("media_1".to_string(), "desc".to_string())])
+            .await
+            .unwrap();
+
+        let update = server
+            .requests()
+            .into_iter()
+            .find(|r| r.method == "PUT")
+            .unwrap();
+        let form: Vec<(String, String)> = url::form_urlencoded::parse(&update.body)
+            .into_owned()
+            .collect();
+        let options: Vec<&str> = form
+            .iter()
+            .filter(|(key, _)| key == "poll[options][]")
+            .map(|(_, value)| value.as_str())
+            .collect();
+        assert_eq!(options, vec!["Cats", "Dogs"]);
+        assert!(form.contains(&("poll[multiple]".to_string(), "true".to_string())));
+        let expires_in: i64 = form
+            .iter()
+            .find(|(key, _)| key == "poll[expires_in]")
+            .unwrap()
+            .1
+            .parse()
+            .unwrap();
+        assert!((3500..=3600).contains(&expires_in));
+    }
+
+    #[tokio::test]
+    async fn test_reply_edits_keep_reply_context() {
+        use crate::test_support::{MockHttpServer, MockResponse};
+
+        let status = status_json("123")
+            .replace(r#""in_reply_to_id": null"#, r#""in_reply_to_id": "100""#)
+            .replace(
+                r#""in_reply_to_account_id": null"#,
+                r#""in_reply_to_account_id": "user456""#,
+            );
+        let edited = status.clone();
+        let server = MockHttpServer::start(move |request| {
+            if request.path.ends_with("/source") {
+                MockResponse::json(
+                    200,
+                    r#"{"id":"123","text":"@other Indeed","spoiler_text":""}"#,
+                )
+            } else {
+                MockResponse::json(200, &status)
+            }
+        })
+        .await;
+        let mut config = create_test_config();
+        config.instance_url = server.url();
+        let client = MastodonClient::new(config);
+
+        client
+            .update_multiple_media("123", vec![("media_1".to_string(), "desc".to_string())])
+            .await
+            .unwrap();
+        client
+            .update_status_with_media("123", &["media_2".to_string()])
+            .await
+            .unwrap();
+
+        let edits: Vec<_> = server
+            .requests()
+            .into_iter()
+            .filter(|r| r.method == "PUT")
+            .collect();
+        assert_eq!(edits.len(), 2);
+        for edit in &edits {
+            let form: Vec<(String, String)> = url::form_urlencoded::parse(&edit.body)
+                .into_owned()
+                .collect();
+            assert!(form
+                .iter()
+                .all(|(key, _)| key != "in_reply_to_id" && key != "visibility"));
+        }
+
+        // The mock reports the edited status as still replying to the same toot
+        let before = client.get_toot("123").await.unwrap();
+        assert_eq!(before.in_reply_to_id.as_deref(), Some("100"));
+        assert!(MastodonClient::check_reply_context(&before, &edited));
+        assert!(!MastodonClient::check_reply_context(
+            &before,
+            &status_json("123")
+        ));
+        assert!(MastodonClient::check_reply_context(
+            &before,
+            r#"{"id":"123"}"#
+        ));
+
+        assert!(
+            MastodonClient::check_editable_fields(["status", "in_reply_to_id"].into_iter())
+                .is_err()
+        );
+        assert!(
+            MastodonClient::check_editable_fields(["status", "media_ids[]"].into_iter()).is_ok()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_media_only_status_edit_follows_allow_empty_text_edit() {
+        use crate::test_support::{MockHttpServer, MockResponse};
+
+        let status = status_json("123");
+        let server = MockHttpServer::start(move |request| {
+            if request.path.ends_with("/source") {
+                MockResponse::json(200, r#"{"id":"123","text":"","spoiler_text":""}"#)
+            } else {
+                MockResponse::json(200, &status)
+            }
+        })
+        .await;
+        let updates = || vec![("media_1".to_string(), "desc".to_string())];
+
+        // By default the empty text is replaced by a zero-width space
+        let mut config = create_test_config();
+        config.instance_url = server.url();
+        let client = MastodonClient::new(config.clone());
+        client
+            .update_multiple_media("123", updates())
+            .await
+            .unwrap();
+        let edits: Vec<_> = server
+            .requests()
+            .into_iter()
+            .filter(|r| r.method == "PUT")
+            .collect();
+        assert_eq!(edits.len(), 1);
+        assert!(url::form_urlencoded::parse(&edits[0].body)
+            .any(|(key, value)| key == "status" && value == ZERO_WIDTH_SPACE));
+
+        config.allow_empty_text_edit = Some(false);
+        let client = MastodonClient::new(config);
+        let result = client.update_multiple_media("123", updates()).await;
+        assert!(matches!(result, Err(MastodonError::InvalidTootData(_))));
+        let result = client
+            .update_status_with_media("123", &["media_2".to_string()])
+            .await;
+        assert!(matches!(result, Err(MastodonError::InvalidTootData(_))));
+        assert_eq!(
+            server
+                .requests()
+                .iter()
+                .filter(|r| r.method == "PUT")
+                .count(),
+            1
+        );
+    }
+
+    #[tokio::test]
+    async fn test_rejected_media_id_dropped_from_batch() {
+        use crate::test_support::{MockHttpServer, MockResponse};
+
+        let status = status_json("123");
+        let server = MockHttpServer::start(move |request| {
+            let rejects_bad_media = request.method == "PUT"
+                && String::from_utf8_lossy(&request.body).contains("=222&");
+            if rejects_bad_media {
+                MockResponse::json(
+                    422,
+                    r#"{"error":"Validation failed: Media attachment 222 does not belong to this status"}"#,
+                )
+            } else if request.path.ends_with("/source") {
+                MockResponse::json(200, r#"{"id":"123","text":"Photos","spoiler_text":""}"#)
+            } else {
+                MockResponse::json(200, &status)
+            }
+        })
+        .await;
+        let mut config = create_test_config();
+        config.instance_url = server.url();
+        let client = MastodonClient::new(config);
+
+        let updates = ["111", "222", "333"]
+            .iter()
+            .map(|id| (id.to_string(), format!("desc {id}")))
+            .collect();
+        client.update_multiple_media("123", updates).await.unwrap();
+
+        let edits: Vec<Vec<(String, String)>> = server
+            .requests()
+            .into_iter()
+            .filter(|r| r.method == "PUT")
+            .map(|r| url::form_urlencoded::parse(&r.body).into_owned().collect())
+            .collect();
+        assert_eq!(edits.len(), 2);
+        let media_ids = |form: &[(String, String)]| -> Vec<String> {
+            form.iter()
+                .filter(|(key, _)| key.ends_with("[id]"))
+                .map(|(_, value)| value.clone())
+                .collect()
+        };
+        assert_eq!(media_ids(&edits[0]), ["111", "222", "333"]);
+        assert_eq!(media_ids(&edits[1]), ["111", "333"]);
+        assert!(edits[1].contains(&(
+            "media_attributes[1][description]".to_string(),
+            "desc 333".to_string()
+        )));
+
+        // With nothing left to apply, the rejection is reported
+        let result = client
+            .update_multiple_media("123", vec![("222".to_string(), "desc".to_string())])
+            .await;
+        assert!(matches!(result, Err(MastodonError::ApiRequestFailed(_))));
+    }
+
+    #[tokio::test]
+    async fn test_status_edit_refuses_to_drop_ended_poll() {
+        use crate::test_support::{MockHttpServer, MockResponse};
+
+        let status = status_json("123").replace(
+            r#""poll": null"#,
+            &format!(
+                r#""poll": {}"#,
+                open_poll_json(Utc::now() - chrono::Duration::hours(1))
+                    .replace(r#""expired": false"#, r#""expired": true"#)
+            ),
+        );
+        let server = MockHttpServer::start(move |request| {
+            if request.path.ends_with("/source") {
+                MockResponse::json(200, r#"{"id":"123","text":"Vote!","spoiler_text":""}"#)
+            } else {
+                MockResponse::json(200, &status)
+            }
+        })
+        .await;
+        let mut config = create_test_config();
+        config.instance_url = server.url();
+        let client = MastodonClient::new(config);
+
+        let result = client.update_status_with_media("123", &[]).await;
+
+        assert!(matches!(result, Err(MastodonError::InvalidTootData(_))));
+        assert!(server.requests().iter().all(|r| r.method == "GET"));
+    }
+
+    #[tokio::test]
+    async fn test_get_toot_retries_service_unavailable() {
+        let server = start_flaky_server(
+            "/api/v1/statuses/123",
+            1,
+            r#"{
+                "id": "123",
+                "uri": "https://example.com/statuses/123",
+                "account": {"id": "1", "username": "u", "acct": "u", "display_name": "U", "url": "https://example.com/@u"},
+                "content": "Hello",
+                "language": null,
+                "media_attachments": [],
+                "created_at": "2024-01-01T00:00:00Z",
+                "url": null,
+                "visibility": "public",
+                "sensitive": false,
+                "spoiler_text": "",
+                "in_reply_to_id": null,
+                "in_reply_to_account_id": null,
+                "mentions": [],
+                "tags": [],
+                "emojis": [],
+                "poll": null
+            }"#,
+        )
+        .await;
+        let mut config = create_test_config();
+        config.instance_url = server.url();
+        let client = MastodonClient::new(config);
+
+        let toot = client.get_toot("123").await.unwrap();
+
+        assert_eq!(toot.id, "123");
+        assert_eq!(server.requests().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_create_media_attachment_without_focus() {
+        use crate::test_support::{MockHttpServer, MockResponse};
+
+        let server =
+            MockHttpServer::start(|_| MockResponse::json(200, r#"{"id":"new_media"}"#)).await;
+        let mut config = create_test_config();
+        config.instance_url = server.url();
+        let client = MastodonClient::new(config);
+
+        client
+            .create_media_attachment(vec![1, 2, 3], "desc", "image.png", "image/png")
+            .await
+            .unwrap();
+
+        let body = String::from_utf8_lossy(&server.requests()[0].body).to_string();
+        assert!(!body.contains("name=\"focus\""));
+    }
+
     #[test]
     fn test_account_serialization() {
         let account = Account {
@@ -2317,6 +4217,8 @@ mod tests {
             tags: Vec::new(),
             emojis: Vec::new(),
             poll: None,
+            filtered: Vec::new(),
+            edited_at: None,
             is_edit: false, // This will be set by the parser
         };
 
@@ -2353,6 +4255,57 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_parse_streaming_event_ignores_boosts() {
+        let client = MastodonClient::new(create_test_config());
+
+        let mut toot: serde_json::Value = serde_json::from_str(
+            r#"{
+                "id": "boost_1",
+                "uri": "https://mastodon.social/users/testuser/statuses/boost_1",
+                "account": {
+                    "id": "user123",
+                    "username": "testuser",
+                    "acct": "testuser",
+                    "display_name": "Test User",
+                    "url": "https://mastodon.social/@testuser"
+                },
+                "content": "",
+                "media_attachments": [],
+                "created_at": "2024-01-01T00:00:00Z",
+                "visibility": "public",
+                "sensitive": false,
+                "spoiler_text": "",
+                "mentions": [],
+                "tags": [],
+                "emojis": [],
+                "reblog": null
+            }"#,
+        )
+        .unwrap();
+        let event = |toot: &serde_json::Value| {
+            serde_json::to_string(&StreamEvent {
+                event: "update".to_string(),
+                payload: Some(toot.to_string()),
+            })
+            .unwrap()
+        };
+
+        // A plain status with `reblog: null` still parses
+        assert!(client
+            .parse_streaming_event(&event(&toot))
+            .unwrap()
+            .is_some());
+
+        // A boost wraps the original status and is dropped
+        let original = toot.clone();
+        toot["reblog"] = original;
+        assert!(client
+            .parse_streaming_event(&event(&toot))
+            .unwrap()
+            .is_none());
+    }
+
     #[test]
     fn test_streaming_url_with_custom_stream() {
         let mut config = create_test_config();
@@ -2360,9 +4313,143 @@ mod tests {
         let client = MastodonClient::new(config);
 
         let url = client.get_streaming_url().unwrap();
-        // Should still default to user stream even when config says false
-        // (this is the current behavior based on the implementation)
-        assert!(url.as_str().contains("stream=user"));
+        // Without an explicit stream selection, non-user mode follows the public stream
+        assert_eq!(
+            url.as_str(),
+            "wss://mastodon.social/api/v1/streaming?access_token=test_token&stream=public"
+        );
+    }
+
+    #[test]
+    fn test_streaming_request_token_placement() {
+        let client = MastodonClient::new(create_test_config());
+        let request = client
+            .streaming_request(&client.get_streaming_url().unwrap())
+            .unwrap();
+        assert_eq!(
+            request.uri().to_string(),
+            "wss://mastodon.social/api/v1/streaming?access_token=test_token&stream=user"
+        );
+        assert!(request.headers().get(AUTHORIZATION).is_none());
+        assert!(request.headers().get(SEC_WEBSOCKET_PROTOCOL).is_none());
+
+        let mut config = create_test_config();
+        config.streaming_auth = Some("header".to_string());
+        let client = MastodonClient::new(config);
+        let request = client
+            .streaming_request(&client.get_streaming_url().unwrap())
+            .unwrap();
+        assert_eq!(
+            request.uri().to_string(),
+            "wss://mastodon.social/api/v1/streaming?stream=user"
+        );
+        assert_eq!(
+            request.headers().get(AUTHORIZATION).unwrap(),
+            "Bearer test_token"
+        );
+        assert_eq!(
+            request.headers().get(SEC_WEBSOCKET_PROTOCOL).unwrap(),
+            "test_token"
+        );
+    }
+
+    #[test]
+    fn test_streaming_url_user_stream_ignores_stream_selector() {
+        let mut config = create_test_config();
+        config.user_stream = Some(true);
+        config.stream = Some("hashtag".to_string());
+        config.stream_tag = Some("photography".to_string());
+        let client = MastodonClient::new(config);
+
+        let url = client.get_streaming_url().unwrap();
+        assert_eq!(
+            url.as_str(),
+            "wss://mastodon.social/api/v1/streaming?access_token=test_token&stream=user"
+        );
+    }
+
+    #[test]
+    fn test_streaming_url_public_local_stream() {
+        let mut config = create_test_config();
+        config.user_stream = Some(false);
+        config.stream = Some("public:local".to_string());
+        let client = MastodonClient::new(config);
+
+        let url = client.get_streaming_url().unwrap();
+        assert_eq!(
+            url.as_str(),
+            "wss://mastodon.social/api/v1/streaming?access_token=test_token&stream=public:local"
+        );
+    }
+
+    #[test]
+    fn test_streaming_url_hashtag_stream() {
+        let mut config = create_test_config();
+        config.user_stream = Some(false);
+        config.stream = Some("hashtag".to_string());
+        config.stream_tag = Some("#Fotografie".to_string());
+        let client = MastodonClient::new(config);
+
+        let url = client.get_streaming_url().unwrap();
+        assert_eq!(
+            url.as_str(),
+            "wss://mastodon.social/api/v1/streaming?access_token=test_token&stream=hashtag&tag=Fotografie"
+        );
+        let pairs: Vec<(String, String)> = url
+            .query_pairs()
+            .map(|(k, v)| (k.into_owned(), v.into_owned()))
+            .collect();
+        assert!(pairs.contains(&("tag".to_string(), "Fotografie".to_string())));
+    }
+
+    #[test]
+    fn test_streaming_url_hashtag_stream_encodes_tag() {
+        let mut config = create_test_config();
+        config.user_stream = Some(false);
+        config.stream = Some("hashtag".to_string());
+        config.stream_tag = Some("café&more".to_string());
+        let client = MastodonClient::new(config);
+
+        let url = client.get_streaming_url().unwrap();
+        let tag = url
+            .query_pairs()
+            .find(|(k, _)| k == "tag")
+            .map(|(_, v)| v.into_owned());
+        assert_eq!(tag.as_deref(), Some("café&more"));
+    }
+
+    #[test]
+    fn test_streaming_url_list_stream() {
+        let mut config = create_test_config();
+        config.user_stream = Some(false);
+        config.stream = Some("list".to_string());
+        config.stream_list = Some("42".to_string());
+        let client = MastodonClient::new(config);
+
+        let url = client.get_streaming_url().unwrap();
+        assert_eq!(
+            url.as_str(),
+            "wss://mastodon.social/api/v1/streaming?access_token=test_token&stream=list&list=42"
+        );
+    }
+
+    #[test]
+    fn test_filters_own_toots_by_stream_type() {
+        // User stream always filters to own toots
+        let mut config = create_test_config();
+        config.own_toots_only = Some(false);
+        assert!(MastodonClient::new(config).filters_own_toots());
+
+        // Non-user streams filter by default
+        let mut config = create_test_config();
+        config.user_stream = Some(false);
+        assert!(MastodonClient::new(config).filters_own_toots());
+
+        // ...but the filter can be disabled
+        let mut config = create_test_config();
+        config.user_stream = Some(false);
+        config.own_toots_only = Some(false);
+        assert!(!MastodonClient::new(config).filters_own_toots());
     }
 
     #[test]
@@ -2418,6 +4505,8 @@ mod tests {
                 tags: Vec::new(),
                 emojis: Vec::new(),
                 poll: None,
+                filtered: Vec::new(),
+                edited_at: None,
                 is_edit: false,
             };
 
@@ -2430,6 +4519,14 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_gifv_uploaded_as_mp4() {
+        assert_eq!(
+            MastodonClient::validate_and_sanitize_mime_type("gifv", "video_1.mp4").unwrap(),
+            "video/mp4"
+        );
+    }
+
     #[test]
     fn test_media_attachment_different_types() {
         let media_types = ["image", "video", "gifv", "audio", "unknown"];
@@ -2482,6 +4579,8 @@ mod tests {
             tags: Vec::new(),
             emojis: Vec::new(),
             poll: None,
+            filtered: Vec::new(),
+            edited_at: None,
             is_edit: false,
         };
 
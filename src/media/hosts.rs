@@ -0,0 +1,410 @@
+//! Host allow/block lists and private address checks for media downloads
+
+use crate::error::MediaError;
+use crate::redact::log_url;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+
+/// Which hosts media may be downloaded from
+#[derive(Debug, Clone, Default)]
+pub struct MediaHostPolicy {
+    /// Only these hosts (and their subdomains) are allowed; empty allows any host
+    pub allowed_hosts: Vec<String>,
+    /// These hosts (and their subdomains) are always rejected
+    pub blocked_hosts: Vec<String>,
+    /// Allow hosts that are or resolve to private, loopback or link-local addresses
+    pub allow_private_hosts: bool,
+    /// Host of the configured Mastodon instance, trusted even on a private address
+    pub instance_host: Option<String>,
+}
+
+/// Most redirects followed for a single media download
+const MAX_REDIRECTS: usize = 10;
+
+impl MediaHostPolicy {
+    /// Policy from `media.allowed_media_hosts`, `blocked_media_hosts` and
+    /// `allow_private_media_hosts`, trusting the host of `mastodon.instance_url`
+    pub fn from_config(config: &crate::config::Config) -> Self {
+        let media = config.media();
+        Self {
+            allowed_hosts: media.allowed_media_hosts.clone().unwrap_or_default(),
+            blocked_hosts: media.blocked_media_hosts.clone().unwrap_or_default(),
+            allow_private_hosts: media.allow_private_media_hosts.unwrap_or(false),
+            instance_host: url::Url::parse(&config.mastodon.instance_url)
+                .ok()
+                .and_then(|url| url.host_str().map(str::to_lowercase)),
+        }
+    }
+
+    /// Check that `url` may be downloaded, resolving its host to catch private addresses
+    ///
+    /// Redirects and the addresses actually connected to are checked again by the client from
+    /// [`Self::http_client`], so a later DNS answer can't point the download elsewhere.
+    pub async fn check(&self, url: &url::Url) -> Result<(), MediaError> {
+        let host = match self.check_host(url)? {
+            HostCheck::Trusted => return Ok(()),
+            HostCheck::Resolve(host) => host,
+        };
+
+        let port = url.port_or_known_default().unwrap_or(443);
+        let addresses: Vec<IpAddr> = match tokio::net::lookup_host((host.as_str(), port)).await {
+            Ok(resolved) => resolved.map(|addr| addr.ip()).collect(),
+            // The download itself reports unresolvable hosts
+            Err(_) => Vec::new(),
+        };
+        if let Some(ip) = addresses.into_iter().find(|ip| is_private(*ip)) {
+            return Err(self.rejected(url, &format!("host resolves to private address {ip}")));
+        }
+        Ok(())
+    }
+
+    /// Checks that need no DNS lookup: host lists, trusted hosts and IP literals
+    fn check_host(&self, url: &url::Url) -> Result<HostCheck, MediaError> {
+        let Some(host) = url.host_str() else {
+            return Err(self.rejected(url, "URL has no host"));
+        };
+        let host = host.trim_end_matches('.').to_lowercase();
+
+        if self.blocked_hosts.iter().any(|h| host_matches(&host, h)) {
+            return Err(self.rejected(url, "host is in media.blocked_media_hosts"));
+        }
+        // An explicitly allowed host is trusted even on a private address
+        if self.is_trusted_host(&host) {
+            return Ok(HostCheck::Trusted);
+        }
+        if !self.allowed_hosts.is_empty() {
+            return Err(self.rejected(url, "host is not in media.allowed_media_hosts"));
+        }
+        if self.allow_private_hosts {
+            return Ok(HostCheck::Trusted);
+        }
+
+        let ip = match url.host() {
+            Some(url::Host::Ipv4(ip)) => IpAddr::V4(ip),
+            Some(url::Host::Ipv6(ip)) => IpAddr::V6(ip),
+            _ => return Ok(HostCheck::Resolve(host)),
+        };
+        if is_private(ip) {
+            return Err(self.rejected(url, &format!("host is private address {ip}")));
+        }
+        Ok(HostCheck::Trusted)
+    }
+
+    /// Hosts in `allowed_hosts` and the instance's own host
+    fn is_trusted_host(&self, host: &str) -> bool {
+        self.allowed_hosts.iter().any(|h| host_matches(host, h))
+            || self
+                .instance_host
+                .as_deref()
+                .is_some_and(|instance| host_matches(host, instance))
+    }
+
+    /// HTTP client for media downloads that enforces the policy on every connection
+    ///
+    /// Each redirect hop is checked like the first URL, and host names are resolved by
+    /// [`PolicyResolver`], which drops private addresses, so the addresses connected to are the
+    /// ones that were checked. Downloads go through `proxy_url` if set.
+    pub fn http_client(&self, proxy_url: Option<&str>) -> reqwest::Client {
+        let policy = self.clone();
+        let redirects = reqwest::redirect::Policy::custom(move |attempt| {
+            if attempt.previous().len() >= MAX_REDIRECTS {
+                return attempt.error("too many redirects");
+            }
+            match policy.check_host(attempt.url()) {
+                Ok(_) => attempt.follow(),
+                Err(e) => attempt.error(e),
+            }
+        });
+
+        let proxy_host = proxy_url
+            .and_then(|proxy| url::Url::parse(proxy).ok())
+            .and_then(|proxy| proxy.host_str().map(str::to_lowercase));
+        let resolver = PolicyResolver {
+            policy: self.clone(),
+            proxy_host,
+        };
+        let builder = reqwest::Client::builder()
+            .redirect(redirects)
+            .dns_resolver(std::sync::Arc::new(resolver));
+
+        let builder = match proxy_url.map(reqwest::Proxy::all) {
+            None => builder,
+            Some(Ok(proxy)) => {
+                tracing::info!(
+                    "Routing media downloads through proxy {}",
+                    log_url(proxy_url.unwrap_or_default())
+                );
+                builder.proxy(proxy)
+            }
+            Some(Err(e)) => {
+                tracing::warn!(
+                    "Invalid media proxy {}, downloading directly: {}",
+                    log_url(proxy_url.unwrap_or_default()),
+                    e
+                );
+                builder
+            }
+        };
+        builder.build().unwrap_or_else(|e| {
+            tracing::warn!("Failed to build media download client: {}", e);
+            reqwest::Client::new()
+        })
+    }
+
+    fn rejected(&self, url: &url::Url, reason: &str) -> MediaError {
+        tracing::warn!(
+            "Refusing to download media from {}: {}",
+            log_url(url.as_str()),
+            reason
+        );
+        MediaError::HostNotAllowed {
+            url: url.to_string(),
+            reason: reason.to_string(),
+        }
+    }
+}
+
+/// Result of the checks that need no DNS lookup
+enum HostCheck {
+    /// Allowed without looking at the addresses the host resolves to
+    Trusted,
+    /// Allowed if the host name resolves to public addresses only
+    Resolve(String),
+}
+
+/// DNS resolver for media downloads that never hands out private addresses
+///
+/// Trusted hosts (allowed hosts, the instance and the media proxy) resolve normally. Resolving
+/// here rather than only in [`MediaHostPolicy::check`] pins the checked addresses for the
+/// connection, so DNS rebinding can't swap in a private address afterwards.
+struct PolicyResolver {
+    policy: MediaHostPolicy,
+    proxy_host: Option<String>,
+}
+
+impl reqwest::dns::Resolve for PolicyResolver {
+    fn resolve(&self, name: reqwest::dns::Name) -> reqwest::dns::Resolving {
+        let host = name.as_str().trim_end_matches('.').to_lowercase();
+        let trusted = self.policy.allow_private_hosts
+            || self.policy.is_trusted_host(&host)
+            || self.proxy_host.as_deref() == Some(host.as_str());
+        let blocked = self
+            .policy
+            .blocked_hosts
+            .iter()
+            .any(|h| host_matches(&host, h));
+
+        Box::pin(async move {
+            if blocked {
+                return Err(format!("{host} is in media.blocked_media_hosts").into());
+            }
+            let addresses: Vec<SocketAddr> = tokio::net::lookup_host((host.as_str(), 0))
+                .await?
+                .filter(|addr| trusted || !is_private(addr.ip()))
+                .collect();
+            if addresses.is_empty() {
+                return Err(format!("{host} does not resolve to a public address").into());
+            }
+            Ok(Box::new(addresses.into_iter()) as reqwest::dns::Addrs)
+        })
+    }
+}
+
+/// Whether `host` is `pattern` or one of its subdomains
+fn host_matches(host: &str, pattern: &str) -> bool {
+    let pattern = pattern.trim().trim_end_matches('.').to_lowercase();
+    !pattern.is_empty()
+        && (host == pattern
+            || host
+                .strip_suffix(pattern.as_str())
+                .is_some_and(|prefix| prefix.ends_with('.')))
+}
+
+/// Loopback, private, link-local, shared and other non-public addresses
+fn is_private(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => is_private_v4(ip),
+        IpAddr::V6(ip) => match ip.to_ipv4_mapped() {
+            Some(ip) => is_private_v4(ip),
+            None => is_private_v6(ip),
+        },
+    }
+}
+
+fn is_private_v4(ip: Ipv4Addr) -> bool {
+    let [a, b, ..] = ip.octets();
+    ip.is_private()
+        || ip.is_loopback()
+        || ip.is_link_local()
+        || ip.is_unspecified()
+        || ip.is_broadcast()
+        // Carrier-grade NAT, 100.64.0.0/10
+        || (a == 100 && (64..128).contains(&b))
+}
+
+fn is_private_v6(ip: Ipv6Addr) -> bool {
+    let first = ip.segments()[0];
+    ip.is_loopback()
+        || ip.is_unspecified()
+        // Unique local, fc00::/7
+        || (first & 0xfe00) == 0xfc00
+        // Link-local, fe80::/10
+        || (first & 0xffc0) == 0xfe80
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn check(policy: &MediaHostPolicy, url: &str) -> Result<(), MediaError> {
+        policy.check(&url::Url::parse(url).unwrap()).await
+    }
+
+    #[tokio::test]
+    async fn test_allowed_hosts_include_subdomains_only() {
+        let policy = MediaHostPolicy {
+            allowed_hosts: vec!["files.example".to_string()],
+            ..Default::default()
+        };
+        assert!(check(&policy, "https://files.example/a.png").await.is_ok());
+        assert!(check(&policy, "https://cdn.files.example/a.png")
+            .await
+            .is_ok());
+        assert!(matches!(
+            check(&policy, "https://evilfiles.example/a.png").await,
+            Err(MediaError::HostNotAllowed { .. })
+        ));
+        assert!(check(&policy, "https://other.example/a.png").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_blocked_hosts_rejected() {
+        let policy = MediaHostPolicy {
+            blocked_hosts: vec!["tracker.example".to_string()],
+            allow_private_hosts: true,
+            ..Default::default()
+        };
+        assert!(check(&policy, "https://img.tracker.example/a.png")
+            .await
+            .is_err());
+        assert!(check(&policy, "https://93.184.216.34/a.png").await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_private_addresses_rejected_by_default() {
+        let policy = MediaHostPolicy::default();
+        for url in [
+            "http://127.0.0.1/a.png",
+            "http://10.1.2.3/a.png",
+            "http://192.168.0.10/a.png",
+            "http://169.254.169.254/latest/meta-data",
+            "http://100.64.0.1/a.png",
+            "http://[::1]/a.png",
+            "http://[fd00::1]/a.png",
+            "http://[::ffff:127.0.0.1]/a.png",
+            "http://localhost/a.png",
+        ] {
+            assert!(
+                check(&policy, url).await.is_err(),
+                "{url} should be rejected"
+            );
+        }
+        assert!(check(&policy, "https://93.184.216.34/a.png").await.is_ok());
+
+        let allow_private = MediaHostPolicy {
+            allow_private_hosts: true,
+            ..Default::default()
+        };
+        assert!(check(&allow_private, "http://127.0.0.1/a.png")
+            .await
+            .is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_instance_host_trusted_on_private_address() {
+        let policy = MediaHostPolicy {
+            instance_host: Some("localhost".to_string()),
+            ..Default::default()
+        };
+        assert!(check(&policy, "http://localhost/a.png").await.is_ok());
+        assert!(check(&policy, "http://127.0.0.1/a.png").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_redirect_target_and_audio_url_checked() {
+        use crate::media::{MediaConfig, MediaProcessor};
+        use crate::test_support::{MockHttpServer, MockResponse};
+
+        let server = MockHttpServer::start(|request| match request.path.as_str() {
+            "/a.png" => MockResponse::bytes(302, "text/plain", Vec::new())
+                .with_header("Location", "/redirected.png"),
+            _ => MockResponse::bytes(200, "image/png", b"png".to_vec()),
+        })
+        .await;
+        let port = server.url().rsplit(':').next().unwrap().to_string();
+
+        // Only `localhost` is allowed; the redirect goes to the same server by IP address
+        let processor = MediaProcessor::with_unified_transformer(MediaConfig {
+            host_policy: MediaHostPolicy {
+                allowed_hosts: vec!["localhost".to_string()],
+                ..Default::default()
+            },
+            download_retries: 0,
+            ..MediaConfig::default()
+        });
+        let localhost = |url: String| url.replace("127.0.0.1", "localhost");
+        let redirecting = MockHttpServer::start(move |_| {
+            MockResponse::bytes(302, "text/plain", Vec::new())
+                .with_header("Location", &format!("http://127.0.0.1:{port}/b.png"))
+        })
+        .await;
+        let result = processor
+            .download_media(&localhost(redirecting.url()))
+            .await;
+        assert!(result.is_err());
+        assert!(server.requests().iter().all(|r| r.path != "/b.png"));
+
+        // A redirect that stays on the allowed host is followed
+        let data = processor
+            .download_media(&localhost(server.url() + "/a.png"))
+            .await
+            .unwrap();
+        assert_eq!(data, b"png");
+
+        // Audio is downloaded through the same policy
+        let media: crate::mastodon::MediaAttachment = serde_json::from_str(&format!(
+            r#"{{"id":"a","type":"audio","url":"{}/a.mp3","preview_url":null,"description":null,"meta":null}}"#,
+            server.url()
+        ))
+        .unwrap();
+        let result = crate::media::audio::process_audio_for_transcript(
+            &media,
+            &processor,
+            &crate::config::WhisperConfig::default(),
+            &crate::config::MediaConfig::default(),
+            &crate::media::audio::TranscriptSummary::default(),
+        )
+        .await;
+        assert!(matches!(result, Err(MediaError::HostNotAllowed { .. })));
+        assert!(server.requests().iter().all(|r| r.path != "/a.mp3"));
+    }
+
+    #[tokio::test]
+    async fn test_resolver_drops_private_addresses() {
+        use reqwest::dns::Resolve;
+
+        let resolver = PolicyResolver {
+            policy: MediaHostPolicy::default(),
+            proxy_host: None,
+        };
+        assert!(resolver
+            .resolve("localhost".parse().unwrap())
+            .await
+            .is_err());
+
+        let resolver = PolicyResolver {
+            policy: MediaHostPolicy::default(),
+            proxy_host: Some("localhost".to_string()),
+        };
+        assert!(resolver.resolve("localhost".parse().unwrap()).await.is_ok());
+    }
+}
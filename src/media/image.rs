@@ -1,7 +1,13 @@
 use crate::error::MediaError;
 use crate::mastodon::MediaAttachment;
-use image::{codecs::jpeg::JpegEncoder, codecs::png::PngEncoder, DynamicImage, GenericImageView};
+use image::{
+    codecs::gif::GifDecoder, codecs::jpeg::JpegEncoder, codecs::png::PngEncoder,
+    codecs::webp::WebPDecoder, codecs::webp::WebPEncoder, imageops, metadata::Orientation,
+    AnimationDecoder, DynamicImage, Frames, GenericImageView, ImageDecoder, ImageReader, RgbaImage,
+};
 use std::collections::HashSet;
+use std::io::Cursor;
+use std::process::Command;
 
 // Re-export the progress reporter from mod.rs
 pub use super::ProgressReporter;
@@ -21,16 +27,313 @@ pub const SUPPORTED_IMAGE_FORMATS: &[&str] = &[
     "image/bmp",
     "image/x-icon",
     "image/avif",
+    "image/heic",
+    "image/heif",
 ];
 
+/// Formats images can be re-encoded in for analysis, as accepted in `media.analysis_output_format`
+pub const ANALYSIS_OUTPUT_FORMATS: &[&str] = &["jpeg", "webp"];
+
+/// Image format for a `media.analysis_output_format` value, `None` if unsupported
+pub fn analysis_output_format(name: &str) -> Option<ImageFormat> {
+    match name.to_lowercase().as_str() {
+        "jpeg" | "jpg" => Some(ImageFormat::Jpeg),
+        "webp" => Some(ImageFormat::WebP),
+        _ => None,
+    }
+}
+
+/// ISO-BMFF brands identifying AVIF images
+const AVIF_BRANDS: &[&[u8]] = &[b"avif", b"avis"];
+
+/// ISO-BMFF brands identifying HEIF/HEIC images
+const HEIF_BRANDS: &[&[u8]] = &[
+    b"heic", b"heix", b"hevc", b"hevx", b"heim", b"heis", b"mif1", b"msf1",
+];
+
+/// HEIF-based containers the `image` crate can't decode, handled by external decoders
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum HeifContainer {
+    Avif,
+    Heic,
+}
+
+/// Detect AVIF/HEIC images from the brands in their `ftyp` box
+fn detect_heif_container(data: &[u8]) -> Option<HeifContainer> {
+    if data.len() < 16 || &data[4..8] != b"ftyp" {
+        return None;
+    }
+
+    let box_size = u32::from_be_bytes([data[0], data[1], data[2], data[3]]) as usize;
+    let box_end = box_size.clamp(16, data.len());
+
+    // Major brand followed by the compatible brands (skipping the minor version)
+    let brands: Vec<&[u8]> = std::iter::once(&data[8..12])
+        .chain(data[16..box_end].chunks_exact(4))
+        .collect();
+
+    // AVIF files also list the generic `mif1` brand, so check them first
+    if brands.iter().any(|brand| AVIF_BRANDS.contains(brand)) {
+        Some(HeifContainer::Avif)
+    } else if brands.iter().any(|brand| HEIF_BRANDS.contains(brand)) {
+        Some(HeifContainer::Heic)
+    } else {
+        None
+    }
+}
+
+/// MIME type of an image detected from its magic bytes, if recognized
+pub fn sniff_image_type(data: &[u8]) -> Option<&'static str> {
+    match detect_heif_container(data) {
+        Some(HeifContainer::Avif) => Some("image/avif"),
+        Some(HeifContainer::Heic) => Some("image/heic"),
+        None => image::guess_format(data)
+            .ok()
+            .map(|format| format.to_mime_type()),
+    }
+}
+
+/// Decode an image and apply its EXIF orientation so the model sees it upright
+fn decode_upright(data: &[u8]) -> Result<DynamicImage, MediaError> {
+    let decoding_failed =
+        |e: image::ImageError| MediaError::DecodingFailed(format!("Failed to decode image: {e}"));
+
+    let mut decoder = ImageReader::new(std::io::Cursor::new(data))
+        .with_guessed_format()
+        .map_err(|e| MediaError::DecodingFailed(format!("Failed to read image: {e}")))?
+        .into_decoder()
+        .map_err(decoding_failed)?;
+    let orientation = decoder.orientation().unwrap_or(Orientation::NoTransforms);
+
+    let mut img = DynamicImage::from_decoder(decoder).map_err(decoding_failed)?;
+    img.apply_orientation(orientation);
+    Ok(img)
+}
+
+/// Decode an AVIF/HEIC image to PNG using libheif's CLI tools, falling back to FFmpeg
+///
+/// The `image` crate's `avif` feature only encodes; decoding needs `avif-native`, which links
+/// the system's libdav1d at build time. The external decoders already needed for HEIC cover
+/// AVIF as well without that build dependency.
+fn decode_heif_container(data: &[u8], container: HeifContainer) -> Result<Vec<u8>, MediaError> {
+    let suffix = match container {
+        HeifContainer::Avif => ".avif",
+        HeifContainer::Heic => ".heic",
+    };
+    let input = super::helpers::TempFile::with_suffix(suffix)?;
+    let output = super::helpers::TempFile::with_suffix(".png")?;
+
+    std::fs::write(input.path(), data).map_err(|e| {
+        MediaError::ProcessingFailed(format!("Failed to write {container:?} image: {e}"))
+    })?;
+
+    let input_path = input.path().to_string_lossy().to_string();
+    let output_path = output.path().to_string_lossy().to_string();
+    let ffmpeg = super::audio::ffmpeg_path().to_string_lossy();
+    let decoders: [(&str, Vec<&str>); 3] = [
+        ("heif-dec", vec![&input_path, &output_path]),
+        ("heif-convert", vec![&input_path, &output_path]),
+        (
+            &ffmpeg,
+            vec![
+                "-v",
+                "error",
+                "-i",
+                &input_path,
+                "-frames:v",
+                "1",
+                "-y",
+                &output_path,
+            ],
+        ),
+    ];
+
+    for (program, args) in decoders {
+        match Command::new(program).args(&args).output() {
+            Ok(result) if result.status.success() => {
+                let decoded = std::fs::read(output.path()).unwrap_or_default();
+                if !decoded.is_empty() {
+                    tracing::debug!("Decoded {:?} image with {}", container, program);
+                    return Ok(decoded);
+                }
+            }
+            Ok(result) => {
+                tracing::debug!(
+                    "{} failed to decode {:?} image: {}",
+                    program,
+                    container,
+                    String::from_utf8_lossy(&result.stderr).trim()
+                );
+            }
+            Err(e) => {
+                tracing::debug!("{} not available: {}", program, e);
+            }
+        }
+    }
+
+    Err(MediaError::DecodingFailed(format!(
+        "Failed to decode {container:?} image: install libheif (heif-dec) or FFmpeg with HEVC/AV1 support"
+    )))
+}
+
+/// Number of frames sampled from an animated GIF/WebP for the description montage
+pub const ANIMATION_SAMPLE_FRAMES: usize = 4;
+
+/// Frames beyond this are ignored when sampling, so long animations aren't decoded in full
+const MAX_ANIMATION_FRAMES: usize = 300;
+
+/// Sampled frames are downscaled to fit this dimension before they are composed, so the
+/// 2x2 montage stays within `DEFAULT_MAX_DIMENSION`
+const ANIMATION_FRAME_MAX_DIMENSION: u32 = DEFAULT_MAX_DIMENSION / 2;
+
+/// Frame iterator for animated GIF/WebP data, `None` for other formats and still WebP
+fn animation_frames(data: &[u8], format: ImageFormat) -> Result<Option<Frames<'_>>, MediaError> {
+    let decoding_failed = |e: image::ImageError| {
+        MediaError::DecodingFailed(format!("Failed to decode animation: {e}"))
+    };
+
+    match format {
+        ImageFormat::Gif => Ok(Some(
+            GifDecoder::new(Cursor::new(data))
+                .map_err(decoding_failed)?
+                .into_frames(),
+        )),
+        ImageFormat::WebP => {
+            let decoder = WebPDecoder::new(Cursor::new(data)).map_err(decoding_failed)?;
+            Ok(decoder.has_animation().then(|| decoder.into_frames()))
+        }
+        _ => Ok(None),
+    }
+}
+
+/// Check whether image data is an animated GIF/WebP with more than one frame
+pub fn is_animated(data: &[u8]) -> bool {
+    let Ok(format) = image::guess_format(data) else {
+        return false;
+    };
+    match animation_frames(data, format) {
+        Ok(Some(frames)) => frames.take(2).count() == 2,
+        _ => false,
+    }
+}
+
+/// Indices of `samples` evenly spaced frames out of `frame_count`, including first and last
+fn sample_frame_indices(frame_count: usize, samples: usize) -> Vec<usize> {
+    if frame_count <= samples {
+        return (0..frame_count).collect();
+    }
+    (0..samples)
+        .map(|i| i * (frame_count - 1) / (samples - 1))
+        .collect()
+}
+
+/// Lay frames out in a grid, left to right and top to bottom
+fn compose_frame_grid(frames: &[RgbaImage]) -> RgbaImage {
+    let (width, height) = frames[0].dimensions();
+    let columns = (frames.len() as f64).sqrt().ceil() as u32;
+    let rows = (frames.len() as u32).div_ceil(columns);
+
+    let mut grid = RgbaImage::new(width * columns, height * rows);
+    for (index, frame) in frames.iter().enumerate() {
+        let index = index as u32;
+        let x = (index % columns) * width;
+        let y = (index / columns) * height;
+        imageops::replace(&mut grid, frame, x as i64, y as i64);
+    }
+    grid
+}
+
+/// Shrink a frame to fit `max_dimension`, keeping its aspect ratio
+fn downscale_frame(frame: RgbaImage, max_dimension: u32) -> RgbaImage {
+    let (width, height) = frame.dimensions();
+    if width <= max_dimension && height <= max_dimension {
+        return frame;
+    }
+    let scale = max_dimension as f64 / width.max(height) as f64;
+    imageops::thumbnail(
+        &frame,
+        ((width as f64 * scale).round() as u32).max(1),
+        ((height as f64 * scale).round() as u32).max(1),
+    )
+}
+
+/// Montage of evenly spaced frames of an animated GIF/WebP, `None` for still images
+///
+/// Frames are decoded twice (count, then sample) so long animations aren't held in memory;
+/// only the first `MAX_ANIMATION_FRAMES` are considered, and sampled frames are downscaled
+/// right after decoding.
+fn decode_animation_montage(
+    data: &[u8],
+    format: ImageFormat,
+) -> Result<Option<DynamicImage>, MediaError> {
+    let Some(frames) = animation_frames(data, format)? else {
+        return Ok(None);
+    };
+    let frame_count = frames.take(MAX_ANIMATION_FRAMES).count();
+    if frame_count < 2 {
+        return Ok(None);
+    }
+
+    let indices = sample_frame_indices(frame_count, ANIMATION_SAMPLE_FRAMES);
+    let last_index = indices.last().copied().unwrap_or_default();
+    let sampled = animation_frames(data, format)?
+        .into_iter()
+        .flatten()
+        .take(last_index + 1)
+        .enumerate()
+        .filter(|(index, _)| indices.contains(index))
+        .map(|(_, frame)| {
+            frame.map(|frame| downscale_frame(frame.into_buffer(), ANIMATION_FRAME_MAX_DIMENSION))
+        })
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| {
+            MediaError::DecodingFailed(format!("Failed to decode animation frame: {e}"))
+        })?;
+
+    tracing::debug!(
+        "Sampled {} of {} animation frames for description",
+        sampled.len(),
+        frame_count
+    );
+    Ok(Some(DynamicImage::ImageRgba8(compose_frame_grid(&sampled))))
+}
+
+/// Add the note telling the model that an image is a grid of frames sampled from an
+/// animation, see `LanguageDetector::get_animation_note`
+pub fn prompt_with_animation_note(prompt_template: &str, animation_note: Option<&str>) -> String {
+    match animation_note {
+        Some(note) => format!("{prompt_template}\n\n{note}"),
+        None => prompt_template.to_string(),
+    }
+}
+
 /// Maximum dimension for image resizing (width or height)
 pub const DEFAULT_MAX_DIMENSION: u32 = 2048;
 
+/// Default size budget in MB for images sent for analysis, just under OpenRouter's 10MB limit
+pub const DEFAULT_ANALYSIS_BUDGET_MB: f64 = 9.5;
+
+/// JPEG quality of the first encoding attempt
+const JPEG_QUALITY: u8 = 65;
+
+/// Lowest JPEG quality tried before downscaling to meet the size budget
+const MIN_JPEG_QUALITY: u8 = 35;
+
+/// JPEG quality reduction per attempt while over the size budget
+const JPEG_QUALITY_STEP: u8 = 15;
+
+/// Images are not downscaled below this dimension to meet the size budget
+const MIN_BUDGET_DIMENSION: u32 = 256;
+
 /// Configuration for image processing
 #[derive(Debug, Clone)]
 pub struct ImageConfig {
     pub max_size_mb: f64,
     pub max_dimension: u32,
+    /// Encoded images are reduced in quality and size until they fit this many MB
+    pub analysis_budget_mb: f64,
+    /// Format images are re-encoded in for analysis
+    pub output_format: ImageFormat,
     #[allow(dead_code)]
     // Used in runtime logic but clippy may not detect it in --all-targets mode
     pub supported_formats: HashSet<String>,
@@ -48,6 +351,8 @@ impl Default for ImageConfig {
         Self {
             max_size_mb: 10.0, // Default from media.rs
             max_dimension: DEFAULT_MAX_DIMENSION,
+            analysis_budget_mb: DEFAULT_ANALYSIS_BUDGET_MB,
+            output_format: ImageFormat::Jpeg,
             supported_formats,
         }
     }
@@ -82,6 +387,58 @@ pub trait ImageTransformer {
     fn get_optimal_format(&self, original_format: ImageFormat) -> ImageFormat;
 }
 
+/// Encode an image as lossless WebP, PNG, or JPEG at the given quality
+fn encode_image(
+    img: &DynamicImage,
+    format: ImageFormat,
+    quality: u8,
+) -> Result<Vec<u8>, MediaError> {
+    let mut output = Vec::new();
+    match format {
+        ImageFormat::Jpeg => {
+            // Convert RGBA to RGB if necessary for JPEG compatibility
+            let rgb_img;
+            let img = match img.color() {
+                image::ColorType::Rgba8 | image::ColorType::Rgba16 => {
+                    rgb_img = DynamicImage::ImageRgb8(img.to_rgb8());
+                    &rgb_img
+                }
+                _ => img,
+            };
+
+            let encoder = JpegEncoder::new_with_quality(&mut output, quality);
+            img.write_with_encoder(encoder)
+                .map_err(|e| MediaError::EncodingFailed(format!("Failed to encode JPEG: {e}")))?;
+        }
+        ImageFormat::WebP => {
+            // The WebP encoder only takes 8-bit RGB(A)
+            let converted;
+            let img = match img {
+                DynamicImage::ImageRgb8(_) | DynamicImage::ImageRgba8(_) => img,
+                _ if img.color().has_alpha() => {
+                    converted = DynamicImage::ImageRgba8(img.to_rgba8());
+                    &converted
+                }
+                _ => {
+                    converted = DynamicImage::ImageRgb8(img.to_rgb8());
+                    &converted
+                }
+            };
+
+            let encoder = WebPEncoder::new_lossless(&mut output);
+            img.write_with_encoder(encoder)
+                .map_err(|e| MediaError::EncodingFailed(format!("Failed to encode WebP: {e}")))?;
+        }
+        _ => {
+            // PNG, also the fallback for other formats
+            let encoder = PngEncoder::new(&mut output);
+            img.write_with_encoder(encoder)
+                .map_err(|e| MediaError::EncodingFailed(format!("Failed to encode PNG: {e}")))?;
+        }
+    }
+    Ok(output)
+}
+
 /// Image transformer implementation
 pub struct ImageProcessor {
     config: ImageConfig,
@@ -144,6 +501,53 @@ impl ImageProcessor {
         Ok(())
     }
 
+    /// Encode the image, lowering JPEG quality and then dimensions until it fits the size budget
+    fn encode_within_budget(
+        &self,
+        mut img: DynamicImage,
+        format: ImageFormat,
+    ) -> Result<Vec<u8>, MediaError> {
+        let budget = (self.config.analysis_budget_mb * 1024.0 * 1024.0) as usize;
+        let mut quality = JPEG_QUALITY;
+
+        loop {
+            let output = encode_image(&img, format, quality)?;
+            if output.len() <= budget {
+                return Ok(output);
+            }
+
+            let (width, height) = img.dimensions();
+            if format == ImageFormat::Jpeg && quality > MIN_JPEG_QUALITY {
+                quality = quality
+                    .saturating_sub(JPEG_QUALITY_STEP)
+                    .max(MIN_JPEG_QUALITY);
+            } else if width.max(height) > MIN_BUDGET_DIMENSION {
+                // Pixel count roughly tracks encoded size; undershoot a little to converge quickly
+                let scale = ((budget as f64 / output.len() as f64).sqrt() * 0.9).clamp(0.5, 0.9);
+                let new_width = ((width as f64 * scale) as u32).max(1);
+                let new_height = ((height as f64 * scale) as u32).max(1);
+                img = img.resize(new_width, new_height, imageops::FilterType::Triangle);
+            } else {
+                return Err(MediaError::ProcessingFailed(format!(
+                    "Image could not be reduced below {:.2}MB (still {:.2}MB at {}x{})",
+                    self.config.analysis_budget_mb,
+                    output.len() as f64 / (1024.0 * 1024.0),
+                    width,
+                    height
+                )));
+            }
+
+            tracing::debug!(
+                "Encoded image is {} bytes, over the {} byte budget; retrying at {}x{}, quality {}",
+                output.len(),
+                budget,
+                img.width(),
+                img.height(),
+                quality
+            );
+        }
+    }
+
     /// Public method: Get optimal format for transformed image
     pub fn get_optimal_format(&self, original_format: ImageFormat) -> ImageFormat {
         <Self as ImageTransformer>::get_optimal_format(self, original_format)
@@ -190,6 +594,19 @@ impl ImageTransformer for ImageProcessor {
         // Check size limits first
         self.check_size_limits(image_data)?;
 
+        // AVIF/HEIC can't be decoded by the image crate, convert them up front
+        let converted;
+        let image_data = match detect_heif_container(image_data) {
+            Some(container) => {
+                if let Some(ref mut reporter) = progress_callback {
+                    reporter.report("Decoding HEIF image...");
+                }
+                converted = decode_heif_container(image_data, container)?;
+                converted.as_slice()
+            }
+            None => image_data,
+        };
+
         if let Some(ref mut reporter) = progress_callback {
             reporter.report("Detecting image format...");
         }
@@ -201,9 +618,12 @@ impl ImageTransformer for ImageProcessor {
             reporter.report("Loading image data...");
         }
 
-        // Load image with streaming support for large images
-        let img = image::load_from_memory(image_data)
-            .map_err(|e| MediaError::DecodingFailed(format!("Failed to decode image: {e}")))?;
+        // Animations become a montage of sampled frames, still images are loaded upright;
+        // the re-encoded output carries no EXIF data
+        let img = match decode_animation_montage(image_data, format)? {
+            Some(montage) => montage,
+            None => decode_upright(image_data)?,
+        };
 
         if let Some(ref mut reporter) = progress_callback {
             reporter.report("Resizing image if needed...");
@@ -219,38 +639,7 @@ impl ImageTransformer for ImageProcessor {
             reporter.report("Encoding image...");
         }
 
-        // Encode to bytes with streaming support
-        let mut output = Vec::new();
-        match output_format {
-            ImageFormat::Png => {
-                let encoder = PngEncoder::new(&mut output);
-                resized_img.write_with_encoder(encoder).map_err(|e| {
-                    MediaError::EncodingFailed(format!("Failed to encode PNG: {e}"))
-                })?;
-            }
-            ImageFormat::Jpeg => {
-                // Convert RGBA to RGB if necessary for JPEG compatibility
-                let rgb_img = match resized_img.color() {
-                    image::ColorType::Rgba8 | image::ColorType::Rgba16 => {
-                        // Convert RGBA to RGB by converting the image data
-                        DynamicImage::ImageRgb8(resized_img.to_rgb8())
-                    }
-                    _ => resized_img,
-                };
-
-                let encoder = JpegEncoder::new_with_quality(&mut output, 65);
-                rgb_img.write_with_encoder(encoder).map_err(|e| {
-                    MediaError::EncodingFailed(format!("Failed to encode JPEG: {e}"))
-                })?;
-            }
-            _ => {
-                // Fallback to PNG for other formats
-                let encoder = PngEncoder::new(&mut output);
-                resized_img.write_with_encoder(encoder).map_err(|e| {
-                    MediaError::EncodingFailed(format!("Failed to encode fallback PNG: {e}"))
-                })?;
-            }
-        }
+        let output = self.encode_within_budget(resized_img, output_format)?;
 
         if let Some(ref mut reporter) = progress_callback {
             reporter.report("Image processing complete");
@@ -279,9 +668,9 @@ impl ImageTransformer for ImageProcessor {
     }
 
     fn get_optimal_format(&self, _original_format: ImageFormat) -> ImageFormat {
-        // Convert all formats to JPEG for OpenRouter analysis to reduce file size
-        // and ensure maximum compatibility
-        ImageFormat::Jpeg
+        // All formats are converted to the configured output format (JPEG by default)
+        // to reduce file size and ensure compatibility with the vision models
+        self.config.output_format
     }
 }
 
@@ -372,6 +761,35 @@ mod tests {
         assert!(!processor.needs_description(&media5));
     }
 
+    #[test]
+    fn test_transform_for_analysis_webp_output() {
+        let processor = ImageProcessor::new(ImageConfig {
+            output_format: analysis_output_format("webp").unwrap(),
+            ..ImageConfig::default()
+        });
+        assert_eq!(
+            processor.get_optimal_format(ImageFormat::Png),
+            ImageFormat::WebP
+        );
+
+        // 16-bit and grayscale sources are converted to 8-bit RGB(A) for the encoder
+        for img in [
+            DynamicImage::new_rgba16(40, 30),
+            DynamicImage::new_luma8(40, 30),
+        ] {
+            let mut png_data = Vec::new();
+            img.write_with_encoder(PngEncoder::new(&mut png_data))
+                .unwrap();
+
+            let output = processor.transform_for_analysis(&png_data).unwrap();
+
+            assert_eq!(image::guess_format(&output).unwrap(), ImageFormat::WebP);
+            // The data URL sent to the model is typed from these bytes
+            assert_eq!(crate::media::sniff_media_type(&output), Some("image/webp"));
+            assert_eq!(image::load_from_memory(&output).unwrap().width(), 40);
+        }
+    }
+
     #[test]
     fn test_image_processor_get_optimal_format() {
         let processor = ImageProcessor::with_default_config();
@@ -423,6 +841,8 @@ mod tests {
         let config = ImageConfig {
             max_size_mb: 1.0, // 1MB limit
             max_dimension: 2048,
+            analysis_budget_mb: DEFAULT_ANALYSIS_BUDGET_MB,
+            output_format: ImageFormat::Jpeg,
             supported_formats: SUPPORTED_IMAGE_FORMATS
                 .iter()
                 .map(|s| s.to_string())
@@ -456,7 +876,119 @@ mod tests {
         assert!(SUPPORTED_IMAGE_FORMATS.contains(&"image/png"));
         assert!(SUPPORTED_IMAGE_FORMATS.contains(&"image/gif"));
         assert!(SUPPORTED_IMAGE_FORMATS.contains(&"image/webp"));
-        assert_eq!(SUPPORTED_IMAGE_FORMATS.len(), 9);
+        assert!(SUPPORTED_IMAGE_FORMATS.contains(&"image/heic"));
+        assert!(SUPPORTED_IMAGE_FORMATS.contains(&"image/heif"));
+        assert_eq!(SUPPORTED_IMAGE_FORMATS.len(), 11);
+    }
+
+    /// `ftyp` box with the given major and compatible brands
+    fn ftyp_box(major: &[u8; 4], compatible: &[&[u8; 4]]) -> Vec<u8> {
+        let size = 16 + 4 * compatible.len() as u32;
+        let mut data = size.to_be_bytes().to_vec();
+        data.extend_from_slice(b"ftyp");
+        data.extend_from_slice(major);
+        data.extend_from_slice(&[0, 0, 0, 0]);
+        for brand in compatible {
+            data.extend_from_slice(*brand);
+        }
+        data
+    }
+
+    #[test]
+    fn test_detect_heif_container() {
+        assert_eq!(
+            detect_heif_container(&ftyp_box(b"avif", &[b"mif1", b"miaf"])),
+            Some(HeifContainer::Avif)
+        );
+        assert_eq!(
+            detect_heif_container(&ftyp_box(b"mif1", &[b"avif"])),
+            Some(HeifContainer::Avif)
+        );
+        assert_eq!(
+            detect_heif_container(&ftyp_box(b"heic", &[b"mif1", b"heic"])),
+            Some(HeifContainer::Heic)
+        );
+        // MP4 video and non-ISO-BMFF data are left to the image crate
+        assert_eq!(detect_heif_container(&ftyp_box(b"isom", &[b"mp41"])), None);
+        assert_eq!(detect_heif_container(b"\x89PNG\r\n\x1a\n"), None);
+    }
+
+    /// Encode a small test image with an external tool
+    fn encode_sample(program: &str, args: &[&str], output: &std::path::Path) -> Vec<u8> {
+        let result = Command::new(program)
+            .args(args)
+            .output()
+            .unwrap_or_else(|e| panic!("{program} is required for this test: {e}"));
+        assert!(
+            result.status.success(),
+            "{program} failed: {}",
+            String::from_utf8_lossy(&result.stderr)
+        );
+        std::fs::read(output).unwrap()
+    }
+
+    fn assert_decodes_to_jpeg(data: &[u8]) {
+        let output = ImageProcessor::with_default_config()
+            .transform_for_analysis(data)
+            .unwrap();
+
+        assert_eq!(&output[..2], &[0xFF, 0xD8]);
+        let decoded = image::load_from_memory(&output).unwrap();
+        assert_eq!(decoded.dimensions(), (16, 16));
+    }
+
+    #[test]
+    #[ignore = "needs FFmpeg with libaom, and libheif or FFmpeg with AV1 decoding; run with --ignored"]
+    fn test_transform_avif_to_jpeg() {
+        let dir = tempfile::tempdir().unwrap();
+        let avif_path = dir.path().join("sample.avif");
+        let avif = encode_sample(
+            "ffmpeg",
+            &[
+                "-v",
+                "error",
+                "-f",
+                "lavfi",
+                "-i",
+                "color=c=red:size=16x16",
+                "-frames:v",
+                "1",
+                "-c:v",
+                "libaom-av1",
+                "-still-picture",
+                "1",
+                "-y",
+                &avif_path.to_string_lossy(),
+            ],
+            &avif_path,
+        );
+
+        assert_eq!(detect_heif_container(&avif), Some(HeifContainer::Avif));
+        assert_decodes_to_jpeg(&avif);
+    }
+
+    #[test]
+    #[ignore = "needs libheif's heif-enc, and libheif or FFmpeg with HEVC decoding; run with --ignored"]
+    fn test_transform_heic_to_jpeg() {
+        let dir = tempfile::tempdir().unwrap();
+        let png_path = dir.path().join("sample.png");
+        let heic_path = dir.path().join("sample.heic");
+        image::DynamicImage::new_rgb8(16, 16)
+            .save(&png_path)
+            .unwrap();
+
+        let heic = encode_sample(
+            "heif-enc",
+            &[
+                &png_path.to_string_lossy(),
+                "-o",
+                &heic_path.to_string_lossy(),
+            ],
+            &heic_path,
+        );
+
+        assert_eq!(detect_heif_container(&heic), Some(HeifContainer::Heic));
+        assert_decodes_to_jpeg(&heic);
     }
 
     #[test]
@@ -516,4 +1048,137 @@ mod tests {
         let loaded_img = image::load_from_memory(&output_data);
         assert!(loaded_img.is_ok(), "Output should be valid image data");
     }
+
+    #[test]
+    fn test_transform_reduces_output_to_size_budget() {
+        // Noise barely compresses, so even the resized JPEG starts far over the budget
+        let mut rng = fastrand::Rng::with_seed(42);
+        let noise = image::RgbImage::from_fn(1200, 900, |_, _| {
+            image::Rgb([rng.u8(..), rng.u8(..), rng.u8(..)])
+        });
+        let mut png_data = Vec::new();
+        DynamicImage::ImageRgb8(noise)
+            .write_with_encoder(PngEncoder::new(&mut png_data))
+            .unwrap();
+
+        let budget_mb = 0.1;
+        let processor = ImageProcessor::new(ImageConfig {
+            analysis_budget_mb: budget_mb,
+            ..ImageConfig::default()
+        });
+        let output = processor.transform_for_analysis(&png_data).unwrap();
+
+        assert!(output.len() <= (budget_mb * 1024.0 * 1024.0) as usize);
+        let loaded = image::load_from_memory(&output).unwrap();
+        assert!(loaded.width() < 1200);
+        // Aspect ratio survives the downscaling
+        let ratio = loaded.width() as f64 / loaded.height() as f64;
+        assert!((ratio - 4.0 / 3.0).abs() < 0.02);
+    }
+
+    #[test]
+    fn test_transform_applies_exif_orientation() {
+        let processor = ImageProcessor::with_default_config();
+
+        // Left half red, right half blue
+        let img = image::RgbImage::from_fn(32, 16, |x, _| {
+            if x < 16 {
+                image::Rgb([255, 0, 0])
+            } else {
+                image::Rgb([0, 0, 255])
+            }
+        });
+
+        // Minimal little-endian TIFF block with Orientation = 6 (rotate 90° clockwise)
+        let exif = vec![
+            0x49, 0x49, 0x2A, 0x00, 0x08, 0x00, 0x00, 0x00, // header, IFD at offset 8
+            0x01, 0x00, // one entry
+            0x12, 0x01, 0x03, 0x00, 0x01, 0x00, 0x00, 0x00, 0x06, 0x00, 0x00,
+            0x00, // orientation
+            0x00, 0x00, 0x00, 0x00, // no next IFD
+        ];
+        let mut jpeg_data = Vec::new();
+        let mut encoder = JpegEncoder::new_with_quality(&mut jpeg_data, 95);
+        image::ImageEncoder::set_exif_metadata(&mut encoder, exif).unwrap();
+        DynamicImage::ImageRgb8(img)
+            .write_with_encoder(encoder)
+            .unwrap();
+
+        let output = processor.transform_for_analysis(&jpeg_data).unwrap();
+        let rotated = image::load_from_memory(&output).unwrap().to_rgb8();
+
+        // The left half ends up on top after rotating clockwise
+        assert_eq!(rotated.dimensions(), (16, 32));
+        let top = rotated.get_pixel(8, 4);
+        let bottom = rotated.get_pixel(8, 28);
+        assert!(top[0] > 200 && top[2] < 60, "top should be red: {top:?}");
+        assert!(
+            bottom[2] > 200 && bottom[0] < 60,
+            "bottom should be blue: {bottom:?}"
+        );
+
+        // EXIF is not carried over into the analysis image
+        assert!(!output.windows(6).any(|w| w == b"Exif\0\0"));
+    }
+
+    fn encode_animated_gif(colors: &[[u8; 4]]) -> Vec<u8> {
+        let mut data = Vec::new();
+        {
+            let mut encoder = image::codecs::gif::GifEncoder::new(&mut data);
+            let frames = colors
+                .iter()
+                .map(|color| image::Frame::new(RgbaImage::from_pixel(8, 8, image::Rgba(*color))));
+            encoder.encode_frames(frames).unwrap();
+        }
+        data
+    }
+
+    #[test]
+    fn test_animated_gif_becomes_frame_montage() {
+        let processor = ImageProcessor::with_default_config();
+        let red = [255, 0, 0, 255];
+        let green = [0, 255, 0, 255];
+        let blue = [0, 0, 255, 255];
+        let white = [255, 255, 255, 255];
+        let gif = encode_animated_gif(&[red, red, green, green, blue, blue, white]);
+
+        assert!(is_animated(&gif));
+        assert_eq!(
+            sample_frame_indices(7, ANIMATION_SAMPLE_FRAMES),
+            [0, 2, 4, 6]
+        );
+
+        let output = processor.transform_for_analysis(&gif).unwrap();
+        let montage = image::load_from_memory(&output).unwrap().to_rgb8();
+
+        // Four sampled 8x8 frames in a 2x2 grid
+        assert_eq!(montage.dimensions(), (16, 16));
+        let close = |pixel: &image::Rgb<u8>, expected: [u8; 4]| {
+            pixel
+                .0
+                .iter()
+                .zip(expected)
+                .all(|(&actual, expected)| actual.abs_diff(expected) < 60)
+        };
+        assert!(close(montage.get_pixel(4, 4), red));
+        assert!(close(montage.get_pixel(12, 4), green));
+        assert!(close(montage.get_pixel(4, 12), blue));
+        assert!(close(montage.get_pixel(12, 12), white));
+
+        // Large frames are shrunk before they are composed
+        let frame = RgbaImage::new(3000, 1500);
+        assert_eq!(
+            downscale_frame(frame, ANIMATION_FRAME_MAX_DIMENSION).dimensions(),
+            (1024, 512)
+        );
+
+        // A single-frame GIF is handled as a still image
+        let still = encode_animated_gif(&[red]);
+        assert!(!is_animated(&still));
+        let output = processor.transform_for_analysis(&still).unwrap();
+        assert_eq!(
+            image::load_from_memory(&output).unwrap().dimensions(),
+            (8, 8)
+        );
+    }
 }
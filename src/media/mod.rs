@@ -1,11 +1,19 @@
 pub mod audio;
 pub mod helpers;
+pub mod hosts;
 pub mod image;
+pub mod ocr;
+pub mod sniff;
 pub mod video;
+pub mod written;
 
 use crate::error::MediaError;
 use crate::mastodon::MediaAttachment;
+use crate::redact::{log_text, log_url};
+use hosts::MediaHostPolicy;
 use std::collections::HashSet;
+use std::time::Duration;
+use written::WrittenDescriptions;
 
 /// Progress reporter for tracking operation progress
 pub struct ProgressReporter {
@@ -81,20 +89,62 @@ pub type ProgressCallback = Option<ProgressReporter>;
 pub type StreamingCallback = Option<StreamingProcessor>;
 
 // Re-export items for backward compatibility
-pub use audio::{is_ffmpeg_available, process_audio_for_transcript, SUPPORTED_AUDIO_FORMATS};
+pub use audio::{
+    is_ffmpeg_available, process_audio_for_transcript, set_ffmpeg_path, SUPPORTED_AUDIO_FORMATS,
+};
 pub use helpers::TempFile;
-pub use image::{ImageFormat, ImageTransformer, SUPPORTED_IMAGE_FORMATS};
-pub use video::{process_video_for_transcript, SUPPORTED_VIDEO_FORMATS};
+pub use image::{
+    is_animated, prompt_with_animation_note, ImageFormat, ImageTransformer, SUPPORTED_IMAGE_FORMATS,
+};
+pub use ocr::{extract_image_text, is_tesseract_available, prompt_with_ocr_text};
+pub use sniff::{resolve_media_type, sniff_media_type};
+pub use video::{
+    is_gifv, process_video_for_keyframe, process_video_for_transcript, NO_SPEECH_DESCRIPTION,
+    SUPPORTED_VIDEO_FORMATS,
+};
 
 /// Maximum file size in MB for processing
 pub const DEFAULT_MAX_SIZE_MB: f64 = 10.0;
 
+/// Maximum size in MB of a single streamed media download
+pub const DEFAULT_MAX_DOWNLOAD_SIZE_MB: u32 = 100;
+
+/// Retries after a transient media download failure
+pub const DEFAULT_DOWNLOAD_RETRIES: u32 = 3;
+
+/// Images with a smaller width or height are not described (0 disables the check)
+pub const DEFAULT_MIN_IMAGE_DIMENSION: u32 = 0;
+
+/// First backoff delay between download attempts, doubled on each retry
+const DOWNLOAD_RETRY_BASE_DELAY: Duration = Duration::from_secs(1);
+
+/// Upper bound for the backoff delay between download attempts
+const DOWNLOAD_RETRY_MAX_DELAY: Duration = Duration::from_secs(30);
+
 /// Configuration for media processing that supports both images and audio
 #[derive(Debug, Clone)]
 pub struct MediaConfig {
     pub max_size_mb: f64,
     pub max_dimension: u32,
     pub supported_formats: HashSet<String>,
+    /// Abort streaming downloads larger than this many MB
+    pub max_download_size_mb: u32,
+    /// Retry transient download failures (connection errors, timeouts, 5xx) this many times
+    pub download_retries: u32,
+    /// HTTP(S) proxy for media downloads
+    pub proxy_url: Option<String>,
+    /// Hosts media may be downloaded from
+    pub host_policy: MediaHostPolicy,
+    /// Images sent for analysis are re-encoded until they fit this many MB
+    pub analysis_budget_mb: f64,
+    /// Format images are re-encoded in for analysis
+    pub analysis_output_format: ImageFormat,
+    /// Skip images whose width or height is below this many pixels (0 disables)
+    pub min_image_dimension: u32,
+    /// Treat descriptions with an AI attribution as missing so they are regenerated
+    pub reprocess_existing_descriptions: bool,
+    /// Custom attribution template recognized besides the built-in ones
+    pub attribution_template: Option<String>,
 }
 
 impl Default for MediaConfig {
@@ -120,6 +170,15 @@ impl Default for MediaConfig {
             max_size_mb: DEFAULT_MAX_SIZE_MB,
             max_dimension: image::DEFAULT_MAX_DIMENSION,
             supported_formats,
+            max_download_size_mb: DEFAULT_MAX_DOWNLOAD_SIZE_MB,
+            download_retries: DEFAULT_DOWNLOAD_RETRIES,
+            proxy_url: None,
+            host_policy: MediaHostPolicy::default(),
+            analysis_budget_mb: image::DEFAULT_ANALYSIS_BUDGET_MB,
+            analysis_output_format: ImageFormat::Jpeg,
+            min_image_dimension: DEFAULT_MIN_IMAGE_DIMENSION,
+            reprocess_existing_descriptions: false,
+            attribution_template: None,
         }
     }
 }
@@ -149,6 +208,12 @@ pub trait MediaTransformer: Send + Sync {
     /// Check if media attachment needs a description
     fn needs_description(&self, media: &MediaAttachment) -> bool;
 
+    /// Check the downloaded data's actual format against the supported formats
+    fn check_content_type(&self, media: &MediaAttachment, data: &[u8]) -> Result<(), MediaError> {
+        let _ = (media, data);
+        Ok(())
+    }
+
     /// Get optimal format for transformed image
     #[allow(dead_code)]
     fn get_optimal_format(&self, original_format: ImageFormat) -> ImageFormat;
@@ -175,6 +240,8 @@ impl UnifiedMediaTransformer {
         let image_config = image::ImageConfig {
             max_size_mb: config.max_size_mb,
             max_dimension: config.max_dimension,
+            analysis_budget_mb: config.analysis_budget_mb,
+            output_format: config.analysis_output_format,
             supported_formats: config
                 .supported_formats
                 .iter()
@@ -216,6 +283,7 @@ impl MediaTransformer for UnifiedMediaTransformer {
         }
 
         // Handle Mastodon API format where type is just "image", "video", etc.
+        // The concrete format is only known after download, see `check_content_type`
         let result = match media_type_lower.as_str() {
             "image" => {
                 let has_image = self
@@ -252,6 +320,12 @@ impl MediaTransformer for UnifiedMediaTransformer {
                 );
                 has_video
             }
+            // Uploaded GIFs are served as silent MP4s
+            video::GIFV_MEDIA_TYPE => {
+                let has_mp4 = self.config.supported_formats.contains("video/mp4");
+                tracing::debug!("Generic 'gifv' type: has_mp4_format = {}", has_mp4);
+                has_mp4
+            }
             _ => {
                 tracing::debug!("Unknown media type: '{}'", media_type);
                 false
@@ -296,6 +370,37 @@ impl MediaTransformer for UnifiedMediaTransformer {
         }
     }
 
+    fn check_content_type(&self, media: &MediaAttachment, data: &[u8]) -> Result<(), MediaError> {
+        if !media.media_type.trim().to_lowercase().starts_with("image") {
+            return Ok(());
+        }
+
+        // Unrecognized data is left to the decoder to reject
+        let Some(sniffed) = image::sniff_image_type(data) else {
+            return Ok(());
+        };
+
+        let allowed = |format: &str| self.config.supported_formats.contains(format);
+        let supported = match sniffed {
+            "image/jpeg" => allowed("image/jpeg") || allowed("image/jpg"),
+            "image/heic" => allowed("image/heic") || allowed("image/heif"),
+            other => allowed(other),
+        };
+
+        if supported {
+            Ok(())
+        } else {
+            tracing::info!(
+                "Media {} is {}, which is not in the supported formats",
+                media.id,
+                sniffed
+            );
+            Err(MediaError::UnsupportedType {
+                media_type: sniffed.to_string(),
+            })
+        }
+    }
+
     fn get_optimal_format(&self, original_format: ImageFormat) -> ImageFormat {
         self.image_processor.get_optimal_format(original_format)
     }
@@ -309,28 +414,77 @@ impl MediaTransformer for UnifiedMediaTransformer {
 pub struct MediaProcessor {
     transformer: Box<dyn MediaTransformer + Send + Sync>,
     http_client: reqwest::Client,
+    max_download_size_mb: u32,
+    download_retries: u32,
+    download_retry_base_delay: Duration,
+    host_policy: MediaHostPolicy,
+    min_image_dimension: u32,
+    reprocess_existing_descriptions: bool,
+    attribution_template: Option<String>,
+    /// Descriptions written by this process, shared between clones
+    written_descriptions: WrittenDescriptions,
 }
 
 impl Clone for MediaProcessor {
     fn clone(&self) -> Self {
         Self {
             transformer: self.transformer.clone_box(),
-            http_client: reqwest::Client::new(),
+            http_client: self.http_client.clone(),
+            max_download_size_mb: self.max_download_size_mb,
+            download_retries: self.download_retries,
+            download_retry_base_delay: self.download_retry_base_delay,
+            host_policy: self.host_policy.clone(),
+            min_image_dimension: self.min_image_dimension,
+            reprocess_existing_descriptions: self.reprocess_existing_descriptions,
+            attribution_template: self.attribution_template.clone(),
+            written_descriptions: self.written_descriptions.clone(),
         }
     }
 }
 
+/// Outcome of a failed download attempt
+enum DownloadFailure {
+    /// Connection error, timeout or server error that may succeed on retry
+    Transient(MediaError),
+    /// Client error, size limit or callback failure that won't change on retry
+    Permanent(MediaError),
+}
+
 impl MediaProcessor {
     pub fn new(transformer: Box<dyn MediaTransformer + Send + Sync>) -> Self {
         Self {
             transformer,
             http_client: reqwest::Client::new(),
+            max_download_size_mb: DEFAULT_MAX_DOWNLOAD_SIZE_MB,
+            download_retries: DEFAULT_DOWNLOAD_RETRIES,
+            download_retry_base_delay: DOWNLOAD_RETRY_BASE_DELAY,
+            host_policy: MediaHostPolicy::default(),
+            min_image_dimension: DEFAULT_MIN_IMAGE_DIMENSION,
+            reprocess_existing_descriptions: false,
+            attribution_template: None,
+            written_descriptions: WrittenDescriptions::default(),
         }
     }
 
     /// Create processor with unified transformer (supports both images and audio)
     pub fn with_unified_transformer(config: MediaConfig) -> Self {
-        Self::new(Box::new(UnifiedMediaTransformer::new(config)))
+        let max_download_size_mb = config.max_download_size_mb;
+        let download_retries = config.download_retries;
+        let min_image_dimension = config.min_image_dimension;
+        let reprocess_existing_descriptions = config.reprocess_existing_descriptions;
+        let attribution_template = config.attribution_template.clone();
+        let host_policy = config.host_policy.clone();
+        let http_client = host_policy.http_client(config.proxy_url.as_deref());
+        Self {
+            http_client,
+            host_policy,
+            max_download_size_mb,
+            download_retries,
+            min_image_dimension,
+            reprocess_existing_descriptions,
+            attribution_template,
+            ..Self::new(Box::new(UnifiedMediaTransformer::new(config)))
+        }
     }
 
     /// Backward compatibility: create processor with image transformer
@@ -343,6 +497,40 @@ impl MediaProcessor {
         Self::with_unified_transformer(MediaConfig::default())
     }
 
+    /// Whether media needs a description from Alternator
+    ///
+    /// With `reprocess_existing_descriptions`, Alternator's own earlier descriptions count as
+    /// missing; human-written descriptions are never replaced.
+    pub fn needs_description(&self, media: &MediaAttachment) -> bool {
+        if self.has_replaceable_description(media) {
+            let undescribed = MediaAttachment {
+                description: None,
+                ..media.clone()
+            };
+            return self.transformer.needs_description(&undescribed);
+        }
+        self.transformer.needs_description(media)
+    }
+
+    /// Whether the media's description carries an AI attribution and may be regenerated
+    ///
+    /// Descriptions this process wrote itself are never replaceable, so its own edits coming
+    /// back through the stream (or recreated under new media IDs) are not described again.
+    pub fn has_replaceable_description(&self, media: &MediaAttachment) -> bool {
+        self.reprocess_existing_descriptions
+            && media.description.as_deref().is_some_and(|description| {
+                crate::language::has_ai_attribution(
+                    description,
+                    self.attribution_template.as_deref(),
+                ) && !self.written_descriptions.contains(description)
+            })
+    }
+
+    /// Descriptions written by this process
+    pub fn written_descriptions(&self) -> &WrittenDescriptions {
+        &self.written_descriptions
+    }
+
     /// Filter media attachments to only include supported types that need descriptions
     pub fn filter_processable_media<'a>(
         &self,
@@ -352,11 +540,56 @@ impl MediaProcessor {
             .iter()
             .filter(|media| {
                 self.transformer.is_supported(&media.media_type)
-                    && self.transformer.needs_description(media)
+                    && self.needs_description(media)
+                    && !self.is_below_min_dimension(media)
             })
             .collect()
     }
 
+    /// Whether an image is too small to describe according to its reported dimensions
+    ///
+    /// Images without dimensions in their metadata pass; their decoded size is checked
+    /// after download with [`Self::data_below_min_dimension`].
+    pub fn is_below_min_dimension(&self, media: &MediaAttachment) -> bool {
+        if self.min_image_dimension == 0 || !media.media_type.to_lowercase().starts_with("image") {
+            return false;
+        }
+
+        let Some(original) = media.meta.as_ref().and_then(|meta| meta.original.as_ref()) else {
+            return false;
+        };
+        let (Some(width), Some(height)) = (original.width, original.height) else {
+            return false;
+        };
+
+        let too_small = width < self.min_image_dimension || height < self.min_image_dimension;
+        if too_small {
+            tracing::info!(
+                "Skipping image {} ({}x{}): smaller than min_image_dimension {}",
+                media.id,
+                width,
+                height,
+                self.min_image_dimension
+            );
+        }
+        too_small
+    }
+
+    /// Dimensions of downloaded image data if it is too small to describe
+    pub fn data_below_min_dimension(&self, data: &[u8]) -> Option<(u32, u32)> {
+        if self.min_image_dimension == 0 {
+            return None;
+        }
+
+        let (width, height) = ::image::ImageReader::new(std::io::Cursor::new(data))
+            .with_guessed_format()
+            .ok()?
+            .into_dimensions()
+            .ok()?;
+        (width < self.min_image_dimension || height < self.min_image_dimension)
+            .then_some((width, height))
+    }
+
     /// Filter media attachments to include image, audio, and video types when enabled
     pub fn filter_processable_media_with_audio<'a>(
         &self,
@@ -368,7 +601,8 @@ impl MediaProcessor {
             .filter(|media| {
                 // Check for image support via transformer
                 let image_supported = self.transformer.is_supported(&media.media_type)
-                    && self.transformer.needs_description(media);
+                    && self.needs_description(media)
+                    && !self.is_below_min_dimension(media);
 
                 // Check for audio support if enabled
                 let audio_supported = if audio_enabled {
@@ -377,27 +611,28 @@ impl MediaProcessor {
                         || media_type_lower.starts_with("audio")
                         || media_type_lower == "audio";
                     is_audio
-                        && media
+                        && (media
                             .description
                             .as_ref()
                             .map_or(true, |desc| desc.trim().is_empty())
+                            || self.has_replaceable_description(media))
                 } else {
                     false
                 };
 
-                // Check for video support if audio is enabled (since video processing uses audio extraction)
-                let video_supported = if audio_enabled {
+                // Check for video support if audio is enabled (since video processing uses audio extraction);
+                // uploaded GIFs ("gifv") are silent, so only their keyframe is described
+                let video_supported = {
                     let media_type_lower = media.media_type.to_lowercase();
                     let is_video = SUPPORTED_VIDEO_FORMATS.contains(&media_type_lower.as_str())
                         || media_type_lower.starts_with("video")
                         || media_type_lower == "video";
-                    is_video
-                        && media
+                    ((is_video && audio_enabled) || is_gifv(&media_type_lower))
+                        && (media
                             .description
                             .as_ref()
                             .map_or(true, |desc| desc.trim().is_empty())
-                } else {
-                    false
+                            || self.has_replaceable_description(media))
                 };
 
                 image_supported || audio_supported || video_supported
@@ -405,6 +640,14 @@ impl MediaProcessor {
             .collect()
     }
 
+    /// Check a media URL against the host policy without downloading it
+    ///
+    /// Used for URLs the description provider fetches itself, which never go through
+    /// [`Self::download_media`].
+    pub async fn check_media_host(&self, url: &url::Url) -> Result<(), MediaError> {
+        self.host_policy.check(url).await
+    }
+
     /// Download media from URL with streaming support
     pub async fn download_media(&self, url: &str) -> Result<Vec<u8>, MediaError> {
         self.download_media_with_callback(url, None).await
@@ -420,7 +663,7 @@ impl MediaProcessor {
         let parsed_url = match url::Url::parse(url) {
             Ok(u) => u,
             Err(e) => {
-                tracing::warn!("Invalid URL format: {}: {}", url, e);
+                tracing::warn!("Invalid URL format: {}: {}", log_url(url), e);
                 return Err(MediaError::DownloadFailed {
                     url: url.to_string(),
                 });
@@ -435,47 +678,107 @@ impl MediaProcessor {
             });
         }
 
-        // Clone URL early to avoid borrow issues in error handling
-        let url_string = url.to_string();
+        self.host_policy.check(&parsed_url).await?;
+
+        let mut attempt = 0;
+        loop {
+            match self.try_download(url, &mut callback).await {
+                Ok(data) => return Ok(data),
+                Err(DownloadFailure::Transient(_)) if attempt < self.download_retries => {
+                    let delay = self.download_retry_delay(attempt);
+                    attempt += 1;
+                    tracing::info!(
+                        "Retrying download of {} in {:?} (attempt {}/{})",
+                        log_url(url),
+                        delay,
+                        attempt,
+                        self.download_retries
+                    );
+                    tokio::time::sleep(delay).await;
+                }
+                Err(DownloadFailure::Transient(e) | DownloadFailure::Permanent(e)) => {
+                    return Err(e)
+                }
+            }
+        }
+    }
+
+    /// Exponential backoff between download attempts, capped at 30 seconds
+    fn download_retry_delay(&self, attempt: u32) -> Duration {
+        let exponential_delay = self.download_retry_base_delay * 2_u32.pow(attempt.min(6));
+        exponential_delay.min(DOWNLOAD_RETRY_MAX_DELAY)
+    }
+
+    /// Single download attempt, classifying failures as transient or permanent
+    async fn try_download(
+        &self,
+        url: &str,
+        callback: &mut StreamingCallback,
+    ) -> Result<Vec<u8>, DownloadFailure> {
+        let download_failed = || MediaError::DownloadFailed {
+            url: url.to_string(),
+        };
 
         let response = self.http_client.get(url).send().await.map_err(|e| {
-            tracing::warn!("Failed to send request to {}: {}", url_string, e);
-            MediaError::DownloadFailed {
-                url: url_string.clone(),
+            tracing::warn!(
+                "Failed to send request to {}: {}",
+                log_url(url),
+                log_text(&e.to_string())
+            );
+            if e.is_connect() || e.is_timeout() || e.is_request() {
+                DownloadFailure::Transient(download_failed())
+            } else {
+                DownloadFailure::Permanent(download_failed())
             }
         })?;
 
-        if !response.status().is_success() {
-            tracing::warn!("HTTP error {} for URL: {}", response.status(), url_string);
-            return Err(MediaError::DownloadFailed { url: url_string });
+        let status = response.status();
+        if !status.is_success() {
+            tracing::warn!("HTTP error {} for URL: {}", status, log_url(url));
+            return Err(
+                if status.is_server_error() || status == reqwest::StatusCode::TOO_MANY_REQUESTS {
+                    DownloadFailure::Transient(download_failed())
+                } else {
+                    DownloadFailure::Permanent(download_failed())
+                },
+            );
         }
 
         // Use streaming download to reduce memory usage for large files
         let mut stream = response.bytes_stream();
         let mut data = Vec::new();
         let mut total_size = 0usize;
+        let max_download_bytes = self.max_download_size_mb as usize * 1024 * 1024;
 
         use futures_util::StreamExt;
         while let Some(chunk_result) = stream.next().await {
             let chunk = chunk_result.map_err(|e| {
-                tracing::warn!("Failed to read chunk from {}: {}", url_string, e);
-                MediaError::DownloadFailed {
-                    url: url_string.clone(),
+                tracing::warn!(
+                    "Failed to read chunk from {}: {}",
+                    log_url(url),
+                    log_text(&e.to_string())
+                );
+                // Chunks already handed to a callback can't be taken back
+                if callback.is_some() {
+                    DownloadFailure::Permanent(download_failed())
+                } else {
+                    DownloadFailure::Transient(download_failed())
                 }
             })?;
 
             // Check for reasonable size limits to prevent memory exhaustion
             total_size += chunk.len();
-            if total_size > 100 * 1024 * 1024 {
-                // 100MB limit
-                return Err(MediaError::ProcessingFailed(
-                    "Media file too large (>100MB)".to_string(),
-                ));
+            if total_size > max_download_bytes {
+                return Err(DownloadFailure::Permanent(MediaError::ProcessingFailed(
+                    format!("Media file too large (>{}MB)", self.max_download_size_mb),
+                )));
             }
 
             // Call callback if provided for streaming processing
             if let Some(ref mut processor) = callback {
-                processor.process(&chunk)?;
+                processor
+                    .process(&chunk)
+                    .map_err(DownloadFailure::Permanent)?;
             }
 
             data.extend_from_slice(&chunk);
@@ -506,7 +809,7 @@ impl MediaProcessor {
             });
         }
 
-        if !self.transformer.needs_description(media) {
+        if !self.needs_description(media) {
             return Err(MediaError::ProcessingFailed(
                 "Media already has description".to_string(),
             ));
@@ -514,10 +817,33 @@ impl MediaProcessor {
 
         // Download media data with streaming support
         let media_data = self.download_media(&media.url).await?;
+        self.transformer.check_content_type(media, &media_data)?;
 
         // Transform for analysis with progress callback
-        self.transformer
-            .transform_for_analysis_with_progress(&media_data, progress_callback)
+        self.transform_blocking(media_data, progress_callback).await
+    }
+
+    /// Prepare already downloaded media data for analysis (resize, re-encode)
+    #[allow(dead_code)] // Used by the library's MediaDescriber
+    pub async fn transform_for_analysis(&self, media_data: &[u8]) -> Result<Vec<u8>, MediaError> {
+        self.transform_blocking(media_data.to_vec(), None).await
+    }
+
+    /// Run the transformer on the blocking thread pool
+    ///
+    /// Decoding, resizing and the external HEIC/AVIF decoders would otherwise stall a runtime
+    /// worker while other media of the toot are processed.
+    async fn transform_blocking(
+        &self,
+        media_data: Vec<u8>,
+        progress_callback: ProgressCallback,
+    ) -> Result<Vec<u8>, MediaError> {
+        let transformer = self.transformer.clone_box();
+        tokio::task::spawn_blocking(move || {
+            transformer.transform_for_analysis_with_progress(&media_data, progress_callback)
+        })
+        .await
+        .map_err(|e| MediaError::ProcessingFailed(format!("Image transform task failed: {e}")))?
     }
 
     /// Download media from an attachment and return the raw bytes for re-upload
@@ -533,7 +859,9 @@ impl MediaProcessor {
         }
 
         // Download the original media data (not transformed for analysis)
-        self.download_media(&media.url).await
+        let media_data = self.download_media(&media.url).await?;
+        self.transformer.check_content_type(media, &media_data)?;
+        Ok(media_data)
     }
 
     /// Get statistics about media attachments
@@ -546,10 +874,7 @@ impl MediaProcessor {
             .count();
         let needs_description = media_attachments
             .iter()
-            .filter(|m| {
-                self.transformer.is_supported(&m.media_type)
-                    && self.transformer.needs_description(m)
-            })
+            .filter(|m| self.transformer.is_supported(&m.media_type) && self.needs_description(m))
             .count();
         let processable = self.filter_processable_media(media_attachments).len();
 
@@ -685,6 +1010,114 @@ mod tests {
         assert_eq!(processable[3].id, "6");
     }
 
+    #[test]
+    fn test_gifv_processable_without_audio() {
+        let gifv = vec![create_test_media("gif", "gifv", None)];
+
+        let processor = MediaProcessor::with_default_config();
+        assert_eq!(processor.filter_processable_media(&gifv).len(), 1);
+        assert_eq!(
+            processor
+                .filter_processable_media_with_audio(&gifv, false)
+                .len(),
+            1
+        );
+
+        // Served as MP4, so it needs MP4 support
+        let mut config = MediaConfig::default();
+        config.supported_formats.remove("video/mp4");
+        let processor = MediaProcessor::with_unified_transformer(config);
+        assert!(processor.filter_processable_media(&gifv).is_empty());
+    }
+
+    #[test]
+    fn test_ai_descriptions_reprocessed_when_enabled() {
+        let ai_description =
+            "A red square — this image description was made by AI: google/gemma-3-27b-it:free";
+        let media_attachments = vec![
+            create_test_media("ai", "image", Some(ai_description.to_string())),
+            create_test_media("human", "image", Some("My cat on the sofa".to_string())),
+            create_test_media(
+                "custom",
+                "image",
+                Some("A dog — via some/model".to_string()),
+            ),
+        ];
+
+        let processor = MediaProcessor::with_unified_transformer(MediaConfig::default());
+        assert!(processor
+            .filter_processable_media(&media_attachments)
+            .is_empty());
+
+        let processor = MediaProcessor::with_unified_transformer(MediaConfig {
+            reprocess_existing_descriptions: true,
+            attribution_template: Some("via {model}".to_string()),
+            ..MediaConfig::default()
+        });
+        let processable: Vec<&str> = processor
+            .filter_processable_media(&media_attachments)
+            .iter()
+            .map(|media| media.id.as_str())
+            .collect();
+        assert_eq!(processable, vec!["ai", "custom"]);
+        assert!(!processor.needs_description(&media_attachments[1]));
+    }
+
+    #[test]
+    fn test_images_below_min_dimension_not_processable() {
+        let processor = MediaProcessor::with_unified_transformer(MediaConfig {
+            min_image_dimension: 64,
+            ..MediaConfig::default()
+        });
+        let sized = |id: &str, width: u32, height: u32| MediaAttachment {
+            meta: Some(crate::mastodon::MediaMeta {
+                original: Some(crate::mastodon::MediaDimensions {
+                    width: Some(width),
+                    height: Some(height),
+                    size: None,
+                    aspect: None,
+                    duration: None,
+                }),
+                small: None,
+                focus: None,
+            }),
+            ..create_test_media(id, "image", None)
+        };
+
+        let media_attachments = vec![
+            sized("pixel", 1, 1),
+            sized("emoji", 32, 32),
+            sized("banner", 1200, 40),
+            sized("photo", 1200, 800),
+            sized("threshold", 64, 64),
+            // Without reported dimensions the decoded image is checked later
+            create_test_media("unknown", "image", None),
+        ];
+
+        let ids: Vec<_> = processor
+            .filter_processable_media_with_audio(&media_attachments, false)
+            .iter()
+            .map(|media| media.id.as_str())
+            .collect();
+        assert_eq!(ids, ["photo", "threshold", "unknown"]);
+
+        let mut tiny_png = Vec::new();
+        ::image::DynamicImage::new_rgb8(16, 16)
+            .write_to(
+                &mut std::io::Cursor::new(&mut tiny_png),
+                ::image::ImageFormat::Png,
+            )
+            .unwrap();
+        assert_eq!(
+            processor.data_below_min_dimension(&tiny_png),
+            Some((16, 16))
+        );
+        assert_eq!(
+            MediaProcessor::with_default_config().data_below_min_dimension(&tiny_png),
+            None
+        );
+    }
+
     #[test]
     fn test_media_processor_get_media_stats() {
         let processor = MediaProcessor::with_default_config();
@@ -751,4 +1184,254 @@ mod tests {
         assert!(transformer.is_supported("VIDEO"));
         assert!(transformer.is_supported("video"));
     }
+
+    #[tokio::test]
+    async fn test_download_respects_configured_max_size() {
+        use crate::test_support::{local_media_config, MockHttpServer, MockResponse};
+
+        const MB: usize = 1024 * 1024;
+        let server = MockHttpServer::start(|request| {
+            let size = if request.path == "/exact" { MB } else { MB + 1 };
+            MockResponse::bytes(200, "application/octet-stream", vec![0u8; size])
+        })
+        .await;
+
+        let processor = MediaProcessor::with_unified_transformer(MediaConfig {
+            max_download_size_mb: 1,
+            ..local_media_config()
+        });
+
+        // Exactly at the limit is still accepted
+        let data = processor
+            .download_media(&format!("{}/exact", server.url()))
+            .await
+            .unwrap();
+        assert_eq!(data.len(), MB);
+
+        // One byte over the limit aborts the download
+        let err = processor
+            .download_media(&format!("{}/over", server.url()))
+            .await
+            .unwrap_err();
+        match err {
+            MediaError::ProcessingFailed(message) => assert!(message.contains(">1MB")),
+            other => panic!("expected ProcessingFailed, got {other:?}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_download_from_private_host_rejected_before_request() {
+        use crate::test_support::{MockHttpServer, MockResponse};
+
+        let server =
+            MockHttpServer::start(|_| MockResponse::bytes(200, "image/png", b"internal".to_vec()))
+                .await;
+
+        let result = MediaProcessor::with_default_config()
+            .download_media(&format!("{}/media/a.png", server.url()))
+            .await;
+        assert!(matches!(result, Err(MediaError::HostNotAllowed { .. })));
+        assert!(server.requests().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_download_retry_log_redacts_url() {
+        use crate::test_support::{local_media_processor, MockHttpServer, MockResponse};
+        use std::sync::{Arc, Mutex};
+
+        #[derive(Clone, Default)]
+        struct Capture(Arc<Mutex<Vec<u8>>>);
+
+        impl std::io::Write for Capture {
+            fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+                self.0.lock().unwrap().extend_from_slice(buf);
+                Ok(buf.len())
+            }
+
+            fn flush(&mut self) -> std::io::Result<()> {
+                Ok(())
+            }
+        }
+
+        let server = MockHttpServer::start(|_| MockResponse::json(503, "{}")).await;
+        let capture = Capture::default();
+        let writer = capture.clone();
+        let subscriber = tracing_subscriber::fmt()
+            .with_writer(move || writer.clone())
+            .with_ansi(false)
+            .finish();
+        let _subscriber = tracing::subscriber::set_default(subscriber);
+        let _redact = crate::redact::RedactUrlsGuard::enable();
+
+        let mut processor = local_media_processor();
+        processor.download_retry_base_delay = Duration::from_millis(10);
+        processor.download_retries = 1;
+        let url = format!("{}/media/a.png?sig=secret", server.url());
+        assert!(processor.download_media(&url).await.is_err());
+
+        let output = String::from_utf8(capture.0.lock().unwrap().clone()).unwrap();
+        assert!(
+            output.contains("Retrying download of http://127.0.0.1/…#"),
+            "{output}"
+        );
+        assert!(!output.contains("secret"), "{output}");
+        assert!(!output.contains("a.png"), "{output}");
+    }
+
+    #[tokio::test]
+    async fn test_download_retries_transient_failures() {
+        use crate::test_support::{local_media_processor, MockHttpServer, MockResponse};
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        use std::sync::Arc;
+
+        let attempts = Arc::new(AtomicUsize::new(0));
+        let server_attempts = attempts.clone();
+        let server = MockHttpServer::start(move |request| {
+            if request.path == "/missing" {
+                return MockResponse::json(404, "{}");
+            }
+            match server_attempts.fetch_add(1, Ordering::SeqCst) {
+                0 | 1 => MockResponse::json(503, "{}"),
+                _ => MockResponse::bytes(200, "image/png", b"image bytes".to_vec()),
+            }
+        })
+        .await;
+
+        let mut processor = local_media_processor();
+        processor.download_retry_base_delay = Duration::from_millis(10);
+
+        let data = processor
+            .download_media(&format!("{}/flaky", server.url()))
+            .await
+            .unwrap();
+        assert_eq!(data, b"image bytes");
+        assert_eq!(attempts.load(Ordering::SeqCst), 3);
+
+        // Client errors fail immediately
+        assert!(processor
+            .download_media(&format!("{}/missing", server.url()))
+            .await
+            .is_err());
+        assert_eq!(
+            server
+                .requests()
+                .iter()
+                .filter(|request| request.path == "/missing")
+                .count(),
+            1
+        );
+
+        // Retries are bounded
+        attempts.store(0, Ordering::SeqCst);
+        processor.download_retries = 1;
+        let err = processor
+            .download_media(&format!("{}/flaky", server.url()))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, MediaError::DownloadFailed { .. }));
+        assert_eq!(attempts.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn test_downloads_go_through_configured_proxy() {
+        use crate::test_support::{MockHttpServer, MockResponse};
+
+        // A forward proxy receives the absolute URL in the request line
+        let proxy = MockHttpServer::start(|_| {
+            MockResponse::bytes(200, "image/png", b"proxied bytes".to_vec())
+        })
+        .await;
+
+        let processor = MediaProcessor::with_unified_transformer(MediaConfig {
+            proxy_url: Some(proxy.url()),
+            ..MediaConfig::default()
+        });
+        let data = processor
+            .clone()
+            .download_media("http://media.unreachable.example/files/image.png")
+            .await
+            .unwrap();
+        assert_eq!(data, b"proxied bytes");
+
+        let requests = proxy.requests();
+        assert_eq!(requests.len(), 1);
+        assert_eq!(
+            requests[0].path,
+            "http://media.unreachable.example/files/image.png"
+        );
+    }
+
+    #[test]
+    fn test_media_processor_default_download_limit() {
+        let processor = MediaProcessor::with_default_config();
+        assert_eq!(processor.max_download_size_mb, DEFAULT_MAX_DOWNLOAD_SIZE_MB);
+        assert_eq!(processor.clone().max_download_size_mb, 100);
+    }
+
+    #[tokio::test]
+    async fn test_restricted_formats_reject_other_images_after_download() {
+        use crate::test_support::{local_media_config, MockHttpServer, MockResponse};
+
+        let encode = |format: ImageFormat| {
+            let mut data = Vec::new();
+            ::image::DynamicImage::new_rgb8(4, 4)
+                .write_to(&mut std::io::Cursor::new(&mut data), format)
+                .unwrap();
+            data
+        };
+        let png = encode(ImageFormat::Png);
+        let jpeg = encode(ImageFormat::Jpeg);
+        let server = MockHttpServer::start(move |request| match request.path.as_str() {
+            "/media/png" => MockResponse::bytes(200, "image/png", png.clone()),
+            _ => MockResponse::bytes(200, "image/jpeg", jpeg.clone()),
+        })
+        .await;
+
+        let processor = MediaProcessor::with_unified_transformer(MediaConfig {
+            supported_formats: ["image/png".to_string()].into_iter().collect(),
+            ..local_media_config()
+        });
+        let media = |id: &str| MediaAttachment {
+            url: format!("{}/media/{id}", server.url()),
+            ..create_test_media(id, "image", None)
+        };
+
+        // Mastodon only reports "image", so both pass the type filter
+        assert_eq!(
+            processor
+                .filter_processable_media(&[media("png"), media("jpeg")])
+                .len(),
+            2
+        );
+
+        assert!(processor
+            .process_media_for_analysis(&media("png"))
+            .await
+            .is_ok());
+
+        for result in [
+            processor.process_media_for_analysis(&media("jpeg")).await,
+            processor
+                .download_media_for_recreation(&media("jpeg"))
+                .await,
+        ] {
+            match result {
+                Err(MediaError::UnsupportedType { media_type }) => {
+                    assert_eq!(media_type, "image/jpeg")
+                }
+                other => panic!("expected UnsupportedType, got {other:?}"),
+            }
+        }
+    }
+
+    #[test]
+    fn test_sniff_image_type() {
+        let mut png = Vec::new();
+        ::image::DynamicImage::new_rgb8(1, 1)
+            .write_to(&mut std::io::Cursor::new(&mut png), ImageFormat::Png)
+            .unwrap();
+        assert_eq!(image::sniff_image_type(&png), Some("image/png"));
+        assert_eq!(image::sniff_image_type(b"GIF89a......"), Some("image/gif"));
+        assert_eq!(image::sniff_image_type(b"not an image"), None);
+    }
 }
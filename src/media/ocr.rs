@@ -0,0 +1,138 @@
+use crate::error::MediaError;
+use crate::media::helpers::TempFile;
+use std::process::Command;
+
+/// Maximum number of OCR characters passed to the vision model
+pub const MAX_OCR_TEXT_LENGTH: usize = 1000;
+
+/// Minimum number of alphanumeric characters for OCR output to count as text
+const MIN_OCR_ALPHANUMERIC: usize = 3;
+
+/// Check if the Tesseract OCR CLI is available on the system
+pub fn is_tesseract_available() -> bool {
+    Command::new("tesseract")
+        .arg("--version")
+        .output()
+        .map(|output| output.status.success())
+        .unwrap_or(false)
+}
+
+/// Extract readable text from an image with Tesseract
+///
+/// Returns `None` if the image contains no recognizable text.
+pub async fn extract_image_text(
+    image_data: &[u8],
+    language: &str,
+) -> Result<Option<String>, MediaError> {
+    // Tesseract detects the image format from the content, not the suffix
+    let image_file = TempFile::with_suffix(".img")?;
+    image_file.write_data(image_data).await?;
+
+    let image_path = image_file.path_buf();
+    let language = language.to_string();
+
+    let output = tokio::task::spawn_blocking(move || {
+        Command::new("tesseract")
+            .arg(&image_path)
+            .args(["stdout", "-l", &language])
+            .output()
+    })
+    .await
+    .map_err(|e| MediaError::ProcessingFailed(format!("Tesseract task failed: {e}")))?
+    .map_err(|e| MediaError::ProcessingFailed(format!("Tesseract execution failed: {e}")))?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(MediaError::ProcessingFailed(format!(
+            "Tesseract OCR failed: {stderr}"
+        )));
+    }
+
+    Ok(clean_ocr_text(&String::from_utf8_lossy(&output.stdout)))
+}
+
+/// Normalize Tesseract output: trim lines, drop blank ones and cap the length
+fn clean_ocr_text(raw: &str) -> Option<String> {
+    let text = raw
+        .lines()
+        .map(|line| line.split_whitespace().collect::<Vec<_>>().join(" "))
+        .filter(|line| !line.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    // Photos without text often yield a few stray symbols
+    let alphanumeric = text.chars().filter(|c| c.is_alphanumeric()).count();
+    if alphanumeric < MIN_OCR_ALPHANUMERIC {
+        return None;
+    }
+
+    if text.chars().count() > MAX_OCR_TEXT_LENGTH {
+        let truncated: String = text.chars().take(MAX_OCR_TEXT_LENGTH).collect();
+        return Some(format!("{}…", truncated.trim_end()));
+    }
+
+    Some(text)
+}
+
+/// Add OCR text to an image prompt so the model can quote it exactly
+pub fn prompt_with_ocr_text(prompt_template: &str, ocr_text: Option<&str>) -> String {
+    match ocr_text {
+        Some(text) => format!(
+            "{prompt_template}\n\nOCR detected the following text in the image. It may contain recognition errors; quote the parts that are actually visible and relevant:\n\"\"\"\n{text}\n\"\"\""
+        ),
+        None => prompt_template.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_clean_ocr_text() {
+        let raw = "  BREAKING   NEWS \n\n\n Cats  now run the internet \n\x0c";
+        assert_eq!(
+            clean_ocr_text(raw),
+            Some("BREAKING NEWS\nCats now run the internet".to_string())
+        );
+
+        assert_eq!(clean_ocr_text(""), None);
+        assert_eq!(clean_ocr_text(" | ~ \n -"), None);
+
+        let long = "a".repeat(MAX_OCR_TEXT_LENGTH + 50);
+        let cleaned = clean_ocr_text(&long).unwrap();
+        assert_eq!(cleaned.chars().count(), MAX_OCR_TEXT_LENGTH + 1);
+        assert!(cleaned.ends_with('…'));
+    }
+
+    #[test]
+    fn test_prompt_with_ocr_text() {
+        let prompt = prompt_with_ocr_text("Describe this image.", Some("SALE 50% OFF"));
+        assert!(prompt.starts_with("Describe this image."));
+        assert!(prompt.contains("\"\"\"\nSALE 50% OFF\n\"\"\""));
+
+        assert_eq!(
+            prompt_with_ocr_text("Describe this image.", None),
+            "Describe this image."
+        );
+    }
+
+    #[tokio::test]
+    async fn test_extract_image_text_without_text() {
+        if !is_tesseract_available() {
+            println!("Skipping OCR test: tesseract not available");
+            return;
+        }
+
+        let image = image::RgbImage::from_pixel(64, 64, image::Rgb([255, 255, 255]));
+        let mut data = Vec::new();
+        image::DynamicImage::ImageRgb8(image)
+            .write_to(
+                &mut std::io::Cursor::new(&mut data),
+                image::ImageFormat::Png,
+            )
+            .unwrap();
+
+        assert_eq!(extract_image_text(&data, "eng").await.unwrap(), None);
+    }
+}
@@ -0,0 +1,104 @@
+//! Media type detection from magic bytes, for attachments with a generic or missing type
+
+use super::image::sniff_image_type;
+
+/// Whether a declared media type says nothing about the actual format
+pub fn is_generic_media_type(media_type: &str) -> bool {
+    let media_type = media_type.trim().to_lowercase();
+    media_type.is_empty()
+        || !media_type.contains('/')
+        || matches!(
+            media_type.as_str(),
+            "application/octet-stream" | "binary/octet-stream"
+        )
+}
+
+/// MIME type of image, audio or video data detected from its leading bytes, if recognized
+pub fn sniff_media_type(data: &[u8]) -> Option<&'static str> {
+    if let Some(image_type) = sniff_image_type(data) {
+        return Some(image_type);
+    }
+
+    match data {
+        [b'I', b'D', b'3', ..] => Some("audio/mpeg"),
+        // MPEG audio frame sync: 11 set bits, layer bits non-zero
+        [0xFF, second, ..] if second & 0xE0 == 0xE0 && second & 0x06 != 0 => Some("audio/mpeg"),
+        [b'f', b'L', b'a', b'C', ..] => Some("audio/flac"),
+        [b'O', b'g', b'g', b'S', ..] => Some("audio/ogg"),
+        [b'R', b'I', b'F', b'F', _, _, _, _, b'W', b'A', b'V', b'E', ..] => Some("audio/wav"),
+        [0x1A, 0x45, 0xDF, 0xA3, ..] => Some("video/webm"),
+        // ISO base media file; HEIF/AVIF brands were already matched as images
+        [_, _, _, _, b'f', b't', b'y', b'p', brand @ ..] => {
+            if brand.starts_with(b"M4A ") || brand.starts_with(b"M4B ") {
+                Some("audio/mp4")
+            } else {
+                Some("video/mp4")
+            }
+        }
+        _ => None,
+    }
+}
+
+/// The declared media type, or the sniffed one if the declared type is generic or missing
+pub fn resolve_media_type(declared: &str, data: &[u8]) -> String {
+    if !is_generic_media_type(declared) {
+        return declared.trim().to_string();
+    }
+
+    match sniff_media_type(data) {
+        Some(sniffed) => {
+            tracing::debug!(
+                "Media declared as '{}' detected as {} from its content",
+                declared,
+                sniffed
+            );
+            sniffed.to_string()
+        }
+        None => declared.trim().to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sniff_media_type_from_magic_bytes() {
+        let cases: [(&[u8], &str); 10] = [
+            (b"\xFF\xD8\xFF\xE0\x00\x10JFIF\x00", "image/jpeg"),
+            (b"\x89PNG\r\n\x1a\n\x00\x00\x00\x0dIHDR", "image/png"),
+            (b"GIF89a\x01\x00\x01\x00", "image/gif"),
+            (b"RIFF\x24\x00\x00\x00WEBPVP8 ", "image/webp"),
+            (b"ID3\x04\x00\x00\x00\x00\x00\x00", "audio/mpeg"),
+            (b"\xFF\xFB\x90\x64\x00\x00", "audio/mpeg"),
+            (b"RIFF\x24\x00\x00\x00WAVEfmt ", "audio/wav"),
+            (b"\x00\x00\x00\x20ftypisom\x00\x00\x02\x00", "video/mp4"),
+            (b"\x00\x00\x00\x20ftypM4A \x00\x00\x00\x00", "audio/mp4"),
+            (b"\x1A\x45\xDF\xA3\x9F\x42\x86\x81", "video/webm"),
+        ];
+        for (data, expected) in cases {
+            assert_eq!(sniff_media_type(data), Some(expected), "{data:?}");
+        }
+
+        assert_eq!(sniff_media_type(b"plain text"), None);
+        assert_eq!(sniff_media_type(b""), None);
+    }
+
+    #[test]
+    fn test_resolve_media_type_only_overrides_generic_types() {
+        let png = b"\x89PNG\r\n\x1a\n\x00\x00\x00\x0dIHDR";
+
+        assert_eq!(resolve_media_type("image", png), "image/png");
+        assert_eq!(
+            resolve_media_type("application/octet-stream", png),
+            "image/png"
+        );
+        assert_eq!(resolve_media_type("", png), "image/png");
+
+        // A specific declared type is trusted
+        assert_eq!(resolve_media_type("image/jpeg", png), "image/jpeg");
+
+        // Unrecognized content keeps the declared type
+        assert_eq!(resolve_media_type("image", b"unknown"), "image");
+    }
+}
@@ -1,10 +1,12 @@
-use crate::config::{OpenRouterConfig, WhisperConfig};
+use crate::config::WhisperConfig;
 use crate::error::MediaError;
 use crate::mastodon::MediaAttachment;
-use crate::media::audio::{is_ffmpeg_available, summarize_transcript};
+use crate::media::audio::{
+    check_min_duration, ffmpeg_command, is_ffmpeg_available, keeps_long_transcripts,
+    media_duration_secs, shorten_transcript, wav_duration_minutes, TranscriptSummary,
+};
 use crate::media::TempFile;
 use crate::whisper_cli::WhisperCli;
-use std::process::Command;
 
 /// Supported video formats for transcription  
 pub const SUPPORTED_VIDEO_FORMATS: &[&str] = &[
@@ -19,12 +21,24 @@ pub const SUPPORTED_VIDEO_FORMATS: &[&str] = &[
     "video/x-matroska", // .mkv
 ];
 
+/// Mastodon's media type for uploaded GIFs, converted to a silent looping MP4
+pub const GIFV_MEDIA_TYPE: &str = "gifv";
+
+/// Check whether a media type is a `gifv`, which has no audio to transcribe
+pub fn is_gifv(media_type: &str) -> bool {
+    media_type.trim().eq_ignore_ascii_case(GIFV_MEDIA_TYPE)
+}
+
+/// Description used for videos whose audio contains no detectable speech
+pub const NO_SPEECH_DESCRIPTION: &str = "Video content without detectable speech";
+
 /// Process video file for transcription using Whisper
 pub async fn process_video_for_transcript(
     media: &MediaAttachment,
+    media_processor: &crate::media::MediaProcessor,
     whisper_config: &WhisperConfig,
     media_config: &crate::config::MediaConfig,
-    openrouter_config: Option<&OpenRouterConfig>,
+    summary: &TranscriptSummary<'_>,
 ) -> Result<String, MediaError> {
     // Check if it's a video file
     let is_video = media.media_type.to_lowercase().starts_with("video")
@@ -42,6 +56,11 @@ pub async fn process_video_for_transcript(
         ));
     }
 
+    // Skip clips too short to transcribe before downloading them
+    if let Some(duration) = media_duration_secs(media) {
+        check_min_duration(duration, whisper_config)?;
+    }
+
     // Check if FFmpeg is available
     if !is_ffmpeg_available() {
         return Err(MediaError::ProcessingFailed(
@@ -49,55 +68,8 @@ pub async fn process_video_for_transcript(
         ));
     }
 
-    // Validate URL before attempting download
-    let parsed_url = match url::Url::parse(&media.url) {
-        Ok(u) => u,
-        Err(e) => {
-            tracing::warn!("Invalid video URL format: {}: {}", media.url, e);
-            return Err(MediaError::DownloadFailed {
-                url: media.url.clone(),
-            });
-        }
-    };
-
-    // Validate URL scheme
-    if !matches!(parsed_url.scheme(), "http" | "https") {
-        tracing::warn!("Unsupported video URL scheme: {}", parsed_url.scheme());
-        return Err(MediaError::DownloadFailed {
-            url: media.url.clone(),
-        });
-    }
-
-    // Download video data
-    let http_client = reqwest::Client::new();
-    let url_string = media.url.clone(); // Clone early to avoid borrow issues
-
-    let response = http_client.get(&media.url).send().await.map_err(|e| {
-        tracing::warn!("Failed to download video from {}: {}", url_string, e);
-        MediaError::DownloadFailed {
-            url: url_string.clone(),
-        }
-    })?;
-
-    if !response.status().is_success() {
-        tracing::warn!(
-            "HTTP error {} for video URL: {}",
-            response.status(),
-            url_string
-        );
-        return Err(MediaError::DownloadFailed { url: url_string });
-    }
-
-    let video_data = response.bytes().await.map_err(|e| {
-        tracing::warn!(
-            "Failed to read video response bytes from {}: {}",
-            url_string,
-            e
-        );
-        MediaError::DownloadFailed {
-            url: url_string.clone(),
-        }
-    })?;
+    // Downloaded through the media processor so the host policy and proxy apply
+    let video_data = media_processor.download_media(&media.url).await?;
 
     // Check video size limits
     let size_mb = video_data.len() as f64 / (1024.0 * 1024.0);
@@ -121,14 +93,60 @@ pub async fn process_video_for_transcript(
 
     // Extract audio from video and convert to WAV format using FFmpeg
     let wav_data = extract_audio_from_video(&video_data).await?;
+    check_min_duration(wav_duration_minutes(&wav_data) * 60.0, whisper_config)?;
 
     // Transcribe audio using Whisper CLI
     let transcript =
-        transcribe_wav_audio_with_whisper_cli(&wav_data, whisper_config, openrouter_config).await?;
+        transcribe_wav_audio_with_whisper_cli(&wav_data, whisper_config, summary).await?;
 
     Ok(transcript)
 }
 
+/// Extract a representative keyframe from video data as JPEG bytes using FFmpeg
+pub async fn process_video_for_keyframe(video_data: &[u8]) -> Result<Vec<u8>, MediaError> {
+    if !is_ffmpeg_available() {
+        return Err(MediaError::ProcessingFailed(
+            "FFmpeg is required for keyframe extraction but not found on system".to_string(),
+        ));
+    }
+
+    let input_file = TempFile::new()?;
+    input_file.write_data(video_data).await?;
+    let output_file = TempFile::with_suffix(".jpg")?;
+
+    let input_path = input_file.path_buf();
+    let output_path = output_file.path_buf();
+
+    // The thumbnail filter picks the most representative frame of each batch of frames
+    let output = tokio::task::spawn_blocking(move || {
+        ffmpeg_command()
+            .arg("-i")
+            .arg(&input_path)
+            .args(["-vf", "thumbnail", "-frames:v", "1", "-q:v", "2", "-y"])
+            .arg(&output_path)
+            .output()
+    })
+    .await
+    .map_err(|e| MediaError::ProcessingFailed(format!("FFmpeg task failed: {e}")))?
+    .map_err(|e| MediaError::ProcessingFailed(format!("FFmpeg execution failed: {e}")))?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(MediaError::ProcessingFailed(format!(
+            "FFmpeg keyframe extraction failed: {stderr}"
+        )));
+    }
+
+    let keyframe = output_file.read_data().await?;
+    if keyframe.is_empty() {
+        return Err(MediaError::ProcessingFailed(
+            "FFmpeg produced an empty keyframe".to_string(),
+        ));
+    }
+
+    Ok(keyframe)
+}
+
 /// Extract audio from video data and convert to WAV format using FFmpeg
 async fn extract_audio_from_video(video_data: &[u8]) -> Result<Vec<u8>, MediaError> {
     let input_file = TempFile::new()?;
@@ -150,7 +168,7 @@ async fn extract_audio_from_video(video_data: &[u8]) -> Result<Vec<u8>, MediaErr
     let output_path_clone = output_file_path.clone();
 
     let output = tokio::task::spawn_blocking(move || {
-        Command::new("ffmpeg")
+        ffmpeg_command()
             .args([
                 "-i",
                 input_path_clone.to_str().ok_or_else(|| {
@@ -200,7 +218,7 @@ async fn extract_audio_from_video(video_data: &[u8]) -> Result<Vec<u8>, MediaErr
 async fn transcribe_wav_audio_with_whisper_cli(
     wav_data: &[u8],
     whisper_config: &WhisperConfig,
-    openrouter_config: Option<&OpenRouterConfig>,
+    summary: &TranscriptSummary<'_>,
 ) -> Result<String, MediaError> {
     // Create Whisper CLI instance
     let whisper_cli = WhisperCli::new(whisper_config)?;
@@ -225,31 +243,17 @@ async fn transcribe_wav_audio_with_whisper_cli(
         .trim()
         .to_string();
 
-    // Apply hard limit of 1500 characters for video descriptions
-    let transcript = if transcript.len() > 1500 {
-        // Try to summarize using LLM if OpenRouter config is available
-        if let Some(openrouter_config) = openrouter_config {
-            match summarize_transcript(&transcript, openrouter_config).await {
-                Ok(summary) => summary,
-                Err(e) => {
-                    tracing::warn!(
-                        "Failed to summarize transcript using LLM: {e}, falling back to truncation"
-                    );
-                    let truncated = transcript.chars().take(1497).collect::<String>();
-                    format!("{truncated}...")
-                }
-            }
-        } else {
-            let truncated = transcript.chars().take(1497).collect::<String>();
-            format!("{truncated}...")
-        }
-    } else {
+    // Keep the transcript within the description limit, unless the processor keeps the
+    // full transcript for a reply thread
+    let transcript = if keeps_long_transcripts(whisper_config) {
         transcript
+    } else {
+        shorten_transcript(transcript, summary).await
     };
 
     // Handle videos without speech (silent videos, music-only, etc.)
     if transcript.is_empty() {
-        Ok("Video content without detectable speech".to_string())
+        Ok(NO_SPEECH_DESCRIPTION.to_string())
     } else {
         Ok(transcript)
     }
@@ -267,6 +271,51 @@ mod tests {
         assert_eq!(SUPPORTED_VIDEO_FORMATS.len(), 9);
     }
 
+    #[tokio::test]
+    async fn test_process_video_for_keyframe() {
+        if !is_ffmpeg_available() {
+            println!("FFmpeg not available, skipping keyframe extraction test");
+            return;
+        }
+
+        // Generate a one second test pattern clip
+        let video_file = TempFile::with_suffix(".mp4").unwrap();
+        let status = ffmpeg_command()
+            .args([
+                "-f",
+                "lavfi",
+                "-i",
+                "testsrc=duration=1:size=64x64:rate=10",
+                "-pix_fmt",
+                "yuv420p",
+                "-y",
+            ])
+            .arg(video_file.path())
+            .output()
+            .unwrap()
+            .status;
+        assert!(status.success());
+
+        let video_data = video_file.read_data().await.unwrap();
+        let keyframe = process_video_for_keyframe(&video_data).await.unwrap();
+
+        assert!(!keyframe.is_empty());
+        assert!(
+            keyframe.starts_with(&[0xFF, 0xD8]),
+            "keyframe should be a JPEG"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_process_video_for_keyframe_rejects_invalid_data() {
+        if !is_ffmpeg_available() {
+            println!("FFmpeg not available, skipping keyframe extraction test");
+            return;
+        }
+
+        assert!(process_video_for_keyframe(b"not a video").await.is_err());
+    }
+
     #[test]
     fn test_video_size_estimation() {
         // Test the video size limit logic
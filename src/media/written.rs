@@ -0,0 +1,94 @@
+//! Descriptions written by this process
+//!
+//! Alternator's own description edits come back through the stream as `status.update` events,
+//! and recreated media arrive with new IDs. Remembering what was written keeps those echoes from
+//! being described again, which with `reprocess_existing_descriptions` would otherwise loop.
+//! The memory is not persisted, so after a restart the backfill describes earlier toots again.
+
+use crate::mastodon::TootEvent;
+use lru::LruCache;
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::num::NonZeroUsize;
+use std::sync::{Arc, Mutex};
+
+/// Number of statuses whose written descriptions are remembered
+const WRITTEN_STATUS_CAPACITY: usize = 5000;
+
+/// Shared memory of the descriptions Alternator wrote, cloned handles see the same entries
+#[derive(Clone)]
+pub struct WrittenDescriptions {
+    inner: Arc<Mutex<Written>>,
+}
+
+struct Written {
+    /// Description hashes written per status, keyed by status ID
+    by_status: LruCache<String, Vec<(String, u64)>>,
+    /// Every remembered description hash, for media that were recreated under a new ID
+    hashes: LruCache<u64, ()>,
+}
+
+impl Default for WrittenDescriptions {
+    fn default() -> Self {
+        let capacity = NonZeroUsize::new(WRITTEN_STATUS_CAPACITY).unwrap();
+        Self {
+            inner: Arc::new(Mutex::new(Written {
+                by_status: LruCache::new(capacity),
+                // Toots have up to four attachments
+                hashes: LruCache::new(capacity.saturating_mul(NonZeroUsize::new(4).unwrap())),
+            })),
+        }
+    }
+}
+
+impl WrittenDescriptions {
+    /// Remember the `(media ID, description)` pairs about to be written to a status
+    pub fn record<'a>(
+        &self,
+        status_id: &str,
+        descriptions: impl IntoIterator<Item = (&'a str, &'a str)>,
+    ) {
+        let mut written = self.inner.lock().unwrap();
+        let mut entries = written.by_status.pop(status_id).unwrap_or_default();
+        for (media_id, description) in descriptions {
+            let hash = description_hash(description);
+            entries.retain(|(id, _)| id != media_id);
+            entries.push((media_id.to_string(), hash));
+            written.hashes.put(hash, ());
+        }
+        written.by_status.put(status_id.to_string(), entries);
+    }
+
+    /// Whether this process wrote `description` (to any status)
+    pub fn contains(&self, description: &str) -> bool {
+        let hash = description_hash(description);
+        self.inner.lock().unwrap().hashes.contains(&hash)
+    }
+
+    /// Whether a status update only echoes descriptions this process wrote to it
+    ///
+    /// True when every attachment carries a description Alternator wrote for this status, so
+    /// media added or left undescribed by the edit still get processed.
+    pub fn is_own_edit(&self, toot: &TootEvent) -> bool {
+        if toot.media_attachments.is_empty() {
+            return false;
+        }
+        let mut written = self.inner.lock().unwrap();
+        let Some(entries) = written.by_status.get(&toot.id) else {
+            return false;
+        };
+        toot.media_attachments.iter().all(|media| {
+            media.description.as_deref().is_some_and(|description| {
+                let hash = description_hash(description);
+                // Recreated media carry a new ID, so the description alone identifies them
+                entries.iter().any(|(_, written)| *written == hash)
+            })
+        })
+    }
+}
+
+/// Hash of a description, ignoring the surrounding whitespace the server may normalize
+fn description_hash(description: &str) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    description.trim().replace("\r\n", "\n").hash(&mut hasher);
+    hasher.finish()
+}
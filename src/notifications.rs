@@ -0,0 +1,206 @@
+//! Webhook notifications for operational events
+
+use crate::config::NotificationsConfig;
+use crate::error::NotificationError;
+use serde_json::{json, Value};
+use std::time::Duration;
+use tracing::{debug, info};
+
+/// Event names accepted in `notifications.events`
+pub const NOTIFICATION_EVENTS: &[&str] = &["low_balance", "processing_error", "started"];
+
+/// An event that can be sent to the notification webhook
+#[derive(Debug, Clone, PartialEq)]
+pub enum NotificationEvent {
+    Started { version: String },
+    LowBalance { balance: f64, threshold: f64 },
+    ProcessingError { error: String },
+}
+
+impl NotificationEvent {
+    /// Event name as used in `notifications.events`
+    pub fn name(&self) -> &'static str {
+        match self {
+            NotificationEvent::Started { .. } => "started",
+            NotificationEvent::LowBalance { .. } => "low_balance",
+            NotificationEvent::ProcessingError { .. } => "processing_error",
+        }
+    }
+
+    /// Human-readable message for chat integrations
+    pub fn message(&self) -> String {
+        match self {
+            NotificationEvent::Started { version } => format!("✅ Alternator v{version} started"),
+            NotificationEvent::LowBalance { balance, threshold } => format!(
+                "⚠️ OpenRouter balance is ${balance:.2}, below the threshold of ${threshold:.2}. Top up at https://openrouter.ai/credits"
+            ),
+            NotificationEvent::ProcessingError { error } => {
+                format!("❌ Alternator error: {error}")
+            }
+        }
+    }
+
+    /// JSON body understood by Slack (`text`), Discord (`content`) and Matrix hookshot (`text`)
+    pub fn payload(&self) -> Value {
+        let message = self.message();
+        let mut payload = json!({
+            "event": self.name(),
+            "text": message,
+            "content": message,
+        });
+
+        let details = match self {
+            NotificationEvent::Started { version } => json!({ "version": version }),
+            NotificationEvent::LowBalance { balance, threshold } => {
+                json!({ "balance": balance, "threshold": threshold })
+            }
+            NotificationEvent::ProcessingError { error } => json!({ "error": error }),
+        };
+        payload["details"] = details;
+
+        payload
+    }
+}
+
+/// Sends selected events to the configured webhook; a no-op without a webhook
+#[derive(Debug, Clone)]
+pub struct Notifier {
+    http_client: reqwest::Client,
+    webhook_url: Option<String>,
+    events: Vec<String>,
+}
+
+impl Notifier {
+    /// Create a notifier from the optional `[notifications]` section
+    pub fn from_config(config: Option<&NotificationsConfig>) -> Self {
+        let http_client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(10))
+            .user_agent(format!("Alternator/{}", env!("CARGO_PKG_VERSION")))
+            .build()
+            .expect("Failed to create HTTP client");
+
+        let webhook_url = config.and_then(|config| config.webhook_url.clone());
+        let events = config
+            .and_then(|config| config.events.clone())
+            .unwrap_or_else(|| NOTIFICATION_EVENTS.iter().map(|e| e.to_string()).collect());
+
+        Self {
+            http_client,
+            webhook_url,
+            events,
+        }
+    }
+
+    /// Notifier that never sends anything
+    pub fn disabled() -> Self {
+        Self::from_config(None)
+    }
+
+    /// Check whether `event` would be sent
+    pub fn is_enabled_for(&self, event: &NotificationEvent) -> bool {
+        self.webhook_url.is_some() && self.events.iter().any(|name| name == event.name())
+    }
+
+    /// POST the event to the webhook if it is enabled
+    pub async fn notify(&self, event: &NotificationEvent) -> Result<(), NotificationError> {
+        let Some(webhook_url) = self.webhook_url.as_deref() else {
+            return Ok(());
+        };
+        if !self.is_enabled_for(event) {
+            debug!(
+                "Notification event '{}' not selected, skipping",
+                event.name()
+            );
+            return Ok(());
+        }
+
+        let response = self
+            .http_client
+            .post(webhook_url)
+            .json(&event.payload())
+            .send()
+            .await
+            .map_err(|e| NotificationError::RequestFailed(e.to_string()))?;
+
+        if !response.status().is_success() {
+            return Err(NotificationError::UnexpectedStatus {
+                status: response.status().as_u16(),
+            });
+        }
+
+        info!("Sent '{}' notification to webhook", event.name());
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{MockHttpServer, MockResponse};
+
+    fn notifier_for(url: &str, events: Option<Vec<&str>>) -> Notifier {
+        Notifier::from_config(Some(&NotificationsConfig {
+            webhook_url: Some(url.to_string()),
+            events: events.map(|events| events.into_iter().map(str::to_string).collect()),
+        }))
+    }
+
+    #[tokio::test]
+    async fn test_low_balance_event_posts_json() {
+        let server = MockHttpServer::start(|_| MockResponse::json(200, "{}")).await;
+        let notifier = notifier_for(&format!("{}/hooks/alternator", server.url()), None);
+
+        notifier
+            .notify(&NotificationEvent::LowBalance {
+                balance: 1.5,
+                threshold: 5.0,
+            })
+            .await
+            .unwrap();
+
+        let requests = server.requests();
+        assert_eq!(requests.len(), 1);
+        assert_eq!(requests[0].method, "POST");
+        assert_eq!(requests[0].path, "/hooks/alternator");
+        assert_eq!(requests[0].header("content-type"), Some("application/json"));
+
+        let body: Value = serde_json::from_slice(&requests[0].body).unwrap();
+        let message = "⚠️ OpenRouter balance is $1.50, below the threshold of $5.00. Top up at https://openrouter.ai/credits";
+        assert_eq!(
+            body,
+            json!({
+                "event": "low_balance",
+                "text": message,
+                "content": message,
+                "details": {"balance": 1.5, "threshold": 5.0},
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn test_unselected_events_are_not_sent() {
+        let server = MockHttpServer::start(|_| MockResponse::json(500, "{}")).await;
+        let notifier = notifier_for(&server.url(), Some(vec!["low_balance"]));
+
+        let started = NotificationEvent::Started {
+            version: "1.0.0".to_string(),
+        };
+        assert!(!notifier.is_enabled_for(&started));
+        notifier.notify(&started).await.unwrap();
+        assert!(server.requests().is_empty());
+
+        // Webhook failures surface as errors for the caller to log
+        let result = notifier
+            .notify(&NotificationEvent::LowBalance {
+                balance: 1.0,
+                threshold: 5.0,
+            })
+            .await;
+        assert!(matches!(
+            result,
+            Err(NotificationError::UnexpectedStatus { status: 500 })
+        ));
+
+        assert!(!Notifier::disabled().is_enabled_for(&started));
+    }
+}
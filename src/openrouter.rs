@@ -1,22 +1,87 @@
 use crate::config::OpenRouterConfig;
+use crate::toot_handler::stats::ProcessingStats;
 use async_trait::async_trait;
 use base64::Engine;
+use lru::LruCache;
 use reqwest::{Client, Response};
 use serde::{Deserialize, Serialize};
-use std::sync::Arc;
+use std::collections::HashMap;
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::num::NonZeroUsize;
+use std::sync::{Arc, Mutex};
 use std::time::{Duration, Instant};
-use tokio::sync::Semaphore;
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
 use tokio::time::sleep;
 use tracing::{debug, error, info, warn};
 
 // Re-export OpenRouterError so tests can access it
 pub use crate::error::OpenRouterError;
 
+/// Default maximum description length (Mastodon's alt-text limit)
+pub const DEFAULT_MAX_DESCRIPTION_LENGTH: usize = 1500;
+
+/// Default minimum length of a usable image description in characters
+pub const DEFAULT_MIN_DESCRIPTION_LENGTH: usize = 10;
+
+/// Default phrases marking a refusal instead of an image description, matched case-insensitively
+pub const DEFAULT_REFUSAL_PATTERNS: &[&str] = &[
+    "i cannot describe",
+    "i can't describe",
+    "i can not describe",
+    "i'm unable to",
+    "i am unable to",
+    "i'm not able to",
+    "i am not able to",
+    "i cannot help",
+    "i can't help",
+    "i'm sorry",
+    "as an ai",
+];
+
+/// Characters ending a sentence, used to cut text without spaces at a sentence boundary
+const SENTENCE_TERMINATORS: &[char] = &[
+    '。', '！', '？', '．', '｡', // CJK full stops and fullwidth punctuation
+    '।', '॥', // Devanagari danda
+    '؟', '۔', // Arabic and Urdu
+    '.', '!', '?',
+];
+
+/// Image handed to the vision model
+#[derive(Debug, Clone, Copy)]
+enum ImageInput<'a> {
+    /// Image bytes, sent as a base64 data URL
+    Data(&'a [u8]),
+    /// Publicly reachable https URL the provider fetches itself
+    Url(&'a str),
+}
+
+impl ImageInput<'_> {
+    /// Size of the embedded image data, 0 for URLs
+    fn data_len(&self) -> usize {
+        match self {
+            Self::Data(data) => data.len(),
+            Self::Url(_) => 0,
+        }
+    }
+
+    /// Bytes identifying the image for the description cache
+    fn cache_bytes(&self) -> &[u8] {
+        match self {
+            Self::Data(data) => data,
+            Self::Url(url) => url.as_bytes(),
+        }
+    }
+}
+
 /// Trait for OpenRouter API operations to enable mocking in tests
+///
+/// Covers the account side of OpenRouter (balance, model listing) used by the CLI and the
+/// integration tests. The toot pipeline goes through [`DescriptionProvider`] instead, which
+/// isn't tied to OpenRouter and reports the model and usage of each description.
 #[async_trait]
 pub trait OpenRouterApi {
     #[allow(dead_code)]
-    async fn get_account_balance(&self) -> Result<f64, OpenRouterError>;
+    async fn get_account_balance(&self) -> Result<Option<f64>, OpenRouterError>;
     #[allow(dead_code)]
     async fn list_models(&self) -> Result<Vec<Model>, OpenRouterError>;
     #[allow(dead_code)]
@@ -29,28 +94,124 @@ pub trait OpenRouterApi {
     async fn process_text(&self, prompt: &str) -> Result<String, OpenRouterError>;
 }
 
+/// Backend that writes media descriptions for the toot pipeline
+///
+/// `OpenRouterClient` is the production implementation; a local model or a mock can be
+/// handed to `TootStreamHandler` instead.
+#[async_trait]
+pub trait DescriptionProvider: Send + Sync {
+    /// Describe an image, returning the text and the model that wrote it
+    async fn describe_image(
+        &self,
+        image_data: &[u8],
+        prompt: &str,
+    ) -> Result<ImageDescription, OpenRouterError>;
+
+    /// Whether images may be passed to `describe_image_url` instead of being downloaded
+    fn supports_image_urls(&self) -> bool {
+        false
+    }
+
+    /// Describe a publicly reachable https image, fetched by the provider itself
+    ///
+    /// Only called when `supports_image_urls` returns true.
+    async fn describe_image_url(
+        &self,
+        url: &str,
+        _prompt: &str,
+    ) -> Result<ImageDescription, OpenRouterError> {
+        Err(OpenRouterError::InvalidResponse(format!(
+            "Image URLs are not supported by this provider: {url}"
+        )))
+    }
+
+    /// Describe several images with the same prompt, returning a result per image in order
+    ///
+    /// Providers that can describe several images in one request override this; the default
+    /// describes each image on its own.
+    async fn describe_images(
+        &self,
+        images: &[(&[u8], &str)],
+        prompt: &str,
+    ) -> Vec<Result<ImageDescription, OpenRouterError>> {
+        let mut results = Vec::with_capacity(images.len());
+        for &(image_data, _) in images {
+            results.push(self.describe_image(image_data, prompt).await);
+        }
+        results
+    }
+
+    /// Answer a text-only prompt, e.g. to summarize a transcript
+    async fn process_text(&self, prompt: &str) -> Result<String, OpenRouterError>;
+
+    /// Model answering `process_text`, for `{model}` in prompts; empty if unknown
+    fn text_model(&self) -> &str {
+        ""
+    }
+
+    /// Maximum description length in characters, including attribution
+    fn max_description_length(&self) -> usize {
+        DEFAULT_MAX_DESCRIPTION_LENGTH
+    }
+
+    /// Number of media attachments that may be described in parallel
+    fn max_concurrent_requests(&self) -> usize {
+        1
+    }
+
+    /// Record that a toot received `media_count` descriptions, for the usage report
+    fn record_described_toot(&self, _media_count: usize) {}
+}
+
 /// Rate limiter for API calls with exponential backoff
+///
+/// Shared between clones of the client; a permit bounds one request until its response is read.
 #[derive(Debug)]
 pub struct RateLimiter {
     semaphore: Arc<Semaphore>,
-    last_request: Option<Instant>,
+    last_request: tokio::sync::Mutex<Option<Instant>>,
     min_interval: Duration,
 }
 
+/// Default maximum number of concurrent OpenRouter requests
+const DEFAULT_MAX_CONCURRENT_REQUESTS: usize = 5;
+
+/// Default minimum delay between OpenRouter requests in milliseconds
+const DEFAULT_MIN_REQUEST_INTERVAL_MS: u64 = 200;
+
 impl RateLimiter {
     pub fn new(max_concurrent: usize, min_interval_ms: u64) -> Self {
         Self {
             semaphore: Arc::new(Semaphore::new(max_concurrent)),
-            last_request: None,
+            last_request: tokio::sync::Mutex::new(None),
             min_interval: Duration::from_millis(min_interval_ms),
         }
     }
 
+    /// Build a rate limiter from the configured limits, falling back to the defaults
+    pub fn from_config(config: &OpenRouterConfig) -> Self {
+        Self::new(
+            config
+                .max_concurrent_requests
+                .unwrap_or(DEFAULT_MAX_CONCURRENT_REQUESTS),
+            config
+                .min_request_interval_ms
+                .unwrap_or(DEFAULT_MIN_REQUEST_INTERVAL_MS),
+        )
+    }
+
     /// Acquire a permit and enforce minimum interval between requests
-    pub async fn acquire(&mut self) -> tokio::sync::SemaphorePermit<'_> {
-        let permit = self.semaphore.acquire().await.unwrap();
+    ///
+    /// Hold the permit until the response has been read so it bounds in-flight requests.
+    pub async fn acquire(&self) -> OwnedSemaphorePermit {
+        let permit = Arc::clone(&self.semaphore)
+            .acquire_owned()
+            .await
+            .expect("rate limiter semaphore is never closed");
 
-        if let Some(last) = self.last_request {
+        // Only the interval wait is serialized; requests themselves run concurrently
+        let mut last_request = self.last_request.lock().await;
+        if let Some(last) = *last_request {
             let elapsed = last.elapsed();
             if elapsed < self.min_interval {
                 let sleep_duration = self.min_interval - elapsed;
@@ -59,16 +220,317 @@ impl RateLimiter {
             }
         }
 
-        self.last_request = Some(Instant::now());
+        *last_request = Some(Instant::now());
         permit
     }
 }
 
+/// A generated image description and the model that wrote it
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImageDescription {
+    pub text: String,
+    pub model: String,
+    /// Token usage reported for the request, `None` if not reported or served from the cache
+    pub usage: Option<Usage>,
+}
+
+/// Default lifetime of a cached image description in seconds
+const DEFAULT_DESCRIPTION_CACHE_TTL_SECS: u64 = 24 * 60 * 60;
+
+/// LRU cache of image descriptions keyed by a hash of the image bytes and prompt
+#[derive(Debug)]
+pub struct DescriptionCache {
+    entries: LruCache<u64, (ImageDescription, Instant)>,
+    ttl: Duration,
+}
+
+impl DescriptionCache {
+    pub fn new(capacity: NonZeroUsize, ttl: Duration) -> Self {
+        Self {
+            entries: LruCache::new(capacity),
+            ttl,
+        }
+    }
+
+    /// Build a cache from the configured size and TTL, `None` if caching is disabled
+    pub fn from_config(config: &OpenRouterConfig) -> Option<Self> {
+        let capacity = NonZeroUsize::new(config.description_cache_size.unwrap_or(0))?;
+        let ttl = Duration::from_secs(
+            config
+                .description_cache_ttl_secs
+                .unwrap_or(DEFAULT_DESCRIPTION_CACHE_TTL_SECS),
+        );
+        Some(Self::new(capacity, ttl))
+    }
+
+    /// Cache key for an image; the prompt is included since it shapes the description
+    pub fn key(image_data: &[u8], prompt: &str) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        image_data.hash(&mut hasher);
+        prompt.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    /// Look up a description, dropping it if it has expired
+    pub fn get(&mut self, key: u64) -> Option<ImageDescription> {
+        match self.entries.get(&key) {
+            Some((description, cached_at)) if cached_at.elapsed() < self.ttl => {
+                Some(description.clone())
+            }
+            Some(_) => {
+                self.entries.pop(&key);
+                None
+            }
+            None => None,
+        }
+    }
+
+    pub fn insert(&mut self, key: u64, description: ImageDescription) {
+        self.entries.put(key, (description, Instant::now()));
+    }
+}
+
+/// Length of the window the daily spend cap applies to
+const SPEND_WINDOW: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// Tracks estimated spend against an optional cap within a 24h window
+#[derive(Debug)]
+pub struct SpendTracker {
+    cap: Option<f64>,
+    window_start: Instant,
+    spent: f64,
+}
+
+impl SpendTracker {
+    pub fn new(cap: Option<f64>) -> Self {
+        Self {
+            cap,
+            window_start: Instant::now(),
+            spent: 0.0,
+        }
+    }
+
+    /// Start a fresh window once the current one has elapsed
+    fn roll_window(&mut self) {
+        if self.window_start.elapsed() >= SPEND_WINDOW {
+            if self.spent > 0.0 {
+                info!(
+                    "Spend window reset (previous window: ${:.4}), resuming requests",
+                    self.spent
+                );
+            }
+            self.window_start = Instant::now();
+            self.spent = 0.0;
+        }
+    }
+
+    /// Fail with `SpendCapReached` if the cap has been reached in the current window
+    pub fn check(&mut self) -> Result<(), OpenRouterError> {
+        self.roll_window();
+
+        match self.cap {
+            Some(cap) if self.spent >= cap => {
+                let resets_in = SPEND_WINDOW
+                    .saturating_sub(self.window_start.elapsed())
+                    .as_secs();
+                Err(OpenRouterError::SpendCapReached {
+                    spent: self.spent,
+                    cap,
+                    resets_in,
+                })
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Add the estimated cost of a request to the current window
+    pub fn record(&mut self, cost: f64) {
+        self.roll_window();
+        self.spent += cost;
+
+        if let Some(cap) = self.cap {
+            debug!(
+                "Estimated spend: ${:.4} of ${:.2} daily cap",
+                self.spent, cap
+            );
+            if self.spent >= cap {
+                warn!(
+                    "Daily spend cap of ${:.2} reached (${:.4}), pausing OpenRouter requests until the window resets",
+                    cap, self.spent
+                );
+            }
+        }
+    }
+}
+
+/// Retries of a request that hit a provider failure before the fallback model takes over
+const PROVIDER_FAILURE_RETRIES: u32 = 1;
+
+/// Default delay before the first retry of a failed request, in milliseconds
+const DEFAULT_RETRY_BASE_DELAY_MS: u64 = 1000;
+
+/// Default factor the retry delay grows by with each attempt
+const DEFAULT_RETRY_BACKOFF_MULTIPLIER: f64 = 2.0;
+
+/// Default upper bound for a single retry delay, in milliseconds
+const DEFAULT_RETRY_MAX_DELAY_MS: u64 = 30_000;
+
+/// Default number of consecutive provider failures that open the circuit breaker
+const DEFAULT_CIRCUIT_BREAKER_THRESHOLD: u32 = 5;
+
+/// Default time the circuit breaker stays open before probing, in seconds
+const DEFAULT_CIRCUIT_BREAKER_COOLDOWN_SECS: u64 = 120;
+
+/// Fails requests fast while the upstream provider keeps failing
+///
+/// Opens after `threshold` consecutive `ProviderFailure`s; once the cooldown has passed a
+/// single probe request is let through, closing the breaker on success and reopening it
+/// on another provider failure.
+#[derive(Debug)]
+pub struct CircuitBreaker {
+    /// 0 disables the breaker
+    threshold: u32,
+    cooldown: Duration,
+    consecutive_failures: u32,
+    opened_at: Option<Instant>,
+    /// A probe request is in flight after the cooldown
+    probing: bool,
+    /// Identifies the current probe, so an abandoned one can't end a later probe
+    probe_id: u64,
+}
+
+impl CircuitBreaker {
+    pub fn new(threshold: u32, cooldown: Duration) -> Self {
+        Self {
+            threshold,
+            cooldown,
+            consecutive_failures: 0,
+            opened_at: None,
+            probing: false,
+            probe_id: 0,
+        }
+    }
+
+    /// Build a circuit breaker from the configured threshold and cooldown
+    pub fn from_config(config: &OpenRouterConfig) -> Self {
+        Self::new(
+            config
+                .circuit_breaker_threshold
+                .unwrap_or(DEFAULT_CIRCUIT_BREAKER_THRESHOLD),
+            Duration::from_secs(
+                config
+                    .circuit_breaker_cooldown_secs
+                    .unwrap_or(DEFAULT_CIRCUIT_BREAKER_COOLDOWN_SECS),
+            ),
+        )
+    }
+
+    /// Fail with `CircuitOpen` while the breaker is open, letting one probe through after the cooldown
+    ///
+    /// Returns the probe's ID when the caller is the probe. A probe that ends without
+    /// `record_success` or `record_failure` must be given up with [`Self::abandon_probe`].
+    pub fn check(&mut self) -> Result<Option<u64>, OpenRouterError> {
+        let Some(opened_at) = self.opened_at else {
+            return Ok(None);
+        };
+
+        let elapsed = opened_at.elapsed();
+        if elapsed >= self.cooldown && !self.probing {
+            info!("Circuit breaker half-open, probing OpenRouter with the next request");
+            self.probing = true;
+            self.probe_id += 1;
+            return Ok(Some(self.probe_id));
+        }
+
+        Err(OpenRouterError::CircuitOpen {
+            failures: self.consecutive_failures,
+            retry_in: self.cooldown.saturating_sub(elapsed).as_secs(),
+        })
+    }
+
+    /// Let the next request probe again when probe `probe_id` ended without a provider answer
+    ///
+    /// Covers probes that were cancelled (e.g. by the processing timeout) or stopped by the
+    /// spend cap, which would otherwise leave the breaker open for good.
+    pub fn abandon_probe(&mut self, probe_id: u64) {
+        if self.probing && self.probe_id == probe_id {
+            debug!("Circuit breaker probe ended without an answer, allowing another probe");
+            self.probing = false;
+        }
+    }
+
+    /// Count a provider failure, opening the breaker at the threshold or after a failed probe
+    pub fn record_failure(&mut self) {
+        if self.threshold == 0 {
+            return;
+        }
+        self.consecutive_failures += 1;
+
+        if self.probing || self.consecutive_failures == self.threshold {
+            warn!(
+                "Circuit breaker open after {} consecutive provider failures, failing OpenRouter requests fast for {}s",
+                self.consecutive_failures,
+                self.cooldown.as_secs()
+            );
+            self.opened_at = Some(Instant::now());
+            self.probing = false;
+        }
+    }
+
+    /// Any answer other than a provider failure means the provider is reachable again
+    pub fn record_success(&mut self) {
+        if self.opened_at.is_some() {
+            info!("Circuit breaker closed, OpenRouter provider is responding again");
+        }
+        self.consecutive_failures = 0;
+        self.opened_at = None;
+        self.probing = false;
+    }
+}
+
+/// Gives up a circuit breaker probe that is dropped before its outcome was recorded
+struct ProbeGuard {
+    probe: Option<(Arc<Mutex<CircuitBreaker>>, u64)>,
+}
+
+impl Drop for ProbeGuard {
+    fn drop(&mut self) {
+        if let Some((breaker, probe_id)) = self.probe.take() {
+            breaker.lock().unwrap().abandon_probe(probe_id);
+        }
+    }
+}
+
+/// Estimate the cost of a request in USD from its token usage and the model's per-token pricing
+pub fn estimate_cost(usage: &Usage, pricing: &ModelPricing) -> f64 {
+    let prompt_price = pricing.prompt.parse::<f64>().unwrap_or(0.0);
+    let completion_price = pricing.completion.parse::<f64>().unwrap_or(0.0);
+
+    match (usage.prompt_tokens, usage.completion_tokens) {
+        (Some(prompt), Some(completion)) => {
+            prompt as f64 * prompt_price + completion as f64 * completion_price
+        }
+        // Without a breakdown, assume the more expensive rate for every token
+        _ => usage.total_tokens.unwrap_or(0) as f64 * prompt_price.max(completion_price),
+    }
+}
+
 /// OpenRouter API client with rate limiting and cost controls
 pub struct OpenRouterClient {
     config: OpenRouterConfig,
     http_client: Client,
-    rate_limiter: Arc<tokio::sync::Mutex<RateLimiter>>,
+    /// Shared between clones so the configured limits cover every request
+    rate_limiter: Arc<RateLimiter>,
+    /// Shared between clones so the spend cap covers every request
+    spend_tracker: Arc<Mutex<SpendTracker>>,
+    /// Per-token pricing by model ID, filled from the model list
+    model_pricing: Arc<Mutex<HashMap<String, ModelPricing>>>,
+    /// Shared between clones, `None` unless `description_cache_size` is set
+    description_cache: Option<Arc<Mutex<DescriptionCache>>>,
+    /// Token usage and described media, shared between clones for the shutdown report
+    stats: Arc<Mutex<ProcessingStats>>,
+    /// Shared between clones so every request sees the provider's state
+    circuit_breaker: Arc<Mutex<CircuitBreaker>>,
 }
 
 impl Clone for OpenRouterClient {
@@ -76,7 +538,12 @@ impl Clone for OpenRouterClient {
         Self {
             config: self.config.clone(),
             http_client: reqwest::Client::new(),
-            rate_limiter: Arc::new(tokio::sync::Mutex::new(RateLimiter::new(5, 200))),
+            rate_limiter: Arc::clone(&self.rate_limiter),
+            spend_tracker: Arc::clone(&self.spend_tracker),
+            model_pricing: Arc::clone(&self.model_pricing),
+            description_cache: self.description_cache.clone(),
+            stats: Arc::clone(&self.stats),
+            circuit_breaker: Arc::clone(&self.circuit_breaker),
         }
     }
 }
@@ -96,6 +563,8 @@ impl std::fmt::Debug for OpenRouterClient {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Model {
     pub id: String,
+    /// Display name, not reported by OpenAI-compatible servers
+    #[serde(default)]
     pub name: String,
     pub description: Option<String>,
     pub pricing: Option<ModelPricing>,
@@ -116,7 +585,18 @@ pub struct AccountBalance {
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct BalanceData {
+    /// Credits spent with this key so far
     pub usage: f64,
+    /// Credit limit of the key, `None` for keys without a limit
+    #[serde(default)]
+    pub limit: Option<f64>,
+}
+
+impl BalanceData {
+    /// Credit left before the key's limit is reached, `None` for keys without a limit
+    pub fn remaining(&self) -> Option<f64> {
+        self.limit.map(|limit| (limit - self.usage).max(0.0))
+    }
 }
 
 /// Image description request
@@ -183,7 +663,7 @@ pub struct ResponseMessage {
     pub reasoning: Option<String>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Usage {
     pub prompt_tokens: Option<u32>,
     pub completion_tokens: Option<u32>,
@@ -206,12 +686,27 @@ pub struct ErrorResponse {
 pub struct ErrorDetail {
     pub message: String,
     #[allow(dead_code)] // May be used for error categorization in future
+    #[serde(default, deserialize_with = "deserialize_error_code")]
     pub code: Option<String>,
     #[serde(rename = "type")]
     #[allow(dead_code)] // May be used for error categorization in future
     pub error_type: Option<String>,
 }
 
+/// OpenRouter sends numeric HTTP-style error codes, other providers use strings
+fn deserialize_error_code<'de, D>(deserializer: D) -> Result<Option<String>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    Ok(
+        match Option::<serde_json::Value>::deserialize(deserializer)? {
+            Some(serde_json::Value::String(code)) => Some(code),
+            Some(serde_json::Value::Null) | None => None,
+            Some(other) => Some(other.to_string()),
+        },
+    )
+}
+
 impl OpenRouterClient {
     /// Create a new OpenRouter client with rate limiting
     pub fn new(config: OpenRouterConfig) -> Self {
@@ -220,14 +715,129 @@ impl OpenRouterClient {
             .build()
             .expect("Failed to create HTTP client");
 
-        // Rate limiter: max 5 concurrent requests, minimum 200ms between requests
-        let rate_limiter = Arc::new(tokio::sync::Mutex::new(RateLimiter::new(5, 200)));
+        let rate_limiter = Arc::new(RateLimiter::from_config(&config));
+
+        let spend_tracker = Arc::new(Mutex::new(SpendTracker::new(config.daily_spend_cap)));
+
+        let description_cache =
+            DescriptionCache::from_config(&config).map(|cache| Arc::new(Mutex::new(cache)));
+
+        let circuit_breaker = Arc::new(Mutex::new(CircuitBreaker::from_config(&config)));
 
         Self {
             config,
             http_client,
             rate_limiter,
+            spend_tracker,
+            model_pricing: Arc::new(Mutex::new(HashMap::new())),
+            description_cache,
+            stats: Arc::new(Mutex::new(ProcessingStats::default())),
+            circuit_breaker,
+        }
+    }
+
+    /// Maximum length of a media description in characters
+    pub fn max_description_length(&self) -> usize {
+        self.config
+            .max_description_length
+            .unwrap_or(DEFAULT_MAX_DESCRIPTION_LENGTH)
+    }
+
+    /// Why a generated image description is unusable, `None` if it passes the quality guard
+    fn low_quality_reason(&self, description: &str) -> Option<String> {
+        let min_length = self
+            .config
+            .min_description_length
+            .unwrap_or(DEFAULT_MIN_DESCRIPTION_LENGTH);
+        let length = description.chars().count();
+        if length < min_length {
+            return Some(format!(
+                "{length} characters, shorter than the minimum of {min_length}"
+            ));
         }
+
+        // Models use typographic apostrophes as often as ASCII ones
+        let normalized = description.to_lowercase().replace('\u{2019}', "'");
+        let matches = |pattern: &str| normalized.contains(&pattern.to_lowercase());
+        let refusal = match &self.config.refusal_patterns {
+            Some(patterns) => patterns.iter().find(|p| matches(p)).cloned(),
+            None => DEFAULT_REFUSAL_PATTERNS
+                .iter()
+                .find(|p| matches(p))
+                .map(|p| p.to_string()),
+        };
+        refusal.map(|pattern| format!("matches refusal pattern '{pattern}'"))
+    }
+
+    /// Maximum number of concurrent requests allowed by the rate limiter
+    pub fn max_concurrent_requests(&self) -> usize {
+        self.config
+            .max_concurrent_requests
+            .unwrap_or(DEFAULT_MAX_CONCURRENT_REQUESTS)
+    }
+
+    /// Short-circuit requests while the daily spend cap is reached
+    fn check_spend_cap(&self) -> Result<(), OpenRouterError> {
+        self.spend_tracker.lock().unwrap().check().inspect_err(|e| {
+            warn!("Skipping OpenRouter request: {}", e);
+        })
+    }
+
+    /// Short-circuit requests while the circuit breaker is open
+    ///
+    /// Hold the returned guard until the outcome is recorded; dropping it early gives up the probe.
+    fn check_circuit_breaker(&self) -> Result<ProbeGuard, OpenRouterError> {
+        let probe_id = self
+            .circuit_breaker
+            .lock()
+            .unwrap()
+            .check()
+            .inspect_err(|e| {
+                warn!("Skipping OpenRouter request: {}", e);
+            })?;
+        Ok(ProbeGuard {
+            probe: probe_id.map(|id| (Arc::clone(&self.circuit_breaker), id)),
+        })
+    }
+
+    /// Feed the outcome of a request (after model fallback) into the circuit breaker
+    fn record_provider_outcome<T>(&self, result: &Result<T, OpenRouterError>) {
+        let mut breaker = self.circuit_breaker.lock().unwrap();
+        match result {
+            Err(OpenRouterError::ProviderFailure { .. }) => breaker.record_failure(),
+            // Our own limits say nothing about the provider
+            Err(OpenRouterError::SpendCapReached { .. } | OpenRouterError::CircuitOpen { .. }) => {}
+            _ => breaker.record_success(),
+        }
+    }
+
+    /// Add a completed request's tokens to the stats and its estimated cost to the spend tracker
+    fn record_usage(&self, model: &str, usage: &Usage) {
+        let cost = self
+            .model_pricing
+            .lock()
+            .unwrap()
+            .get(model)
+            .map(|pricing| estimate_cost(usage, pricing));
+        self.stats.lock().unwrap().record_usage(usage, cost);
+
+        if self.config.daily_spend_cap.is_none() {
+            return;
+        }
+        match cost {
+            Some(cost) => self.spend_tracker.lock().unwrap().record(cost),
+            None => debug!("No pricing known for model {}, not counting spend", model),
+        }
+    }
+
+    /// Count a toot whose attachments were described
+    pub fn record_described_toot(&self, media_count: usize) {
+        self.stats.lock().unwrap().record_toot(media_count);
+    }
+
+    /// Toots, media and token usage accumulated by this client and its clones
+    pub fn processing_stats(&self) -> ProcessingStats {
+        self.stats.lock().unwrap().clone()
     }
 
     /// Sanitize text for safe Mastodon API usage
@@ -246,7 +856,7 @@ impl OpenRouterClient {
         normalized.trim().to_string()
     }
 
-    /// Safely truncate text at character boundaries, preferring word boundaries
+    /// Safely truncate text at character boundaries, preferring word and then sentence boundaries
     fn safe_truncate(text: &str, max_chars: usize) -> String {
         if text.chars().count() <= max_chars {
             return text.to_string();
@@ -267,9 +877,31 @@ impl OpenRouterClient {
             }
         }
 
+        // Languages without spaces (Japanese, Chinese) are cut after the last full sentence,
+        // as long as that keeps at least half of the limit
+        if let Some((end_byte_pos, terminator)) = truncated
+            .char_indices()
+            .rfind(|(_, c)| SENTENCE_TERMINATORS.contains(c))
+        {
+            let end_byte_pos = end_byte_pos + terminator.len_utf8();
+            if truncated[..end_byte_pos].chars().count() > max_chars / 2 {
+                return truncated[..end_byte_pos].to_string();
+            }
+        }
+
         format!("{truncated}…")
     }
 
+    /// Whether `base_url` is OpenRouter itself rather than a generic OpenAI-compatible server
+    ///
+    /// Only OpenRouter has the `/auth/key` balance endpoint and the attribution headers.
+    pub fn is_openrouter_provider(&self) -> bool {
+        self.config
+            .provider
+            .as_deref()
+            .map_or(true, |provider| provider == "openrouter")
+    }
+
     /// Get the base URL for OpenRouter API
     fn base_url(&self) -> &str {
         self.config
@@ -351,6 +983,17 @@ impl OpenRouterClient {
                     });
                 }
 
+                // Check for unknown or unavailable models
+                let error_msg_lower = error_msg.to_lowercase();
+                if status == 404
+                    || error_msg_lower.contains("not a valid model")
+                    || error_msg_lower.contains("no endpoints found")
+                    || error_msg_lower.contains("model not found")
+                {
+                    warn!("OpenRouter model not available: {}", error_msg);
+                    return Err(OpenRouterError::ModelNotAvailable { model: error_msg });
+                }
+
                 // Check for provider failures (common with OpenRouter)
                 if error_msg.to_lowercase().contains("provider")
                     && (error_msg.to_lowercase().contains("error")
@@ -412,12 +1055,8 @@ impl OpenRouterClient {
         let mut attempt = 0;
 
         loop {
-            // Acquire rate limiting permit
-            {
-                let mut limiter = self.rate_limiter.lock().await;
-                let _permit = limiter.acquire().await;
-                // Permit is dropped here, but rate limiting is enforced by the acquire() call
-            }
+            // Held until the response is read, but not while backing off before a retry
+            let permit = self.rate_limiter.acquire().await;
 
             debug!("Making OpenRouter API request (attempt {})", attempt + 1);
 
@@ -437,20 +1076,28 @@ impl OpenRouterClient {
                 }
             );
             info!("Content-Type: application/json");
-            info!("HTTP-Referer: https://github.com/rmoriz/alternator");
-            info!("X-Title: Alternator - Mastodon Media Describer");
+            if self.is_openrouter_provider() {
+                info!("HTTP-Referer: https://github.com/rmoriz/alternator");
+                info!("X-Title: Alternator - Mastodon Media Describer");
+            }
             info!("=== End HTTP Request Debug ===");
 
-            let response = request_fn()
+            let mut request = request_fn()
                 .header("Authorization", format!("Bearer {}", self.config.api_key))
-                .header("Content-Type", "application/json")
-                .header("HTTP-Referer", "https://github.com/rmoriz/alternator")
-                .header("X-Title", "Alternator - Mastodon Media Describer")
+                .header("Content-Type", "application/json");
+            if self.is_openrouter_provider() {
+                request = request
+                    .header("HTTP-Referer", "https://github.com/rmoriz/alternator")
+                    .header("X-Title", "Alternator - Mastodon Media Describer");
+            }
+            let response = request
                 .send()
                 .await
                 .map_err(|e| OpenRouterError::ApiRequestFailed(format!("Request failed: {e}")))?;
+            let result = self.handle_response::<T>(response).await;
+            drop(permit);
 
-            match self.handle_response::<T>(response).await {
+            match result {
                 Ok(result) => {
                     debug!("OpenRouter API request successful");
                     return Ok(result);
@@ -478,6 +1125,36 @@ impl OpenRouterClient {
                     error!("Authentication failed - check API key");
                     return Err(OpenRouterError::AuthenticationFailed);
                 }
+                Err(OpenRouterError::ModelNotAvailable { model }) => {
+                    // Don't retry unavailable models - callers may fall back to another model
+                    warn!(
+                        "Model not available - not retrying: {}. Check the configured model names; `alternator --list-models` shows the available ones",
+                        model
+                    );
+                    return Err(OpenRouterError::ModelNotAvailable { model });
+                }
+                Err(e @ OpenRouterError::ProviderFailure { .. }) => {
+                    // Transient, but a couple of attempts is enough; after that callers switch
+                    // to the fallback model instead of spending the full retry budget
+                    if attempt >= max_retries.min(PROVIDER_FAILURE_RETRIES) {
+                        warn!(
+                            "Provider still failing after {} attempts: {}",
+                            attempt + 1,
+                            e
+                        );
+                        return Err(e);
+                    }
+
+                    let delay = self.retry_delay(attempt);
+                    warn!(
+                        "Provider failure (attempt {}): {}, retrying in {}ms",
+                        attempt + 1,
+                        e,
+                        delay.as_millis()
+                    );
+                    sleep(delay).await;
+                    attempt += 1;
+                }
                 Err(OpenRouterError::InsufficientBalance { .. }) => {
                     // Don't retry balance errors
                     error!("Insufficient balance - please top up your account");
@@ -492,24 +1169,52 @@ impl OpenRouterClient {
                         return Err(e);
                     }
 
-                    let delay = 2_u64.pow(attempt) * 1000; // Exponential backoff in ms
-                    let delay = delay.min(30000); // Cap at 30 seconds
-
+                    let delay = self.retry_delay(attempt);
                     warn!(
                         "API request failed (attempt {}): {}, retrying in {}ms",
                         attempt + 1,
                         e,
-                        delay
+                        delay.as_millis()
                     );
-                    sleep(Duration::from_millis(delay)).await;
+                    sleep(delay).await;
                     attempt += 1;
                 }
             }
         }
     }
 
-    /// Get account balance for startup validation
-    pub async fn get_account_balance(&self) -> Result<f64, OpenRouterError> {
+    /// Exponential backoff before retry `attempt + 1`: `retry_base_delay_ms` grown by
+    /// `retry_backoff_multiplier` per attempt, capped at `retry_max_delay_ms`
+    fn retry_delay(&self, attempt: u32) -> Duration {
+        let base = self
+            .config
+            .retry_base_delay_ms
+            .unwrap_or(DEFAULT_RETRY_BASE_DELAY_MS);
+        let multiplier = self
+            .config
+            .retry_backoff_multiplier
+            .unwrap_or(DEFAULT_RETRY_BACKOFF_MULTIPLIER);
+        let max = self
+            .config
+            .retry_max_delay_ms
+            .unwrap_or(DEFAULT_RETRY_MAX_DELAY_MS);
+
+        let delay = base as f64 * multiplier.powi(attempt.min(64) as i32);
+        Duration::from_millis(delay.min(max as f64) as u64)
+    }
+
+    /// Get the remaining credit of the API key, `None` if the key has no credit limit
+    ///
+    /// OpenRouter reports spend (`usage`) and the key's `limit`; the remaining credit is
+    /// their difference. Fails without a request for generic OpenAI-compatible providers,
+    /// which have no balance endpoint.
+    pub async fn get_account_balance(&self) -> Result<Option<f64>, OpenRouterError> {
+        if !self.is_openrouter_provider() {
+            return Err(OpenRouterError::ApiRequestFailed(
+                "Account balance is only available from OpenRouter".to_string(),
+            ));
+        }
+
         info!("Checking OpenRouter account balance");
 
         let response: AccountBalance = self
@@ -522,25 +1227,26 @@ impl OpenRouterClient {
             )
             .await?;
 
-        let balance = response.data.usage;
-        info!("OpenRouter account balance: ${:.2}", balance);
+        let remaining = response.data.remaining();
+        match remaining {
+            Some(remaining) => info!(
+                "OpenRouter credit remaining: ${:.2} (used ${:.2} of ${:.2})",
+                remaining,
+                response.data.usage,
+                response.data.limit.unwrap_or_default()
+            ),
+            None => info!(
+                "OpenRouter key has no credit limit (used ${:.2})",
+                response.data.usage
+            ),
+        }
 
-        Ok(balance)
+        Ok(remaining)
     }
 
     /// List available models for startup validation
     pub async fn list_models(&self) -> Result<Vec<Model>, OpenRouterError> {
-        info!("Fetching OpenRouter model list");
-
-        let response: ModelsResponse = self
-            .api_request_with_retry(
-                || self.http_client.get(format!("{}/models", self.base_url())),
-                3,
-            )
-            .await?;
-
-        let models = response.data;
-        info!("Retrieved {} models from OpenRouter", models.len());
+        let models = self.fetch_models().await?;
 
         // Check if configured model is available
         let configured_model = &self.config.model;
@@ -560,31 +1266,394 @@ impl OpenRouterClient {
         Ok(models)
     }
 
+    /// Fetch the available models without checking the configured ones
+    pub async fn fetch_models(&self) -> Result<Vec<Model>, OpenRouterError> {
+        info!("Fetching OpenRouter model list");
+
+        let response: ModelsResponse = self
+            .api_request_with_retry(
+                || self.http_client.get(format!("{}/models", self.base_url())),
+                3,
+            )
+            .await?;
+
+        let models = response.data;
+        info!("Retrieved {} models from OpenRouter", models.len());
+
+        // Remember pricing for spend cap estimates
+        *self.model_pricing.lock().unwrap() = models
+            .iter()
+            .filter_map(|m| m.pricing.clone().map(|pricing| (m.id.clone(), pricing)))
+            .collect();
+
+        Ok(models)
+    }
+
     /// Generate description for an image using OpenRouter API with fallback support
     pub async fn describe_image(
         &self,
         image_data: &[u8],
         prompt: &str,
     ) -> Result<String, OpenRouterError> {
+        self.describe_image_with_source(image_data, prompt)
+            .await
+            .map(|description| description.text)
+    }
+
+    /// Generate description for an image along with the model that wrote it,
+    /// served from the description cache when enabled
+    pub async fn describe_image_with_source(
+        &self,
+        image_data: &[u8],
+        prompt: &str,
+    ) -> Result<ImageDescription, OpenRouterError> {
+        self.describe_image_input(ImageInput::Data(image_data), prompt)
+            .await
+    }
+
+    /// Generate description for a publicly reachable https image that the provider fetches
+    /// itself, skipping the download and base64 upload
+    pub async fn describe_image_url(
+        &self,
+        url: &str,
+        prompt: &str,
+    ) -> Result<ImageDescription, OpenRouterError> {
+        self.describe_image_input(ImageInput::Url(url), prompt)
+            .await
+    }
+
+    /// Describe several images, one description per image in order
+    ///
+    /// `images` are the image bytes with their MIME type. Models that accept several images
+    /// per message describe them all in one request; otherwise each image is described on its own.
+    #[allow(dead_code)] // Public API; the toot pipeline goes through `DescriptionProvider`
+    pub async fn describe_images(
+        &self,
+        images: &[(&[u8], &str)],
+        prompt: &str,
+    ) -> Result<Vec<String>, OpenRouterError> {
+        self.describe_images_with_source(images, prompt)
+            .await
+            .into_iter()
+            .map(|description| description.map(|description| description.text))
+            .collect()
+    }
+
+    /// Describe several images in one request, returning a result per image in order
+    ///
+    /// Images already in the description cache are served from it and only the rest are
+    /// batched. Falls back to one `describe_image_with_source` call per image, with its
+    /// fallback model and retries, when the batched request fails, is cut off at `max_tokens`
+    /// or its answer isn't one description per image, and for single entries of the answer
+    /// that are empty or refusals.
+    pub async fn describe_images_with_source(
+        &self,
+        images: &[(&[u8], &str)],
+        prompt: &str,
+    ) -> Vec<Result<ImageDescription, OpenRouterError>> {
+        let mut results: Vec<Option<Result<ImageDescription, OpenRouterError>>> = images
+            .iter()
+            .map(|&(image_data, _)| self.cached_description(image_data, prompt).map(Ok))
+            .collect();
+        let pending: Vec<usize> = (0..images.len())
+            .filter(|&index| results[index].is_none())
+            .collect();
+
+        if pending.len() >= 2 {
+            let batch: Vec<(&[u8], &str)> = pending.iter().map(|&index| images[index]).collect();
+            let batched = match self
+                .check_spend_cap()
+                .and_then(|()| self.check_circuit_breaker())
+            {
+                Ok(_probe) => {
+                    let result = self.describe_images_batched(&batch, prompt).await;
+                    self.record_provider_outcome(&result);
+                    result
+                }
+                Err(e) => Err(e),
+            };
+            match batched {
+                Ok((descriptions, model, mut usage)) => {
+                    info!(
+                        "Described {} images in one request with vision model {}",
+                        batch.len(),
+                        model
+                    );
+                    for (&index, raw) in pending.iter().zip(descriptions) {
+                        let image_data = images[index].0;
+                        let result = match self.finish_description(&raw, &model) {
+                            Ok(text) => {
+                                let description = ImageDescription {
+                                    text,
+                                    model: model.clone(),
+                                    // The request's usage is reported once, with the first image
+                                    usage: usage.take(),
+                                };
+                                self.cache_description(image_data, prompt, &description);
+                                Ok(description)
+                            }
+                            Err(e) => {
+                                warn!("{} in batched answer, describing the image on its own", e);
+                                self.describe_image_with_source(image_data, prompt).await
+                            }
+                        };
+                        results[index] = Some(result);
+                    }
+                }
+                // The per-image calls below fail fast with the same error
+                Err(
+                    OpenRouterError::SpendCapReached { .. }
+                    | OpenRouterError::CircuitOpen { .. }
+                    | OpenRouterError::InsufficientBalance { .. },
+                ) => {}
+                Err(e) => warn!(
+                    "Describing {} images in one request failed: {}. Describing them one by one",
+                    batch.len(),
+                    e
+                ),
+            }
+        }
+
+        let mut described = Vec::with_capacity(images.len());
+        for (&(image_data, _), result) in images.iter().zip(results) {
+            described.push(match result {
+                Some(result) => result,
+                None => self.describe_image_with_source(image_data, prompt).await,
+            });
+        }
+        described
+    }
+
+    /// Cached description of `image_data` for `prompt`, if the description cache is enabled
+    fn cached_description(&self, image_data: &[u8], prompt: &str) -> Option<ImageDescription> {
+        let cache = self.description_cache.as_ref()?;
+        let description = cache
+            .lock()
+            .unwrap()
+            .get(DescriptionCache::key(image_data, prompt))?;
+        Some(ImageDescription {
+            usage: None,
+            ..description
+        })
+    }
+
+    /// Remember a description of `image_data` for `prompt` if the description cache is enabled
+    fn cache_description(&self, image_data: &[u8], prompt: &str, description: &ImageDescription) {
+        if let Some(cache) = &self.description_cache {
+            cache.lock().unwrap().insert(
+                DescriptionCache::key(image_data, prompt),
+                description.clone(),
+            );
+        }
+    }
+
+    /// Send all images in one message with the primary vision model and split the answer
+    /// into one raw description per image
+    async fn describe_images_batched(
+        &self,
+        images: &[(&[u8], &str)],
+        prompt: &str,
+    ) -> Result<(Vec<String>, String, Option<Usage>), OpenRouterError> {
+        let model = &self.config.vision_model;
+        if prompt.trim().is_empty() {
+            return Err(OpenRouterError::InvalidResponse(
+                "Empty prompt provided".to_string(),
+            ));
+        }
+
+        let count = images.len();
+        let processed_prompt = prompt
+            .replace("{model}", model)
+            .replace("{max_length}", &self.max_description_length().to_string());
+        let mut content = vec![ContentPart::Text {
+            text: format!(
+                "{processed_prompt}\n\n{count} images are attached. Describe each of them separately, following the instructions above. Answer only with a JSON array of exactly {count} strings, one description per image, in the order the images are attached."
+            ),
+        }];
+        for &(image_data, mime_type) in images {
+            if image_data.is_empty() {
+                return Err(OpenRouterError::InvalidResponse(
+                    "Empty image data provided".to_string(),
+                ));
+            }
+            let size_mb = image_data.len() as f64 / (1024.0 * 1024.0);
+            if size_mb > 10.0 {
+                return Err(OpenRouterError::ImageTooLarge {
+                    size_mb,
+                    max_mb: 10.0,
+                });
+            }
+            let base64_image = base64::prelude::BASE64_STANDARD.encode(image_data);
+            content.push(ContentPart::ImageUrl {
+                image_url: ImageUrl {
+                    url: format!("data:{mime_type};base64,{base64_image}"),
+                },
+            });
+        }
+
+        debug!(
+            "Generating descriptions for {} images in one request using model: {}",
+            count, model
+        );
+        let request = ImageDescriptionRequest {
+            model: model.to_string(),
+            messages: vec![Message {
+                role: "user".to_string(),
+                content,
+            }],
+            // The answer holds one description per image
+            max_tokens: self
+                .config
+                .max_tokens
+                .map(|max_tokens| max_tokens.saturating_mul(count as u32)),
+            reasoning: Some(ReasoningConfig {
+                exclude: Some(true),
+                enabled: None,
+                effort: None,
+                max_tokens: None,
+            }),
+        };
+
+        let response: ImageDescriptionResponse = self
+            .api_request_with_retry(
+                || {
+                    self.http_client
+                        .post(format!("{}/chat/completions", self.base_url()))
+                        .json(&request)
+                },
+                2,
+            )
+            .await?;
+        if let Some(usage) = &response.usage {
+            self.record_usage(model, usage);
+        }
+
+        let choice = response.choices.first();
+        if choice.and_then(|choice| choice.finish_reason.as_deref()) == Some("length") {
+            return Err(OpenRouterError::InvalidResponse(format!(
+                "Answer for {count} images from {model} was cut off at max_tokens"
+            )));
+        }
+        let answer = choice
+            .map(|choice| choice.message.content.as_str())
+            .unwrap_or_default();
+        let descriptions = parse_batched_descriptions(answer, count).ok_or_else(|| {
+            OpenRouterError::InvalidResponse(format!(
+                "Expected a JSON array of {count} descriptions from {model}"
+            ))
+        })?;
+        Ok((descriptions, model.to_string(), response.usage))
+    }
+
+    /// Describe image bytes or an image URL, served from the description cache when enabled
+    #[tracing::instrument(
+        name = "describe_image",
+        skip_all,
+        fields(image_bytes = image.data_len(), model = tracing::field::Empty, cached)
+    )]
+    async fn describe_image_input(
+        &self,
+        image: ImageInput<'_>,
+        prompt: &str,
+    ) -> Result<ImageDescription, OpenRouterError> {
+        let Some(cache) = &self.description_cache else {
+            let description = self.describe_image_uncached(image, prompt).await?;
+            tracing::Span::current().record("model", description.model.as_str());
+            return Ok(description);
+        };
+
+        let key = DescriptionCache::key(image.cache_bytes(), prompt);
+        if let Some(description) = cache.lock().unwrap().get(key) {
+            info!("Using cached image description");
+            let span = tracing::Span::current();
+            span.record("model", description.model.as_str());
+            span.record("cached", true);
+            return Ok(ImageDescription {
+                usage: None,
+                ..description
+            });
+        }
+
+        let description = self.describe_image_uncached(image, prompt).await?;
+        tracing::Span::current().record("model", description.model.as_str());
+        cache.lock().unwrap().insert(key, description.clone());
+        Ok(description)
+    }
+
+    /// Generate description for an image unless the spend cap or circuit breaker prevents it
+    async fn describe_image_uncached(
+        &self,
+        image: ImageInput<'_>,
+        prompt: &str,
+    ) -> Result<ImageDescription, OpenRouterError> {
+        self.check_spend_cap()?;
+        let _probe = self.check_circuit_breaker()?;
+
+        let result = self.describe_image_with_fallback(image, prompt).await;
+        self.record_provider_outcome(&result);
+        result
+    }
+
+    /// Generate description for an image, trying the fallback vision model if the primary fails
+    async fn describe_image_with_fallback(
+        &self,
+        image: ImageInput<'_>,
+        prompt: &str,
+    ) -> Result<ImageDescription, OpenRouterError> {
+        let primary_model = &self.config.vision_model;
+        let fallback_model = &self.config.vision_fallback_model;
+
         // Try primary vision model first
         match self
-            .describe_image_with_model(image_data, prompt, &self.config.vision_model)
+            .describe_image_with_model(image, prompt, primary_model)
             .await
         {
-            Ok(result) => Ok(result),
-            Err(OpenRouterError::ProviderFailure { provider, message }) => {
+            Ok(description) => {
+                info!(
+                    "Image description generated by vision model {}",
+                    primary_model
+                );
+                Ok(description)
+            }
+            Err(
+                e @ (OpenRouterError::ProviderFailure { .. }
+                | OpenRouterError::ModelNotAvailable { .. }),
+            ) if !fallback_model.is_empty() && fallback_model != primary_model => {
                 warn!(
-                    "Primary vision model {} failed (Provider: {}): {}. Trying fallback model {}",
-                    self.config.vision_model, provider, message, self.config.vision_fallback_model
+                    "Primary vision model {} failed: {}. Trying fallback model {}",
+                    primary_model, e, fallback_model
                 );
 
-                // Try fallback model
-                self.describe_image_with_model(
-                    image_data,
-                    prompt,
-                    &self.config.vision_fallback_model,
-                )
-                .await
+                // Try fallback model once
+                let description = self
+                    .describe_image_with_model(image, prompt, fallback_model)
+                    .await?;
+                info!(
+                    "Image description generated by fallback vision model {}",
+                    fallback_model
+                );
+                Ok(description)
+            }
+            // Flaky models sometimes answer with nothing usable or a refusal; give it one more go
+            Err(
+                e @ (OpenRouterError::EmptyDescription { .. }
+                | OpenRouterError::LowQualityDescription { .. }),
+            ) => {
+                let retry_model = if fallback_model.is_empty() {
+                    primary_model
+                } else {
+                    fallback_model
+                };
+                warn!("{}. Retrying once with model {}", e, retry_model);
+
+                let description = self
+                    .describe_image_with_model(image, prompt, retry_model)
+                    .await?;
+                info!(
+                    "Image description generated by vision model {} on retry",
+                    retry_model
+                );
+                Ok(description)
             }
             Err(e) => Err(e),
         }
@@ -593,12 +1662,12 @@ impl OpenRouterClient {
     /// Generate description for an image using a specific model
     async fn describe_image_with_model(
         &self,
-        image_data: &[u8],
+        image: ImageInput<'_>,
         prompt: &str,
         model: &str,
-    ) -> Result<String, OpenRouterError> {
+    ) -> Result<ImageDescription, OpenRouterError> {
         // Validate input parameters
-        if image_data.is_empty() {
+        if image.cache_bytes().is_empty() {
             return Err(OpenRouterError::InvalidResponse(
                 "Empty image data provided".to_string(),
             ));
@@ -610,23 +1679,38 @@ impl OpenRouterClient {
             ));
         }
 
-        // Replace {model} placeholder in prompt with actual model name
-        let processed_prompt = prompt.replace("{model}", model);
+        // Replace placeholders in prompt with the model name and length limit
+        let max_length = self.max_description_length();
+        let processed_prompt = prompt
+            .replace("{model}", model)
+            .replace("{max_length}", &max_length.to_string());
 
         debug!("Generating image description using model: {}", model);
 
-        // Validate image size
-        let size_mb = image_data.len() as f64 / (1024.0 * 1024.0);
-        if size_mb > 10.0 {
-            return Err(OpenRouterError::ImageTooLarge {
-                size_mb,
-                max_mb: 10.0,
-            });
-        }
+        let image_url = match image {
+            ImageInput::Data(image_data) => {
+                // Validate image size
+                let size_mb = image_data.len() as f64 / (1024.0 * 1024.0);
+                if size_mb > 10.0 {
+                    return Err(OpenRouterError::ImageTooLarge {
+                        size_mb,
+                        max_mb: 10.0,
+                    });
+                }
 
-        // Convert image to base64 data URL
-        let base64_image = base64::prelude::BASE64_STANDARD.encode(image_data);
-        let data_url = format!("data:image/jpeg;base64,{base64_image}");
+                // Convert image to base64 data URL
+                let base64_image = base64::prelude::BASE64_STANDARD.encode(image_data);
+                let mime_type = crate::media::sniff_media_type(image_data).unwrap_or("image/jpeg");
+                format!("data:{mime_type};base64,{base64_image}")
+            }
+            ImageInput::Url(url) => {
+                debug!(
+                    "Passing image URL through to the provider: {}",
+                    crate::redact::log_url(url)
+                );
+                url.to_string()
+            }
+        };
 
         let request = ImageDescriptionRequest {
             model: model.to_string(),
@@ -637,7 +1721,7 @@ impl OpenRouterClient {
                         text: processed_prompt,
                     },
                     ContentPart::ImageUrl {
-                        image_url: ImageUrl { url: data_url },
+                        image_url: ImageUrl { url: image_url },
                     },
                 ],
             }],
@@ -667,8 +1751,10 @@ impl OpenRouterClient {
             }
         );
         debug!("  Content-Type: application/json");
-        debug!("  HTTP-Referer: https://github.com/rmoriz/alternator");
-        debug!("  X-Title: Alternator - Mastodon Media Describer");
+        if self.is_openrouter_provider() {
+            debug!("  HTTP-Referer: https://github.com/rmoriz/alternator");
+            debug!("  X-Title: Alternator - Mastodon Media Describer");
+        }
         debug!("Request Body:");
         debug!(
             "{}",
@@ -697,29 +1783,14 @@ impl OpenRouterClient {
         // Validate that we have at least one choice with content
         let choice = &response.choices[0];
         if choice.message.content.trim().is_empty() {
-            return Err(OpenRouterError::InvalidResponse(
-                "Empty content in response choice".to_string(),
-            ));
+            return Err(OpenRouterError::EmptyDescription {
+                model: model.to_string(),
+            });
         }
 
         // Extract the main content (not reasoning tokens) from the response
         let raw_description = choice.message.content.trim();
 
-        // Sanitize the description to remove any problematic characters
-        let description = Self::sanitize_description(raw_description);
-
-        debug!(
-            "OpenRouter response - raw length: {}, sanitized length: {}, content preview: '{}'",
-            raw_description.len(),
-            description.len(),
-            // Use safe_truncate for Unicode-safe preview
-            if description.chars().count() > 100 {
-                Self::safe_truncate(&description, 100)
-            } else {
-                description.to_string()
-            }
-        );
-
         // Log if reasoning tokens were present but excluded
         if let Some(reasoning) = &choice.message.reasoning {
             debug!(
@@ -729,11 +1800,12 @@ impl OpenRouterClient {
         }
 
         // Log token usage if available
-        if let Some(usage) = response.usage {
+        if let Some(usage) = &response.usage {
             debug!(
                 "Token usage - Prompt: {:?}, Completion: {:?}, Total: {:?}",
                 usage.prompt_tokens, usage.completion_tokens, usage.total_tokens
             );
+            self.record_usage(model, usage);
 
             // Check if we hit the token limit
             if let Some(max_tokens) = self.config.max_tokens {
@@ -745,22 +1817,61 @@ impl OpenRouterClient {
             }
         }
 
+        let final_description = self.finish_description(raw_description, model)?;
+        Ok(ImageDescription {
+            text: final_description,
+            model: model.to_string(),
+            usage: response.usage,
+        })
+    }
+
+    /// Sanitize a description from `model`, reject empty or low-quality ones and truncate it
+    /// to the description limit
+    fn finish_description(
+        &self,
+        raw_description: &str,
+        model: &str,
+    ) -> Result<String, OpenRouterError> {
+        let max_length = self.max_description_length();
+
+        // Sanitize the description to remove any problematic characters
+        let description = Self::sanitize_description(raw_description);
+
+        debug!(
+            "OpenRouter response - raw length: {}, sanitized length: {}, content preview: '{}'",
+            raw_description.len(),
+            description.len(),
+            // Use safe_truncate for Unicode-safe preview
+            if description.chars().count() > 100 {
+                Self::safe_truncate(&description, 100)
+            } else {
+                description.to_string()
+            }
+        );
+
         if description.is_empty() {
-            return Err(OpenRouterError::InvalidResponse(
-                "Empty description returned".to_string(),
-            ));
+            return Err(OpenRouterError::EmptyDescription {
+                model: model.to_string(),
+            });
+        }
+
+        if let Some(reason) = self.low_quality_reason(&description) {
+            return Err(OpenRouterError::LowQualityDescription {
+                model: model.to_string(),
+                reason,
+            });
         }
 
-        // Ensure description respects character limit (1500 chars total including AI attribution)
-        const MAX_DESCRIPTION_LENGTH: usize = 1500;
-        let final_description = if description.chars().count() > MAX_DESCRIPTION_LENGTH {
+        // Ensure description respects the instance's character limit
+        let final_description = if description.chars().count() > max_length {
             warn!(
                 "Description too long ({} chars), truncating to {} chars",
                 description.chars().count(),
-                MAX_DESCRIPTION_LENGTH
+                max_length
             );
 
-            Self::safe_truncate(&description, MAX_DESCRIPTION_LENGTH)
+            // Leave room for the ellipsis safe_truncate appends
+            Self::safe_truncate(&description, max_length.saturating_sub(1))
         } else {
             description
         };
@@ -771,21 +1882,38 @@ impl OpenRouterClient {
 
     /// Process text using OpenRouter API with fallback support (for transcript summarization)
     pub async fn process_text(&self, prompt: &str) -> Result<String, OpenRouterError> {
+        self.check_spend_cap()?;
+        let _probe = self.check_circuit_breaker()?;
+
+        let result = self.process_text_with_fallback(prompt).await;
+        self.record_provider_outcome(&result);
+        result
+    }
+
+    /// Process text, trying the fallback text model if the primary fails
+    async fn process_text_with_fallback(&self, prompt: &str) -> Result<String, OpenRouterError> {
+        let primary_model = &self.config.text_model;
+        let fallback_model = &self.config.text_fallback_model;
+
         // Try primary text model first
-        match self
-            .process_text_with_model(prompt, &self.config.text_model)
-            .await
-        {
-            Ok(result) => Ok(result),
-            Err(OpenRouterError::ProviderFailure { provider, message }) => {
+        match self.process_text_with_model(prompt, primary_model).await {
+            Ok(text) => {
+                info!("Text generated by text model {}", primary_model);
+                Ok(text)
+            }
+            Err(
+                e @ (OpenRouterError::ProviderFailure { .. }
+                | OpenRouterError::ModelNotAvailable { .. }),
+            ) if !fallback_model.is_empty() && fallback_model != primary_model => {
                 warn!(
-                    "Primary text model {} failed (Provider: {}): {}. Trying fallback model {}",
-                    self.config.text_model, provider, message, self.config.text_fallback_model
+                    "Primary text model {} failed: {}. Trying fallback model {}",
+                    primary_model, e, fallback_model
                 );
 
-                // Try fallback model
-                self.process_text_with_model(prompt, &self.config.text_fallback_model)
-                    .await
+                // Try fallback model once
+                let text = self.process_text_with_model(prompt, fallback_model).await?;
+                info!("Text generated by fallback text model {}", fallback_model);
+                Ok(text)
             }
             Err(e) => Err(e),
         }
@@ -838,8 +1966,10 @@ impl OpenRouterClient {
             }
         );
         debug!("  Content-Type: application/json");
-        debug!("  HTTP-Referer: https://github.com/rmoriz/alternator");
-        debug!("  X-Title: Alternator - Mastodon Media Describer");
+        if self.is_openrouter_provider() {
+            debug!("  HTTP-Referer: https://github.com/rmoriz/alternator");
+            debug!("  X-Title: Alternator - Mastodon Media Describer");
+        }
         debug!("Request Body:");
         debug!(
             "{}",
@@ -897,6 +2027,7 @@ impl OpenRouterClient {
                 "Token usage - Prompt: {:?}, Completion: {:?}, Total: {:?}",
                 usage.prompt_tokens, usage.completion_tokens, usage.total_tokens
             );
+            self.record_usage(model, &usage);
 
             // Check if we hit the token limit
             if let Some(max_tokens) = self.config.max_tokens {
@@ -921,12 +2052,12 @@ impl OpenRouterClient {
 
 #[async_trait]
 impl OpenRouterApi for OpenRouterClient {
-    async fn get_account_balance(&self) -> Result<f64, OpenRouterError> {
+    async fn get_account_balance(&self) -> Result<Option<f64>, OpenRouterError> {
         self.get_account_balance().await
     }
 
     async fn list_models(&self) -> Result<Vec<Model>, OpenRouterError> {
-        self.list_models().await
+        self.fetch_models().await
     }
 
     async fn describe_image(
@@ -942,10 +2073,71 @@ impl OpenRouterApi for OpenRouterClient {
     }
 }
 
+#[async_trait]
+impl DescriptionProvider for OpenRouterClient {
+    async fn describe_image(
+        &self,
+        image_data: &[u8],
+        prompt: &str,
+    ) -> Result<ImageDescription, OpenRouterError> {
+        self.describe_image_with_source(image_data, prompt).await
+    }
+
+    fn supports_image_urls(&self) -> bool {
+        self.config.image_url_passthrough.unwrap_or(false)
+    }
+
+    async fn describe_images(
+        &self,
+        images: &[(&[u8], &str)],
+        prompt: &str,
+    ) -> Vec<Result<ImageDescription, OpenRouterError>> {
+        self.describe_images_with_source(images, prompt).await
+    }
+
+    async fn describe_image_url(
+        &self,
+        url: &str,
+        prompt: &str,
+    ) -> Result<ImageDescription, OpenRouterError> {
+        self.describe_image_url(url, prompt).await
+    }
+
+    async fn process_text(&self, prompt: &str) -> Result<String, OpenRouterError> {
+        self.process_text(prompt).await
+    }
+
+    fn text_model(&self) -> &str {
+        &self.config.text_model
+    }
+
+    fn max_description_length(&self) -> usize {
+        self.max_description_length()
+    }
+
+    fn max_concurrent_requests(&self) -> usize {
+        self.max_concurrent_requests()
+    }
+
+    fn record_described_toot(&self, media_count: usize) {
+        self.record_described_toot(media_count)
+    }
+}
+
+/// Descriptions from a batched answer, which should be a JSON array of `count` strings,
+/// possibly wrapped in a code fence or surrounding text
+fn parse_batched_descriptions(answer: &str, count: usize) -> Option<Vec<String>> {
+    let start = answer.find('[')?;
+    let end = answer.rfind(']')?;
+    let descriptions: Vec<String> = serde_json::from_str(answer.get(start..=end)?).ok()?;
+    (descriptions.len() == count).then_some(descriptions)
+}
+
 /// Mock OpenRouter client for testing
 #[derive(Debug)]
 pub struct MockOpenRouterClient {
-    pub balance: f64,
+    /// Remaining credit, `None` for an account without a credit limit
+    pub balance: Option<f64>,
     #[allow(dead_code)]
     // Used in test configurations but may not be detected by clippy in --all-targets mode
     pub models: Vec<Model>,
@@ -968,7 +2160,7 @@ impl Default for MockOpenRouterClient {
 impl MockOpenRouterClient {
     pub fn new() -> Self {
         Self {
-            balance: 25.50,
+            balance: Some(25.50),
             models: vec![
                 Model {
                     id: "anthropic/claude-3-haiku".to_string(),
@@ -1001,7 +2193,7 @@ impl MockOpenRouterClient {
     #[allow(dead_code)]
     pub fn with_error(error: OpenRouterError) -> Self {
         Self {
-            balance: 25.50,
+            balance: Some(25.50),
             models: vec![],
             description_response: String::new(),
             text_response: String::new(),
@@ -1012,7 +2204,13 @@ impl MockOpenRouterClient {
 
     #[allow(dead_code)]
     pub fn with_balance(mut self, balance: f64) -> Self {
-        self.balance = balance;
+        self.balance = Some(balance);
+        self
+    }
+
+    #[allow(dead_code)]
+    pub fn with_unlimited_balance(mut self) -> Self {
+        self.balance = None;
         self
     }
 
@@ -1031,7 +2229,7 @@ impl MockOpenRouterClient {
 
 #[async_trait]
 impl OpenRouterApi for MockOpenRouterClient {
-    async fn get_account_balance(&self) -> Result<f64, OpenRouterError> {
+    async fn get_account_balance(&self) -> Result<Option<f64>, OpenRouterError> {
         if self.should_fail {
             return Err(self.error_type.as_ref().unwrap().clone());
         }
@@ -1064,6 +2262,33 @@ impl OpenRouterApi for MockOpenRouterClient {
     }
 }
 
+#[async_trait]
+impl DescriptionProvider for MockOpenRouterClient {
+    async fn describe_image(
+        &self,
+        image_data: &[u8],
+        prompt: &str,
+    ) -> Result<ImageDescription, OpenRouterError> {
+        let text = OpenRouterApi::describe_image(self, image_data, prompt).await?;
+        Ok(ImageDescription {
+            text,
+            model: MOCK_MODEL.to_string(),
+            usage: None,
+        })
+    }
+
+    async fn process_text(&self, prompt: &str) -> Result<String, OpenRouterError> {
+        OpenRouterApi::process_text(self, prompt).await
+    }
+
+    fn text_model(&self) -> &str {
+        MOCK_MODEL
+    }
+}
+
+/// Model name the mock client reports for its descriptions
+pub const MOCK_MODEL: &str = "mock/model";
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1072,13 +2297,33 @@ mod tests {
     fn create_test_config() -> OpenRouterConfig {
         OpenRouterConfig {
             api_key: "test_key".to_string(),
+            api_key_file: None,
             model: "mistralai/mistral-small-3.2-24b-instruct:free".to_string(),
             vision_model: "mistralai/mistral-small-3.2-24b-instruct:free".to_string(),
             vision_fallback_model: "google/gemma-3-27b-it:free".to_string(),
             text_model: "mistralai/mistral-small-3.2-24b-instruct:free".to_string(),
             text_fallback_model: "moonshotai/kimi-k2:free".to_string(),
             base_url: Some("https://test.openrouter.ai/api/v1".to_string()),
+            provider: None,
             max_tokens: Some(150),
+            daily_spend_cap: None,
+            max_concurrent_requests: None,
+            min_request_interval_ms: None,
+            description_cache_size: None,
+            description_cache_ttl_secs: None,
+            max_description_length: None,
+            min_description_length: None,
+            refusal_patterns: None,
+            circuit_breaker_threshold: None,
+            circuit_breaker_cooldown_secs: None,
+            retry_base_delay_ms: None,
+            retry_backoff_multiplier: None,
+            retry_max_delay_ms: None,
+            image_url_passthrough: None,
+            use_toot_text_as_context: None,
+            chart_hashtags: None,
+            chart_prompt: None,
+            batch_image_descriptions: None,
         }
     }
 
@@ -1106,7 +2351,7 @@ mod tests {
 
     #[tokio::test]
     async fn test_rate_limiter() {
-        let mut rate_limiter = RateLimiter::new(2, 100);
+        let rate_limiter = RateLimiter::new(2, 100);
 
         let start = Instant::now();
         let _permit1 = rate_limiter.acquire().await;
@@ -1118,6 +2363,75 @@ mod tests {
         assert!(elapsed >= Duration::from_millis(100));
     }
 
+    #[tokio::test]
+    async fn test_client_uses_configured_rate_limits() {
+        let mut config = create_test_config();
+        config.max_concurrent_requests = Some(3);
+        config.min_request_interval_ms = Some(300);
+        let client = OpenRouterClient::new(config);
+
+        let rate_limiter = &client.rate_limiter;
+        assert_eq!(rate_limiter.semaphore.available_permits(), 3);
+        assert_eq!(rate_limiter.min_interval, Duration::from_millis(300));
+
+        let start = Instant::now();
+        drop(rate_limiter.acquire().await);
+        drop(rate_limiter.acquire().await);
+
+        // Second request should be delayed by the configured interval
+        assert!(start.elapsed() >= Duration::from_millis(300));
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_requests_bounded_across_clones() {
+        use crate::test_support::{MockHttpServer, MockResponse};
+
+        let server = MockHttpServer::start(|_| {
+            MockResponse::json(200, "{}").with_delay(Duration::from_millis(200))
+        })
+        .await;
+        let mut config = create_test_config();
+        config.base_url = Some(server.url());
+        config.max_concurrent_requests = Some(2);
+        config.min_request_interval_ms = Some(0);
+        let client = OpenRouterClient::new(config);
+        let clone = client.clone();
+
+        async fn request(client: &OpenRouterClient) {
+            let url = format!("{}/models", client.base_url());
+            client
+                .api_request_with_retry::<serde_json::Value>(|| client.http_client.get(&url), 0)
+                .await
+                .unwrap();
+        }
+
+        // Four slow requests through two permits shared by the clones take two rounds
+        let start = Instant::now();
+        tokio::join!(
+            request(&client),
+            request(&client),
+            request(&clone),
+            request(&clone)
+        );
+        assert!(start.elapsed() >= Duration::from_millis(400));
+        assert_eq!(server.requests().len(), 4);
+        assert_eq!(client.rate_limiter.semaphore.available_permits(), 2);
+    }
+
+    #[test]
+    fn test_rate_limiter_defaults() {
+        let rate_limiter = RateLimiter::from_config(&create_test_config());
+
+        assert_eq!(
+            rate_limiter.semaphore.available_permits(),
+            DEFAULT_MAX_CONCURRENT_REQUESTS
+        );
+        assert_eq!(
+            rate_limiter.min_interval,
+            Duration::from_millis(DEFAULT_MIN_REQUEST_INTERVAL_MS)
+        );
+    }
+
     #[test]
     fn test_image_description_request_serialization() {
         let request = ImageDescriptionRequest {
@@ -1218,6 +2532,41 @@ mod tests {
 
         let response: AccountBalance = serde_json::from_value(json_response).unwrap();
         assert_eq!(response.data.usage, 25.50);
+        assert_eq!(response.data.limit, None);
+    }
+
+    #[test]
+    fn test_remaining_balance_for_limited_and_unlimited_keys() {
+        let limited: AccountBalance =
+            serde_json::from_value(json!({"data": {"usage": 7.5, "limit": 10.0}})).unwrap();
+        assert_eq!(limited.data.remaining(), Some(2.5));
+
+        // Spend beyond the limit doesn't go negative
+        let exhausted: AccountBalance =
+            serde_json::from_value(json!({"data": {"usage": 12.0, "limit": 10.0}})).unwrap();
+        assert_eq!(exhausted.data.remaining(), Some(0.0));
+
+        // OpenRouter sends `null` for keys without a credit limit
+        let unlimited: AccountBalance =
+            serde_json::from_value(json!({"data": {"usage": 250.0, "limit": null}})).unwrap();
+        assert_eq!(unlimited.data.remaining(), None);
+    }
+
+    #[tokio::test]
+    async fn test_get_account_balance_returns_remaining_credit() {
+        use crate::test_support::{MockHttpServer, MockResponse};
+
+        for (body, expected) in [
+            (r#"{"data":{"usage":0.25,"limit":20.0}}"#, Some(19.75)),
+            (r#"{"data":{"usage":0.25,"limit":null}}"#, None),
+        ] {
+            let server = MockHttpServer::start(move |_| MockResponse::json(200, body)).await;
+            let mut config = create_test_config();
+            config.base_url = Some(server.url());
+            let client = OpenRouterClient::new(config);
+
+            assert_eq!(client.get_account_balance().await.unwrap(), expected);
+        }
     }
 
     #[test]
@@ -1405,6 +2754,28 @@ mod tests {
         assert!(!result.is_empty());
     }
 
+    #[test]
+    fn test_safe_truncate_cuts_cjk_at_sentence_end() {
+        let japanese_text = "木製のテーブルに半分ほどビールが注がれた透明なグラスと、中に角切りのチェダーチーズスナックが入ったガラスのボウルが置かれている。グラスとボウルは、トーンがかかった柴編みのコースターの上にあり、そのコースターはテーブルの上に置かれています。背景は落ち着いた灰色で、飲食品を目立たせています。";
+        let result = OpenRouterClient::safe_truncate(japanese_text, 100);
+        assert!(result.ends_with("置かれている。"));
+        assert!(japanese_text.starts_with(&result));
+
+        let chinese_text = "一只橘色的猫躺在窗台上晒太阳！它的眼睛半闭着，看起来非常放松。窗外是一片绿色的花园，阳光洒在花朵上？";
+        let result = OpenRouterClient::safe_truncate(chinese_text, 40);
+        assert_eq!(
+            result,
+            "一只橘色的猫躺在窗台上晒太阳！它的眼睛半闭着，看起来非常放松。"
+        );
+
+        // A sentence end too early in the text is not worth losing the rest
+        let result = OpenRouterClient::safe_truncate(chinese_text, 30);
+        assert_eq!(
+            result,
+            "一只橘色的猫躺在窗台上晒太阳！它的眼睛半闭着，看起来非常放松…"
+        );
+    }
+
     #[test]
     fn test_safe_truncate_mixed_unicode() {
         // Mix of ASCII, Japanese, and emoji
@@ -1457,21 +2828,19 @@ mod tests {
     fn test_rate_limiter_concurrent_permits() {
         let rt = tokio::runtime::Runtime::new().unwrap();
         rt.block_on(async {
-            let rate_limiter = Arc::new(tokio::sync::Mutex::new(RateLimiter::new(2, 50)));
+            let rate_limiter = Arc::new(RateLimiter::new(2, 50));
 
             // Test that we can acquire multiple permits concurrently
             let rate_limiter1 = rate_limiter.clone();
             let rate_limiter2 = rate_limiter.clone();
 
             let task1 = tokio::spawn(async move {
-                let mut guard = rate_limiter1.lock().await;
-                let permit = guard.acquire().await;
+                let permit = rate_limiter1.acquire().await;
                 drop(permit);
             });
 
             let task2 = tokio::spawn(async move {
-                let mut guard = rate_limiter2.lock().await;
-                let permit = guard.acquire().await;
+                let permit = rate_limiter2.acquire().await;
                 drop(permit);
             });
 
@@ -1484,13 +2853,33 @@ mod tests {
     fn test_openrouter_config_defaults() {
         let config = OpenRouterConfig {
             api_key: "test".to_string(),
+            api_key_file: None,
             model: "test-model".to_string(),
             vision_model: "test-vision-model".to_string(),
             vision_fallback_model: "test-vision-fallback-model".to_string(),
             text_model: "test-text-model".to_string(),
             text_fallback_model: "test-text-fallback-model".to_string(),
             base_url: None,
+            provider: None,
             max_tokens: None,
+            daily_spend_cap: None,
+            max_concurrent_requests: None,
+            min_request_interval_ms: None,
+            description_cache_size: None,
+            description_cache_ttl_secs: None,
+            max_description_length: None,
+            min_description_length: None,
+            refusal_patterns: None,
+            circuit_breaker_threshold: None,
+            circuit_breaker_cooldown_secs: None,
+            retry_base_delay_ms: None,
+            retry_backoff_multiplier: None,
+            retry_max_delay_ms: None,
+            image_url_passthrough: None,
+            use_toot_text_as_context: None,
+            chart_hashtags: None,
+            chart_prompt: None,
+            batch_image_descriptions: None,
         };
 
         let client = OpenRouterClient::new(config);
@@ -1701,6 +3090,12 @@ mod tests {
             deserialized.error_type,
             Some("validation_error".to_string())
         );
+
+        // OpenRouter uses numeric codes
+        let numeric: ErrorDetail =
+            serde_json::from_str(r#"{"message":"Not found","code":404}"#).unwrap();
+        assert_eq!(numeric.code, Some("404".to_string()));
+        assert_eq!(numeric.error_type, None);
     }
 
     #[test]
@@ -1845,13 +3240,33 @@ mod tests {
         // Test that the vision model is correctly used instead of the general model
         let config = OpenRouterConfig {
             api_key: "test".to_string(),
+            api_key_file: None,
             model: "general-model".to_string(),
             vision_model: "vision-model".to_string(),
             vision_fallback_model: "fallback-vision-model".to_string(),
             text_model: "text-model".to_string(),
             text_fallback_model: "fallback-text-model".to_string(),
             base_url: None,
+            provider: None,
             max_tokens: None,
+            daily_spend_cap: None,
+            max_concurrent_requests: None,
+            min_request_interval_ms: None,
+            description_cache_size: None,
+            description_cache_ttl_secs: None,
+            max_description_length: None,
+            min_description_length: None,
+            refusal_patterns: None,
+            circuit_breaker_threshold: None,
+            circuit_breaker_cooldown_secs: None,
+            retry_base_delay_ms: None,
+            retry_backoff_multiplier: None,
+            retry_max_delay_ms: None,
+            image_url_passthrough: None,
+            use_toot_text_as_context: None,
+            chart_hashtags: None,
+            chart_prompt: None,
+            batch_image_descriptions: None,
         };
 
         let client = OpenRouterClient::new(config);
@@ -1869,13 +3284,33 @@ mod tests {
         // Test that the text fallback models are correctly configured
         let config = OpenRouterConfig {
             api_key: "test".to_string(),
+            api_key_file: None,
             model: "general-model".to_string(),
             vision_model: "vision-model".to_string(),
             vision_fallback_model: "fallback-vision-model".to_string(),
             text_model: "text-model".to_string(),
             text_fallback_model: "fallback-text-model".to_string(),
             base_url: None,
+            provider: None,
             max_tokens: None,
+            daily_spend_cap: None,
+            max_concurrent_requests: None,
+            min_request_interval_ms: None,
+            description_cache_size: None,
+            description_cache_ttl_secs: None,
+            max_description_length: None,
+            min_description_length: None,
+            refusal_patterns: None,
+            circuit_breaker_threshold: None,
+            circuit_breaker_cooldown_secs: None,
+            retry_base_delay_ms: None,
+            retry_backoff_multiplier: None,
+            retry_max_delay_ms: None,
+            image_url_passthrough: None,
+            use_toot_text_as_context: None,
+            chart_hashtags: None,
+            chart_prompt: None,
+            batch_image_descriptions: None,
         };
 
         let client = OpenRouterClient::new(config);
@@ -1902,4 +3337,925 @@ mod tests {
         let result = OpenRouterClient::sanitize_description(input);
         assert_eq!(result, "");
     }
+
+    /// Mock OpenRouter server failing `failing_model` with `failure` and describing everything else
+    async fn start_vision_server(
+        failing_model: &'static str,
+        failure: crate::test_support::MockResponse,
+    ) -> crate::test_support::MockHttpServer {
+        crate::test_support::MockHttpServer::start(move |request| {
+            let body: serde_json::Value = serde_json::from_slice(&request.body).unwrap();
+            let model = body["model"].as_str().unwrap_or_default().to_string();
+            if model == failing_model {
+                failure.clone()
+            } else {
+                crate::test_support::MockResponse::json(
+                    200,
+                    &json!({
+                        "choices": [{
+                            "message": {"content": format!("Described by {model}")},
+                            "finish_reason": "stop"
+                        }]
+                    })
+                    .to_string(),
+                )
+            }
+        })
+        .await
+    }
+
+    fn requested_models(server: &crate::test_support::MockHttpServer) -> Vec<String> {
+        server
+            .requests()
+            .iter()
+            .map(|r| {
+                let body: serde_json::Value = serde_json::from_slice(&r.body).unwrap();
+                body["model"].as_str().unwrap_or_default().to_string()
+            })
+            .collect()
+    }
+
+    fn create_vision_test_config(server_url: &str) -> OpenRouterConfig {
+        let mut config = create_test_config();
+        config.vision_model = "primary/vision".to_string();
+        config.vision_fallback_model = "fallback/vision".to_string();
+        config.base_url = Some(server_url.to_string());
+        config
+    }
+
+    #[tokio::test]
+    async fn test_describe_images_in_one_request() {
+        let server = crate::test_support::MockHttpServer::start(|request| {
+            let body: serde_json::Value = serde_json::from_slice(&request.body).unwrap();
+            let images = body["messages"][0]["content"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .filter(|part| part["type"] == "image_url")
+                .count();
+            let content = if images > 1 {
+                // Wrapped in a code fence, as some models like to do
+                "```json\n[\"A red square\", \"A blue circle\", \"A green triangle\"]\n```"
+                    .to_string()
+            } else {
+                "A single shape".to_string()
+            };
+            crate::test_support::MockResponse::json(
+                200,
+                &json!({"choices": [{"message": {"content": content}, "finish_reason": "stop"}]})
+                    .to_string(),
+            )
+        })
+        .await;
+        let client = OpenRouterClient::new(create_vision_test_config(&server.url()));
+        let images: Vec<(&[u8], &str)> = vec![
+            (b"one", "image/png"),
+            (b"two", "image/jpeg"),
+            (b"three", "image/webp"),
+        ];
+
+        let descriptions = client.describe_images(&images, "Describe").await.unwrap();
+        assert_eq!(
+            descriptions,
+            vec!["A red square", "A blue circle", "A green triangle"]
+        );
+        let requests = server.requests();
+        assert_eq!(requests.len(), 1);
+        let body = String::from_utf8_lossy(&requests[0].body);
+        assert!(body.contains("data:image/jpeg;base64,"));
+        assert!(body.contains("JSON array of exactly 3 strings"));
+
+        // An answer that doesn't match the number of images falls back to one call per image
+        assert_eq!(parse_batched_descriptions("[\"only one\"]", 2), None);
+        let descriptions = client
+            .describe_images(&images[..2], "Describe")
+            .await
+            .unwrap();
+        assert_eq!(descriptions, vec!["A single shape", "A single shape"]);
+        assert_eq!(server.requests().len(), 4);
+    }
+
+    /// Mock vision server answering batched requests with `finish_reason` and one
+    /// "A batched shape {n}" per image, and single images with "A single shape"
+    async fn start_batch_server(
+        finish_reason: &'static str,
+    ) -> crate::test_support::MockHttpServer {
+        crate::test_support::MockHttpServer::start(move |request| {
+            let body: serde_json::Value = serde_json::from_slice(&request.body).unwrap();
+            let images = body["messages"][0]["content"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .filter(|part| part["type"] == "image_url")
+                .count();
+            let (content, finish_reason) = if images > 1 {
+                let shapes: Vec<String> = (0..images).map(|n| format!("A batched shape {n}")).collect();
+                (serde_json::to_string(&shapes).unwrap(), finish_reason)
+            } else {
+                ("A single shape".to_string(), "stop")
+            };
+            crate::test_support::MockResponse::json(
+                200,
+                &json!({"choices": [{"message": {"content": content}, "finish_reason": finish_reason}]})
+                    .to_string(),
+            )
+        })
+        .await
+    }
+
+    #[tokio::test]
+    async fn test_truncated_batched_answer_described_one_by_one() {
+        let server = start_batch_server("length").await;
+        let mut config = create_vision_test_config(&server.url());
+        config.max_tokens = Some(150);
+        let client = OpenRouterClient::new(config);
+        let images: Vec<(&[u8], &str)> = vec![(b"one", "image/png"), (b"two", "image/png")];
+
+        let descriptions = client.describe_images(&images, "Describe").await.unwrap();
+        assert_eq!(descriptions, vec!["A single shape", "A single shape"]);
+
+        let requests = server.requests();
+        assert_eq!(requests.len(), 3);
+        let batched: serde_json::Value = serde_json::from_slice(&requests[0].body).unwrap();
+        assert_eq!(batched["max_tokens"], 300);
+        let single: serde_json::Value = serde_json::from_slice(&requests[1].body).unwrap();
+        assert_eq!(single["max_tokens"], 150);
+    }
+
+    #[tokio::test]
+    async fn test_batched_descriptions_use_and_fill_cache() {
+        let server = start_batch_server("stop").await;
+        let mut config = create_vision_test_config(&server.url());
+        config.description_cache_size = Some(10);
+        let client = OpenRouterClient::new(config);
+        let images: Vec<(&[u8], &str)> = vec![
+            (b"one", "image/png"),
+            (b"two", "image/png"),
+            (b"three", "image/png"),
+        ];
+
+        client
+            .describe_image_with_source(b"one", "Describe")
+            .await
+            .unwrap();
+        let descriptions = client.describe_images(&images, "Describe").await.unwrap();
+        assert_eq!(
+            descriptions,
+            vec!["A single shape", "A batched shape 0", "A batched shape 1"]
+        );
+
+        // Only the uncached images were batched
+        let requests = server.requests();
+        assert_eq!(requests.len(), 2);
+        assert_eq!(
+            String::from_utf8_lossy(&requests[1].body)
+                .matches("data:image/png;base64,")
+                .count(),
+            2
+        );
+
+        // The batched descriptions were cached per image
+        let descriptions = client.describe_images(&images, "Describe").await.unwrap();
+        assert_eq!(
+            descriptions,
+            vec!["A single shape", "A batched shape 0", "A batched shape 1"]
+        );
+        assert_eq!(server.requests().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_describe_image_retries_once_after_empty_description() {
+        // Control characters only: non-blank, but nothing left after sanitizing
+        let empty = || {
+            crate::test_support::MockResponse::json(
+                200,
+                &json!({
+                    "choices": [{
+                        "message": {"content": "\u{0}\u{7}\u{1b}"},
+                        "finish_reason": "stop"
+                    }]
+                })
+                .to_string(),
+            )
+        };
+
+        let server = start_vision_server("primary/vision", empty()).await;
+        let client = OpenRouterClient::new(create_vision_test_config(&server.url()));
+        let description = client
+            .describe_image_with_source(b"image", "Describe")
+            .await
+            .unwrap();
+        assert_eq!(description.text, "Described by fallback/vision");
+        assert_eq!(
+            requested_models(&server),
+            vec!["primary/vision", "fallback/vision"]
+        );
+
+        // Without a fallback the same model is retried, then the error surfaces
+        let server = start_vision_server("primary/vision", empty()).await;
+        let mut config = create_vision_test_config(&server.url());
+        config.vision_fallback_model = String::new();
+        let client = OpenRouterClient::new(config);
+        let result = client.describe_image(b"image", "Describe").await;
+        assert!(matches!(
+            result,
+            Err(OpenRouterError::EmptyDescription { .. })
+        ));
+        assert_eq!(
+            requested_models(&server),
+            vec!["primary/vision", "primary/vision"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_describe_image_retries_refusal_with_fallback_model() {
+        let refusal = crate::test_support::MockResponse::json(
+            200,
+            &json!({
+                "choices": [{
+                    "message": {"content": "I\u{2019}m sorry, but I cannot describe this image."},
+                    "finish_reason": "stop"
+                }]
+            })
+            .to_string(),
+        );
+
+        let server = start_vision_server("primary/vision", refusal.clone()).await;
+        let client = OpenRouterClient::new(create_vision_test_config(&server.url()));
+        let description = client
+            .describe_image_with_source(b"image", "Describe")
+            .await
+            .unwrap();
+        assert_eq!(description.text, "Described by fallback/vision");
+        assert_eq!(
+            requested_models(&server),
+            vec!["primary/vision", "fallback/vision"]
+        );
+
+        // Gives up when the fallback refuses as well
+        let server = start_vision_server("fallback/vision", refusal).await;
+        let mut config = create_vision_test_config(&server.url());
+        config.vision_model = "fallback/vision".to_string();
+        let client = OpenRouterClient::new(config);
+        let result = client.describe_image(b"image", "Describe").await;
+        assert!(matches!(
+            result,
+            Err(OpenRouterError::LowQualityDescription { .. })
+        ));
+        assert_eq!(
+            requested_models(&server),
+            vec!["fallback/vision", "fallback/vision"]
+        );
+    }
+
+    #[test]
+    fn test_low_quality_reason() {
+        let client = OpenRouterClient::new(create_test_config());
+        assert!(client.low_quality_reason("An image.").is_some());
+        assert!(client
+            .low_quality_reason("I can't describe people in images.")
+            .is_some());
+        assert!(client
+            .low_quality_reason("A tabby cat asleep on a sunny windowsill.")
+            .is_none());
+
+        let mut config = create_test_config();
+        config.min_description_length = Some(0);
+        config.refusal_patterns = Some(vec!["Blurry".to_string()]);
+        let client = OpenRouterClient::new(config);
+        assert!(client.low_quality_reason("An image.").is_none());
+        assert!(client
+            .low_quality_reason("I can't describe people in images.")
+            .is_none());
+        assert!(client.low_quality_reason("A blurry photo.").is_some());
+    }
+
+    #[tokio::test]
+    async fn test_describe_image_falls_back_on_model_not_available() {
+        let server = start_vision_server(
+            "primary/vision",
+            crate::test_support::MockResponse::json(
+                404,
+                r#"{"error":{"message":"No endpoints found for primary/vision.","code":404}}"#,
+            ),
+        )
+        .await;
+        let client = OpenRouterClient::new(create_vision_test_config(&server.url()));
+
+        let description = client
+            .describe_image_with_source(b"image", "Describe")
+            .await
+            .unwrap();
+
+        assert_eq!(description.text, "Described by fallback/vision");
+        assert_eq!(description.model, "fallback/vision");
+        // No retries against the unavailable model, exactly one fallback attempt
+        assert_eq!(
+            requested_models(&server),
+            vec!["primary/vision", "fallback/vision"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_describe_image_falls_back_on_provider_failure() {
+        let server = start_vision_server(
+            "primary/vision",
+            crate::test_support::MockResponse::json(
+                502,
+                r#"{"error":{"message":"Provider returned error (Provider: Mistral)","code":502}}"#,
+            ),
+        )
+        .await;
+        let client = OpenRouterClient::new(create_vision_test_config(&server.url()));
+
+        let description = client.describe_image(b"image", "Describe").await.unwrap();
+
+        assert_eq!(description, "Described by fallback/vision");
+        assert_eq!(
+            requested_models(&server).last().map(String::as_str),
+            Some("fallback/vision")
+        );
+    }
+
+    #[test]
+    fn test_retry_delay_follows_configured_backoff() {
+        let delays = |client: &OpenRouterClient| -> Vec<u64> {
+            (0..6)
+                .map(|attempt| client.retry_delay(attempt).as_millis() as u64)
+                .collect()
+        };
+
+        let client = OpenRouterClient::new(create_test_config());
+        assert_eq!(delays(&client), vec![1000, 2000, 4000, 8000, 16000, 30000]);
+
+        let mut config = create_test_config();
+        config.retry_base_delay_ms = Some(500);
+        config.retry_backoff_multiplier = Some(3.0);
+        config.retry_max_delay_ms = Some(10_000);
+        let client = OpenRouterClient::new(config);
+        assert_eq!(delays(&client), vec![500, 1500, 4500, 10000, 10000, 10000]);
+        // Large attempt numbers stay at the cap instead of overflowing
+        assert_eq!(client.retry_delay(u32::MAX), Duration::from_millis(10_000));
+    }
+
+    #[tokio::test]
+    async fn test_provider_failure_retried_but_unavailable_model_not() {
+        let provider_failure = crate::test_support::MockResponse::json(
+            502,
+            r#"{"error":{"message":"Provider returned error (Provider: Mistral)","code":502}}"#,
+        );
+        let server = start_vision_server("primary/vision", provider_failure).await;
+        let client = OpenRouterClient::new(create_vision_test_config(&server.url()));
+        client.describe_image(b"image", "Describe").await.unwrap();
+        // One retry against the failing provider, then the fallback model
+        assert_eq!(
+            requested_models(&server),
+            vec!["primary/vision", "primary/vision", "fallback/vision"]
+        );
+
+        let unavailable = crate::test_support::MockResponse::json(
+            404,
+            r#"{"error":{"message":"No endpoints found for primary/vision.","code":404}}"#,
+        );
+        let server = start_vision_server("primary/vision", unavailable).await;
+        let mut config = create_vision_test_config(&server.url());
+        config.vision_fallback_model = String::new();
+        let client = OpenRouterClient::new(config);
+        let error = client
+            .describe_image(b"image", "Describe")
+            .await
+            .unwrap_err();
+        assert!(matches!(error, OpenRouterError::ModelNotAvailable { .. }));
+        assert!(error.to_string().contains("--list-models"));
+        assert_eq!(requested_models(&server), vec!["primary/vision"]);
+    }
+
+    #[tokio::test]
+    async fn test_repeated_provider_failures_open_circuit_breaker() {
+        let server = start_vision_server(
+            "primary/vision",
+            crate::test_support::MockResponse::json(
+                502,
+                r#"{"error":{"message":"Provider returned error (Provider: Mistral)","code":502}}"#,
+            ),
+        )
+        .await;
+        let mut config = create_vision_test_config(&server.url());
+        config.vision_fallback_model = String::new();
+        config.circuit_breaker_threshold = Some(1);
+        let client = OpenRouterClient::new(config);
+
+        assert!(matches!(
+            client.describe_image(b"image", "Describe").await,
+            Err(OpenRouterError::ProviderFailure { .. })
+        ));
+        let requests = server.requests().len();
+
+        // Fails fast without another request, including from clones
+        let started = Instant::now();
+        for client in [client.clone(), client] {
+            assert!(matches!(
+                client.describe_image(b"other image", "Describe").await,
+                Err(OpenRouterError::CircuitOpen { failures: 1, .. })
+            ));
+        }
+        assert!(started.elapsed() < Duration::from_millis(500));
+        assert_eq!(server.requests().len(), requests);
+    }
+
+    #[test]
+    fn test_circuit_breaker_half_opens_after_cooldown() {
+        let mut breaker = CircuitBreaker::new(2, Duration::from_millis(50));
+
+        breaker.record_failure();
+        assert!(breaker.check().is_ok());
+        breaker.record_failure();
+        assert!(matches!(
+            breaker.check(),
+            Err(OpenRouterError::CircuitOpen { failures: 2, .. })
+        ));
+
+        // A single probe after the cooldown; a failed probe reopens immediately
+        std::thread::sleep(Duration::from_millis(60));
+        assert!(breaker.check().is_ok());
+        assert!(breaker.check().is_err());
+        breaker.record_failure();
+        assert!(breaker.check().is_err());
+
+        // A successful probe closes the breaker
+        std::thread::sleep(Duration::from_millis(60));
+        assert!(breaker.check().is_ok());
+        breaker.record_success();
+        assert!(breaker.check().is_ok());
+        breaker.record_failure();
+        assert!(breaker.check().is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_dropped_probe_does_not_keep_circuit_open() {
+        use std::sync::atomic::{AtomicU8, Ordering};
+
+        // 0: provider failing, 1: provider hanging, 2: provider answering
+        let phase = Arc::new(AtomicU8::new(0));
+        let server_phase = Arc::clone(&phase);
+        let server = crate::test_support::MockHttpServer::start(move |_| {
+            let answer = crate::test_support::MockResponse::json(
+                200,
+                r#"{"choices":[{"message":{"content":"A red square"},"finish_reason":"stop"}]}"#,
+            );
+            match server_phase.load(Ordering::SeqCst) {
+                0 => crate::test_support::MockResponse::json(
+                    502,
+                    r#"{"error":{"message":"Provider returned error","code":502}}"#,
+                ),
+                1 => answer.with_delay(Duration::from_secs(10)),
+                _ => answer,
+            }
+        })
+        .await;
+        let mut config = create_vision_test_config(&server.url());
+        config.vision_fallback_model = String::new();
+        config.circuit_breaker_threshold = Some(1);
+        config.circuit_breaker_cooldown_secs = Some(0);
+        config.retry_base_delay_ms = Some(1);
+        let client = OpenRouterClient::new(config);
+
+        assert!(matches!(
+            client.describe_image(b"image", "Describe").await,
+            Err(OpenRouterError::ProviderFailure { .. })
+        ));
+
+        // The probe is cancelled, like a toot hitting the processing timeout
+        phase.store(1, Ordering::SeqCst);
+        let probe = client.describe_image(b"probe", "Describe");
+        assert!(tokio::time::timeout(Duration::from_millis(200), probe)
+            .await
+            .is_err());
+
+        // The next request probes again instead of failing fast forever
+        phase.store(2, Ordering::SeqCst);
+        let description = client.describe_image(b"next", "Describe").await.unwrap();
+        assert!(description.starts_with("A red square"));
+    }
+
+    #[test]
+    fn test_circuit_breaker_abandoned_probe_allows_another() {
+        let mut breaker = CircuitBreaker::new(1, Duration::ZERO);
+        breaker.record_failure();
+
+        let probe = breaker.check().unwrap().expect("first request probes");
+        assert!(breaker.check().is_err());
+        breaker.abandon_probe(probe);
+        let next = breaker.check().unwrap().expect("another probe is allowed");
+
+        // A stale probe can't end the current one
+        breaker.abandon_probe(probe);
+        assert!(breaker.check().is_err());
+        breaker.abandon_probe(next);
+        assert!(breaker.check().is_ok());
+    }
+
+    #[test]
+    fn test_circuit_breaker_disabled_with_zero_threshold() {
+        let mut breaker = CircuitBreaker::new(0, Duration::from_secs(60));
+        for _ in 0..10 {
+            breaker.record_failure();
+        }
+        assert!(breaker.check().is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_describe_image_data_url_matches_image_format() {
+        let server = start_vision_server(
+            "fallback/vision",
+            crate::test_support::MockResponse::json(500, "{}"),
+        )
+        .await;
+        let client = OpenRouterClient::new(create_vision_test_config(&server.url()));
+
+        for (format, mime_type) in [
+            (image::ImageFormat::Png, "image/png"),
+            (image::ImageFormat::WebP, "image/webp"),
+            (image::ImageFormat::Jpeg, "image/jpeg"),
+        ] {
+            let mut data = Vec::new();
+            image::DynamicImage::new_rgb8(4, 4)
+                .write_to(&mut std::io::Cursor::new(&mut data), format)
+                .unwrap();
+            client.describe_image(&data, "Describe").await.unwrap();
+
+            let body: serde_json::Value =
+                serde_json::from_slice(&server.requests().last().unwrap().body).unwrap();
+            let url = body["messages"][0]["content"][1]["image_url"]["url"]
+                .as_str()
+                .unwrap();
+            assert!(
+                url.starts_with(&format!("data:{mime_type};base64,")),
+                "{url:.40}"
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_describe_image_uses_primary_when_available() {
+        let server = start_vision_server(
+            "fallback/vision",
+            crate::test_support::MockResponse::json(500, "{}"),
+        )
+        .await;
+        let client = OpenRouterClient::new(create_vision_test_config(&server.url()));
+
+        let description = client.describe_image(b"image", "Describe").await.unwrap();
+
+        assert_eq!(description, "Described by primary/vision");
+        assert_eq!(requested_models(&server), vec!["primary/vision"]);
+    }
+
+    #[tokio::test]
+    async fn test_describe_image_honors_custom_max_description_length() {
+        let server = crate::test_support::MockHttpServer::start(|_| {
+            crate::test_support::MockResponse::json(
+                200,
+                &json!({
+                    "choices": [{
+                        "message": {"content": "A small brown dog chasing a bright red ball across a sunny park"},
+                        "finish_reason": "stop"
+                    }]
+                })
+                .to_string(),
+            )
+        })
+        .await;
+        let mut config = create_vision_test_config(&server.url());
+        config.max_description_length = Some(40);
+        let client = OpenRouterClient::new(config);
+
+        let description = client
+            .describe_image(b"image", "Describe in under {max_length} characters")
+            .await
+            .unwrap();
+
+        // Cut at the last word boundary, ellipsis included in the limit
+        assert_eq!(description, "A small brown dog chasing a bright red…");
+        assert!(description.chars().count() <= 40);
+
+        let body = String::from_utf8_lossy(&server.requests()[0].body).to_string();
+        assert!(body.contains("Describe in under 40 characters"));
+    }
+
+    #[tokio::test]
+    async fn test_describe_image_does_not_fall_back_on_other_errors() {
+        let server = start_vision_server(
+            "primary/vision",
+            crate::test_support::MockResponse::json(
+                401,
+                r#"{"error":{"message":"Invalid API key","code":401}}"#,
+            ),
+        )
+        .await;
+        let client = OpenRouterClient::new(create_vision_test_config(&server.url()));
+
+        let result = client.describe_image(b"image", "Describe").await;
+
+        assert!(matches!(result, Err(OpenRouterError::AuthenticationFailed)));
+        assert_eq!(requested_models(&server), vec!["primary/vision"]);
+    }
+
+    #[tokio::test]
+    async fn test_process_text_falls_back_on_provider_failure() {
+        let server = start_vision_server(
+            "primary/text",
+            crate::test_support::MockResponse::json(
+                502,
+                r#"{"error":{"message":"Provider returned error (Provider: Mistral)","code":502}}"#,
+            ),
+        )
+        .await;
+        let mut config = create_vision_test_config(&server.url());
+        config.text_model = "primary/text".to_string();
+        config.text_fallback_model = "fallback/text".to_string();
+        let client = OpenRouterClient::new(config);
+
+        let summary = client.process_text("Summarize").await.unwrap();
+
+        assert_eq!(summary, "Described by fallback/text");
+        let models = requested_models(&server);
+        assert_eq!(models.first().map(String::as_str), Some("primary/text"));
+        assert_eq!(models.last().map(String::as_str), Some("fallback/text"));
+    }
+
+    #[tokio::test]
+    async fn test_spend_cap_short_circuits_describe_calls() {
+        let server = crate::test_support::MockHttpServer::start(|_| {
+            crate::test_support::MockResponse::json(
+                200,
+                &json!({
+                    "choices": [{
+                        "message": {"content": "A described image"},
+                        "finish_reason": "stop"
+                    }],
+                    "usage": {"prompt_tokens": 800, "completion_tokens": 200, "total_tokens": 1000}
+                })
+                .to_string(),
+            )
+        })
+        .await;
+        let mut config = create_vision_test_config(&server.url());
+        config.daily_spend_cap = Some(0.50);
+        let client = OpenRouterClient::new(config);
+        client.model_pricing.lock().unwrap().insert(
+            "primary/vision".to_string(),
+            ModelPricing {
+                prompt: "0.0005".to_string(),
+                completion: "0.001".to_string(),
+            },
+        );
+
+        // First call succeeds and costs 800 * 0.0005 + 200 * 0.001 = $0.60
+        client.describe_image(b"image", "Describe").await.unwrap();
+        assert!((client.spend_tracker.lock().unwrap().spent - 0.60).abs() < 1e-9);
+
+        // Clones share the tracker, and further calls never reach the API
+        let result = client.clone().describe_image(b"image", "Describe").await;
+        assert!(matches!(
+            result,
+            Err(OpenRouterError::SpendCapReached { cap, .. }) if cap == 0.50
+        ));
+        let result = client.process_text("Summarize").await;
+        assert!(matches!(
+            result,
+            Err(OpenRouterError::SpendCapReached { .. })
+        ));
+        assert_eq!(server.requests().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_stats_aggregate_token_usage_across_describe_calls() {
+        let server = crate::test_support::MockHttpServer::start(|_| {
+            crate::test_support::MockResponse::json(
+                200,
+                &json!({
+                    "choices": [{
+                        "message": {"content": "A described image"},
+                        "finish_reason": "stop"
+                    }],
+                    "usage": {"prompt_tokens": 800, "completion_tokens": 200, "total_tokens": 1000}
+                })
+                .to_string(),
+            )
+        })
+        .await;
+        let client = OpenRouterClient::new(create_vision_test_config(&server.url()));
+        client.model_pricing.lock().unwrap().insert(
+            "primary/vision".to_string(),
+            ModelPricing {
+                prompt: "0.000001".to_string(),
+                completion: "0.000002".to_string(),
+            },
+        );
+
+        // Usage is counted without a spend cap, and clones share the stats
+        client.describe_image(b"image", "Describe").await.unwrap();
+        client
+            .clone()
+            .describe_image(b"other image", "Describe")
+            .await
+            .unwrap();
+        client.record_described_toot(2);
+
+        let stats = client.processing_stats();
+        assert_eq!(stats.processed_toots_count, 1);
+        assert_eq!(stats.media_described_count, 2);
+        assert_eq!(stats.requests_count, 2);
+        assert_eq!(stats.prompt_tokens, 1600);
+        assert_eq!(stats.completion_tokens, 400);
+        assert_eq!(stats.total_tokens, 2000);
+        // 2 * (800 * 0.000001 + 200 * 0.000002) = $0.0024
+        assert!((stats.estimated_cost - 0.0024).abs() < 1e-9);
+        assert!(stats.summary().contains("2000 tokens"));
+    }
+
+    #[tokio::test]
+    async fn test_description_cache_avoids_repeat_requests() {
+        let server = start_vision_server(
+            "unused/model",
+            crate::test_support::MockResponse::json(500, "{}"),
+        )
+        .await;
+        let mut config = create_vision_test_config(&server.url());
+        config.description_cache_size = Some(10);
+        let client = OpenRouterClient::new(config);
+
+        let first = client.describe_image(b"image", "Describe").await.unwrap();
+        let second = client
+            .clone()
+            .describe_image(b"image", "Describe")
+            .await
+            .unwrap();
+        assert_eq!(first, second);
+        assert_eq!(server.requests().len(), 1);
+
+        // Different bytes or a different prompt are separate entries
+        client.describe_image(b"other", "Describe").await.unwrap();
+        client.describe_image(b"image", "Beschreibe").await.unwrap();
+        assert_eq!(server.requests().len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_description_cache_disabled_by_default() {
+        let server = start_vision_server(
+            "unused/model",
+            crate::test_support::MockResponse::json(500, "{}"),
+        )
+        .await;
+        let client = OpenRouterClient::new(create_vision_test_config(&server.url()));
+        assert!(client.description_cache.is_none());
+
+        client.describe_image(b"image", "Describe").await.unwrap();
+        client.describe_image(b"image", "Describe").await.unwrap();
+        assert_eq!(server.requests().len(), 2);
+    }
+
+    #[test]
+    fn test_description_cache_expires_entries() {
+        let mut cache =
+            DescriptionCache::new(NonZeroUsize::new(2).unwrap(), Duration::from_millis(0));
+        let key = DescriptionCache::key(b"image", "Describe");
+        let description = |text: &str| ImageDescription {
+            text: text.to_string(),
+            model: "primary/vision".to_string(),
+            usage: None,
+        };
+        cache.insert(key, description("A red square"));
+        assert_eq!(cache.get(key), None);
+
+        let mut cache =
+            DescriptionCache::new(NonZeroUsize::new(1).unwrap(), Duration::from_secs(60));
+        cache.insert(1, description("first"));
+        cache.insert(2, description("second"));
+        assert_eq!(cache.get(1), None);
+        assert_eq!(cache.get(2), Some(description("second")));
+    }
+
+    #[test]
+    fn test_spend_tracker_resets_after_window() {
+        let mut tracker = SpendTracker::new(Some(1.0));
+        tracker.record(1.5);
+        assert!(tracker.check().is_err());
+
+        tracker.window_start = Instant::now()
+            .checked_sub(SPEND_WINDOW)
+            .unwrap_or_else(Instant::now);
+        assert!(tracker.check().is_ok());
+        assert_eq!(tracker.spent, 0.0);
+    }
+
+    #[test]
+    fn test_spend_tracker_without_cap_never_blocks() {
+        let mut tracker = SpendTracker::new(None);
+        tracker.record(1000.0);
+        assert!(tracker.check().is_ok());
+    }
+
+    #[test]
+    fn test_estimate_cost() {
+        let pricing = ModelPricing {
+            prompt: "0.000001".to_string(),
+            completion: "0.000002".to_string(),
+        };
+
+        let usage = Usage {
+            prompt_tokens: Some(1000),
+            completion_tokens: Some(500),
+            total_tokens: Some(1500),
+        };
+        assert!((estimate_cost(&usage, &pricing) - 0.002).abs() < 1e-12);
+
+        // Without a breakdown the higher rate is applied to every token
+        let usage = Usage {
+            prompt_tokens: None,
+            completion_tokens: None,
+            total_tokens: Some(1500),
+        };
+        assert!((estimate_cost(&usage, &pricing) - 0.003).abs() < 1e-12);
+    }
+
+    async fn start_openai_compatible_server() -> crate::test_support::MockHttpServer {
+        crate::test_support::MockHttpServer::start(|request| {
+            if request.path == "/v1/models" {
+                // vLLM-style listing: no names or pricing
+                return crate::test_support::MockResponse::json(
+                    200,
+                    r#"{"object":"list","data":[{"id":"local/vision","object":"model","owned_by":"vllm"}]}"#,
+                );
+            }
+            crate::test_support::MockResponse::json(
+                200,
+                &json!({
+                    "choices": [{
+                        "message": {"content": "A local description"},
+                        "finish_reason": "stop"
+                    }]
+                })
+                .to_string(),
+            )
+        })
+        .await
+    }
+
+    #[tokio::test]
+    async fn test_openai_provider_skips_openrouter_specifics() {
+        let server = start_openai_compatible_server().await;
+        let mut config = create_vision_test_config(&format!("{}/v1", server.url()));
+        config.provider = Some("openai".to_string());
+        config.model = "local/vision".to_string();
+        config.vision_model = "local/vision".to_string();
+        let client = OpenRouterClient::new(config);
+        assert!(!client.is_openrouter_provider());
+
+        // No balance endpoint: fails without a request
+        assert!(client.get_account_balance().await.is_err());
+        assert!(server.requests().is_empty());
+
+        let models = client.list_models().await.unwrap();
+        assert_eq!(models.len(), 1);
+        assert_eq!(models[0].id, "local/vision");
+        assert!(models[0].name.is_empty());
+
+        let description = client
+            .describe_image_with_source(b"image", "Describe")
+            .await
+            .unwrap();
+        assert_eq!(description.text, "A local description");
+
+        let requests = server.requests();
+        assert_eq!(requests.len(), 2);
+        for request in &requests {
+            assert_eq!(request.header("Authorization"), Some("Bearer test_key"));
+            assert_eq!(request.header("HTTP-Referer"), None);
+            assert_eq!(request.header("X-Title"), None);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_openrouter_provider_sends_attribution_headers() {
+        let server = start_openai_compatible_server().await;
+        let client = OpenRouterClient::new(create_vision_test_config(&server.url()));
+        assert!(client.is_openrouter_provider());
+
+        client
+            .describe_image_with_source(b"image", "Describe")
+            .await
+            .unwrap();
+
+        let requests = server.requests();
+        assert_eq!(
+            requests[0].header("HTTP-Referer"),
+            Some("https://github.com/rmoriz/alternator")
+        );
+        assert_eq!(
+            requests[0].header("X-Title"),
+            Some("Alternator - Mastodon Media Describer")
+        );
+    }
 }
@@ -0,0 +1,136 @@
+//! Redaction of URLs in log output (`logging.redact_urls`)
+//!
+//! Media URLs can carry signed tokens and the streaming URL can carry the access token, so shared
+//! log environments can replace them with the host and a short hash of the full URL. The hash
+//! keeps log lines about the same URL correlatable without revealing it.
+
+use std::borrow::Cow;
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Whether URLs are redacted in log output, set once at startup from the logging config
+static REDACT_URLS: AtomicBool = AtomicBool::new(false);
+
+/// Enable or disable URL redaction in log output
+pub fn set_redact_urls(enabled: bool) {
+    REDACT_URLS.store(enabled, Ordering::Relaxed);
+}
+
+/// Whether URL redaction is enabled
+pub fn redact_urls_enabled() -> bool {
+    REDACT_URLS.load(Ordering::Relaxed)
+}
+
+/// A URL as it should appear in logs: verbatim, or redacted when `logging.redact_urls` is on
+pub fn log_url(url: &str) -> Cow<'_, str> {
+    if redact_urls_enabled() {
+        Cow::Owned(redact_url(url))
+    } else {
+        Cow::Borrowed(url)
+    }
+}
+
+/// Free text (e.g. a raw streaming payload) with every http(s)/ws(s) URL redacted when enabled
+pub fn log_text(text: &str) -> Cow<'_, str> {
+    if redact_urls_enabled() {
+        Cow::Owned(redact_text(text))
+    } else {
+        Cow::Borrowed(text)
+    }
+}
+
+/// Replace everything after the host with a hash of the full URL
+///
+/// `https://files.example/media/a.png?sig=secret` becomes `https://files.example/…#1a2b3c4d`.
+pub fn redact_url(url: &str) -> String {
+    let hash = short_hash(url);
+    match url::Url::parse(url) {
+        Ok(parsed) => match parsed.host_str() {
+            Some(host) => format!("{}://{}/…#{hash}", parsed.scheme(), host),
+            None => format!("[redacted url #{hash}]"),
+        },
+        Err(_) => format!("[redacted url #{hash}]"),
+    }
+}
+
+/// Redact every URL found in `text`, leaving the rest untouched
+fn redact_text(text: &str) -> String {
+    const SCHEMES: [&str; 4] = ["https://", "http://", "wss://", "ws://"];
+
+    let mut result = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = SCHEMES.iter().filter_map(|scheme| rest.find(scheme)).min() {
+        result.push_str(&rest[..start]);
+        let candidate = &rest[start..];
+        let end = candidate
+            .find(|c: char| c.is_whitespace() || matches!(c, '"' | '\'' | '\\' | '<' | '>'))
+            .unwrap_or(candidate.len());
+        result.push_str(&redact_url(&candidate[..end]));
+        rest = &candidate[end..];
+    }
+    result.push_str(rest);
+    result
+}
+
+/// Enables URL redaction until dropped, for tests
+///
+/// The setting is global, so tests that depend on it hold a shared lock while it is enabled.
+#[cfg(test)]
+pub(crate) struct RedactUrlsGuard(#[allow(dead_code)] std::sync::MutexGuard<'static, ()>);
+
+#[cfg(test)]
+impl RedactUrlsGuard {
+    pub(crate) fn enable() -> Self {
+        static LOCK: std::sync::Mutex<()> = std::sync::Mutex::new(());
+        let guard = LOCK
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        set_redact_urls(true);
+        Self(guard)
+    }
+}
+
+#[cfg(test)]
+impl Drop for RedactUrlsGuard {
+    fn drop(&mut self) {
+        set_redact_urls(false);
+    }
+}
+
+fn short_hash(value: &str) -> String {
+    let mut hasher = DefaultHasher::new();
+    value.hash(&mut hasher);
+    format!("{:08x}", hasher.finish() as u32)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_redacted_url_hides_path_and_query() {
+        let url = "https://files.example/media/a.png?X-Amz-Signature=secret";
+        let redacted = redact_url(url);
+
+        assert_ne!(redacted, url);
+        assert!(redacted.starts_with("https://files.example/…#"));
+        assert!(!redacted.contains("secret"));
+        assert!(!redacted.contains("a.png"));
+        // The same URL always redacts the same way so log lines stay correlatable
+        assert_eq!(redacted, redact_url(url));
+        assert_ne!(redacted, redact_url("https://files.example/media/b.png"));
+
+        let redact = RedactUrlsGuard::enable();
+        assert_eq!(log_url(url), redacted);
+        drop(redact);
+        assert_eq!(log_url(url), url);
+
+        let text = redact_text(
+            r#"{"url":"https://files.example/a.png?sig=secret","stream":"wss://social.example/api/v1/streaming?access_token=token"}"#,
+        );
+        assert!(!text.contains("secret"));
+        assert!(!text.contains("access_token"));
+        assert!(text.starts_with(r#"{"url":"https://files.example/…#"#));
+        assert!(text.contains(r#""stream":"wss://social.example/…#"#));
+    }
+}
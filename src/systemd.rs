@@ -0,0 +1,162 @@
+//! systemd service notifications (sd_notify) and watchdog keep-alives
+
+use crate::health::HealthState;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::task::JoinHandle;
+use tracing::{debug, info, warn};
+
+/// Destination for sd_notify state messages such as `READY=1`
+pub trait SystemdNotifier: Send + Sync {
+    fn notify(&self, state: &str) -> std::io::Result<()>;
+}
+
+/// Sends notifications to the datagram socket systemd passes in `NOTIFY_SOCKET`
+#[derive(Debug, Clone)]
+pub struct NotifySocket {
+    path: String,
+}
+
+impl NotifySocket {
+    pub fn new(path: impl Into<String>) -> Self {
+        Self { path: path.into() }
+    }
+
+    /// Socket from `NOTIFY_SOCKET`, if running under a `Type=notify` unit
+    pub fn from_env() -> Option<Self> {
+        std::env::var("NOTIFY_SOCKET")
+            .ok()
+            .filter(|path| !path.is_empty())
+            .map(Self::new)
+    }
+}
+
+impl SystemdNotifier for NotifySocket {
+    #[cfg(unix)]
+    fn notify(&self, state: &str) -> std::io::Result<()> {
+        use std::os::unix::net::UnixDatagram;
+
+        let socket = UnixDatagram::unbound()?;
+        // A leading '@' refers to the Linux abstract socket namespace
+        #[cfg(target_os = "linux")]
+        if let Some(name) = self.path.strip_prefix('@') {
+            use std::os::linux::net::SocketAddrExt;
+            let addr = std::os::unix::net::SocketAddr::from_abstract_name(name)?;
+            socket.send_to_addr(state.as_bytes(), &addr)?;
+            return Ok(());
+        }
+        socket.send_to(state.as_bytes(), &self.path)?;
+        Ok(())
+    }
+
+    #[cfg(not(unix))]
+    fn notify(&self, _state: &str) -> std::io::Result<()> {
+        Err(std::io::Error::new(
+            std::io::ErrorKind::Unsupported,
+            "sd_notify requires Unix domain sockets",
+        ))
+    }
+}
+
+/// Keep-alive interval requested by systemd: half of `WATCHDOG_USEC`, if set
+pub fn watchdog_interval_from_env() -> Option<Duration> {
+    let usec: u64 = std::env::var("WATCHDOG_USEC").ok()?.parse().ok()?;
+    (usec > 0).then(|| Duration::from_micros(usec / 2))
+}
+
+/// Tell systemd that startup has finished
+pub fn notify_ready(notifier: &dyn SystemdNotifier) {
+    match notifier.notify("READY=1") {
+        Ok(()) => info!("Notified systemd that Alternator is ready"),
+        Err(e) => warn!("Failed to notify systemd readiness: {}", e),
+    }
+}
+
+/// Tell systemd that Alternator is shutting down
+pub fn notify_stopping(notifier: &dyn SystemdNotifier) {
+    if let Err(e) = notifier.notify("STOPPING=1") {
+        warn!("Failed to notify systemd about shutdown: {}", e);
+    }
+}
+
+/// Ping the watchdog every `interval` while the stream is healthy
+///
+/// Pings stop while the stream is disconnected, so systemd restarts a stuck process.
+pub fn spawn_watchdog(
+    notifier: Arc<dyn SystemdNotifier>,
+    interval: Duration,
+    health: HealthState,
+) -> JoinHandle<()> {
+    info!("Pinging systemd watchdog every {:?}", interval);
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(interval);
+        ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+        loop {
+            ticker.tick().await;
+            if !health.is_ready() {
+                debug!("Stream not healthy, skipping watchdog ping");
+                continue;
+            }
+            if let Err(e) = notifier.notify("WATCHDOG=1") {
+                warn!("Failed to ping systemd watchdog: {}", e);
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    #[derive(Default)]
+    struct RecordingNotifier {
+        states: Mutex<Vec<String>>,
+    }
+
+    impl SystemdNotifier for RecordingNotifier {
+        fn notify(&self, state: &str) -> std::io::Result<()> {
+            self.states.lock().unwrap().push(state.to_string());
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_ready_and_watchdog_notifications() {
+        let notifier = Arc::new(RecordingNotifier::default());
+        notify_ready(notifier.as_ref());
+        assert_eq!(*notifier.states.lock().unwrap(), ["READY=1"]);
+
+        let health = HealthState::new();
+        let watchdog = spawn_watchdog(notifier.clone(), Duration::from_millis(10), health.clone());
+
+        // No pings while the stream is down
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert_eq!(notifier.states.lock().unwrap().len(), 1);
+
+        health.set_credentials_verified(true);
+        health.set_websocket_connected(true);
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        watchdog.abort();
+
+        let states = notifier.states.lock().unwrap();
+        assert!(states.len() > 1);
+        assert!(states[1..].iter().all(|state| state == "WATCHDOG=1"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_notify_socket_sends_datagram() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("notify.sock");
+        let receiver = std::os::unix::net::UnixDatagram::bind(&path).unwrap();
+
+        NotifySocket::new(path.to_string_lossy())
+            .notify("READY=1")
+            .unwrap();
+
+        let mut buffer = [0u8; 64];
+        let len = receiver.recv(&mut buffer).unwrap();
+        assert_eq!(&buffer[..len], b"READY=1");
+    }
+}
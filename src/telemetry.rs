@@ -0,0 +1,549 @@
+//! OpenTelemetry span export over OTLP/HTTP with JSON encoding
+//!
+//! [`OtlpLayer`] runs alongside the log output: it records spans created with
+//! `#[tracing::instrument]` and sends them in batches to a collector's `/v1/traces` endpoint.
+//!
+//! This is a small exporter of its own rather than `tracing-opentelemetry` with
+//! `opentelemetry-otlp`: Alternator only needs closed spans POSTed as JSON, while the SDK crates
+//! bring in protobuf code generation and a set of crates whose versions have to move in lockstep
+//! with each other and with `tracing-opentelemetry`. Trace and span IDs are random, as the OTLP
+//! spec expects.
+//!
+//! Export never holds up processing: spans wait in a bounded queue and are dropped (and counted
+//! in a warning) while it is full, e.g. during a collector outage. A batch the collector
+//! answers with 429, 502, 503 or 504, or that can't be sent at all, is retried twice and then
+//! dropped.
+
+use crate::config::TracingConfig;
+use serde_json::{json, Value};
+use std::cell::Cell;
+use std::fmt::Debug;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, SyncSender, TrySendError};
+use std::sync::{Arc, OnceLock};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use tracing::field::{Field, Visit};
+use tracing::span::{Attributes, Id, Record};
+use tracing::{debug, warn, Subscriber};
+use tracing_subscriber::layer::Context;
+use tracing_subscriber::registry::LookupSpan;
+use tracing_subscriber::Layer;
+
+/// Default `service.name` of exported spans
+pub const DEFAULT_SERVICE_NAME: &str = "alternator";
+
+/// Queued spans are exported at least this often
+const EXPORT_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Export as soon as this many spans are queued
+const MAX_BATCH_SIZE: usize = 512;
+
+/// Spans waiting for the exporter; further spans are dropped while the queue is full
+const MAX_QUEUED_SPANS: usize = 2048;
+
+/// Further attempts for a batch the collector could not accept
+const EXPORT_RETRIES: u32 = 2;
+
+/// Delay before the first retry of a batch, doubled on each further retry
+const EXPORT_RETRY_BASE_DELAY: Duration = Duration::from_secs(1);
+
+/// OTLP span kind "internal"
+const SPAN_KIND_INTERNAL: u8 = 1;
+
+/// Export queue of the first layer created, flushed by [`flush`] before shutdown
+static GLOBAL_EXPORTER: OnceLock<SyncSender<ExportMessage>> = OnceLock::new();
+
+thread_local! {
+    /// Set on the exporter thread so the spans of its own HTTP requests aren't exported
+    static IS_EXPORTER_THREAD: Cell<bool> = const { Cell::new(false) };
+}
+
+enum ExportMessage {
+    Span(SpanRecord),
+    /// Export everything queued, then acknowledge
+    Flush(mpsc::Sender<()>),
+}
+
+/// A closed span ready for export
+#[derive(Debug, Clone)]
+struct SpanRecord {
+    trace_id: u128,
+    span_id: u64,
+    parent_span_id: Option<u64>,
+    name: &'static str,
+    target: &'static str,
+    start: SystemTime,
+    end: SystemTime,
+    attributes: Vec<(String, Value)>,
+}
+
+/// Span state kept in the registry while the span is open
+struct SpanState {
+    trace_id: u128,
+    span_id: u64,
+    parent_span_id: Option<u64>,
+    start: SystemTime,
+    attributes: Vec<(String, Value)>,
+}
+
+/// `tracing_subscriber` layer exporting closed spans to an OTLP/HTTP collector
+pub struct OtlpLayer {
+    sender: SyncSender<ExportMessage>,
+    /// Spans dropped because the queue was full, reported by the exporter
+    dropped: Arc<AtomicU64>,
+}
+
+impl OtlpLayer {
+    /// Start the exporter for the `[tracing]` config, `None` if no endpoint is configured
+    pub fn from_config(config: Option<&TracingConfig>) -> Option<Self> {
+        let config = config?;
+        let endpoint = config.endpoint.as_deref()?;
+        let service_name = config
+            .service_name
+            .as_deref()
+            .unwrap_or(DEFAULT_SERVICE_NAME);
+        Some(Self::new(endpoint, service_name))
+    }
+
+    /// Start an exporter thread sending spans to `{endpoint}/v1/traces`
+    pub fn new(endpoint: &str, service_name: &str) -> Self {
+        Self::with_queue_capacity(endpoint, service_name, MAX_QUEUED_SPANS)
+    }
+
+    fn with_queue_capacity(endpoint: &str, service_name: &str, capacity: usize) -> Self {
+        let (sender, receiver) = mpsc::sync_channel(capacity);
+        let dropped = Arc::new(AtomicU64::new(0));
+        let exporter = Exporter {
+            url: format!("{}/v1/traces", endpoint.trim_end_matches('/')),
+            service_name: service_name.to_string(),
+            dropped: Arc::clone(&dropped),
+            retry_base_delay: EXPORT_RETRY_BASE_DELAY,
+        };
+
+        std::thread::Builder::new()
+            .name("otlp-exporter".to_string())
+            .spawn(move || exporter.run(receiver))
+            .expect("Failed to spawn OTLP exporter thread");
+
+        let _ = GLOBAL_EXPORTER.set(sender.clone());
+        Self { sender, dropped }
+    }
+
+    /// Export all queued spans, waiting up to `timeout`; `false` if that didn't finish in time
+    #[allow(dead_code)] // The binary flushes through the global `flush`
+    pub fn flush(&self, timeout: Duration) -> bool {
+        flush_sender(&self.sender, timeout)
+    }
+}
+
+/// Export the spans queued by the installed layer, e.g. before shutdown
+pub fn flush(timeout: Duration) -> bool {
+    GLOBAL_EXPORTER
+        .get()
+        .map_or(true, |sender| flush_sender(sender, timeout))
+}
+
+/// `false` right away if the queue is full, as the exporter can't catch up within `timeout` then
+fn flush_sender(sender: &SyncSender<ExportMessage>, timeout: Duration) -> bool {
+    let (ack, done) = mpsc::channel();
+    sender.try_send(ExportMessage::Flush(ack)).is_ok() && done.recv_timeout(timeout).is_ok()
+}
+
+fn is_exporter_thread() -> bool {
+    IS_EXPORTER_THREAD.with(Cell::get)
+}
+
+impl<S> Layer<S> for OtlpLayer
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    fn on_new_span(&self, attrs: &Attributes<'_>, id: &Id, ctx: Context<'_, S>) {
+        if is_exporter_thread() {
+            return;
+        }
+        let Some(span) = ctx.span(id) else {
+            return;
+        };
+
+        // Child spans continue their parent's trace
+        let parent = span.parent().and_then(|parent| {
+            parent
+                .extensions()
+                .get::<SpanState>()
+                .map(|state| (state.trace_id, state.span_id))
+        });
+
+        let mut attributes = Vec::new();
+        attrs.record(&mut AttributeVisitor(&mut attributes));
+
+        span.extensions_mut().insert(SpanState {
+            trace_id: parent.map_or_else(|| fastrand::u128(1..), |(trace_id, _)| trace_id),
+            span_id: fastrand::u64(1..),
+            parent_span_id: parent.map(|(_, span_id)| span_id),
+            start: SystemTime::now(),
+            attributes,
+        });
+    }
+
+    fn on_record(&self, id: &Id, values: &Record<'_>, ctx: Context<'_, S>) {
+        let Some(span) = ctx.span(id) else {
+            return;
+        };
+        let mut extensions = span.extensions_mut();
+        if let Some(state) = extensions.get_mut::<SpanState>() {
+            values.record(&mut AttributeVisitor(&mut state.attributes));
+        }
+    }
+
+    fn on_close(&self, id: Id, ctx: Context<'_, S>) {
+        let Some(span) = ctx.span(&id) else {
+            return;
+        };
+        let Some(state) = span.extensions_mut().remove::<SpanState>() else {
+            return;
+        };
+
+        let record = SpanRecord {
+            trace_id: state.trace_id,
+            span_id: state.span_id,
+            parent_span_id: state.parent_span_id,
+            name: span.name(),
+            target: span.metadata().target(),
+            start: state.start,
+            end: SystemTime::now(),
+            attributes: state.attributes,
+        };
+        // The exporter only stops when the layer is dropped
+        if let Err(TrySendError::Full(_)) = self.sender.try_send(ExportMessage::Span(record)) {
+            self.dropped.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+}
+
+/// Collects span fields as OTLP attribute values
+struct AttributeVisitor<'a>(&'a mut Vec<(String, Value)>);
+
+impl AttributeVisitor<'_> {
+    fn push(&mut self, field: &Field, value: Value) {
+        self.0.push((field.name().to_string(), value));
+    }
+}
+
+impl Visit for AttributeVisitor<'_> {
+    fn record_i64(&mut self, field: &Field, value: i64) {
+        self.push(field, json!({ "intValue": value.to_string() }));
+    }
+
+    fn record_u64(&mut self, field: &Field, value: u64) {
+        self.push(field, json!({ "intValue": value.to_string() }));
+    }
+
+    fn record_bool(&mut self, field: &Field, value: bool) {
+        self.push(field, json!({ "boolValue": value }));
+    }
+
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.push(field, json!({ "stringValue": value }));
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn Debug) {
+        self.push(field, json!({ "stringValue": format!("{value:?}") }));
+    }
+}
+
+/// Exporter thread state
+struct Exporter {
+    url: String,
+    service_name: String,
+    dropped: Arc<AtomicU64>,
+    retry_base_delay: Duration,
+}
+
+/// Outcome of sending one batch
+enum ExportOutcome {
+    Exported,
+    /// Rate limited, server error or connection failure; the batch may be sent again
+    Retryable(String),
+    /// Rejected for good, e.g. a malformed request
+    Rejected(String),
+}
+
+impl Exporter {
+    /// Batch spans from `receiver` and POST them until the layer is dropped
+    fn run(self, receiver: Receiver<ExportMessage>) {
+        IS_EXPORTER_THREAD.with(|flag| flag.set(true));
+
+        let runtime = match tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+        {
+            Ok(runtime) => runtime,
+            Err(e) => {
+                warn!(
+                    "Failed to start OTLP exporter, spans will not be exported: {}",
+                    e
+                );
+                return;
+            }
+        };
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(10))
+            .build()
+            .unwrap_or_default();
+
+        let mut batch = Vec::new();
+        let mut reported_dropped = 0;
+        let mut deadline = Instant::now() + EXPORT_INTERVAL;
+        loop {
+            let (ack, disconnected) =
+                match receiver.recv_timeout(deadline.saturating_duration_since(Instant::now())) {
+                    Ok(ExportMessage::Span(record)) => {
+                        batch.push(record);
+                        if batch.len() < MAX_BATCH_SIZE {
+                            continue;
+                        }
+                        (None, false)
+                    }
+                    Ok(ExportMessage::Flush(ack)) => (Some(ack), false),
+                    Err(RecvTimeoutError::Timeout) => (None, false),
+                    Err(RecvTimeoutError::Disconnected) => (None, true),
+                };
+
+            let dropped = self.dropped.load(Ordering::Relaxed);
+            if dropped > reported_dropped {
+                warn!(
+                    "Dropped {} spans because the OTLP export queue was full",
+                    dropped - reported_dropped
+                );
+                reported_dropped = dropped;
+            }
+
+            if !batch.is_empty() {
+                runtime.block_on(self.export(&client, &batch));
+                batch.clear();
+            }
+            if let Some(ack) = ack {
+                let _ = ack.send(());
+            }
+            if disconnected {
+                return;
+            }
+            deadline = Instant::now() + EXPORT_INTERVAL;
+        }
+    }
+
+    /// Send a batch, retrying failures that may go away
+    async fn export(&self, client: &reqwest::Client, batch: &[SpanRecord]) {
+        let request = export_request(&self.service_name, batch);
+        let mut attempt = 0;
+        loop {
+            let error = match self.send(client, &request).await {
+                ExportOutcome::Exported => {
+                    debug!("Exported {} spans to {}", batch.len(), self.url);
+                    return;
+                }
+                ExportOutcome::Retryable(error) if attempt < EXPORT_RETRIES => {
+                    let delay = self.retry_base_delay * 2_u32.pow(attempt);
+                    debug!(
+                        "Retrying export of {} spans to {} in {:?}: {}",
+                        batch.len(),
+                        self.url,
+                        delay,
+                        error
+                    );
+                    tokio::time::sleep(delay).await;
+                    attempt += 1;
+                    continue;
+                }
+                ExportOutcome::Retryable(error) | ExportOutcome::Rejected(error) => error,
+            };
+            warn!(
+                "Failed to export {} spans to {}: {}",
+                batch.len(),
+                self.url,
+                error
+            );
+            return;
+        }
+    }
+
+    async fn send(&self, client: &reqwest::Client, request: &Value) -> ExportOutcome {
+        match client.post(&self.url).json(request).send().await {
+            Ok(response) if response.status().is_success() => ExportOutcome::Exported,
+            Ok(response) => {
+                let status = response.status();
+                let error = format!("collector answered HTTP {status}");
+                // Retryable status codes per the OTLP/HTTP spec
+                if matches!(status.as_u16(), 429 | 502 | 503 | 504) {
+                    ExportOutcome::Retryable(error)
+                } else {
+                    ExportOutcome::Rejected(error)
+                }
+            }
+            Err(e) => ExportOutcome::Retryable(e.to_string()),
+        }
+    }
+}
+
+/// OTLP/HTTP JSON `ExportTraceServiceRequest` for a batch of spans
+fn export_request(service_name: &str, spans: &[SpanRecord]) -> Value {
+    let version = env!("CARGO_PKG_VERSION");
+    json!({
+        "resourceSpans": [{
+            "resource": {
+                "attributes": [
+                    { "key": "service.name", "value": { "stringValue": service_name } },
+                    { "key": "service.version", "value": { "stringValue": version } }
+                ]
+            },
+            "scopeSpans": [{
+                "scope": { "name": "alternator", "version": version },
+                "spans": spans.iter().map(span_json).collect::<Vec<_>>()
+            }]
+        }]
+    })
+}
+
+fn span_json(span: &SpanRecord) -> Value {
+    let mut attributes: Vec<Value> = span
+        .attributes
+        .iter()
+        .map(|(key, value)| json!({ "key": key, "value": value }))
+        .collect();
+    attributes.push(json!({ "key": "code.namespace", "value": { "stringValue": span.target } }));
+
+    json!({
+        "traceId": format!("{:032x}", span.trace_id),
+        "spanId": format!("{:016x}", span.span_id),
+        "parentSpanId": span.parent_span_id.map(|id| format!("{id:016x}")).unwrap_or_default(),
+        "name": span.name,
+        "kind": SPAN_KIND_INTERNAL,
+        "startTimeUnixNano": unix_nanos(span.start).to_string(),
+        "endTimeUnixNano": unix_nanos(span.end).to_string(),
+        "attributes": attributes
+    })
+}
+
+fn unix_nanos(time: SystemTime) -> u128 {
+    time.duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_nanos())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{MockHttpServer, MockResponse};
+    use tracing_subscriber::layer::SubscriberExt;
+
+    #[test]
+    fn test_layer_disabled_without_endpoint() {
+        assert!(OtlpLayer::from_config(None).is_none());
+        assert!(OtlpLayer::from_config(Some(&TracingConfig::default())).is_none());
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_layer_exports_nested_spans() {
+        let server = MockHttpServer::start(|_| MockResponse::json(200, "{}")).await;
+        let layer = OtlpLayer::new(&format!("{}/", server.url()), "alternator-test");
+        let flush = layer.sender.clone();
+        let subscriber = tracing_subscriber::registry().with(layer);
+
+        tracing::subscriber::with_default(subscriber, || {
+            let outer = tracing::info_span!("process_toot", toot_id = "111");
+            let _entered = outer.enter();
+            tracing::info_span!("describe_image", image_bytes = 42_u64, cached = false)
+                .in_scope(|| {});
+        });
+        assert!(flush_sender(&flush, Duration::from_secs(10)));
+
+        let requests = server.requests();
+        assert_eq!(requests.len(), 1);
+        assert_eq!(requests[0].method, "POST");
+        assert_eq!(requests[0].path, "/v1/traces");
+
+        let body: Value = serde_json::from_slice(&requests[0].body).unwrap();
+        let resource = &body["resourceSpans"][0];
+        assert_eq!(
+            resource["resource"]["attributes"][0]["value"]["stringValue"],
+            "alternator-test"
+        );
+
+        // Children close first
+        let spans = resource["scopeSpans"][0]["spans"].as_array().unwrap();
+        assert_eq!(spans.len(), 2);
+        let (inner, outer) = (&spans[0], &spans[1]);
+        assert_eq!(inner["name"], "describe_image");
+        assert_eq!(outer["name"], "process_toot");
+        assert_eq!(inner["traceId"], outer["traceId"]);
+        assert_eq!(inner["parentSpanId"], outer["spanId"]);
+        assert_eq!(outer["parentSpanId"], "");
+        assert_eq!(inner["traceId"].as_str().unwrap().len(), 32);
+        assert_eq!(
+            outer["attributes"][0],
+            json!({ "key": "toot_id", "value": { "stringValue": "111" } })
+        );
+        assert_eq!(
+            inner["attributes"][0],
+            json!({ "key": "image_bytes", "value": { "intValue": "42" } })
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_export_retries_unavailable_collector() {
+        let attempts = std::sync::atomic::AtomicUsize::new(0);
+        let server = MockHttpServer::start(move |_| {
+            if attempts.fetch_add(1, Ordering::SeqCst) == 0 {
+                MockResponse::json(503, "{}")
+            } else {
+                MockResponse::json(200, "{}")
+            }
+        })
+        .await;
+        let exporter = Exporter {
+            url: format!("{}/v1/traces", server.url()),
+            service_name: DEFAULT_SERVICE_NAME.to_string(),
+            dropped: Arc::default(),
+            retry_base_delay: Duration::from_millis(10),
+        };
+        let span = SpanRecord {
+            trace_id: 1,
+            span_id: 1,
+            parent_span_id: None,
+            name: "process_toot",
+            target: "alternator",
+            start: SystemTime::now(),
+            end: SystemTime::now(),
+            attributes: Vec::new(),
+        };
+
+        exporter.export(&reqwest::Client::new(), &[span]).await;
+        assert_eq!(server.requests().len(), 2);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_spans_dropped_while_queue_full() {
+        // A collector that hangs keeps the exporter busy with the first batch
+        let server = MockHttpServer::start(|_| {
+            MockResponse::json(200, "{}").with_delay(Duration::from_secs(2))
+        })
+        .await;
+        let layer = OtlpLayer::with_queue_capacity(&server.url(), "alternator-test", 4);
+        let sender = layer.sender.clone();
+        let dropped = Arc::clone(&layer.dropped);
+        let subscriber = tracing_subscriber::registry().with(layer);
+
+        tracing::subscriber::with_default(subscriber, || {
+            tracing::info_span!("first").in_scope(|| {});
+            let (ack, _done) = mpsc::channel();
+            sender.try_send(ExportMessage::Flush(ack)).unwrap();
+            std::thread::sleep(Duration::from_millis(200));
+
+            for _ in 0..20 {
+                tracing::info_span!("queued").in_scope(|| {});
+            }
+        });
+
+        assert_eq!(dropped.load(Ordering::Relaxed), 16);
+    }
+}
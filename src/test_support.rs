@@ -0,0 +1,253 @@
+//! Minimal in-process HTTP server for exercising HTTP clients in unit tests
+
+use crate::media::hosts::MediaHostPolicy;
+use crate::media::{MediaConfig, MediaProcessor};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::task::JoinHandle;
+
+/// A request received by the mock server
+#[derive(Debug, Clone)]
+pub struct RecordedRequest {
+    pub method: String,
+    /// Path including the query string
+    pub path: String,
+    pub headers: Vec<(String, String)>,
+    pub body: Vec<u8>,
+}
+
+impl RecordedRequest {
+    /// Case-insensitive header lookup
+    #[allow(dead_code)] // Test helper function
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+/// Response returned by the mock server's handler
+#[derive(Debug, Clone)]
+pub struct MockResponse {
+    pub status: u16,
+    pub headers: Vec<(String, String)>,
+    pub body: Vec<u8>,
+    /// Delay before the response is sent
+    pub delay: Option<Duration>,
+}
+
+impl MockResponse {
+    /// JSON response with the given status
+    pub fn json(status: u16, body: &str) -> Self {
+        Self {
+            status,
+            headers: vec![("Content-Type".to_string(), "application/json".to_string())],
+            body: body.as_bytes().to_vec(),
+            delay: None,
+        }
+    }
+
+    /// Binary response with the given content type
+    pub fn bytes(status: u16, content_type: &str, body: Vec<u8>) -> Self {
+        Self {
+            status,
+            headers: vec![("Content-Type".to_string(), content_type.to_string())],
+            body,
+            delay: None,
+        }
+    }
+
+    /// Add a response header
+    #[allow(dead_code)] // Test helper function
+    pub fn with_header(mut self, name: &str, value: &str) -> Self {
+        self.headers.push((name.to_string(), value.to_string()));
+        self
+    }
+
+    /// Hold the response back for `delay`, simulating a slow upstream
+    #[allow(dead_code)] // Test helper function
+    pub fn with_delay(mut self, delay: Duration) -> Self {
+        self.delay = Some(delay);
+        self
+    }
+}
+
+type Handler = dyn Fn(&RecordedRequest) -> MockResponse + Send + Sync;
+
+/// HTTP/1.1 server bound to a random local port; one request per connection
+pub struct MockHttpServer {
+    addr: std::net::SocketAddr,
+    requests: Arc<Mutex<Vec<RecordedRequest>>>,
+    task: JoinHandle<()>,
+}
+
+impl MockHttpServer {
+    /// Start a server that answers every request using `handler`
+    pub async fn start<F>(handler: F) -> Self
+    where
+        F: Fn(&RecordedRequest) -> MockResponse + Send + Sync + 'static,
+    {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let handler: Arc<Handler> = Arc::new(handler);
+
+        let task_requests = requests.clone();
+        let task = tokio::spawn(async move {
+            while let Ok((stream, _)) = listener.accept().await {
+                let requests = task_requests.clone();
+                let handler = handler.clone();
+                tokio::spawn(async move {
+                    let _ = Self::serve(stream, requests, handler).await;
+                });
+            }
+        });
+
+        Self {
+            addr,
+            requests,
+            task,
+        }
+    }
+
+    /// Base URL of the server, e.g. `http://127.0.0.1:12345`
+    pub fn url(&self) -> String {
+        format!("http://{}", self.addr)
+    }
+
+    /// All requests received so far
+    pub fn requests(&self) -> Vec<RecordedRequest> {
+        self.requests.lock().unwrap().clone()
+    }
+
+    async fn serve(
+        mut stream: TcpStream,
+        requests: Arc<Mutex<Vec<RecordedRequest>>>,
+        handler: Arc<Handler>,
+    ) -> std::io::Result<()> {
+        let mut buffer = Vec::new();
+        let mut chunk = [0u8; 8192];
+
+        // Read until the end of the headers
+        let header_end = loop {
+            let read = stream.read(&mut chunk).await?;
+            if read == 0 {
+                return Ok(());
+            }
+            buffer.extend_from_slice(&chunk[..read]);
+            if let Some(pos) = buffer.windows(4).position(|w| w == b"\r\n\r\n") {
+                break pos + 4;
+            }
+        };
+
+        let head = String::from_utf8_lossy(&buffer[..header_end]).to_string();
+        let mut lines = head.split("\r\n");
+        let mut request_line = lines.next().unwrap_or_default().split_whitespace();
+        let method = request_line.next().unwrap_or_default().to_string();
+        let path = request_line.next().unwrap_or_default().to_string();
+        let headers: Vec<(String, String)> = lines
+            .filter_map(|line| line.split_once(':'))
+            .map(|(k, v)| (k.trim().to_string(), v.trim().to_string()))
+            .collect();
+
+        let find = |name: &str| {
+            headers
+                .iter()
+                .find(|(k, _)| k.eq_ignore_ascii_case(name))
+                .map(|(_, v)| v.clone())
+        };
+        let content_length: usize = find("content-length")
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(0);
+        let chunked = find("transfer-encoding").is_some_and(|v| v.contains("chunked"));
+
+        let mut body = buffer[header_end..].to_vec();
+        if chunked {
+            while !body.windows(5).any(|w| w == b"0\r\n\r\n") {
+                let read = stream.read(&mut chunk).await?;
+                if read == 0 {
+                    break;
+                }
+                body.extend_from_slice(&chunk[..read]);
+            }
+            body = Self::decode_chunked(&body);
+        } else {
+            while body.len() < content_length {
+                let read = stream.read(&mut chunk).await?;
+                if read == 0 {
+                    break;
+                }
+                body.extend_from_slice(&chunk[..read]);
+            }
+        }
+
+        let request = RecordedRequest {
+            method,
+            path,
+            headers,
+            body,
+        };
+        let response = handler(&request);
+        requests.lock().unwrap().push(request);
+
+        if let Some(delay) = response.delay {
+            tokio::time::sleep(delay).await;
+        }
+
+        let mut head = format!(
+            "HTTP/1.1 {} Mock\r\nContent-Length: {}\r\nConnection: close\r\n",
+            response.status,
+            response.body.len()
+        );
+        for (name, value) in &response.headers {
+            head.push_str(&format!("{name}: {value}\r\n"));
+        }
+        head.push_str("\r\n");
+
+        stream.write_all(head.as_bytes()).await?;
+        stream.write_all(&response.body).await?;
+        stream.shutdown().await
+    }
+
+    fn decode_chunked(data: &[u8]) -> Vec<u8> {
+        let mut decoded = Vec::new();
+        let mut rest = data;
+        while let Some(pos) = rest.windows(2).position(|w| w == b"\r\n") {
+            let size_str = String::from_utf8_lossy(&rest[..pos]);
+            let size = usize::from_str_radix(size_str.trim(), 16).unwrap_or(0);
+            if size == 0 {
+                break;
+            }
+            let start = pos + 2;
+            let end = (start + size).min(rest.len());
+            decoded.extend_from_slice(&rest[start..end]);
+            rest = &rest[(end + 2).min(rest.len())..];
+        }
+        decoded
+    }
+}
+
+impl Drop for MockHttpServer {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+/// Media config that may download from the loopback address mock servers listen on
+pub fn local_media_config() -> MediaConfig {
+    MediaConfig {
+        host_policy: MediaHostPolicy {
+            allow_private_hosts: true,
+            ..Default::default()
+        },
+        ..MediaConfig::default()
+    }
+}
+
+/// Media processor that may download from mock servers
+pub fn local_media_processor() -> MediaProcessor {
+    MediaProcessor::with_unified_transformer(local_media_config())
+}
@@ -1,5 +1,6 @@
 use crate::error::{AlternatorError, MastodonError};
 use crate::mastodon::{MastodonStream, MediaRecreation};
+use crate::toot_handler::race::has_new_description;
 use tracing::debug;
 
 /// Recreate media attachments with descriptions and race condition checks
@@ -13,29 +14,73 @@ pub async fn recreate_media_with_race_check(
         return Ok(());
     }
 
+    ensure_media_undescribed(mastodon_client, toot_id, &media_recreations).await?;
+
+    // Recreate all media attachments with descriptions (includes cleanup)
+    match mastodon_client
+        .recreate_media_with_descriptions(toot_id, media_recreations, original_media_ids)
+        .await
+    {
+        Ok(()) => Ok(()),
+        Err(e) => Err(AlternatorError::Mastodon(e)),
+    }
+}
+
+/// Update media descriptions in place with race condition checks
+pub async fn update_media_with_race_check(
+    mastodon_client: &impl MastodonStream,
+    toot_id: &str,
+    media_recreations: &[MediaRecreation],
+) -> Result<(), AlternatorError> {
+    if media_recreations.is_empty() {
+        return Ok(());
+    }
+
+    ensure_media_undescribed(mastodon_client, toot_id, media_recreations).await?;
+
+    let media_updates: Vec<(String, String)> = media_recreations
+        .iter()
+        .map(|recreation| {
+            (
+                recreation.original_media_id.clone(),
+                recreation.description.clone(),
+            )
+        })
+        .collect();
+
+    mastodon_client
+        .update_multiple_media(toot_id, media_updates)
+        .await
+        .map_err(AlternatorError::Mastodon)
+}
+
+/// Verify that all described media still exist in the toot and still need descriptions
+///
+/// A description is only expected if it is the one being replaced.
+async fn ensure_media_undescribed(
+    mastodon_client: &impl MastodonStream,
+    toot_id: &str,
+    media_recreations: &[MediaRecreation],
+) -> Result<(), AlternatorError> {
     // Get current toot state to verify no race conditions
     let current_toot = mastodon_client
         .get_toot(toot_id)
         .await
         .map_err(AlternatorError::Mastodon)?;
 
-    // Check that all media we're trying to recreate still exist and need descriptions
-    // media_recreations and original_media_ids are parallel arrays
-    for media_id in &original_media_ids {
+    // Check that all media we're trying to describe still exist and need descriptions
+    for recreation in media_recreations {
+        let media_id = &recreation.original_media_id;
         if let Some(current_media) = current_toot
             .media_attachments
             .iter()
             .find(|m| m.id == *media_id)
         {
             // Check if this media already has a description (processed by another instance)
-            if current_media.description.is_some()
-                && !current_media
-                    .description
-                    .as_ref()
-                    .unwrap()
-                    .trim()
-                    .is_empty()
-            {
+            if has_new_description(
+                current_media.description.as_deref(),
+                recreation.replaced_description.as_deref(),
+            ) {
                 debug!(
                     "Media {} already has description, race condition detected",
                     media_id
@@ -56,12 +101,5 @@ pub async fn recreate_media_with_race_check(
         }
     }
 
-    // Recreate all media attachments with descriptions (includes cleanup)
-    match mastodon_client
-        .recreate_media_with_descriptions(toot_id, media_recreations, original_media_ids)
-        .await
-    {
-        Ok(()) => Ok(()),
-        Err(e) => Err(AlternatorError::Mastodon(e)),
-    }
+    Ok(())
 }
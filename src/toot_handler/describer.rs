@@ -0,0 +1,254 @@
+use crate::config::RuntimeConfig;
+use crate::error::{AlternatorError, MediaError};
+use crate::language::LanguageDetector;
+use crate::mastodon::MediaAttachment;
+use crate::media::MediaProcessor;
+use crate::openrouter::{OpenRouterClient, Usage};
+use crate::toot_handler::processor;
+
+/// A generated media description with the model that wrote it
+#[allow(dead_code)] // Library API for embedding, not used by the binary
+#[derive(Debug, Clone, PartialEq)]
+pub struct DescriptionOutput {
+    /// Description text, without attribution
+    pub text: String,
+    /// Model that produced the description (the fallback model if the primary failed)
+    pub model: String,
+    /// Token usage reported by OpenRouter, `None` if not reported or served from the cache
+    pub usage: Option<Usage>,
+}
+
+/// Generates a description for a single attachment, without touching the toot
+///
+/// This is the core of toot processing for use as a library: it downloads the media,
+/// prepares it for analysis and asks OpenRouter for a description in the given language.
+#[allow(dead_code)] // Library API for embedding, not used by the binary
+#[derive(Clone)]
+pub struct MediaDescriber {
+    openrouter_client: OpenRouterClient,
+    media_processor: MediaProcessor,
+    language_detector: LanguageDetector,
+    config: RuntimeConfig,
+}
+
+#[allow(dead_code)] // Library API for embedding, not used by the binary
+impl MediaDescriber {
+    pub fn new(
+        openrouter_client: OpenRouterClient,
+        media_processor: MediaProcessor,
+        language_detector: LanguageDetector,
+        config: RuntimeConfig,
+    ) -> Self {
+        Self {
+            openrouter_client,
+            media_processor,
+            language_detector,
+            config,
+        }
+    }
+
+    /// Describe an image attachment using the prompt for `language`
+    ///
+    /// Audio and video need transcription and are only handled by the toot processor;
+    /// they fail with `MediaError::UnsupportedType`.
+    pub async fn describe_media(
+        &self,
+        media: &MediaAttachment,
+        language: &str,
+    ) -> Result<DescriptionOutput, AlternatorError> {
+        if !media.media_type.to_lowercase().starts_with("image") {
+            return Err(MediaError::UnsupportedType {
+                media_type: media.media_type.clone(),
+            }
+            .into());
+        }
+
+        let prompt_template = self.language_detector.get_prompt_template(language)?;
+
+        let original_data = self
+            .media_processor
+            .download_media_for_recreation(media)
+            .await?;
+        let processed_data = self
+            .media_processor
+            .transform_for_analysis(&original_data)
+            .await?;
+        let ocr_text = processor::extract_ocr_text(media, &processed_data, &self.config).await;
+
+        let prompt = processor::image_prompt(
+            prompt_template,
+            crate::media::is_animated(&original_data)
+                .then(|| self.language_detector.get_animation_note(language)),
+            ocr_text.as_deref(),
+            None,
+        );
+        let description = self
+            .openrouter_client
+            .describe_image_with_source(&processed_data, &prompt)
+            .await?;
+
+        Ok(DescriptionOutput {
+            text: description.text,
+            model: description.model,
+            usage: description.usage,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+    use crate::test_support::{local_media_processor, MockHttpServer, MockResponse};
+
+    fn create_describer(server_url: &str) -> MediaDescriber {
+        let config: Config = toml::from_str(&format!(
+            r#"
+[mastodon]
+instance_url = "{server_url}"
+access_token = "test_token"
+
+[openrouter]
+api_key = "test_key"
+base_url = "{server_url}/openrouter"
+vision_model = "primary/vision"
+vision_fallback_model = "fallback/vision"
+"#
+        ))
+        .unwrap();
+        let config = RuntimeConfig::new(config);
+
+        MediaDescriber::new(
+            OpenRouterClient::new(config.config().openrouter.clone()),
+            local_media_processor(),
+            LanguageDetector::new(),
+            config,
+        )
+    }
+
+    fn create_test_media(server_url: &str, media_type: &str) -> MediaAttachment {
+        MediaAttachment {
+            id: "media_1".to_string(),
+            media_type: media_type.to_string(),
+            url: format!("{server_url}/media/file"),
+            preview_url: None,
+            description: None,
+            meta: None,
+        }
+    }
+
+    fn create_test_png() -> Vec<u8> {
+        let mut data = Vec::new();
+        image::DynamicImage::new_rgb8(8, 8)
+            .write_to(
+                &mut std::io::Cursor::new(&mut data),
+                image::ImageFormat::Png,
+            )
+            .unwrap();
+        data
+    }
+
+    async fn start_server(primary_status: u16) -> MockHttpServer {
+        let png = create_test_png();
+        MockHttpServer::start(move |request| {
+            if request.path.starts_with("/media/") {
+                return MockResponse::bytes(200, "image/png", png.clone());
+            }
+
+            let body: serde_json::Value = serde_json::from_slice(&request.body).unwrap();
+            let model = body["model"].as_str().unwrap_or_default().to_string();
+            if model == "primary/vision" && primary_status != 200 {
+                return MockResponse::json(
+                    primary_status,
+                    r#"{"error":{"message":"No endpoints found for primary/vision.","code":404}}"#,
+                );
+            }
+            MockResponse::json(
+                200,
+                &serde_json::json!({
+                    "choices": [{
+                        "message": {"content": format!("Described by {model}")},
+                        "finish_reason": "stop"
+                    }],
+                    "usage": {"prompt_tokens": 120, "completion_tokens": 30, "total_tokens": 150}
+                })
+                .to_string(),
+            )
+        })
+        .await
+    }
+
+    #[tokio::test]
+    async fn test_describe_media_returns_text_model_and_usage() {
+        let server = start_server(200).await;
+        let describer = create_describer(&server.url());
+
+        let output = describer
+            .describe_media(&create_test_media(&server.url(), "image"), "de")
+            .await
+            .unwrap();
+
+        assert_eq!(
+            output,
+            DescriptionOutput {
+                text: "Described by primary/vision".to_string(),
+                model: "primary/vision".to_string(),
+                usage: Some(Usage {
+                    prompt_tokens: Some(120),
+                    completion_tokens: Some(30),
+                    total_tokens: Some(150),
+                }),
+            }
+        );
+
+        // The prompt for the requested language was used
+        let completion = server
+            .requests()
+            .into_iter()
+            .find(|r| r.path == "/openrouter/chat/completions")
+            .unwrap();
+        let body: serde_json::Value = serde_json::from_slice(&completion.body).unwrap();
+        let prompt = body["messages"][0]["content"][0]["text"].as_str().unwrap();
+        assert_eq!(
+            prompt,
+            LanguageDetector::new()
+                .get_prompt_template("de")
+                .unwrap()
+                .replace("{model}", "primary/vision")
+                .replace(
+                    "{max_length}",
+                    &crate::openrouter::DEFAULT_MAX_DESCRIPTION_LENGTH.to_string()
+                )
+        );
+    }
+
+    #[tokio::test]
+    async fn test_describe_media_reports_fallback_model() {
+        let server = start_server(404).await;
+        let describer = create_describer(&server.url());
+
+        let output = describer
+            .describe_media(&create_test_media(&server.url(), "image/png"), "en")
+            .await
+            .unwrap();
+
+        assert_eq!(output.text, "Described by fallback/vision");
+        assert_eq!(output.model, "fallback/vision");
+    }
+
+    #[tokio::test]
+    async fn test_describe_media_rejects_non_images() {
+        let server = start_server(200).await;
+        let describer = create_describer(&server.url());
+
+        let result = describer
+            .describe_media(&create_test_media(&server.url(), "audio"), "en")
+            .await;
+
+        assert!(matches!(
+            result,
+            Err(AlternatorError::Media(MediaError::UnsupportedType { .. }))
+        ));
+        assert!(server.requests().is_empty());
+    }
+}
@@ -0,0 +1,124 @@
+//! Dead-letter log of toots that failed to process, kept for `--reprocess-failed`
+
+use serde::{Deserialize, Serialize};
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::Path;
+use tracing::warn;
+
+/// A toot that failed to process, stored as one JSON line in `failed_toots_file`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FailedToot {
+    pub toot_id: String,
+    /// The failure happened while processing an edit of the toot
+    pub edit: bool,
+    pub error: String,
+    pub timestamp: String,
+}
+
+impl FailedToot {
+    pub fn new(toot_id: &str, edit: bool, error: impl std::fmt::Display) -> Self {
+        Self {
+            toot_id: toot_id.to_string(),
+            edit,
+            error: error.to_string(),
+            timestamp: chrono::Utc::now().to_rfc3339(),
+        }
+    }
+}
+
+/// Append a failed toot to the log at `path`
+///
+/// Write failures are logged, never returned, so a broken log doesn't stop processing.
+pub fn record(path: &Path, failed: &FailedToot) {
+    let result = serde_json::to_string(failed)
+        .map_err(std::io::Error::from)
+        .and_then(|line| {
+            if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+                std::fs::create_dir_all(parent)?;
+            }
+            OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(path)?
+                .write_all(format!("{line}\n").as_bytes())
+        });
+
+    if let Err(e) = result {
+        warn!(
+            "Failed to record failed toot {} in {}: {}",
+            failed.toot_id,
+            path.display(),
+            e
+        );
+    }
+}
+
+/// Read the log at `path`, keeping the latest entry per toot in the order toots first failed
+///
+/// A missing file is an empty log.
+pub fn load(path: &Path) -> Result<Vec<FailedToot>, std::io::Error> {
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+
+    let mut failed: Vec<FailedToot> = Vec::new();
+    for line in std::fs::read_to_string(path)?.lines() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        match serde_json::from_str::<FailedToot>(line) {
+            Ok(entry) => match failed.iter_mut().find(|f| f.toot_id == entry.toot_id) {
+                Some(existing) => *existing = entry,
+                None => failed.push(entry),
+            },
+            Err(e) => warn!("Ignoring malformed failed toot entry: {} ({})", line, e),
+        }
+    }
+    Ok(failed)
+}
+
+/// Replace the log at `path` with `failed`, removing the file when nothing is left
+pub fn rewrite(path: &Path, failed: &[FailedToot]) -> Result<(), std::io::Error> {
+    if failed.is_empty() {
+        return match std::fs::remove_file(path) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e),
+            _ => Ok(()),
+        };
+    }
+
+    let mut contents = String::new();
+    for entry in failed {
+        contents.push_str(&serde_json::to_string(entry)?);
+        contents.push('\n');
+    }
+    std::fs::write(path, contents)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_log_round_trip_keeps_latest_entry_per_toot() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("failed").join("toots.jsonl");
+        assert!(load(&path).unwrap().is_empty());
+
+        record(&path, &FailedToot::new("1", false, "timeout"));
+        record(&path, &FailedToot::new("2", true, "HTTP 500"));
+        record(&path, &FailedToot::new("1", false, "HTTP 502"));
+
+        let failed = load(&path).unwrap();
+        assert_eq!(failed.len(), 2);
+        assert_eq!(failed[0].toot_id, "1");
+        assert_eq!(failed[0].error, "HTTP 502");
+        assert!(failed[1].edit);
+
+        rewrite(&path, &failed[1..]).unwrap();
+        assert_eq!(load(&path).unwrap(), failed[1..]);
+
+        rewrite(&path, &[]).unwrap();
+        assert!(!path.exists());
+    }
+}
@@ -1,52 +1,206 @@
-use crate::config::RuntimeConfig;
+use crate::config::{ConfigError, RuntimeConfig};
 use crate::error::{AlternatorError, MastodonError};
 use crate::language::LanguageDetector;
 use crate::mastodon::{MastodonClient, MastodonStream, TootEvent};
 use crate::media::MediaProcessor;
-use crate::openrouter::OpenRouterClient;
+use crate::openrouter::DescriptionProvider;
+use crate::toot_handler::failed::{self, FailedToot};
 use crate::toot_handler::processor;
+use crate::toot_handler::queue::{self, QueueOverflow, TootSender, DEFAULT_QUEUE_CAPACITY};
+use crate::toot_handler::state::{self, ProcessedStore};
 use crate::toot_handler::stats::ProcessingStats;
+use futures_util::stream::{FuturesUnordered, StreamExt};
 use lru::LruCache;
+use std::collections::HashSet;
 use std::num::NonZeroUsize;
-use tracing::{debug, error, info, warn};
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::watch;
+use tokio::time::Instant;
+use tracing::{debug, error, info, warn, Instrument};
 
 /// Handler for processing incoming toot events from WebSocket stream
 pub struct TootStreamHandler {
     mastodon_client: MastodonClient,
-    openrouter_client: OpenRouterClient,
+    description_provider: Box<dyn DescriptionProvider>,
     media_processor: MediaProcessor,
     language_detector: LanguageDetector,
-    processed_toots: LruCache<String, ()>,
-    processed_edits: LruCache<String, ()>,
+    processed_toots: Mutex<LruCache<String, ()>>,
+    processed_edits: Mutex<LruCache<String, ()>>,
+    /// Media IDs that had a description when each toot was last seen, to notice cleared alt text
+    described_media: Mutex<LruCache<String, Vec<String>>>,
+    /// State file, shared with the backfill so only one writer appends to and compacts it
+    processed_store: Option<Arc<Mutex<ProcessedStore>>>,
+    /// Toot IDs a previous backfill handled, until the backfill takes them
+    backfilled_toots: Vec<String>,
+    /// Dead-letter log for toots that failed to process
+    failed_toots_file: Option<PathBuf>,
+    processing_timeout: Duration,
+    /// Minimum pause between finishing one toot and starting the next, across all workers
+    inter_toot_delay: Duration,
+    /// Pause before processing a newly posted toot so the server can finish processing its media
+    new_toot_delay: Duration,
+    /// When the pipeline last finished a toot, for pacing the next one
+    last_toot_finished: Mutex<Option<Instant>>,
+    /// Stream toots processed at once
+    workers: usize,
+    /// Flips to `true` when the application is shutting down
+    shutdown: Option<watch::Receiver<bool>>,
     config: RuntimeConfig,
 }
 
+/// Default number of processed toots/edits remembered for deduplication
+pub(crate) const DEFAULT_PROCESSED_CACHE_CAPACITY: usize = 5000;
+
+/// Number of processed toots/edits remembered for deduplication and in the state file
+pub(crate) fn processed_cache_capacity(config: &RuntimeConfig) -> NonZeroUsize {
+    config
+        .config()
+        .mastodon
+        .processed_cache_size
+        .and_then(NonZeroUsize::new)
+        .unwrap_or(NonZeroUsize::new(DEFAULT_PROCESSED_CACHE_CAPACITY).unwrap())
+}
+
+/// Default upper bound for processing a single toot, in seconds
+pub const DEFAULT_PROCESSING_TIMEOUT_SECS: u64 = 300;
+
+/// Default time an in-flight toot may keep processing after a shutdown signal, in seconds
+pub const DEFAULT_SHUTDOWN_GRACE_SECS: u64 = 30;
+
+/// Default number of stream toots processed at once
+pub const DEFAULT_WORKERS: usize = 1;
+
 impl TootStreamHandler {
     /// Create a new toot stream handler
+    ///
+    /// Descriptions come from `description_provider`, usually an `OpenRouterClient`.
     pub fn new(
         mastodon_client: MastodonClient,
-        openrouter_client: OpenRouterClient,
+        description_provider: impl DescriptionProvider + 'static,
         media_processor: MediaProcessor,
         language_detector: LanguageDetector,
         config: RuntimeConfig,
     ) -> Self {
-        // Use LRU cache with capacity of 5000 entries to prevent memory leaks
-        let capacity = NonZeroUsize::new(5000).unwrap();
+        // Bounded LRU caches so a long-running bot doesn't leak memory; the oldest IDs expire
+        let capacity = processed_cache_capacity(&config);
+        let mut processed_toots = LruCache::new(capacity);
+        let mut processed_edits = LruCache::new(capacity);
+        let described_media = LruCache::new(capacity);
+
+        // Restore previously processed toots/edits so restarts don't reprocess them
+        let mut backfilled_toots = Vec::new();
+        let processed_store = Self::open_processed_store(&config).map(|(store, entries)| {
+            for toot_id in entries.toots {
+                processed_toots.put(toot_id, ());
+            }
+            for edit_key in entries.edits {
+                processed_edits.put(edit_key, ());
+            }
+            backfilled_toots = entries.backfill;
+            Arc::new(Mutex::new(store))
+        });
+
+        let processing_timeout = Duration::from_secs(
+            config
+                .config()
+                .mastodon
+                .processing_timeout_secs
+                .unwrap_or(DEFAULT_PROCESSING_TIMEOUT_SECS),
+        );
+
+        let failed_toots_file = config
+            .config()
+            .mastodon
+            .failed_toots_file
+            .as_ref()
+            .map(PathBuf::from);
+
+        let inter_toot_delay =
+            Duration::from_millis(config.config().mastodon.inter_toot_delay_ms.unwrap_or(0));
+        let new_toot_delay =
+            Duration::from_secs(config.config().mastodon.new_toot_delay_secs.unwrap_or(0));
+        let workers = config
+            .config()
+            .mastodon
+            .workers
+            .unwrap_or(DEFAULT_WORKERS)
+            .max(1);
 
         Self {
             mastodon_client,
-            openrouter_client,
+            description_provider: Box::new(description_provider),
             media_processor,
             language_detector,
-            processed_toots: LruCache::new(capacity),
-            processed_edits: LruCache::new(capacity),
+            processed_toots: Mutex::new(processed_toots),
+            processed_edits: Mutex::new(processed_edits),
+            described_media: Mutex::new(described_media),
+            processed_store,
+            backfilled_toots,
+            failed_toots_file,
+            processing_timeout,
+            inter_toot_delay,
+            new_toot_delay,
+            last_toot_finished: Mutex::new(None),
+            workers,
+            shutdown: None,
             config,
         }
     }
 
+    /// Stop accepting new stream events once `shutdown` is set to `true`
+    ///
+    /// Toots that are already being processed are finished first.
+    pub fn set_shutdown_receiver(&mut self, shutdown: watch::Receiver<bool>) {
+        self.shutdown = Some(shutdown);
+    }
+
+    /// Whether shutdown was requested (or its sender is gone)
+    fn is_shutting_down(&self) -> bool {
+        self.shutdown
+            .as_ref()
+            .is_some_and(|shutdown| *shutdown.borrow() || shutdown.has_changed().is_err())
+    }
+
+    /// Hand the state file and the toot IDs a previous backfill handled to the backfill
+    ///
+    /// `None` without a state file or in dry-run mode, in which case backfill progress is
+    /// not persisted.
+    pub fn take_backfill_progress(&mut self) -> Option<(Arc<Mutex<ProcessedStore>>, Vec<String>)> {
+        let store = self.processed_store.clone()?;
+        Some((store, std::mem::take(&mut self.backfilled_toots)))
+    }
+
+    /// Open the configured state file, if any (disabled in dry-run mode)
+    fn open_processed_store(
+        config: &RuntimeConfig,
+    ) -> Option<(ProcessedStore, crate::toot_handler::state::ProcessedEntries)> {
+        let state_file = config.config().mastodon.state_file.as_deref()?;
+
+        if config.is_dry_run() {
+            info!("Dry-run mode: not using state file {}", state_file);
+            return None;
+        }
+
+        match ProcessedStore::open(state_file, processed_cache_capacity(config).get()) {
+            Ok(opened) => Some(opened),
+            Err(e) => {
+                warn!(
+                    "Failed to open state file {}: {}. Processed toots will not be persisted",
+                    state_file, e
+                );
+                None
+            }
+        }
+    }
+
     /// Start processing toot stream - main entry point
     pub async fn start_processing(&mut self) -> Result<(), AlternatorError> {
         info!("Starting toot stream processing");
+        if self.config.is_dry_run() {
+            info!("Dry-run mode enabled: generated descriptions will be logged, not applied");
+        }
 
         // Connect to Mastodon WebSocket stream
         self.mastodon_client
@@ -56,196 +210,538 @@ impl TootStreamHandler {
 
         info!("✓ Connected to Mastodon stream - listening for toots");
 
+        let stream = self.mastodon_client.take_stream();
+        self.process_stream(stream).await
+    }
+
+    /// Read `stream` in a background listener and process the queued toots with
+    /// `mastodon.workers` workers
+    ///
+    /// The listener keeps answering pings while slow toots are being processed. Up to
+    /// `workers` toots are processed at once, but events of the same toot never overlap: an
+    /// event whose toot is still being processed is held back (and stops taking further toots
+    /// from the queue) until the earlier event finished, so an edit can't overtake the toot it
+    /// edits. Media within a toot are processed concurrently up to `mastodon.max_inflight`.
+    /// Toots still queued on shutdown are kept in the failed toots file for `--reprocess-failed`.
+    async fn process_stream(&self, stream: MastodonClient) -> Result<(), AlternatorError> {
+        let capacity = self
+            .config
+            .config()
+            .mastodon
+            .queue_capacity
+            .unwrap_or(DEFAULT_QUEUE_CAPACITY);
+        let (sender, mut receiver) =
+            queue::toot_queue(capacity, QueueOverflow::from_config(&self.config));
+        let mut listener = tokio::spawn(listen_for_toots(stream, sender, self.shutdown.clone()));
+        // Wakes the loop on a quiet stream so processed entries still reach the state file
+        let mut flush_tick = tokio::time::interval(state::FLUSH_INTERVAL);
+        let mut shutdown = self.shutdown.clone();
+
+        let mut in_flight = FuturesUnordered::new();
+        // IDs of the toots being processed, so a second event of one waits for the first
+        let mut busy: HashSet<String> = HashSet::new();
+        let mut held_back: Option<TootEvent> = None;
+
         // Main processing loop
-        loop {
-            match self.listen_and_process().await {
+        let result = loop {
+            if let Some(store) = &self.processed_store {
+                store.lock().unwrap().flush_if_due();
+            }
+            if self.is_shutting_down() {
+                info!("Shutdown requested, no longer accepting new toots");
+                break Ok(());
+            }
+
+            if in_flight.len() < self.workers
+                && held_back
+                    .as_ref()
+                    .is_some_and(|toot| !busy.contains(&toot.id))
+            {
+                let toot = held_back.take().unwrap();
+                busy.insert(toot.id.clone());
+                in_flight.push(self.handle_queued_toot(toot));
+            }
+            let accepting = held_back.is_none() && in_flight.len() < self.workers;
+
+            tokio::select! {
+                biased;
+                _ = shutdown_requested(&mut shutdown) => continue,
+                Some((toot_id, result)) = in_flight.next() => {
+                    busy.remove(&toot_id);
+                    if let Err(e) = result {
+                        // Recoverable errors are handled in handle_toot; propagate the rest
+                        error!("Error in toot processing loop: {}", e);
+                        break Err(e);
+                    }
+                }
+                toot = receiver.recv(), if accepting => {
+                    let Some(toot) = toot else {
+                        // The listener stopped on shutdown or on an unrecoverable error
+                        break (&mut listener).await.unwrap_or_else(|e| Err(e.into()));
+                    };
+                    if busy.contains(&toot.id) {
+                        debug!("Toot {} is still being processed, holding back its next event", toot.id);
+                        held_back = Some(toot);
+                    } else {
+                        busy.insert(toot.id.clone());
+                        in_flight.push(self.handle_queued_toot(toot));
+                    }
+                }
+                _ = flush_tick.tick() => {}
+            }
+        };
+
+        listener.abort();
+        // Toots that are already being processed are finished first
+        while let Some((toot_id, result)) = in_flight.next().await {
+            if let Err(e) = result {
+                error!("Error processing toot {} while stopping: {}", toot_id, e);
+            }
+        }
+        if let Some(store) = &self.processed_store {
+            if let Err(e) = store.lock().unwrap().flush() {
+                warn!("Failed to write the state file on shutdown: {}", e);
+            }
+        }
+        let mut unprocessed: Vec<TootEvent> = held_back.into_iter().collect();
+        unprocessed.extend(receiver.drain().await);
+        self.record_unprocessed(&unprocessed);
+        result
+    }
+
+    /// Process a toot taken from the queue, returning its ID with the result
+    async fn handle_queued_toot(&self, toot: TootEvent) -> (String, Result<(), AlternatorError>) {
+        let result = self.handle_toot(&toot).await;
+        (toot.id, result)
+    }
+
+    /// Keep toots still queued on shutdown in the failed toots file, if configured
+    fn record_unprocessed(&self, toots: &[TootEvent]) {
+        if toots.is_empty() {
+            return;
+        }
+        match &self.failed_toots_file {
+            Some(path) if !self.config.is_dry_run() => {
+                for toot in toots {
+                    failed::record(
+                        path,
+                        &FailedToot::new(&toot.id, toot.is_edit, "Not processed before shutdown"),
+                    );
+                }
+                warn!(
+                    "{} queued toots were not processed, kept in {} for --reprocess-failed",
+                    toots.len(),
+                    path.display()
+                );
+            }
+            _ => warn!("{} queued toots were not processed", toots.len()),
+        }
+    }
+
+    /// Fetch a single toot by ID and run the processing pipeline once
+    ///
+    /// Unlike the stream loop, failures are returned to the caller and the toot is
+    /// not marked as processed, so a later run can retry it.
+    pub async fn process_single(&mut self, toot_id: &str) -> Result<(), AlternatorError> {
+        if self.is_already_processed(toot_id) {
+            info!("Toot {} has already been processed, skipping", toot_id);
+            return Ok(());
+        }
+
+        self.fetch_and_process(toot_id, false).await
+    }
+
+    /// Re-run every toot in the failed toots file through the pipeline
+    ///
+    /// Toots that succeed are removed from the file; toots that fail again stay in it with
+    /// the new error. The file is left untouched in dry-run mode.
+    pub async fn reprocess_failed(&mut self) -> Result<(), AlternatorError> {
+        let Some(path) = self.failed_toots_file.clone() else {
+            return Err(AlternatorError::Config(ConfigError::MissingRequired(
+                "mastodon.failed_toots_file is required for --reprocess-failed".to_string(),
+            )));
+        };
+
+        let entries = failed::load(&path)?;
+        if entries.is_empty() {
+            info!("No failed toots recorded in {}", path.display());
+            return Ok(());
+        }
+
+        info!(
+            "Reprocessing {} failed toots from {}",
+            entries.len(),
+            path.display()
+        );
+        let mut still_failing = Vec::new();
+        for entry in entries {
+            if let Err(e) = self.fetch_and_process(&entry.toot_id, entry.edit).await {
+                error!("Toot {} failed again: {}", entry.toot_id, e);
+                still_failing.push(FailedToot::new(&entry.toot_id, entry.edit, e));
+            }
+        }
+
+        if still_failing.is_empty() {
+            info!("✓ All failed toots reprocessed");
+        } else {
+            warn!("{} toots are still failing", still_failing.len());
+        }
+
+        if self.config.is_dry_run() {
+            return Ok(());
+        }
+        failed::rewrite(&path, &still_failing)?;
+        Ok(())
+    }
+
+    /// Fetch an own toot by ID and run the pipeline, treating it as an edit when `edit` is set
+    async fn fetch_and_process(
+        &mut self,
+        toot_id: &str,
+        edit: bool,
+    ) -> Result<(), AlternatorError> {
+        let span = toot_span(toot_id);
+        self.fetch_and_process_toot(toot_id, edit)
+            .instrument(span)
+            .await
+    }
+
+    async fn fetch_and_process_toot(
+        &mut self,
+        toot_id: &str,
+        edit: bool,
+    ) -> Result<(), AlternatorError> {
+        let mut toot = self
+            .mastodon_client
+            .get_toot(toot_id)
+            .await
+            .map_err(AlternatorError::Mastodon)?;
+        toot.is_edit = edit;
+
+        // Ownership check needs the authenticated user, which is only known after verification
+        let is_own_toot = match self.mastodon_client.is_own_toot(&toot) {
+            Err(MastodonError::UserVerificationFailed) => {
+                self.mastodon_client
+                    .verify_credentials()
+                    .await
+                    .map_err(AlternatorError::Mastodon)?;
+                self.mastodon_client.is_own_toot(&toot)
+            }
+            result => result,
+        }
+        .map_err(AlternatorError::Mastodon)?;
+
+        if !is_own_toot {
+            return Err(AlternatorError::Mastodon(MastodonError::InvalidTootData(
+                format!("Toot {toot_id} was not posted by the authenticated user"),
+            )));
+        }
+
+        info!(
+            "Processing toot: {} (media: {})",
+            toot.id,
+            toot.media_attachments.len()
+        );
+        if self.config.is_dry_run() {
+            info!("Dry-run mode enabled: generated descriptions will be logged, not applied");
+        }
+
+        self.run_pipeline(&toot).await?;
+
+        self.mark_as_processed(toot.id.clone());
+        info!("✓ Successfully processed toot: {}", toot.id);
+        Ok(())
+    }
+
+    /// Run the processing pipeline for a toot, giving up after the processing timeout
+    async fn run_pipeline(&self, toot: &TootEvent) -> Result<(), AlternatorError> {
+        let pipeline = async {
+            if toot.is_edit {
+                processor::process_edited_toot(
+                    toot,
+                    &self.mastodon_client,
+                    self.description_provider.as_ref(),
+                    &self.media_processor,
+                    &self.language_detector,
+                    &self.config,
+                )
+                .await
+            } else {
+                processor::process_toot(
+                    toot,
+                    &self.mastodon_client,
+                    self.description_provider.as_ref(),
+                    &self.media_processor,
+                    &self.language_detector,
+                    &self.config,
+                )
+                .await
+            }
+        };
+
+        tokio::time::timeout(self.processing_timeout, pipeline)
+            .await
+            .map_err(|_| AlternatorError::ProcessingTimeout {
+                toot_id: toot.id.clone(),
+                timeout_secs: self.processing_timeout.as_secs(),
+            })?
+    }
+
+    /// Run the pipeline for a stream toot, pausing first so consecutive toots are at least
+    /// `inter_toot_delay` apart
+    async fn run_paced_pipeline(&self, toot: &TootEvent) -> Result<(), AlternatorError> {
+        let last_finished = *self.last_toot_finished.lock().unwrap();
+        if let Some(finished) = last_finished {
+            let elapsed = finished.elapsed();
+            if elapsed < self.inter_toot_delay {
+                let remaining = self.inter_toot_delay - elapsed;
+                debug!(
+                    "Waiting {}ms before processing toot {}",
+                    remaining.as_millis(),
+                    toot.id
+                );
+                tokio::time::sleep(remaining).await;
+            }
+        }
+
+        let result = self.run_pipeline(toot).await;
+        if !self.inter_toot_delay.is_zero() {
+            *self.last_toot_finished.lock().unwrap() = Some(Instant::now());
+        }
+        result
+    }
+
+    /// Process a toot event received from the stream
+    async fn handle_toot(&self, toot: &TootEvent) -> Result<(), AlternatorError> {
+        let span = toot_span(&toot.id);
+        self.handle_toot_event(toot).instrument(span).await
+    }
+
+    async fn handle_toot_event(&self, toot: &TootEvent) -> Result<(), AlternatorError> {
+        let cleared = self.record_described_media(toot);
+
+        if toot.is_edit {
+            // Handle edit events with content-aware deduplication; an edit that removed a
+            // description is always re-described, even when its media IDs were seen before
+            if !cleared.is_empty() {
+                info!(
+                    "Description removed from media {} of toot {}, re-describing",
+                    cleared.join(", "),
+                    toot.id
+                );
+            } else if self
+                .media_processor
+                .written_descriptions()
+                .is_own_edit(toot)
+            {
+                debug!(
+                    "Skipping edit {}: it only carries descriptions written by Alternator",
+                    toot.id
+                );
+                self.mark_edit_as_processed(toot);
+                return Ok(());
+            } else if self.is_edit_already_processed(toot) {
+                debug!(
+                    "Skipping already processed edit: {} (media: {})",
+                    toot.id,
+                    toot.media_attachments.len()
+                );
+                return Ok(());
+            }
+
+            info!(
+                "Processing edited toot: {} (media: {})",
+                toot.id,
+                toot.media_attachments.len()
+            );
+
+            // Process the edited toot
+            match self.run_paced_pipeline(toot).await {
                 Ok(()) => {
-                    // Continue processing
-                    continue;
+                    self.mark_edit_as_processed(toot);
+                    info!("✓ Successfully processed edited toot: {}", toot.id);
+                }
+                Err(e @ AlternatorError::ProcessingTimeout { .. }) => {
+                    // Not marked as processed, so a later edit can retry it
+                    warn!("{}, skipping", e);
                 }
                 Err(e) => {
-                    error!("Error in toot processing loop: {}", e);
+                    // Log error but continue processing other toots
+                    error!("Failed to process edited toot {}: {}", toot.id, e);
 
-                    // Handle specific error types
+                    // Still mark as processed to avoid retry loops for non-recoverable errors
+                    self.mark_edit_as_processed(toot);
+                    self.record_failure(toot, &e);
+
+                    // Return error for recoverable issues that should be handled at higher level
                     match &e {
-                        AlternatorError::Mastodon(
-                            MastodonError::Disconnected(_) | MastodonError::ConnectionFailed(_),
+                        AlternatorError::Mastodon(MastodonError::RateLimitExceeded { .. })
+                        | AlternatorError::OpenRouter(
+                            crate::error::OpenRouterError::RateLimitExceeded { .. },
                         ) => {
-                            warn!("Connection lost, will attempt to reconnect");
-                            // The MastodonClient will handle reconnection automatically
+                            return Err(e);
                         }
                         _ => {
-                            // For other errors, propagate up to main application
-                            return Err(e);
+                            // For other errors, log and continue
+                            warn!(
+                                "Non-recoverable error processing edited toot {}, continuing: {}",
+                                toot.id, e
+                            );
                         }
                     }
                 }
             }
-        }
-    }
-
-    /// Listen for a single toot event and process it
-    async fn listen_and_process(&mut self) -> Result<(), AlternatorError> {
-        // Listen for toot events
-        match self.mastodon_client.listen().await {
-            Ok(Some(toot)) => {
-                // Verify this is from the authenticated user (already done in MastodonClient)
+        } else {
+            // Handle new toot events with existing logic
+            if self.is_already_processed(toot.id.as_str()) {
+                debug!("Skipping already processed toot: {}", toot.id);
+                return Ok(());
+            }
 
-                if toot.is_edit {
-                    // Handle edit events with content-aware deduplication
-                    if self.is_edit_already_processed(&toot) {
-                        debug!(
-                            "Skipping already processed edit: {} (media: {})",
-                            toot.id,
-                            toot.media_attachments.len()
-                        );
+            if !self.new_toot_delay.is_zero() && !toot.media_attachments.is_empty() {
+                debug!(
+                    "Waiting {}s for the server to finish processing media of toot {}",
+                    self.new_toot_delay.as_secs_f32(),
+                    toot.id
+                );
+                let mut shutdown = self.shutdown.clone();
+                tokio::select! {
+                    biased;
+                    _ = shutdown_requested(&mut shutdown) => {
+                        // Not marked as processed, so --reprocess-failed can pick it up
+                        info!("Shutdown requested, not processing toot {}", toot.id);
+                        self.record_unprocessed(std::slice::from_ref(toot));
                         return Ok(());
                     }
+                    _ = tokio::time::sleep(self.new_toot_delay) => {}
+                }
+            }
 
-                    info!(
-                        "Processing edited toot: {} (media: {})",
-                        toot.id,
-                        toot.media_attachments.len()
-                    );
+            info!(
+                "Processing toot: {} (media: {})",
+                toot.id,
+                toot.media_attachments.len()
+            );
 
-                    // Process the edited toot
-                    match processor::process_edited_toot(
-                        &toot,
-                        &self.mastodon_client,
-                        &self.openrouter_client,
-                        &self.media_processor,
-                        &self.language_detector,
-                        &self.config,
-                    )
-                    .await
-                    {
-                        Ok(()) => {
-                            self.mark_edit_as_processed(&toot);
-                            info!("✓ Successfully processed edited toot: {}", toot.id);
-                        }
-                        Err(e) => {
-                            // Log error but continue processing other toots
-                            error!("Failed to process edited toot {}: {}", toot.id, e);
-
-                            // Still mark as processed to avoid retry loops for non-recoverable errors
-                            self.mark_edit_as_processed(&toot);
-
-                            // Return error for recoverable issues that should be handled at higher level
-                            match &e {
-                                AlternatorError::Mastodon(MastodonError::RateLimitExceeded {
-                                    ..
-                                })
-                                | AlternatorError::OpenRouter(
-                                    crate::error::OpenRouterError::RateLimitExceeded { .. },
-                                ) => {
-                                    return Err(e);
-                                }
-                                _ => {
-                                    // For other errors, log and continue
-                                    warn!(
-                                        "Non-recoverable error processing edited toot {}, continuing: {}",
-                                        toot.id, e
-                                    );
-                                }
-                            }
-                        }
-                    }
-                } else {
-                    // Handle new toot events with existing logic
-                    if self.is_already_processed(toot.id.as_str()) {
-                        debug!("Skipping already processed toot: {}", toot.id);
-                        return Ok(());
-                    }
+            // Process the toot
+            match self.run_paced_pipeline(toot).await {
+                Ok(()) => {
+                    self.mark_as_processed(toot.id.clone());
+                    info!("✓ Successfully processed toot: {}", toot.id);
+                }
+                Err(e @ AlternatorError::ProcessingTimeout { .. }) => {
+                    // Not marked as processed, so a later edit can retry it
+                    warn!("{}, skipping", e);
+                }
+                Err(e) => {
+                    // Log error but continue processing other toots
+                    error!("Failed to process toot {}: {}", toot.id, e);
 
-                    info!(
-                        "Processing toot: {} (media: {})",
-                        toot.id,
-                        toot.media_attachments.len()
-                    );
+                    // Still mark as processed to avoid retry loops for non-recoverable errors
+                    self.mark_as_processed(toot.id.clone());
+                    self.record_failure(toot, &e);
 
-                    // Process the toot
-                    match processor::process_toot(
-                        &toot,
-                        &self.mastodon_client,
-                        &self.openrouter_client,
-                        &self.media_processor,
-                        &self.language_detector,
-                        &self.config,
-                    )
-                    .await
-                    {
-                        Ok(()) => {
-                            self.mark_as_processed(toot.id.clone());
-                            info!("✓ Successfully processed toot: {}", toot.id);
+                    // Return error for recoverable issues that should be handled at higher level
+                    match &e {
+                        AlternatorError::Mastodon(MastodonError::RateLimitExceeded { .. })
+                        | AlternatorError::OpenRouter(
+                            crate::error::OpenRouterError::RateLimitExceeded { .. },
+                        ) => {
+                            return Err(e);
                         }
-                        Err(e) => {
-                            // Log error but continue processing other toots
-                            error!("Failed to process toot {}: {}", toot.id, e);
-
-                            // Still mark as processed to avoid retry loops for non-recoverable errors
-                            self.mark_as_processed(toot.id.clone());
-
-                            // Return error for recoverable issues that should be handled at higher level
-                            match &e {
-                                AlternatorError::Mastodon(MastodonError::RateLimitExceeded {
-                                    ..
-                                })
-                                | AlternatorError::OpenRouter(
-                                    crate::error::OpenRouterError::RateLimitExceeded { .. },
-                                ) => {
-                                    return Err(e);
-                                }
-                                _ => {
-                                    // For other errors, log and continue
-                                    warn!(
-                                        "Non-recoverable error processing toot {}, continuing: {}",
-                                        toot.id, e
-                                    );
-                                }
-                            }
+                        _ => {
+                            // For other errors, log and continue
+                            warn!(
+                                "Non-recoverable error processing toot {}, continuing: {}",
+                                toot.id, e
+                            );
                         }
                     }
                 }
             }
-            Ok(None) => {
-                // No toot received, continue listening
-                debug!("No toot received, continuing to listen");
-            }
-            Err(e) => {
-                error!("Error listening for toots: {}", e);
-                return Err(AlternatorError::Mastodon(e));
-            }
         }
 
         Ok(())
     }
 
+    /// Add a toot that won't be retried to the failed toots file, if configured
+    fn record_failure(&self, toot: &TootEvent, error: &AlternatorError) {
+        if self.config.is_dry_run() {
+            return;
+        }
+        if let Some(path) = &self.failed_toots_file {
+            failed::record(path, &FailedToot::new(&toot.id, toot.is_edit, error));
+        }
+    }
+
+    /// Remember which media of the toot currently have a description
+    ///
+    /// Returns the IDs of media that had a description when the toot was last seen but no
+    /// longer do.
+    fn record_described_media(&self, toot: &TootEvent) -> Vec<String> {
+        let described: Vec<String> = toot
+            .media_attachments
+            .iter()
+            .filter(|m| {
+                m.description
+                    .as_deref()
+                    .is_some_and(|d| !d.trim().is_empty())
+            })
+            .map(|m| m.id.clone())
+            .collect();
+
+        let mut described_media = self.described_media.lock().unwrap();
+        let cleared = match described_media.get(&toot.id) {
+            Some(previously_described) => toot
+                .media_attachments
+                .iter()
+                .filter(|m| previously_described.contains(&m.id) && !described.contains(&m.id))
+                .map(|m| m.id.clone())
+                .collect(),
+            None => Vec::new(),
+        };
+
+        described_media.put(toot.id.clone(), described);
+        cleared
+    }
+
     /// Check if a toot has already been processed
-    fn is_already_processed(&mut self, toot_id: &str) -> bool {
-        self.processed_toots.get(toot_id).is_some()
+    fn is_already_processed(&self, toot_id: &str) -> bool {
+        self.processed_toots.lock().unwrap().get(toot_id).is_some()
     }
 
     /// Mark a toot as processed to prevent duplicate processing
-    fn mark_as_processed(&mut self, toot_id: String) {
+    fn mark_as_processed(&self, toot_id: String) {
+        if let Some(store) = &self.processed_store {
+            store.lock().unwrap().record_toot(&toot_id);
+        }
         // LRU cache automatically manages size and evicts least recently used entries
-        self.processed_toots.put(toot_id, ());
+        self.processed_toots.lock().unwrap().put(toot_id, ());
     }
 
     /// Check if an edit has already been processed
-    fn is_edit_already_processed(&mut self, toot: &TootEvent) -> bool {
+    fn is_edit_already_processed(&self, toot: &TootEvent) -> bool {
         let edit_key = self.generate_edit_key(toot);
-        self.processed_edits.get(&edit_key).is_some()
+        self.processed_edits
+            .lock()
+            .unwrap()
+            .get(&edit_key)
+            .is_some()
     }
 
     /// Mark an edit as processed to prevent duplicate processing
-    fn mark_edit_as_processed(&mut self, toot: &TootEvent) {
+    fn mark_edit_as_processed(&self, toot: &TootEvent) {
         let edit_key = self.generate_edit_key(toot);
+        if let Some(store) = &self.processed_store {
+            store.lock().unwrap().record_edit(&edit_key);
+        }
         // LRU cache automatically manages size and evicts least recently used entries
-        self.processed_edits.put(edit_key, ());
+        self.processed_edits.lock().unwrap().put(edit_key, ());
     }
 
-    /// Generate a unique key for an edit based on toot ID and media attachment IDs
-    /// This ensures that adding new media to an existing toot will be processed
+    /// Generate a unique key for an edit based on toot ID, edit timestamp and media attachment IDs
+    /// This ensures that adding new media to an existing toot will be processed, and that an
+    /// edit re-delivered on reconnect is skipped while a newer edit of the same toot is not
     fn generate_edit_key(&self, toot: &TootEvent) -> String {
         let mut media_ids: Vec<String> = toot
             .media_attachments
@@ -253,14 +749,892 @@ impl TootStreamHandler {
             .map(|m| m.id.clone())
             .collect();
         media_ids.sort(); // Ensure consistent ordering
-        format!("{}:{}", toot.id, media_ids.join(","))
+        match toot.edited_at {
+            Some(edited_at) => format!(
+                "{}@{}:{}",
+                toot.id,
+                edited_at.to_rfc3339(),
+                media_ids.join(",")
+            ),
+            None => format!("{}:{}", toot.id, media_ids.join(",")),
+        }
     }
 
     /// Get statistics about processed toots
     #[allow(dead_code)] // Public API method, may be used in future
     pub fn get_processing_stats(&self) -> ProcessingStats {
         ProcessingStats {
-            processed_toots_count: self.processed_toots.len(),
+            processed_toots_count: self.processed_toots.lock().unwrap().len(),
+            ..ProcessingStats::default()
         }
     }
 }
+
+/// Span wrapping everything logged while a toot is handled, including OpenRouter retries
+///
+/// The correlation id tells apart interleaved log lines of concurrently processed toots and
+/// separate attempts at the same toot (e.g. an edit or `--reprocess-failed` run).
+fn toot_span(toot_id: &str) -> tracing::Span {
+    tracing::info_span!(
+        "toot",
+        correlation_id = %format!("{:08x}", fastrand::u32(..)),
+        toot_id = %toot_id
+    )
+}
+
+/// Resolves once shutdown is requested (or its sender is gone), never without a receiver
+async fn shutdown_requested(shutdown: &mut Option<watch::Receiver<bool>>) {
+    match shutdown {
+        Some(shutdown) => {
+            let _ = shutdown.wait_for(|stop| *stop).await;
+        }
+        None => std::future::pending().await,
+    }
+}
+
+/// Read toots from `stream` into the queue until shutdown or an unrecoverable error
+///
+/// Lost connections are re-established by the client, so only other errors end the listener.
+async fn listen_for_toots(
+    mut stream: MastodonClient,
+    sender: TootSender,
+    mut shutdown: Option<watch::Receiver<bool>>,
+) -> Result<(), AlternatorError> {
+    loop {
+        // Listen for toot events, giving up on waiting once shutdown is requested
+        let event = match shutdown.as_mut() {
+            Some(shutdown) => tokio::select! {
+                biased;
+                _ = shutdown.wait_for(|stop| *stop) => return Ok(()),
+                event = stream.listen() => event,
+            },
+            None => stream.listen().await,
+        };
+
+        match event {
+            Ok(Some(toot)) => sender.push(toot).await,
+            Ok(None) => {
+                // No toot received, continue listening
+                debug!("No toot received, continuing to listen");
+            }
+            Err(e @ (MastodonError::Disconnected(_) | MastodonError::ConnectionFailed(_))) => {
+                error!("Error listening for toots: {}", e);
+                warn!("Connection lost, will attempt to reconnect");
+            }
+            Err(e) => {
+                error!("Error listening for toots: {}", e);
+                return Err(AlternatorError::Mastodon(e));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+    use crate::mastodon::{Account, MediaAttachment};
+    use crate::openrouter::{MockOpenRouterClient, OpenRouterClient, MOCK_MODEL};
+    use crate::test_support::{local_media_processor, MockHttpServer, MockResponse};
+    use chrono::Utc;
+
+    fn create_test_handler(state_file: Option<&std::path::Path>) -> TootStreamHandler {
+        create_test_handler_for("https://mastodon.example", state_file)
+    }
+
+    fn create_test_handler_for(
+        instance_url: &str,
+        state_file: Option<&std::path::Path>,
+    ) -> TootStreamHandler {
+        let mut config: Config = toml::from_str(&format!(
+            r#"
+[mastodon]
+instance_url = "{instance_url}"
+access_token = "token"
+
+[openrouter]
+api_key = "key"
+base_url = "{instance_url}/openrouter"
+"#
+        ))
+        .unwrap();
+        config.mastodon.state_file = state_file.map(|p| p.to_string_lossy().to_string());
+
+        TootStreamHandler::new(
+            MastodonClient::new(config.mastodon.clone()),
+            OpenRouterClient::new(config.openrouter.clone()),
+            local_media_processor(),
+            LanguageDetector::new(),
+            RuntimeConfig::new(config),
+        )
+    }
+
+    #[test]
+    fn test_processed_cache_evicts_least_recently_seen() {
+        let config: Config = toml::from_str(
+            r#"
+[mastodon]
+instance_url = "https://mastodon.example"
+access_token = "token"
+processed_cache_size = 3
+
+[openrouter]
+api_key = "key"
+"#,
+        )
+        .unwrap();
+        let handler = TootStreamHandler::new(
+            MastodonClient::new(config.mastodon.clone()),
+            OpenRouterClient::new(config.openrouter.clone()),
+            local_media_processor(),
+            LanguageDetector::new(),
+            RuntimeConfig::new(config),
+        );
+
+        for toot_id in ["1", "2", "3"] {
+            handler.mark_as_processed(toot_id.to_string());
+        }
+        // Seeing "1" again keeps it fresh, so "2" is now the oldest
+        assert!(handler.is_already_processed("1"));
+
+        handler.mark_as_processed("4".to_string());
+
+        assert!(handler.is_already_processed("1"));
+        assert!(!handler.is_already_processed("2"));
+        assert!(handler.is_already_processed("3"));
+        assert!(handler.is_already_processed("4"));
+        assert_eq!(handler.get_processing_stats().processed_toots_count, 3);
+    }
+
+    #[test]
+    fn test_edit_dedup_keyed_on_edited_at() {
+        let handler = create_test_handler(None);
+        let mut edit = create_test_toot("111", Some("https://example.com/a.png".to_string()));
+        edit.is_edit = true;
+        edit.edited_at = Some("2024-05-01T10:00:00Z".parse().unwrap());
+        handler.mark_edit_as_processed(&edit);
+
+        // The same edit re-delivered after a reconnect is skipped
+        let redelivered = edit.clone();
+        assert!(handler.is_edit_already_processed(&redelivered));
+
+        // A newer edit with the same media is processed again
+        let mut newer = edit.clone();
+        newer.edited_at = Some("2024-05-01T10:05:00Z".parse().unwrap());
+        assert!(!handler.is_edit_already_processed(&newer));
+    }
+
+    #[test]
+    fn test_processed_ids_persist_across_handlers() {
+        let dir = tempfile::tempdir().unwrap();
+        let state_file = dir.path().join("processed.txt");
+
+        {
+            let handler = create_test_handler(Some(&state_file));
+            assert!(!handler.is_already_processed("111"));
+            handler.mark_as_processed("111".to_string());
+        }
+
+        let handler = create_test_handler(Some(&state_file));
+        assert!(handler.is_already_processed("111"));
+        assert!(!handler.is_already_processed("222"));
+    }
+
+    #[test]
+    fn test_processed_ids_not_persisted_without_state_file() {
+        let handler = create_test_handler(None);
+        handler.mark_as_processed("111".to_string());
+        assert!(handler.processed_store.is_none());
+
+        let handler = create_test_handler(None);
+        assert!(!handler.is_already_processed("111"));
+    }
+
+    fn create_test_toot(id: &str, media_url: Option<String>) -> TootEvent {
+        TootEvent {
+            id: id.to_string(),
+            uri: format!("https://mastodon.example/users/testuser/statuses/{id}"),
+            account: Account {
+                id: "user_1".to_string(),
+                username: "testuser".to_string(),
+                acct: "testuser".to_string(),
+                display_name: "Test User".to_string(),
+                url: "https://mastodon.example/@testuser".to_string(),
+            },
+            content: "A picture".to_string(),
+            language: Some("en".to_string()),
+            media_attachments: media_url
+                .into_iter()
+                .map(|url| MediaAttachment {
+                    id: format!("{id}_media"),
+                    media_type: "image".to_string(),
+                    url,
+                    preview_url: None,
+                    description: None,
+                    meta: None,
+                })
+                .collect(),
+            created_at: Utc::now(),
+            url: None,
+            visibility: "public".to_string(),
+            in_reply_to_id: None,
+            in_reply_to_account_id: None,
+            mentions: Vec::new(),
+            sensitive: false,
+            spoiler_text: String::new(),
+            tags: Vec::new(),
+            emojis: Vec::new(),
+            poll: None,
+            filtered: Vec::new(),
+            edited_at: None,
+            is_edit: false,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_slow_toot_times_out_and_handler_moves_on() {
+        let server = MockHttpServer::start(|request| {
+            if request.path.starts_with("/media/") {
+                MockResponse::bytes(200, "image/png", Vec::new())
+                    .with_delay(Duration::from_secs(10))
+            } else {
+                MockResponse::json(404, r#"{"error":"Record not found"}"#)
+            }
+        })
+        .await;
+
+        let mut handler = create_test_handler_for(&server.url(), None);
+        handler.processing_timeout = Duration::from_millis(200);
+
+        let slow_toot = create_test_toot("slow", Some(format!("{}/media/slow.png", server.url())));
+        let started = std::time::Instant::now();
+        handler.handle_toot(&slow_toot).await.unwrap();
+
+        assert!(started.elapsed() < Duration::from_secs(5));
+        // The pipeline never got past the media download
+        assert!(server
+            .requests()
+            .iter()
+            .all(|r| r.method == "GET" && !r.path.starts_with("/openrouter/")));
+        // Timed-out toots stay eligible for a retry
+        assert!(!handler.is_already_processed("slow"));
+
+        // The next toot is handled normally
+        let next_toot = create_test_toot("next", None);
+        handler.handle_toot(&next_toot).await.unwrap();
+        assert!(handler.is_already_processed("next"));
+    }
+
+    #[tokio::test]
+    async fn test_failed_toot_recorded_in_failed_toots_file() {
+        let server =
+            MockHttpServer::start(|_| MockResponse::json(404, r#"{"error":"Record not found"}"#))
+                .await;
+        let dir = tempfile::tempdir().unwrap();
+        let failed_file = dir.path().join("failed.jsonl");
+        let mut handler = create_test_handler_for(&server.url(), None);
+        handler.failed_toots_file = Some(failed_file.clone());
+
+        let toot = create_test_toot("broken", Some(format!("{}/media/a.png", server.url())));
+        handler.handle_toot(&toot).await.unwrap();
+        assert!(handler.is_already_processed("broken"));
+
+        let recorded = failed::load(&failed_file).unwrap();
+        assert_eq!(recorded.len(), 1);
+        assert_eq!(recorded[0].toot_id, "broken");
+        assert!(!recorded[0].edit);
+        assert!(!recorded[0].error.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_queued_toots_kept_on_shutdown() {
+        let dir = tempfile::tempdir().unwrap();
+        let failed_file = dir.path().join("failed.jsonl");
+        let mut handler = create_test_handler_for("http://127.0.0.1:9", None);
+        handler.failed_toots_file = Some(failed_file.clone());
+
+        let (sender, mut receiver) = queue::toot_queue(5, QueueOverflow::Block);
+        sender.push(create_test_toot("queued_1", None)).await;
+        sender.push(create_test_toot("queued_2", None)).await;
+        handler.record_unprocessed(&receiver.drain().await);
+
+        let recorded = failed::load(&failed_file).unwrap();
+        let ids: Vec<&str> = recorded.iter().map(|f| f.toot_id.as_str()).collect();
+        assert_eq!(ids, ["queued_1", "queued_2"]);
+        assert!(recorded[0].error.contains("shutdown"));
+    }
+
+    #[tokio::test]
+    async fn test_reprocess_failed_consumes_file() {
+        let status = serde_json::to_string(&create_test_toot("fixed", None)).unwrap();
+        let server = MockHttpServer::start(move |request| match request.path.as_str() {
+            "/api/v1/statuses/fixed" => MockResponse::json(200, &status),
+            "/api/v1/accounts/verify_credentials" => MockResponse::json(
+                200,
+                r#"{"id":"user_1","username":"testuser","acct":"testuser","display_name":"Test User","url":"https://mastodon.example/@testuser"}"#,
+            ),
+            _ => MockResponse::json(404, r#"{"error":"Record not found"}"#),
+        })
+        .await;
+        let dir = tempfile::tempdir().unwrap();
+        let failed_file = dir.path().join("failed.jsonl");
+        failed::record(&failed_file, &FailedToot::new("fixed", false, "HTTP 502"));
+        failed::record(&failed_file, &FailedToot::new("deleted", false, "HTTP 502"));
+
+        let mut handler = create_test_handler_for(&server.url(), None);
+        handler.failed_toots_file = Some(failed_file.clone());
+        handler.reprocess_failed().await.unwrap();
+
+        // Only the toot that failed again is left
+        let remaining = failed::load(&failed_file).unwrap();
+        assert_eq!(remaining.len(), 1);
+        assert_eq!(remaining[0].toot_id, "deleted");
+        assert!(handler.is_already_processed("fixed"));
+
+        handler.failed_toots_file = Some(dir.path().join("missing.jsonl"));
+        handler.reprocess_failed().await.unwrap();
+        handler.failed_toots_file = None;
+        assert!(handler.reprocess_failed().await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_edit_clearing_description_is_reprocessed() {
+        let server =
+            MockHttpServer::start(|_| MockResponse::json(404, r#"{"error":"Record not found"}"#))
+                .await;
+        let handler = create_test_handler_for(&server.url(), None);
+        let media_requests = || {
+            server
+                .requests()
+                .iter()
+                .filter(|r| r.path.starts_with("/media/"))
+                .count()
+        };
+
+        let mut described = create_test_toot("111", Some(format!("{}/media/a.png", server.url())));
+        described.is_edit = true;
+        described.media_attachments[0].description = Some("A cat on a sofa".to_string());
+        handler.handle_toot(&described).await.unwrap();
+        // Already described, nothing to download
+        assert_eq!(media_requests(), 0);
+
+        // The user clears the alt text; the media ID is unchanged
+        let mut cleared = described.clone();
+        cleared.media_attachments[0].description = Some(String::new());
+        handler.handle_toot(&cleared).await.unwrap();
+        assert_eq!(media_requests(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_new_toot_delay_applied_to_new_toots_only() {
+        let server =
+            MockHttpServer::start(|_| MockResponse::json(404, r#"{"error":"Record not found"}"#))
+                .await;
+        let mut handler = create_test_handler_for(&server.url(), None);
+        handler.new_toot_delay = Duration::from_millis(300);
+
+        let toot = create_test_toot("new", Some(format!("{}/media/a.png", server.url())));
+        let started = std::time::Instant::now();
+        handler.handle_toot(&toot).await.unwrap();
+        assert!(started.elapsed() >= Duration::from_millis(300));
+
+        let mut edit = create_test_toot("edited", Some(format!("{}/media/b.png", server.url())));
+        edit.is_edit = true;
+        let started = std::time::Instant::now();
+        handler.handle_toot(&edit).await.unwrap();
+        assert!(started.elapsed() < Duration::from_millis(300));
+    }
+
+    #[tokio::test]
+    async fn test_new_toot_delay_interrupted_by_shutdown() {
+        let server =
+            MockHttpServer::start(|_| MockResponse::json(404, r#"{"error":"Record not found"}"#))
+                .await;
+        let dir = tempfile::tempdir().unwrap();
+        let failed_file = dir.path().join("failed.jsonl");
+        let mut handler = create_test_handler_for(&server.url(), None);
+        handler.failed_toots_file = Some(failed_file.clone());
+        handler.new_toot_delay = Duration::from_secs(3600);
+        let (shutdown_tx, shutdown_rx) = watch::channel(false);
+        handler.set_shutdown_receiver(shutdown_rx);
+
+        let toot = create_test_toot("delayed", Some(format!("{}/media/a.png", server.url())));
+        let stop = async {
+            tokio::time::sleep(Duration::from_millis(50)).await;
+            shutdown_tx.send(true).unwrap();
+        };
+        let (result, ()) = tokio::time::timeout(
+            Duration::from_secs(5),
+            futures_util::future::join(handler.handle_toot(&toot), stop),
+        )
+        .await
+        .expect("shutdown should cut the delay short");
+        result.unwrap();
+
+        // Nothing was downloaded and the toot is left for --reprocess-failed
+        assert!(server.requests().is_empty());
+        assert!(!handler.is_already_processed("delayed"));
+        let recorded = failed::load(&failed_file).unwrap();
+        assert_eq!(recorded.len(), 1);
+        assert_eq!(recorded[0].toot_id, "delayed");
+    }
+
+    #[tokio::test]
+    async fn test_handler_with_mock_provider_updates_toot() {
+        let mut png = Vec::new();
+        image::DynamicImage::ImageRgb8(image::RgbImage::from_pixel(
+            8,
+            8,
+            image::Rgb([200, 30, 30]),
+        ))
+        .write_to(&mut std::io::Cursor::new(&mut png), image::ImageFormat::Png)
+        .unwrap();
+
+        // The instance reports the same attachment, so the edit doesn't look like a race
+        let status = serde_json::to_string(&create_test_toot(
+            "toot_1",
+            Some("https://mastodon.example/media/a.png".to_string()),
+        ))
+        .unwrap();
+        let server = MockHttpServer::start(move |request| {
+            match (request.method.as_str(), request.path.as_str()) {
+                (_, path) if path.starts_with("/media/") => {
+                    MockResponse::bytes(200, "image/png", png.clone())
+                }
+                ("GET", "/api/v1/statuses/toot_1/source") => MockResponse::json(
+                    200,
+                    r#"{"id":"toot_1","text":"A picture","spoiler_text":""}"#,
+                ),
+                ("GET", "/api/v1/statuses/toot_1") | ("PUT", "/api/v1/statuses/toot_1") => {
+                    MockResponse::json(200, &status)
+                }
+                _ => MockResponse::json(404, r#"{"error":"Record not found"}"#),
+            }
+        })
+        .await;
+
+        let mut handler = create_test_handler_for(&server.url(), None);
+        handler.description_provider =
+            Box::new(MockOpenRouterClient::new().with_description("A red square".to_string()));
+
+        let toot = create_test_toot("toot_1", Some(format!("{}/media/a.png", server.url())));
+        handler.handle_toot(&toot).await.unwrap();
+
+        let requests = server.requests();
+        let edit = requests
+            .iter()
+            .find(|r| r.method == "PUT")
+            .expect("status should be edited");
+        let body = String::from_utf8_lossy(&edit.body);
+        assert!(body.contains("A+red+square"));
+        assert!(body.contains(&MOCK_MODEL.replace('/', "%2F")));
+        // No description was requested from OpenRouter
+        assert!(!requests.iter().any(|r| r.path.starts_with("/openrouter/")));
+        assert!(handler.is_already_processed("toot_1"));
+    }
+
+    #[tokio::test]
+    async fn test_echo_of_own_edit_not_described_again() {
+        let mut png = Vec::new();
+        image::DynamicImage::ImageRgb8(image::RgbImage::from_pixel(
+            8,
+            8,
+            image::Rgb([200, 30, 30]),
+        ))
+        .write_to(&mut std::io::Cursor::new(&mut png), image::ImageFormat::Png)
+        .unwrap();
+
+        let status = serde_json::to_string(&create_test_toot(
+            "toot_1",
+            Some("https://mastodon.example/media/a.png".to_string()),
+        ))
+        .unwrap();
+        let server = MockHttpServer::start(move |request| {
+            match (request.method.as_str(), request.path.as_str()) {
+                (_, path) if path.starts_with("/media/") => {
+                    MockResponse::bytes(200, "image/png", png.clone())
+                }
+                ("POST", "/openrouter/chat/completions") => MockResponse::json(
+                    200,
+                    r#"{"choices":[{"message":{"content":"A red square"},"finish_reason":"stop"}]}"#,
+                ),
+                ("GET", "/api/v1/statuses/toot_1/source") => MockResponse::json(
+                    200,
+                    r#"{"id":"toot_1","text":"A picture","spoiler_text":""}"#,
+                ),
+                ("GET", "/api/v1/statuses/toot_1") | ("PUT", "/api/v1/statuses/toot_1") => {
+                    MockResponse::json(200, &status)
+                }
+                _ => MockResponse::json(404, r#"{"error":"Record not found"}"#),
+            }
+        })
+        .await;
+
+        let mut handler = create_test_handler_for(&server.url(), None);
+        handler.media_processor =
+            MediaProcessor::with_unified_transformer(crate::media::MediaConfig {
+                reprocess_existing_descriptions: true,
+                ..crate::test_support::local_media_config()
+            });
+
+        let mut toot = create_test_toot("toot_1", Some(format!("{}/media/a.png", server.url())));
+        handler.handle_toot_event(&toot).await.unwrap();
+
+        let requests = server.requests();
+        let edit = requests
+            .iter()
+            .find(|r| r.method == "PUT")
+            .expect("status should be edited");
+        let written = url::form_urlencoded::parse(&edit.body)
+            .find(|(key, value)| key.contains("description") && value.contains("A red square"))
+            .map(|(_, value)| value.into_owned())
+            .expect("description in edit");
+
+        // The stream reports Alternator's own edit back as a `status.update`
+        toot.media_attachments[0].description = Some(written);
+        toot.edited_at = Some(Utc::now());
+        toot.is_edit = true;
+        handler.handle_toot_event(&toot).await.unwrap();
+
+        let completions = server
+            .requests()
+            .iter()
+            .filter(|r| r.path == "/openrouter/chat/completions")
+            .count();
+        assert_eq!(completions, 1);
+        assert_eq!(
+            server
+                .requests()
+                .iter()
+                .filter(|r| r.method == "PUT")
+                .count(),
+            1
+        );
+    }
+
+    #[tokio::test]
+    async fn test_inter_toot_delay_applied_between_toots() {
+        let mut handler = create_test_handler(None);
+        handler.inter_toot_delay = Duration::from_millis(300);
+
+        let started = std::time::Instant::now();
+        handler
+            .handle_toot(&create_test_toot("first", None))
+            .await
+            .unwrap();
+        // Nothing was processed before, so the first toot starts right away
+        assert!(started.elapsed() < Duration::from_millis(300));
+
+        handler
+            .handle_toot(&create_test_toot("second", None))
+            .await
+            .unwrap();
+        assert!(started.elapsed() >= Duration::from_millis(300));
+        assert!(handler.is_already_processed("first"));
+        assert!(handler.is_already_processed("second"));
+    }
+
+    #[tokio::test]
+    async fn test_toot_logs_carry_correlation_id() {
+        use std::sync::{Arc, Mutex};
+
+        #[derive(Clone, Default)]
+        struct Capture(Arc<Mutex<Vec<u8>>>);
+
+        impl std::io::Write for Capture {
+            fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+                self.0.lock().unwrap().extend_from_slice(buf);
+                Ok(buf.len())
+            }
+
+            fn flush(&mut self) -> std::io::Result<()> {
+                Ok(())
+            }
+        }
+
+        let capture = Capture::default();
+        let writer = capture.clone();
+        let subscriber = tracing_subscriber::fmt()
+            .with_writer(move || writer.clone())
+            .with_ansi(false)
+            .with_max_level(tracing::Level::DEBUG)
+            .finish();
+        let _guard = tracing::subscriber::set_default(subscriber);
+
+        let handler = create_test_handler(None);
+        for id in ["first", "second"] {
+            handler
+                .handle_toot(&create_test_toot(id, None))
+                .await
+                .unwrap();
+        }
+
+        let output = String::from_utf8(capture.0.lock().unwrap().clone()).unwrap();
+        let correlation_ids = |toot_id: &str| {
+            let lines: Vec<&str> = output
+                .lines()
+                .filter(|line| line.contains(&format!("toot_id={toot_id}")))
+                .collect();
+            // Handler and processor lines for the toot all sit in its span
+            assert!(lines.iter().any(|line| line.contains("process_toot")));
+            assert!(lines
+                .iter()
+                .any(|line| line.contains(&format!("Successfully processed toot: {toot_id}"))));
+            lines
+                .iter()
+                .map(|line| {
+                    let start = line.find("correlation_id=").expect("line without id") + 15;
+                    line[start..start + 8].to_string()
+                })
+                .collect::<std::collections::HashSet<_>>()
+        };
+
+        let first = correlation_ids("first");
+        let second = correlation_ids("second");
+        assert_eq!(first.len(), 1, "one stable id per toot: {first:?}");
+        assert_eq!(second.len(), 1, "one stable id per toot: {second:?}");
+        assert_ne!(first, second);
+    }
+
+    #[tokio::test]
+    async fn test_pings_answered_while_processing_is_backed_up() {
+        use futures_util::{SinkExt, StreamExt};
+        use tokio_tungstenite::tungstenite::Message;
+
+        let api =
+            MockHttpServer::start(|_| MockResponse::json(404, r#"{"error":"Record not found"}"#))
+                .await;
+        let config: Config = toml::from_str(&format!(
+            r#"
+[mastodon]
+instance_url = "{}"
+access_token = "token"
+queue_capacity = 1
+queue_overflow = "drop_oldest"
+
+[openrouter]
+api_key = "key"
+"#,
+            api.url()
+        ))
+        .unwrap();
+        let mut handler = TootStreamHandler::new(
+            MastodonClient::new(config.mastodon.clone()),
+            OpenRouterClient::new(config.openrouter.clone()),
+            local_media_processor(),
+            LanguageDetector::new(),
+            RuntimeConfig::new(config.clone()),
+        );
+        // The first toot with media waits an hour, so everything after it backs up
+        handler.new_toot_delay = Duration::from_secs(3600);
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let media_base = api.url();
+        let (pong_tx, pong_rx) = tokio::sync::oneshot::channel();
+        let server = tokio::spawn(async move {
+            let (socket, _) = listener.accept().await.unwrap();
+            let mut ws = tokio_tungstenite::accept_async(socket).await.unwrap();
+            for id in ["1", "2", "3", "4"] {
+                let toot = create_test_toot(id, Some(format!("{media_base}/media/{id}.png")));
+                let event = serde_json::json!({
+                    "event": "update",
+                    "payload": serde_json::to_string(&toot).unwrap(),
+                });
+                ws.send(Message::Text(event.to_string().into()))
+                    .await
+                    .unwrap();
+            }
+            ws.send(Message::Ping(b"alive".to_vec().into()))
+                .await
+                .unwrap();
+            while let Some(Ok(message)) = ws.next().await {
+                if let Message::Pong(data) = message {
+                    let _ = pong_tx.send(data.to_vec());
+                    break;
+                }
+            }
+            std::future::pending::<()>().await;
+        });
+
+        let (ws, _) = tokio_tungstenite::connect_async(format!("ws://{addr}/"))
+            .await
+            .unwrap();
+        let mut stream = MastodonClient::new(config.mastodon.clone());
+        stream.attach_websocket("user_1", ws);
+
+        let (shutdown_tx, shutdown_rx) = watch::channel(false);
+        handler.set_shutdown_receiver(shutdown_rx);
+        let processing = tokio::spawn(async move { handler.process_stream(stream).await });
+
+        let pong = tokio::time::timeout(Duration::from_secs(5), pong_rx)
+            .await
+            .expect("ping should be answered while the processor is busy")
+            .unwrap();
+        assert_eq!(pong, b"alive");
+        assert!(!processing.is_finished());
+
+        shutdown_tx.send(true).unwrap();
+        processing.abort();
+        server.abort();
+    }
+
+    #[tokio::test]
+    async fn test_workers_process_toots_concurrently_in_per_toot_order() {
+        use futures_util::SinkExt;
+        use tokio_tungstenite::tungstenite::Message;
+
+        let mut png = Vec::new();
+        image::DynamicImage::ImageRgb8(image::RgbImage::from_pixel(
+            8,
+            8,
+            image::Rgb([200, 30, 30]),
+        ))
+        .write_to(&mut std::io::Cursor::new(&mut png), image::ImageFormat::Png)
+        .unwrap();
+
+        let statuses: Arc<Mutex<Vec<(String, String)>>> = Arc::default();
+        let server_statuses = statuses.clone();
+        let api = MockHttpServer::start(move |request| {
+            let path = request.path.as_str();
+            if path.starts_with("/media/") {
+                // Slow downloads keep each toot busy long enough to overlap
+                return MockResponse::bytes(200, "image/png", png.clone())
+                    .with_delay(Duration::from_millis(300));
+            }
+            if path == "/openrouter/chat/completions" {
+                return MockResponse::json(
+                    200,
+                    r#"{"choices":[{"message":{"content":"A red square"},"finish_reason":"stop"}]}"#,
+                );
+            }
+            let id = path
+                .trim_start_matches("/api/v1/statuses/")
+                .trim_end_matches("/source");
+            if path.ends_with("/source") {
+                return MockResponse::json(
+                    200,
+                    &format!(r#"{{"id":"{id}","text":"A picture","spoiler_text":""}}"#),
+                );
+            }
+            match server_statuses
+                .lock()
+                .unwrap()
+                .iter()
+                .find(|(status_id, _)| status_id == id)
+            {
+                Some((_, status)) => MockResponse::json(200, status),
+                None => MockResponse::json(404, r#"{"error":"Record not found"}"#),
+            }
+        })
+        .await;
+
+        let toot_a = create_test_toot("a", Some(format!("{}/media/a.png", api.url())));
+        let toot_b = create_test_toot("b", Some(format!("{}/media/b.png", api.url())));
+        let mut edit_a = toot_a.clone();
+        edit_a.edited_at = Some(Utc::now());
+        for toot in [&toot_a, &toot_b] {
+            statuses
+                .lock()
+                .unwrap()
+                .push((toot.id.clone(), serde_json::to_string(toot).unwrap()));
+        }
+
+        let config: Config = toml::from_str(&format!(
+            r#"
+[mastodon]
+instance_url = "{0}"
+access_token = "token"
+workers = 3
+
+[openrouter]
+api_key = "key"
+base_url = "{0}/openrouter"
+"#,
+            api.url()
+        ))
+        .unwrap();
+        let mut handler = TootStreamHandler::new(
+            MastodonClient::new(config.mastodon.clone()),
+            OpenRouterClient::new(config.openrouter.clone()),
+            local_media_processor(),
+            LanguageDetector::new(),
+            RuntimeConfig::new(config.clone()),
+        );
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let server = tokio::spawn(async move {
+            let (socket, _) = listener.accept().await.unwrap();
+            let mut ws = tokio_tungstenite::accept_async(socket).await.unwrap();
+            for (event, toot) in [
+                ("update", toot_a),
+                ("update", toot_b),
+                ("status.update", edit_a),
+            ] {
+                let event = serde_json::json!({
+                    "event": event,
+                    "payload": serde_json::to_string(&toot).unwrap(),
+                });
+                ws.send(Message::Text(event.to_string().into()))
+                    .await
+                    .unwrap();
+            }
+            std::future::pending::<()>().await;
+        });
+
+        let (ws, _) = tokio_tungstenite::connect_async(format!("ws://{addr}/"))
+            .await
+            .unwrap();
+        let mut stream = MastodonClient::new(config.mastodon.clone());
+        stream.attach_websocket("user_1", ws);
+
+        let (shutdown_tx, shutdown_rx) = watch::channel(false);
+        handler.set_shutdown_receiver(shutdown_rx);
+        let status_updates = |path: &str| {
+            api.requests()
+                .iter()
+                .filter(|r| r.method == "PUT" && r.path == path)
+                .count()
+        };
+        let processing = handler.process_stream(stream);
+        tokio::pin!(processing);
+        tokio::time::timeout(Duration::from_secs(10), async {
+            loop {
+                tokio::select! {
+                    result = &mut processing => panic!("processing stopped: {result:?}"),
+                    _ = tokio::time::sleep(Duration::from_millis(20)) => {}
+                }
+                if status_updates("/api/v1/statuses/a") == 2
+                    && status_updates("/api/v1/statuses/b") == 1
+                {
+                    break;
+                }
+            }
+        })
+        .await
+        .expect("both toots and the edit should be processed");
+        shutdown_tx.send(true).unwrap();
+        processing.await.unwrap();
+        server.abort();
+
+        let requests = api.requests();
+        let position = |method: &str, path: &str, nth: usize| {
+            requests
+                .iter()
+                .enumerate()
+                .filter(|(_, r)| r.method == method && r.path == path)
+                .nth(nth)
+                .map(|(i, _)| i)
+                .unwrap_or_else(|| panic!("no request #{nth} {method} {path}"))
+        };
+        let toot_a_updated = position("PUT", "/api/v1/statuses/a", 0);
+        // Toot b was started while toot a was still being processed
+        assert!(position("GET", "/media/b.png", 0) < toot_a_updated);
+        // The edit of toot a waited for toot a, even with a worker free; every pipeline
+        // downloads the media twice (original and analysis copy)
+        assert!(position("GET", "/media/a.png", 2) > toot_a_updated);
+        assert!(handler.is_already_processed("a"));
+        assert!(handler.is_already_processed("b"));
+    }
+}
@@ -1,8 +1,15 @@
 pub mod coordinator;
+pub mod describer;
+pub mod failed;
 pub mod handler;
 pub mod processor;
+pub mod queue;
 pub mod race;
+pub mod state;
 pub mod stats;
 
 // Re-export the main struct for backward compatibility
 pub use handler::TootStreamHandler;
+
+#[allow(unused_imports)] // Library API for embedding, not used by the binary
+pub use describer::{DescriptionOutput, MediaDescriber};
@@ -1,9 +1,15 @@
 use crate::config::RuntimeConfig;
 use crate::error::AlternatorError;
-use crate::language::LanguageDetector;
-use crate::mastodon::{MastodonClient, MediaAttachment, MediaRecreation, TootEvent};
+use crate::events::{DescribedMedia, TootResultEvent};
+use crate::language::{LanguageDetector, MediaKind, ATTRIBUTION_SEPARATOR};
+use crate::mastodon::{
+    MastodonClient, MastodonStream, MediaAttachment, MediaRecreation, TootEvent,
+};
+use crate::media::audio::TranscriptSummary;
 use crate::media::MediaProcessor;
-use crate::openrouter::OpenRouterClient;
+use crate::openrouter::DescriptionProvider;
+use futures_util::stream::{self, StreamExt};
+use std::borrow::Cow;
 use tracing::{debug, error, info, warn};
 
 /// Strategy pattern for processing different media types
@@ -17,6 +23,8 @@ trait MediaProcessingStrategy: Send + Sync {
         &self,
         media: &MediaAttachment,
         media_processor: &MediaProcessor,
+        description_provider: &dyn DescriptionProvider,
+        templates: &DescriptionTemplates<'_>,
         config: &RuntimeConfig,
     ) -> Result<Option<MediaRecreation>, AlternatorError>;
 }
@@ -34,6 +42,8 @@ impl MediaProcessingStrategy for AudioProcessingStrategy {
         &self,
         media: &MediaAttachment,
         media_processor: &MediaProcessor,
+        description_provider: &dyn DescriptionProvider,
+        templates: &DescriptionTemplates<'_>,
         config: &RuntimeConfig,
     ) -> Result<Option<MediaRecreation>, AlternatorError> {
         // Check if audio processing is enabled
@@ -62,9 +72,10 @@ impl MediaProcessingStrategy for AudioProcessingStrategy {
         // Transcribe the audio to get description
         let transcript = match crate::media::process_audio_for_transcript(
             media,
+            media_processor,
             config.config().whisper(),
             config.config().media(),
-            Some(&config.config().openrouter),
+            &templates.transcript_summary(description_provider),
         )
         .await
         {
@@ -82,21 +93,36 @@ impl MediaProcessingStrategy for AudioProcessingStrategy {
                 );
                 return Ok(None);
             }
+            Err(e @ crate::error::MediaError::TooShort { .. }) => {
+                info!("Skipping audio {}: {}", media.id, e);
+                return Ok(None);
+            }
             Err(e) => {
                 error!("Failed to transcribe audio {}: {}", media.id, e);
                 return Err(AlternatorError::Media(e));
             }
         };
 
+        let (transcript, full_transcript) = split_long_transcript(
+            transcript,
+            &templates.transcript_summary(description_provider),
+        )
+        .await;
+
         // Determine appropriate file extension for audio
         let extension = get_audio_file_extension(&media.media_type);
         let filename = format!("audio_{}.{}", media.id, extension);
 
         Ok(Some(MediaRecreation {
+            original_media_id: media.id.clone(),
             data: original_audio_data,
             description: transcript,
             media_type: media.media_type.clone(),
             filename,
+            focus: media.meta.as_ref().and_then(|meta| meta.focus),
+            replaced_description: media.description.clone(),
+            model: None,
+            full_transcript,
         }))
     }
 }
@@ -107,13 +133,15 @@ struct VideoProcessingStrategy;
 #[async_trait::async_trait]
 impl MediaProcessingStrategy for VideoProcessingStrategy {
     fn can_handle(&self, media_type: &str) -> bool {
-        media_type.to_lowercase().starts_with("video")
+        media_type.to_lowercase().starts_with("video") || crate::media::is_gifv(media_type)
     }
 
     async fn process_media(
         &self,
         media: &MediaAttachment,
         media_processor: &MediaProcessor,
+        description_provider: &dyn DescriptionProvider,
+        templates: &DescriptionTemplates<'_>,
         config: &RuntimeConfig,
     ) -> Result<Option<MediaRecreation>, AlternatorError> {
         debug!(
@@ -121,8 +149,25 @@ impl MediaProcessingStrategy for VideoProcessingStrategy {
             media.id, media.media_type
         );
 
+        // Uploaded GIFs have no soundtrack; their keyframe is all there is to describe
+        let silent = crate::media::is_gifv(&media.media_type);
+
         // Check if audio processing is enabled (required for video transcription)
-        if !config.is_audio_enabled() {
+        if !silent && !config.is_audio_enabled() {
+            // Without FFmpeg/Whisper the server-generated thumbnail still shows what the video is about
+            if media.preview_url.is_some() {
+                info!(
+                    "Audio processing unavailable, describing video {} from its preview image",
+                    media.id
+                );
+                return describe_video_preview(
+                    media,
+                    media_processor,
+                    description_provider,
+                    templates,
+                )
+                .await;
+            }
             debug!(
                 "Audio processing disabled, skipping video file: {} ({})",
                 media.id, media.media_type
@@ -148,33 +193,81 @@ impl MediaProcessingStrategy for VideoProcessingStrategy {
                 e
             })?;
 
+        // Describe a representative keyframe so silent clips and visuals are covered too
+        let (visual_description, model) =
+            describe_video_keyframe(media, &original_video_data, description_provider, templates)
+                .await
+                .unzip();
+
         // Transcribe the video audio to get description
-        let transcript = match crate::media::process_video_for_transcript(
-            media,
-            config.config().whisper(),
-            config.config().media(),
-            Some(&config.config().openrouter),
-        )
-        .await
-        {
-            Ok(transcript) => {
-                info!(
-                    "Generated transcript for video {}: {}",
-                    media.id, transcript
-                );
-                transcript
-            }
-            Err(crate::error::MediaError::UnsupportedType { .. }) => {
+        let transcript = if silent {
+            if visual_description.is_none() {
                 warn!(
-                    "Video type {} not supported for transcription, skipping",
-                    media.media_type
+                    "Could not describe GIF video {} from its keyframe, skipping",
+                    media.id
                 );
                 return Ok(None);
             }
-            Err(e) => {
-                error!("Failed to transcribe video {}: {}", media.id, e);
-                return Err(AlternatorError::Media(e));
+            None
+        } else {
+            match crate::media::process_video_for_transcript(
+                media,
+                media_processor,
+                config.config().whisper(),
+                config.config().media(),
+                &templates.transcript_summary(description_provider),
+            )
+            .await
+            {
+                Ok(transcript) => {
+                    info!(
+                        "Generated transcript for video {}: {}",
+                        media.id, transcript
+                    );
+                    Some(transcript)
+                }
+                Err(crate::error::MediaError::UnsupportedType { .. }) => {
+                    warn!(
+                        "Video type {} not supported for transcription, skipping",
+                        media.media_type
+                    );
+                    return Ok(None);
+                }
+                Err(e @ crate::error::MediaError::TooShort { .. }) => {
+                    if visual_description.is_none() {
+                        info!("Skipping video {}: {}", media.id, e);
+                        return Ok(None);
+                    }
+                    info!(
+                        "Not transcribing video {}, using keyframe description only: {}",
+                        media.id, e
+                    );
+                    None
+                }
+                Err(e) if visual_description.is_some() => {
+                    warn!(
+                        "Failed to transcribe video {}, using keyframe description only: {}",
+                        media.id, e
+                    );
+                    None
+                }
+                Err(e) => {
+                    error!("Failed to transcribe video {}: {}", media.id, e);
+                    return Err(AlternatorError::Media(e));
+                }
+            }
+        };
+
+        let (transcript, full_transcript) = match transcript {
+            Some(transcript) => {
+                let (transcript, full_transcript) = split_long_transcript(
+                    transcript,
+                    &templates.transcript_summary(description_provider),
+                )
+                .await;
+                (Some(transcript), full_transcript)
             }
+            None => (None, None),
         };
 
         // Determine appropriate file extension for video
@@ -182,10 +275,19 @@ impl MediaProcessingStrategy for VideoProcessingStrategy {
         let filename = format!("video_{}.{}", media.id, extension);
 
         Ok(Some(MediaRecreation {
+            original_media_id: media.id.clone(),
             data: original_video_data,
-            description: transcript,
+            description: combine_video_description(
+                visual_description,
+                transcript,
+                templates.max_length,
+            ),
             media_type: media.media_type.clone(),
             filename,
+            focus: media.meta.as_ref().and_then(|meta| meta.focus),
+            replaced_description: media.description.clone(),
+            model,
+            full_transcript,
         }))
     }
 }
@@ -201,13 +303,334 @@ impl MediaProcessingStrategy for ImageProcessingStrategy {
 
     async fn process_media(
         &self,
-        _media: &MediaAttachment,
-        _media_processor: &MediaProcessor,
-        _config: &RuntimeConfig,
+        media: &MediaAttachment,
+        media_processor: &MediaProcessor,
+        description_provider: &dyn DescriptionProvider,
+        templates: &DescriptionTemplates<'_>,
+        config: &RuntimeConfig,
     ) -> Result<Option<MediaRecreation>, AlternatorError> {
-        // Images are handled separately in the main processing loop
-        // due to the need for parallel processing
-        Ok(None)
+        let Some(image) =
+            prepare_image(media, media_processor, description_provider, config).await?
+        else {
+            return Ok(None);
+        };
+        describe_prepared_image(image, description_provider, templates)
+            .await
+            .map_err(AlternatorError::OpenRouter)
+    }
+}
+
+/// Download, check and transform an image for description
+///
+/// Returns `None` for images that are skipped (unsupported format or below the minimum size).
+async fn prepare_image(
+    media: &MediaAttachment,
+    media_processor: &MediaProcessor,
+    description_provider: &dyn DescriptionProvider,
+    config: &RuntimeConfig,
+) -> Result<Option<PreparedImage>, AlternatorError> {
+    // The provider fetches public https images itself; nothing to download or transform
+    if description_provider.supports_image_urls() {
+        if let Some(url) = passthrough_url(media) {
+            // The provider fetches the URL, but the host lists still apply to it
+            if let Err(e) = media_processor.check_media_host(&url).await {
+                error!("Refusing to pass media {} to the provider: {}", media.id, e);
+                return Err(e.into());
+            }
+            let image = PreparedImage {
+                media: media.clone(),
+                original_data: Vec::new(),
+                processed_data: Vec::new(),
+                passthrough_url: Some(media.url.clone()),
+                ocr_text: None,
+                animated: false,
+            };
+            return Ok(Some(image));
+        }
+    }
+
+    // Download original image data for recreation
+    let original_data = match media_processor.download_media_for_recreation(media).await {
+        Ok(data) => data,
+        Err(crate::error::MediaError::UnsupportedType { media_type }) => {
+            info!(
+                "Image {} is {}, which is not in the supported formats, skipping",
+                media.id, media_type
+            );
+            return Ok(None);
+        }
+        Err(e) => {
+            error!(
+                "Failed to download media {} for recreation: {}",
+                media.id, e
+            );
+            return Err(e.into());
+        }
+    };
+
+    if let Some((width, height)) = media_processor.data_below_min_dimension(&original_data) {
+        info!(
+            "Skipping image {} ({}x{}): smaller than the minimum image dimension",
+            media.id, width, height
+        );
+        return Ok(None);
+    }
+
+    // Process media for analysis (resized/optimized version)
+    let processed_data = media_processor
+        .process_media_for_analysis(media)
+        .await
+        .map_err(|e| {
+            error!("Failed to process media {} for analysis: {}", media.id, e);
+            e
+        })?;
+
+    let ocr_text = extract_ocr_text(media, &processed_data, config).await;
+
+    Ok(Some(PreparedImage {
+        media: media.clone(),
+        animated: crate::media::is_animated(&original_data),
+        original_data,
+        processed_data,
+        passthrough_url: None,
+        ocr_text,
+    }))
+}
+
+/// Prompt and attribution templates for the toot's language
+struct DescriptionTemplates<'a> {
+    /// Image prompt
+    prompt: &'a str,
+    /// Frames long audio and video transcripts for summarizing
+    audio_prompt: &'a str,
+    /// Describes a video clip from its keyframe
+    video_prompt: &'a str,
+    /// Note added to the image prompt for animation montages
+    animation_note: &'a str,
+    /// `None` when attribution is disabled
+    attribution: Option<&'a str>,
+    /// Maximum description length including the attribution
+    max_length: usize,
+    /// Text of the toot, given to the model as context when `use_toot_text_as_context` is set
+    toot_text: Option<String>,
+    /// Chart/diagram instructions added to the image prompt when the toot has a chart hashtag
+    chart_prompt: Option<&'a str>,
+}
+
+impl<'a> DescriptionTemplates<'a> {
+    /// Look up the prompts for `language`, each falling back to English
+    fn for_language(
+        language_detector: &'a LanguageDetector,
+        language: &str,
+        attribution: Option<&'a str>,
+        max_length: usize,
+    ) -> Result<Self, crate::error::LanguageError> {
+        Ok(Self {
+            prompt: language_detector.get_media_prompt_template(language, MediaKind::Image)?,
+            audio_prompt: language_detector
+                .get_media_prompt_template(language, MediaKind::Audio)?,
+            video_prompt: language_detector
+                .get_media_prompt_template(language, MediaKind::Video)?,
+            animation_note: language_detector.get_animation_note(language),
+            attribution,
+            max_length,
+            toot_text: None,
+            chart_prompt: None,
+        })
+    }
+
+    /// Prompt for a media kind
+    fn prompt_for(&self, kind: MediaKind) -> &'a str {
+        match kind {
+            MediaKind::Image => self.prompt,
+            MediaKind::Audio => self.audio_prompt,
+            MediaKind::Video => self.video_prompt,
+        }
+    }
+
+    /// How over-long audio and video transcripts are summarized by `description_provider`
+    ///
+    /// Video soundtracks are summarized like audio, the keyframe uses the video prompt.
+    fn transcript_summary<'b>(
+        &'b self,
+        description_provider: &'b dyn DescriptionProvider,
+    ) -> TranscriptSummary<'b> {
+        TranscriptSummary {
+            provider: Some(description_provider),
+            prompt: self.audio_prompt,
+            max_length: self.max_length,
+        }
+    }
+
+    /// Image prompt, with the chart instructions when the toot was tagged as a chart
+    fn image_prompt_template(&self) -> Cow<'a, str> {
+        match self.chart_prompt {
+            Some(chart_prompt) => Cow::Owned(format!("{}\n\n{chart_prompt}", self.prompt)),
+            None => Cow::Borrowed(self.prompt),
+        }
+    }
+}
+
+/// Append the attribution for `model`, truncating the description so the total fits the limit
+fn apply_attribution(
+    description: &str,
+    model: &str,
+    attribution: Option<&str>,
+    max_length: usize,
+) -> String {
+    let description = description.trim();
+    let Some(template) = attribution else {
+        return description.to_string();
+    };
+
+    let suffix = format!(
+        "{ATTRIBUTION_SEPARATOR}{}",
+        template.replace("{model}", model)
+    );
+    let available = max_length.saturating_sub(suffix.chars().count());
+
+    if description.chars().count() > available {
+        let truncated = description
+            .chars()
+            .take(available.saturating_sub(3))
+            .collect::<String>();
+        format!("{}...{suffix}", truncated.trim_end())
+    } else {
+        format!("{description}{suffix}")
+    }
+}
+
+/// Extract and describe a representative keyframe of a video, if possible
+///
+/// Returns the description and the model that wrote it.
+async fn describe_video_keyframe(
+    media: &MediaAttachment,
+    video_data: &[u8],
+    description_provider: &dyn DescriptionProvider,
+    templates: &DescriptionTemplates<'_>,
+) -> Option<(String, String)> {
+    let keyframe = match crate::media::process_video_for_keyframe(video_data).await {
+        Ok(keyframe) => keyframe,
+        Err(e) => {
+            warn!("Failed to extract keyframe from video {}: {}", media.id, e);
+            return None;
+        }
+    };
+
+    match description_provider
+        .describe_image(&keyframe, templates.prompt_for(MediaKind::Video))
+        .await
+    {
+        Ok(description) => {
+            info!(
+                "Generated keyframe description for video {}: {}",
+                media.id, description.text
+            );
+            Some((
+                apply_attribution(
+                    &description.text,
+                    &description.model,
+                    templates.attribution,
+                    templates.max_length,
+                ),
+                description.model,
+            ))
+        }
+        Err(e) => {
+            warn!("Failed to describe keyframe of video {}: {}", media.id, e);
+            None
+        }
+    }
+}
+
+/// Describe a video from its `preview_url` thumbnail when it can't be transcribed
+///
+/// The video itself is only downloaded if the description has to be applied by recreating it.
+async fn describe_video_preview(
+    media: &MediaAttachment,
+    media_processor: &MediaProcessor,
+    description_provider: &dyn DescriptionProvider,
+    templates: &DescriptionTemplates<'_>,
+) -> Result<Option<MediaRecreation>, AlternatorError> {
+    let Some(preview_url) = media.preview_url.as_deref() else {
+        return Ok(None);
+    };
+
+    let preview = media_processor.download_media(preview_url).await?;
+    let processed = media_processor.transform_for_analysis(&preview).await?;
+    let description = description_provider
+        .describe_image(&processed, templates.prompt_for(MediaKind::Video))
+        .await?;
+    info!(
+        "Generated preview description for video {}: {}",
+        media.id, description.text
+    );
+
+    Ok(Some(MediaRecreation {
+        original_media_id: media.id.clone(),
+        // Downloaded by `download_missing_media_data` only if the media has to be recreated
+        data: Vec::new(),
+        description: apply_attribution(
+            &description.text,
+            &description.model,
+            templates.attribution,
+            templates.max_length,
+        ),
+        media_type: media.media_type.clone(),
+        filename: format!(
+            "video_{}.{}",
+            media.id,
+            get_video_file_extension(&media.media_type)
+        ),
+        focus: media.meta.as_ref().and_then(|meta| meta.focus),
+        replaced_description: media.description.clone(),
+        model: Some(description.model),
+        full_transcript: None,
+    }))
+}
+
+/// Shorten a transcript the transcriber kept whole for `whisper.long_transcript_mode = "reply"`
+///
+/// Returns the alt text and, if it had to be shortened, the full transcript for the reply thread.
+async fn split_long_transcript(
+    transcript: String,
+    summary: &TranscriptSummary<'_>,
+) -> (String, Option<String>) {
+    let shortened = crate::media::audio::shorten_transcript(transcript.clone(), summary).await;
+    if shortened == transcript {
+        (transcript, None)
+    } else {
+        (shortened, Some(transcript))
+    }
+}
+
+/// Merge a keyframe description and a transcript into a single video description
+fn combine_video_description(
+    visual: Option<String>,
+    transcript: Option<String>,
+    max_length: usize,
+) -> String {
+    // The no-speech placeholder adds nothing once the visuals are described
+    let transcript = transcript.filter(|t| {
+        let t = t.trim();
+        !t.is_empty() && (visual.is_none() || t != crate::media::NO_SPEECH_DESCRIPTION)
+    });
+
+    let combined = match (visual, transcript) {
+        (Some(visual), Some(transcript)) => format!("{visual}\n\n{transcript}"),
+        (Some(visual), None) => visual,
+        (None, Some(transcript)) => transcript,
+        (None, None) => crate::media::NO_SPEECH_DESCRIPTION.to_string(),
+    };
+
+    if combined.chars().count() > max_length {
+        let truncated = combined
+            .chars()
+            .take(max_length.saturating_sub(3))
+            .collect::<String>();
+        format!("{truncated}...")
+    } else {
+        combined
     }
 }
 
@@ -228,7 +651,7 @@ fn get_audio_file_extension(media_type: &str) -> &'static str {
 /// Get appropriate file extension for video media type
 fn get_video_file_extension(media_type: &str) -> &'static str {
     match media_type {
-        "video/mp4" => "mp4",
+        "video/mp4" | "gifv" => "mp4",
         "video/mpeg" => "mpeg",
         "video/quicktime" => "mov",
         "video/x-msvideo" => "avi",
@@ -255,31 +678,98 @@ fn get_image_file_extension(media_type: &str) -> &'static str {
 }
 
 /// Process a single toot - check for media, generate descriptions, and update
+#[tracing::instrument(
+    skip_all,
+    fields(toot_id = %toot.id, media_count = toot.media_attachments.len())
+)]
 pub async fn process_toot(
     toot: &TootEvent,
     mastodon_client: &MastodonClient,
-    openrouter_client: &OpenRouterClient,
+    description_provider: &dyn DescriptionProvider,
     media_processor: &MediaProcessor,
     language_detector: &LanguageDetector,
     config: &RuntimeConfig,
 ) -> Result<(), AlternatorError> {
-    process_toot_internal(
+    let result = process_toot_internal(
         toot,
         mastodon_client,
-        openrouter_client,
+        description_provider,
         media_processor,
         language_detector,
         config,
         false,
     )
-    .await
+    .await;
+    emit_result_event(toot, false, config, result)
+}
+
+/// Description length limit: the instance's alt text limit, unless a lower one is configured
+///
+/// Without an instance limit the provider's configured or default limit applies.
+fn effective_max_length(
+    instance_limit: Option<usize>,
+    configured: Option<usize>,
+    provider_limit: usize,
+) -> usize {
+    match (instance_limit, configured) {
+        (Some(instance), Some(configured)) => instance.min(configured),
+        (Some(instance), None) => instance,
+        (None, _) => provider_limit,
+    }
+}
+
+/// Reason for skipping a toot based on its sensitive/content warning flags, if any
+fn skip_reason(toot: &TootEvent, config: &RuntimeConfig) -> Option<&'static str> {
+    let mastodon = &config.config().mastodon;
+    if mastodon.skip_sensitive.unwrap_or(false) && toot.sensitive {
+        Some("marked as sensitive (skip_sensitive enabled)")
+    } else if mastodon.skip_spoiler.unwrap_or(false) && !toot.spoiler_text.is_empty() {
+        Some("has a content warning (skip_spoiler enabled)")
+    } else if mastodon.skip_replies.unwrap_or(false) && toot.in_reply_to_id.is_some() {
+        Some("is a reply (skip_replies enabled)")
+    } else if mastodon.respect_filters.unwrap_or(false) && toot.hiding_filter().is_some() {
+        Some("matches a keyword filter (respect_filters enabled)")
+    } else if mastodon
+        .process_visibilities
+        .as_ref()
+        .is_some_and(|visibilities| !visibilities.contains(&toot.visibility))
+    {
+        Some("its visibility is not in process_visibilities")
+    } else if !mastodon.allow_empty_text_edit.unwrap_or(true)
+        && mastodon.delivery_mode.as_deref() != Some("dm")
+        && MastodonClient::extract_text_from_html(&toot.content)
+            .trim()
+            .is_empty()
+    {
+        // Editing needs some text, which would be a zero-width space
+        Some("has no text (allow_empty_text_edit disabled)")
+    } else if let Some(poll) = toot
+        .poll
+        .as_ref()
+        .filter(|_| mastodon.delivery_mode.as_deref() != Some("dm"))
+    {
+        // Editing a status without its poll parameters removes the poll; DMs leave it alone
+        if mastodon.poll_handling.as_deref() == Some("skip") {
+            Some("has a poll (poll_handling = \"skip\")")
+        } else if poll.edit_params(chrono::Utc::now()).is_none() {
+            Some("has a poll that can't be preserved when editing (ended or ending soon)")
+        } else {
+            debug!(
+                "Toot {} has an open poll, re-applying it when editing",
+                toot.id
+            );
+            None
+        }
+    } else {
+        None
+    }
 }
 
 /// Process an edited toot - focus on new/changed media without descriptions
 pub async fn process_edited_toot(
     toot: &TootEvent,
     mastodon_client: &MastodonClient,
-    openrouter_client: &OpenRouterClient,
+    description_provider: &dyn DescriptionProvider,
     media_processor: &MediaProcessor,
     language_detector: &LanguageDetector,
     config: &RuntimeConfig,
@@ -288,28 +778,55 @@ pub async fn process_edited_toot(
         "Processing edited toot {} - checking for new media without descriptions",
         toot.id
     );
-    process_toot_internal(
+    let result = process_toot_internal(
         toot,
         mastodon_client,
-        openrouter_client,
+        description_provider,
         media_processor,
         language_detector,
         config,
         true,
     )
-    .await
+    .await;
+    emit_result_event(toot, true, config, result)
+}
+
+/// Write the result event for a toot that had media to describe or failed
+fn emit_result_event(
+    toot: &TootEvent,
+    is_edit: bool,
+    config: &RuntimeConfig,
+    result: Result<Vec<DescribedMedia>, AlternatorError>,
+) -> Result<(), AlternatorError> {
+    let media_ids = || {
+        toot.media_attachments
+            .iter()
+            .map(|media| media.id.clone())
+            .collect()
+    };
+    let event = match &result {
+        Ok(described) if described.is_empty() => return Ok(()),
+        Ok(described) => TootResultEvent::success(&toot.id, media_ids(), described.clone()),
+        Err(e) => TootResultEvent::failure(&toot.id, media_ids(), e.to_string()),
+    };
+    crate::events::emit(
+        config.config().events.as_ref(),
+        &event.with_flags(is_edit, config.is_dry_run()),
+    );
+
+    result.map(|_| ())
 }
 
 /// Internal implementation for processing toots
 async fn process_toot_internal(
     toot: &TootEvent,
     mastodon_client: &MastodonClient,
-    openrouter_client: &OpenRouterClient,
+    description_provider: &dyn DescriptionProvider,
     media_processor: &MediaProcessor,
     language_detector: &LanguageDetector,
     config: &RuntimeConfig,
     is_edit: bool,
-) -> Result<(), AlternatorError> {
+) -> Result<Vec<DescribedMedia>, AlternatorError> {
     // Early return if no media attachments
     if toot.media_attachments.is_empty() {
         debug!(
@@ -317,7 +834,18 @@ async fn process_toot_internal(
             if is_edit { "Edit" } else { "Toot" },
             toot.id
         );
-        return Ok(());
+        return Ok(Vec::new());
+    }
+
+    // Leave toots alone that editing would disturb: re-surfaced sensitive/CW'd toots or lost polls
+    if let Some(reason) = skip_reason(toot, config) {
+        info!(
+            "Skipping {} {}: {}",
+            if is_edit { "edit" } else { "toot" },
+            toot.id,
+            reason
+        );
+        return Ok(Vec::new());
     }
 
     // Filter media that needs processing
@@ -330,7 +858,7 @@ async fn process_toot_internal(
             if is_edit { "Edit" } else { "Toot" },
             toot.id
         );
-        return Ok(());
+        return Ok(Vec::new());
     }
 
     info!(
@@ -341,23 +869,60 @@ async fn process_toot_internal(
     );
 
     // Detect language for prompt selection
-    let detected_language = detect_toot_language(toot, language_detector)?;
-    let prompt_template = language_detector
-        .get_prompt_template(&detected_language)
-        .map_err(AlternatorError::Language)?;
+    let detected_language = language_detector.resolve_language(toot);
+
+    let toot_language = source_language(toot, language_detector, &detected_language);
+    if !is_language_processed(&toot_language, config) {
+        info!(
+            "Skipping {} {}: language '{}' is not in language.process_languages",
+            if is_edit { "edit" } else { "toot" },
+            toot.id,
+            toot_language
+        );
+        return Ok(Vec::new());
+    }
 
     debug!(
-        "Using language '{}' with prompt template",
+        "Using language '{}' with prompt templates",
         detected_language
     );
 
+    let max_length = effective_max_length(
+        mastodon_client.description_limit().await,
+        config.config().openrouter.max_description_length,
+        description_provider.max_description_length(),
+    );
+
+    let attribution = config.config().attribution.clone().unwrap_or_default();
+    let mut templates = DescriptionTemplates::for_language(
+        language_detector,
+        &detected_language,
+        attribution.enabled.unwrap_or(true).then(|| {
+            attribution
+                .template
+                .as_deref()
+                .unwrap_or_else(|| language_detector.get_attribution_template(&detected_language))
+        }),
+        max_length,
+    )
+    .map_err(AlternatorError::Language)?;
+    if config
+        .config()
+        .openrouter
+        .use_toot_text_as_context
+        .unwrap_or(false)
+    {
+        templates.toot_text = toot_context_text(toot);
+    }
+    templates.chart_prompt = chart_prompt(toot, config);
+
     // Process all media using strategies
     let media_processing_result = process_media_attachments(
         &processable_media,
         mastodon_client,
-        openrouter_client,
+        description_provider,
         media_processor,
-        prompt_template,
+        &templates,
         config,
         &toot.id,
     )
@@ -365,23 +930,174 @@ async fn process_toot_internal(
 
     // Recreate media if we have any successful processing results
     if !media_processing_result.media_recreations.is_empty() {
+        description_provider.record_described_toot(media_processing_result.media_recreations.len());
+        let described = media_processing_result
+            .media_recreations
+            .iter()
+            .map(|recreation| DescribedMedia {
+                media_id: recreation.original_media_id.clone(),
+                description: recreation.description.clone(),
+                model: recreation.model.clone(),
+            })
+            .collect();
+        let full_transcripts: Vec<String> = media_processing_result
+            .media_recreations
+            .iter()
+            .filter_map(|recreation| recreation.full_transcript.clone())
+            .collect();
+
+        if config.is_dry_run() {
+            log_dry_run_recreations(
+                &toot.id,
+                &media_processing_result.media_recreations,
+                &media_processing_result.original_media_ids,
+                is_edit,
+            );
+            for transcript in &full_transcripts {
+                info!(
+                    "Dry run: would reply to toot {} with the full {}-character transcript",
+                    toot.id,
+                    transcript.chars().count()
+                );
+            }
+            return Ok(described);
+        }
+
+        if config.config().mastodon.delivery_mode.as_deref() == Some("dm") {
+            let messages = format_description_dms(
+                toot,
+                &media_processing_result.media_recreations,
+                mastodon_client.max_characters().await,
+            );
+            for message in &messages {
+                mastodon_client
+                    .send_dm(message)
+                    .await
+                    .map_err(AlternatorError::Mastodon)?;
+            }
+            info!(
+                "Sent {} descriptions for {} {} as a direct message",
+                media_processing_result.media_recreations.len(),
+                if is_edit { "edit" } else { "toot" },
+                toot.id
+            );
+            return Ok(described);
+        }
+
+        // Remembered before the edit so its echo from the stream is recognized as our own
+        media_processor.written_descriptions().record(
+            &toot.id,
+            media_processing_result
+                .media_recreations
+                .iter()
+                .map(|recreation| {
+                    (
+                        recreation.original_media_id.as_str(),
+                        recreation.description.as_str(),
+                    )
+                }),
+        );
+
+        // Editing descriptions in place keeps the original attachments and their metadata
+        if config.config().mastodon.prefer_inplace_edit.unwrap_or(true)
+            && update_media_descriptions_in_place(
+                mastodon_client,
+                &toot.id,
+                &media_processing_result.media_recreations,
+                is_edit,
+            )
+            .await?
+        {
+            post_transcript_replies(mastodon_client, toot, &full_transcripts).await;
+            return Ok(described);
+        }
+
+        let media_recreations = download_missing_media_data(
+            toot,
+            media_processor,
+            media_processing_result.media_recreations,
+        )
+        .await?;
         recreate_media_attachments(
             mastodon_client,
             &toot.id,
-            media_processing_result.media_recreations,
+            media_recreations,
             media_processing_result.original_media_ids,
             is_edit,
         )
         .await?;
+        post_transcript_replies(mastodon_client, toot, &full_transcripts).await;
+
+        Ok(described)
     } else {
         info!(
             "No media attachments to recreate for {} {}",
             if is_edit { "edit" } else { "toot" },
             toot.id
         );
+        Ok(Vec::new())
     }
+}
 
-    Ok(())
+/// Post full transcripts whose alt text only holds a summary as reply threads under the toot
+///
+/// The replies use the toot's visibility. Failures are only logged since the descriptions
+/// are already applied.
+async fn post_transcript_replies(
+    mastodon_client: &MastodonClient,
+    toot: &TootEvent,
+    full_transcripts: &[String],
+) {
+    for transcript in full_transcripts {
+        match mastodon_client
+            .post_reply_thread(&toot.id, transcript, &toot.visibility)
+            .await
+        {
+            Ok(replies) => info!(
+                "Posted full transcript for toot {} as {} replies",
+                toot.id, replies
+            ),
+            Err(e) => warn!("Failed to post full transcript for toot {}: {}", toot.id, e),
+        }
+    }
+}
+
+/// Download media that was described without its data (images by URL, videos from their
+/// preview), since recreation re-uploads the data
+async fn download_missing_media_data(
+    toot: &TootEvent,
+    media_processor: &MediaProcessor,
+    mut media_recreations: Vec<MediaRecreation>,
+) -> Result<Vec<MediaRecreation>, AlternatorError> {
+    for recreation in media_recreations.iter_mut().filter(|r| r.data.is_empty()) {
+        let Some(media) = toot
+            .media_attachments
+            .iter()
+            .find(|m| m.id == recreation.original_media_id)
+        else {
+            continue;
+        };
+
+        recreation.data = media_processor.download_media_for_recreation(media).await?;
+        recreation.media_type =
+            crate::media::resolve_media_type(&media.media_type, &recreation.data);
+        if !recreation.media_type.to_lowercase().starts_with("video") {
+            recreation.filename = format!(
+                "image_{}.{}",
+                media.id,
+                get_image_file_extension(&recreation.media_type)
+            );
+        }
+    }
+    Ok(media_recreations)
+}
+
+/// Outcome of processing a single attachment
+enum MediaOutcome {
+    /// Described, or skipped with `None`
+    Described(Option<MediaRecreation>),
+    /// Image prepared for a batched description request (`openrouter.batch_image_descriptions`)
+    Prepared(PreparedImage),
 }
 
 /// Result of processing media attachments
@@ -390,13 +1106,69 @@ struct MediaProcessingResult {
     original_media_ids: Vec<String>,
 }
 
+/// Image downloaded and prepared for description
+struct PreparedImage {
+    media: MediaAttachment,
+    /// Empty when the image is passed to the provider by URL
+    original_data: Vec<u8>,
+    processed_data: Vec<u8>,
+    /// Public https URL sent to the provider instead of `processed_data`
+    passthrough_url: Option<String>,
+    /// Text found in the image by OCR, if enabled
+    ocr_text: Option<String>,
+    /// Animated GIF/WebP, sent as a montage of sampled frames
+    animated: bool,
+}
+
+/// Media URL the provider may fetch itself, only public https URLs qualify
+fn passthrough_url(media: &MediaAttachment) -> Option<url::Url> {
+    match url::Url::parse(&media.url) {
+        Ok(url) if url.scheme() == "https" => Some(url),
+        _ => {
+            debug!(
+                "Media {} is not served over https, downloading it instead of passing the URL",
+                media.id
+            );
+            None
+        }
+    }
+}
+
+/// Run OCR on an image prepared for analysis, logging failures instead of aborting
+pub(crate) async fn extract_ocr_text(
+    media: &MediaAttachment,
+    processed_data: &[u8],
+    config: &RuntimeConfig,
+) -> Option<String> {
+    if !config.is_ocr_enabled() {
+        return None;
+    }
+
+    let language = config.config().ocr().language.as_deref().unwrap_or("eng");
+    match crate::media::extract_image_text(processed_data, language).await {
+        Ok(Some(text)) => {
+            debug!("OCR found {} characters in media {}", text.len(), media.id);
+            Some(text)
+        }
+        Ok(None) => None,
+        Err(e) => {
+            warn!("OCR failed for media {}: {}", media.id, e);
+            None
+        }
+    }
+}
+
 /// Process all media attachments using appropriate strategies
+///
+/// Attachments are processed concurrently, bounded by the OpenRouter concurrency limit.
+/// A failing attachment is logged and left out; the first error is only returned if no
+/// attachment could be processed.
 async fn process_media_attachments(
     processable_media: &[&MediaAttachment],
     mastodon_client: &MastodonClient,
-    openrouter_client: &OpenRouterClient,
+    description_provider: &dyn DescriptionProvider,
     media_processor: &MediaProcessor,
-    prompt_template: &str,
+    templates: &DescriptionTemplates<'_>,
     config: &RuntimeConfig,
     toot_id: &str,
 ) -> Result<MediaProcessingResult, AlternatorError> {
@@ -405,112 +1177,146 @@ async fn process_media_attachments(
         Box::new(VideoProcessingStrategy),
         Box::new(ImageProcessingStrategy),
     ];
+    let strategies = &strategies;
+    let batch_images = config
+        .config()
+        .openrouter
+        .batch_image_descriptions
+        .unwrap_or(false);
 
-    let mut media_recreations = Vec::new();
-    let mut original_media_ids = Vec::new();
-    let mut prepared_images = Vec::new();
-
-    for &media in processable_media {
-        info!(
-            "Preparing media attachment: {} ({})",
-            media.id, media.media_type
-        );
+    let concurrency = description_provider.max_concurrent_requests();
+    debug!(
+        "Processing {} media attachments with up to {} in parallel",
+        processable_media.len(),
+        concurrency
+    );
 
-        // Check for race conditions before processing
-        if let Err(e) =
-            crate::toot_handler::race::check_race_condition(mastodon_client, toot_id, &media.id)
-                .await
-        {
-            match e {
-                AlternatorError::Mastodon(crate::error::MastodonError::RaceConditionDetected) => {
-                    info!("Race condition detected for media {}, skipping", media.id);
-                    continue;
-                }
-                _ => {
-                    warn!(
-                        "Could not check race condition for media {}: {}",
-                        media.id, e
-                    );
-                    // Continue processing but log the warning
-                }
-            }
-        }
+    let tasks: Vec<_> = processable_media
+        .iter()
+        .enumerate()
+        .map(|(index, &media)| async move {
+            let _permit = mastodon_client.acquire_inflight().await;
+            info!(
+                "Processing media attachment: {} ({})",
+                media.id, media.media_type
+            );
 
-        // Find appropriate strategy and process
-        let mut processed = false;
-        for strategy in &strategies {
-            if strategy.can_handle(&media.media_type) {
-                match strategy
-                    .process_media(media, media_processor, config)
-                    .await?
-                {
-                    Some(media_recreation) => {
-                        // Direct recreation (audio/video)
-                        media_recreations.push(media_recreation);
-                        original_media_ids.push(media.id.clone());
+            // Check for race conditions before processing
+            if let Err(e) =
+                crate::toot_handler::race::check_race_condition(mastodon_client, toot_id, media)
+                    .await
+            {
+                match e {
+                    AlternatorError::Mastodon(
+                        crate::error::MastodonError::RaceConditionDetected,
+                    ) => {
+                        info!("Race condition detected for media {}, skipping", media.id);
+                        return (index, media, Ok(MediaOutcome::Described(None)));
                     }
-                    None => {
-                        // Handle images separately (they need parallel processing)
-                        if media.media_type.to_lowercase().starts_with("image") {
-                            // Download original image data for recreation
-                            let original_image_data =
-                                match media_processor.download_media_for_recreation(media).await {
-                                    Ok(data) => data,
-                                    Err(e) => {
-                                        error!(
-                                            "Failed to download media {} for recreation: {}",
-                                            media.id, e
-                                        );
-                                        continue;
-                                    }
-                                };
-
-                            // Process media for analysis (resized/optimized version)
-                            let processed_media_data =
-                                match media_processor.process_media_for_analysis(media).await {
-                                    Ok(data) => data,
-                                    Err(e) => {
-                                        error!(
-                                            "Failed to process media {} for analysis: {}",
-                                            media.id, e
-                                        );
-                                        continue;
-                                    }
-                                };
-
-                            prepared_images.push((
-                                media.clone(),
-                                original_image_data,
-                                processed_media_data,
-                            ));
-                        }
-                        // Strategy handled but returned None (e.g., disabled processing)
+                    _ => {
+                        warn!(
+                            "Could not check race condition for media {}: {}",
+                            media.id, e
+                        );
+                        // Continue processing but log the warning
                     }
                 }
-                processed = true;
-                break;
             }
-        }
 
-        if !processed {
-            debug!(
-                "No strategy found for media type: {} ({})",
-                media.id, media.media_type
-            );
+            // Find appropriate strategy and process
+            let Some(strategy) = strategies
+                .iter()
+                .find(|strategy| strategy.can_handle(&media.media_type))
+            else {
+                debug!(
+                    "No strategy found for media type: {} ({})",
+                    media.id, media.media_type
+                );
+                return (index, media, Ok(MediaOutcome::Described(None)));
+            };
+
+            // Batched images are only prepared here and described together afterwards
+            if batch_images && ImageProcessingStrategy.can_handle(&media.media_type) {
+                let result = prepare_image(media, media_processor, description_provider, config)
+                    .await
+                    .map(|image| {
+                        image.map_or(MediaOutcome::Described(None), MediaOutcome::Prepared)
+                    });
+                return (index, media, result);
+            }
+
+            let result = strategy
+                .process_media(
+                    media,
+                    media_processor,
+                    description_provider,
+                    templates,
+                    config,
+                )
+                .await
+                .map(MediaOutcome::Described);
+            (index, media, result)
+        })
+        .collect();
+
+    let mut outcomes: Vec<_> = stream::iter(tasks)
+        .buffer_unordered(concurrency)
+        .collect()
+        .await;
+
+    // Keep the attachment order of the toot for the batched update
+    outcomes.sort_by_key(|(index, _, _)| *index);
+
+    // Describe the prepared images together and put their results back in place
+    let mut results = Vec::with_capacity(outcomes.len());
+    let mut prepared = Vec::new();
+    let mut prepared_positions = Vec::new();
+    for (index, media, outcome) in outcomes {
+        let result = match outcome {
+            Ok(MediaOutcome::Described(recreation)) => Ok(recreation),
+            Ok(MediaOutcome::Prepared(image)) => {
+                prepared_positions.push(results.len());
+                prepared.push(image);
+                Ok(None)
+            }
+            Err(e) => Err(e),
+        };
+        results.push((index, media, result));
+    }
+    if !prepared.is_empty() {
+        let described = describe_prepared_images(prepared, description_provider, templates).await;
+        for (position, result) in prepared_positions.into_iter().zip(described) {
+            results[position].2 = result.map_err(AlternatorError::OpenRouter);
         }
     }
 
-    // Process images in parallel if any were prepared
-    if !prepared_images.is_empty() {
-        info!(
-            "Prepared {} image attachments, starting parallel description generation",
-            prepared_images.len()
-        );
+    let mut media_recreations = Vec::new();
+    let mut original_media_ids = Vec::new();
+    let mut first_error = None;
 
-        let image_recreations =
-            process_images_in_parallel(prepared_images, openrouter_client, prompt_template).await?;
+    for (_, media, result) in results {
+        match result {
+            Ok(Some(media_recreation)) => {
+                // Replaced audio/video originals are cleaned up after recreation
+                if !media.media_type.to_lowercase().starts_with("image") {
+                    original_media_ids.push(media.id.clone());
+                }
+                media_recreations.push(media_recreation);
+            }
+            Ok(None) => {
+                // Skipped (e.g. race condition, disabled processing or token limit)
+            }
+            Err(e) => {
+                error!("Failed to process media {}: {}", media.id, e);
+                first_error.get_or_insert(e);
+            }
+        }
+    }
 
-        media_recreations.extend(image_recreations);
+    if media_recreations.is_empty() {
+        if let Some(e) = first_error {
+            return Err(e);
+        }
     }
 
     Ok(MediaProcessingResult {
@@ -519,72 +1325,386 @@ async fn process_media_attachments(
     })
 }
 
-/// Process images in parallel using OpenRouter
-async fn process_images_in_parallel(
-    prepared_images: Vec<(MediaAttachment, Vec<u8>, Vec<u8>)>,
-    openrouter_client: &OpenRouterClient,
+/// Language the toot is written in: the author-selected language, else the one detected from
+/// its text, before any fallback to `language.default_language`
+fn source_language(
+    toot: &TootEvent,
+    language_detector: &LanguageDetector,
+    resolved_language: &str,
+) -> String {
+    toot.language
+        .as_deref()
+        .map(str::trim)
+        .filter(|language| !language.is_empty())
+        .map(str::to_string)
+        .or_else(|| language_detector.detect_language(&toot.content).ok())
+        .unwrap_or_else(|| resolved_language.to_string())
+}
+
+/// Whether `language.process_languages` allows `language`; regional variants like "en-GB"
+/// match their base language
+fn is_language_processed(language: &str, config: &RuntimeConfig) -> bool {
+    let Some(allowed) = config
+        .config()
+        .language
+        .as_ref()
+        .and_then(|language| language.process_languages.as_ref())
+        .filter(|allowed| !allowed.is_empty())
+    else {
+        return true;
+    };
+
+    let base = |code: &str| {
+        code.trim()
+            .split(['-', '_'])
+            .next()
+            .unwrap_or_default()
+            .to_lowercase()
+    };
+    allowed.iter().any(|code| base(code) == base(language))
+}
+
+/// Hashtags that select the chart prompt when `openrouter.chart_hashtags` isn't set
+const DEFAULT_CHART_HASHTAGS: &[&str] = &["chart", "graph", "diagram", "infographic"];
+
+/// Instructions added to the image prompt for charts, graphs and diagrams
+const DEFAULT_CHART_PROMPT: &str = "This image is a chart, graph or diagram. Name its type and title, the axes with their labels, units and ranges, and the legend. Transcribe the data points or values that are shown and describe the main trend or relationship. For diagrams, describe the elements and how they are connected.";
+
+/// Chart instructions for the image prompt if the toot has one of the chart hashtags
+fn chart_prompt<'a>(toot: &TootEvent, config: &'a RuntimeConfig) -> Option<&'a str> {
+    let openrouter = &config.config().openrouter;
+    let is_chart_tag = |name: &str| match &openrouter.chart_hashtags {
+        Some(hashtags) => hashtags.iter().any(|tag| {
+            tag.trim()
+                .trim_start_matches('#')
+                .eq_ignore_ascii_case(name)
+        }),
+        None => DEFAULT_CHART_HASHTAGS
+            .iter()
+            .any(|tag| tag.eq_ignore_ascii_case(name)),
+    };
+    if !toot.tags.iter().any(|tag| is_chart_tag(&tag.name)) {
+        return None;
+    }
+
+    debug!(
+        "Toot {} is tagged as a chart, using the chart prompt",
+        toot.id
+    );
+    Some(
+        openrouter
+            .chart_prompt
+            .as_deref()
+            .unwrap_or(DEFAULT_CHART_PROMPT),
+    )
+}
+
+/// Maximum number of characters of the toot text added to the image prompt
+const MAX_TOOT_CONTEXT_LENGTH: usize = 500;
+
+/// Plain text of the toot for the image prompt, `None` if it has no text
+fn toot_context_text(toot: &TootEvent) -> Option<String> {
+    let text = MastodonClient::extract_text_from_html(&toot.content)
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        // Keep the text from closing the quote around it
+        .replace("\"\"\"", "\"");
+    if text.is_empty() {
+        return None;
+    }
+
+    if text.chars().count() > MAX_TOOT_CONTEXT_LENGTH {
+        let truncated: String = text.chars().take(MAX_TOOT_CONTEXT_LENGTH).collect();
+        Some(format!("{}…", truncated.trim_end()))
+    } else {
+        Some(text)
+    }
+}
+
+/// Image prompt with the notes for animations, text found by OCR and the toot's own text
+pub(crate) fn image_prompt(
     prompt_template: &str,
-) -> Result<Vec<MediaRecreation>, AlternatorError> {
-    // Generate descriptions in parallel
-    let description_tasks: Vec<_> = prepared_images
+    animation_note: Option<&str>,
+    ocr_text: Option<&str>,
+    toot_text: Option<&str>,
+) -> String {
+    let prompt = crate::media::prompt_with_animation_note(prompt_template, animation_note);
+    let prompt = crate::media::prompt_with_ocr_text(&prompt, ocr_text);
+    match toot_text {
+        Some(text) => format!(
+            "{prompt}\n\nThe image is attached to a post with the following text. Use it as context, e.g. for the names of people, pets or places, but only describe what is visible:\n\"\"\"\n{text}\n\"\"\""
+        ),
+        None => prompt,
+    }
+}
+
+/// Describe a prepared image with OpenRouter
+///
+/// Returns `Ok(None)` if the image exceeds the model's token limit.
+async fn describe_prepared_image(
+    image: PreparedImage,
+    description_provider: &dyn DescriptionProvider,
+    templates: &DescriptionTemplates<'_>,
+) -> Result<Option<MediaRecreation>, crate::error::OpenRouterError> {
+    let PreparedImage {
+        media,
+        original_data,
+        processed_data,
+        passthrough_url,
+        ocr_text,
+        animated,
+    } = image;
+
+    let prompt = image_prompt(
+        &templates.image_prompt_template(),
+        animated.then_some(templates.animation_note),
+        ocr_text.as_deref(),
+        templates.toot_text.as_deref(),
+    );
+    let description = match passthrough_url.as_deref() {
+        Some(url) => description_provider.describe_image_url(url, &prompt).await,
+        None => {
+            description_provider
+                .describe_image(&processed_data, &prompt)
+                .await
+        }
+    };
+    image_recreation(media, original_data, description, templates)
+}
+
+/// Describe prepared images, batching those without per-image notes into one request
+///
+/// A batch shares one prompt, so images with OCR text or an animation note, and images
+/// passed by URL, are described on their own. Results are in the order of `images`.
+async fn describe_prepared_images(
+    images: Vec<PreparedImage>,
+    description_provider: &dyn DescriptionProvider,
+    templates: &DescriptionTemplates<'_>,
+) -> Vec<Result<Option<MediaRecreation>, crate::error::OpenRouterError>> {
+    let batchable = |image: &PreparedImage| {
+        image.passthrough_url.is_none() && image.ocr_text.is_none() && !image.animated
+    };
+    if images.iter().filter(|image| batchable(image)).count() < 2 {
+        let mut results = Vec::with_capacity(images.len());
+        for image in images {
+            results.push(describe_prepared_image(image, description_provider, templates).await);
+        }
+        return results;
+    }
+
+    let (batch, single): (Vec<_>, Vec<_>) = images
+        .into_iter()
+        .enumerate()
+        .partition(|(_, image)| batchable(image));
+
+    let prompt = image_prompt(
+        &templates.image_prompt_template(),
+        None,
+        None,
+        templates.toot_text.as_deref(),
+    );
+    let inputs: Vec<(&[u8], &str)> = batch
         .iter()
-        .map(|(media, _original_data, processed_data)| {
-            let media_id = media.id.clone();
-            async move {
-                let result = openrouter_client
-                    .describe_image(processed_data, prompt_template)
-                    .await;
-                (media_id, result)
-            }
+        .map(|(_, image)| {
+            (
+                image.processed_data.as_slice(),
+                crate::media::sniff_media_type(&image.processed_data).unwrap_or("image/jpeg"),
+            )
         })
         .collect();
+    info!("Describing {} images in one request", inputs.len());
+    let descriptions = description_provider.describe_images(&inputs, &prompt).await;
 
-    let description_results = futures_util::future::join_all(description_tasks).await;
+    let mut results: Vec<_> = batch
+        .into_iter()
+        .zip(descriptions)
+        .map(|((index, image), description)| {
+            (
+                index,
+                image_recreation(image.media, image.original_data, description, templates),
+            )
+        })
+        .collect();
+    for (index, image) in single {
+        results.push((
+            index,
+            describe_prepared_image(image, description_provider, templates).await,
+        ));
+    }
+    results.sort_by_key(|(index, _)| *index);
+    results.into_iter().map(|(_, result)| result).collect()
+}
 
-    // Process results and build media recreations
-    let mut media_recreations = Vec::new();
+/// Recreation carrying the description of an image; token limit overruns skip the image
+fn image_recreation(
+    media: MediaAttachment,
+    original_data: Vec<u8>,
+    description: Result<crate::openrouter::ImageDescription, crate::error::OpenRouterError>,
+    templates: &DescriptionTemplates<'_>,
+) -> Result<Option<MediaRecreation>, crate::error::OpenRouterError> {
+    match description {
+        Ok(description) => {
+            info!(
+                "Generated description for media {}: {}",
+                media.id, description.text
+            );
 
-    for ((media, original_data, _processed_data), (result_media_id, description_result)) in
-        prepared_images
-            .into_iter()
-            .zip(description_results.into_iter())
-    {
-        debug_assert_eq!(
-            media.id, result_media_id,
-            "Media ID mismatch in parallel processing"
-        );
+            // Mastodon may only report a generic "image" type
+            let media_type = crate::media::resolve_media_type(&media.media_type, &original_data);
+            let extension = get_image_file_extension(&media_type);
+            let filename = format!("image_{}.{}", media.id, extension);
 
-        match description_result {
-            Ok(description) => {
-                info!(
-                    "Generated description for media {}: {}",
-                    media.id, description
-                );
+            Ok(Some(MediaRecreation {
+                original_media_id: media.id.clone(),
+                data: original_data,
+                description: apply_attribution(
+                    &description.text,
+                    &description.model,
+                    templates.attribution,
+                    templates.max_length,
+                ),
+                media_type,
+                filename,
+                focus: media.meta.as_ref().and_then(|meta| meta.focus),
+                replaced_description: media.description.clone(),
+                model: Some(description.model),
+                full_transcript: None,
+            }))
+        }
+        Err(crate::error::OpenRouterError::TokenLimitExceeded { .. }) => {
+            warn!("Token limit exceeded for media {}, skipping", media.id);
+            Ok(None)
+        }
+        Err(e) => {
+            error!(
+                "Failed to generate description for media {}: {}",
+                media.id, e
+            );
+            Err(e)
+        }
+    }
+}
 
-                let extension = get_image_file_extension(&media.media_type);
-                let filename = format!("image_{}.{}", media.id, extension);
+/// Log the media that would be recreated instead of editing the toot (dry-run mode)
+fn log_dry_run_recreations(
+    toot_id: &str,
+    media_recreations: &[MediaRecreation],
+    original_media_ids: &[String],
+    is_edit: bool,
+) {
+    info!(
+        "[dry-run] Would recreate {} media attachments for {} {} (replacing media: {:?})",
+        media_recreations.len(),
+        if is_edit { "edit" } else { "toot" },
+        toot_id,
+        original_media_ids
+    );
 
-                media_recreations.push(MediaRecreation {
-                    data: original_data,
-                    description,
-                    media_type: media.media_type.clone(),
-                    filename,
-                });
-            }
-            Err(crate::error::OpenRouterError::TokenLimitExceeded { .. }) => {
-                warn!("Token limit exceeded for media {}, skipping", media.id);
-            }
-            Err(e) => {
-                error!(
-                    "Failed to generate description for media {}: {}",
-                    media.id, e
-                );
-                return Err(AlternatorError::OpenRouter(e));
-            }
+    for recreation in media_recreations {
+        info!(
+            "[dry-run] {} {} ({}): {}",
+            toot_id, recreation.filename, recreation.media_type, recreation.description
+        );
+    }
+}
+
+/// Direct messages with the generated descriptions of a toot, for `delivery_mode = "dm"`
+///
+/// Descriptions are packed into as few messages as fit `max_characters`; a single description
+/// longer than that is split with [`MastodonClient::split_into_posts`].
+fn format_description_dms(
+    toot: &TootEvent,
+    media_recreations: &[MediaRecreation],
+    max_characters: usize,
+) -> Vec<String> {
+    let toot_url = toot.url.as_deref().unwrap_or(&toot.uri);
+    let mut messages = Vec::new();
+    let mut message = format!("Alt text for {toot_url}");
+    for (index, recreation) in media_recreations.iter().enumerate() {
+        let entry = if media_recreations.len() > 1 {
+            format!("{}. {}", index + 1, recreation.description)
+        } else {
+            recreation.description.clone()
+        };
+        if message.chars().count() + 2 + entry.chars().count() <= max_characters {
+            message.push_str("\n\n");
+            message.push_str(&entry);
+            continue;
         }
+        messages.push(std::mem::take(&mut message));
+        if entry.chars().count() <= max_characters {
+            message = entry;
+        } else {
+            let mut parts = MastodonClient::split_into_posts(&entry, max_characters);
+            message = parts.pop().unwrap_or_default();
+            messages.extend(parts);
+        }
+    }
+    if !message.is_empty() {
+        messages.push(message);
     }
+    messages
+}
 
-    Ok(media_recreations)
+/// Edit media descriptions in place via `media_attributes`
+///
+/// Returns `false` if the instance rejected the edit and the media should be recreated instead.
+async fn update_media_descriptions_in_place(
+    mastodon_client: &MastodonClient,
+    toot_id: &str,
+    media_recreations: &[MediaRecreation],
+    is_edit: bool,
+) -> Result<bool, AlternatorError> {
+    info!(
+        "Updating {} media descriptions in place for toot {}",
+        media_recreations.len(),
+        toot_id
+    );
+
+    match crate::toot_handler::coordinator::update_media_with_race_check(
+        mastodon_client,
+        toot_id,
+        media_recreations,
+    )
+    .await
+    {
+        Ok(()) => {
+            info!(
+                "✓ Successfully updated {} media descriptions in place for {}: {}",
+                media_recreations.len(),
+                if is_edit { "edit" } else { "toot" },
+                toot_id
+            );
+            Ok(true)
+        }
+        Err(AlternatorError::Mastodon(crate::error::MastodonError::RaceConditionDetected)) => {
+            info!(
+                "Race condition detected during in-place edit for {} {}, operation aborted",
+                if is_edit { "edit" } else { "toot" },
+                toot_id
+            );
+            Ok(true)
+        }
+        Err(AlternatorError::Mastodon(crate::error::MastodonError::ApiRequestFailed(e))) => {
+            warn!(
+                "Instance rejected in-place description edit for {} {}, falling back to media recreation: {}",
+                if is_edit { "edit" } else { "toot" },
+                toot_id,
+                e
+            );
+            Ok(false)
+        }
+        Err(e) => {
+            error!(
+                "Failed to update media descriptions for {} {}: {}",
+                if is_edit { "edit" } else { "toot" },
+                toot_id,
+                e
+            );
+            Err(e)
+        }
+    }
 }
 
 /// Recreate media attachments with descriptions
@@ -639,30 +1759,1713 @@ async fn recreate_media_attachments(
     Ok(())
 }
 
-/// Detect the language of a toot with fallback handling
-#[allow(clippy::result_large_err)] // AlternatorError is large but needed for comprehensive error handling
-pub fn detect_toot_language(
-    toot: &TootEvent,
-    language_detector: &LanguageDetector,
-) -> Result<String, AlternatorError> {
-    // First, check if the toot has a language attribute
-    if let Some(ref lang) = toot.language {
-        if !lang.trim().is_empty() {
-            debug!("Using toot language attribute: {}", lang);
-            return Ok(lang.clone());
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+    use crate::mastodon::Account;
+    use crate::openrouter::{OpenRouterClient, DEFAULT_MAX_DESCRIPTION_LENGTH};
+    use crate::test_support::{
+        local_media_config, local_media_processor, MockHttpServer, MockResponse,
+    };
+    use chrono::Utc;
+
+    fn create_test_config(server_url: &str) -> Config {
+        toml::from_str(&format!(
+            r#"
+[mastodon]
+instance_url = "{server_url}"
+access_token = "test_token"
+
+[openrouter]
+api_key = "test_key"
+base_url = "{server_url}/openrouter"
+"#
+        ))
+        .unwrap()
+    }
+
+    fn create_test_toot(server_url: &str) -> TootEvent {
+        TootEvent {
+            id: "toot_1".to_string(),
+            uri: format!("{server_url}/users/testuser/statuses/toot_1"),
+            account: Account {
+                id: "user_1".to_string(),
+                username: "testuser".to_string(),
+                acct: "testuser".to_string(),
+                display_name: "Test User".to_string(),
+                url: format!("{server_url}/@testuser"),
+            },
+            content: "A picture".to_string(),
+            language: Some("en".to_string()),
+            media_attachments: vec![MediaAttachment {
+                id: "media_1".to_string(),
+                media_type: "image".to_string(),
+                url: format!("{server_url}/media/image.png"),
+                preview_url: None,
+                description: None,
+                meta: None,
+            }],
+            created_at: Utc::now(),
+            url: None,
+            visibility: "public".to_string(),
+            in_reply_to_id: None,
+            in_reply_to_account_id: None,
+            mentions: Vec::new(),
+            sensitive: false,
+            spoiler_text: String::new(),
+            tags: Vec::new(),
+            emojis: Vec::new(),
+            poll: None,
+            filtered: Vec::new(),
+            edited_at: None,
+            is_edit: false,
+        }
+    }
+
+    fn create_test_png() -> Vec<u8> {
+        let image = image::RgbImage::from_pixel(8, 8, image::Rgb([200, 30, 30]));
+        let mut data = Vec::new();
+        image::DynamicImage::ImageRgb8(image)
+            .write_to(
+                &mut std::io::Cursor::new(&mut data),
+                image::ImageFormat::Png,
+            )
+            .unwrap();
+        data
+    }
+
+    #[tokio::test]
+    async fn test_dry_run_generates_description_without_editing() {
+        let png = create_test_png();
+        let server = MockHttpServer::start(move |request| {
+            if request.path.starts_with("/media/") {
+                MockResponse::bytes(200, "image/png", png.clone())
+            } else if request.path.starts_with("/openrouter/chat/completions") {
+                MockResponse::json(
+                    200,
+                    r#"{"choices":[{"message":{"content":"A red square"},"finish_reason":"stop"}]}"#,
+                )
+            } else {
+                MockResponse::json(404, r#"{"error":"Record not found"}"#)
+            }
+        })
+        .await;
+
+        let config = RuntimeConfig::new(create_test_config(&server.url())).with_dry_run(true);
+        let mastodon_client = MastodonClient::new(config.config().mastodon.clone());
+        let openrouter_client = OpenRouterClient::new(config.config().openrouter.clone());
+        let media_processor = local_media_processor();
+        let language_detector = LanguageDetector::new();
+
+        process_toot(
+            &create_test_toot(&server.url()),
+            &mastodon_client,
+            &openrouter_client,
+            &media_processor,
+            &language_detector,
+            &config,
+        )
+        .await
+        .unwrap();
+
+        let requests = server.requests();
+
+        // The description was still generated via OpenRouter
+        let completion = requests
+            .iter()
+            .find(|r| r.method == "POST" && r.path.starts_with("/openrouter/chat/completions"))
+            .expect("description should be generated in dry-run mode");
+        assert!(String::from_utf8_lossy(&completion.body).contains("data:image/"));
+
+        // ...but no write request reached the Mastodon API
+        let mastodon_writes: Vec<_> = requests
+            .iter()
+            .filter(|r| r.path.starts_with("/api/") && r.method != "GET")
+            .map(|r| format!("{} {}", r.method, r.path))
+            .collect();
+        assert!(
+            mastodon_writes.is_empty(),
+            "unexpected Mastodon writes in dry-run mode: {mastodon_writes:?}"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_ocr_text_included_in_image_prompt() {
+        let server = MockHttpServer::start(|_| {
+            MockResponse::json(
+                200,
+                r#"{"choices":[{"message":{"content":"A sign reading SALE 50% OFF"},"finish_reason":"stop"}]}"#,
+            )
+        })
+        .await;
+
+        let config = create_test_config(&server.url());
+        let openrouter_client = OpenRouterClient::new(config.openrouter.clone());
+        let media = create_test_toot(&server.url()).media_attachments[0].clone();
+        let prepared = |ocr_text: Option<&str>| PreparedImage {
+            media: media.clone(),
+            original_data: create_test_png(),
+            processed_data: create_test_png(),
+            passthrough_url: None,
+            ocr_text: ocr_text.map(str::to_string),
+            animated: false,
+        };
+
+        let templates = DescriptionTemplates {
+            prompt: "Describe this image.",
+            audio_prompt: "Summarize this transcript.",
+            video_prompt: "Describe this clip.",
+            animation_note: "This is an animation.",
+            attribution: None,
+            max_length: DEFAULT_MAX_DESCRIPTION_LENGTH,
+            toot_text: None,
+            chart_prompt: None,
+        };
+
+        let recreation = describe_prepared_image(
+            prepared(Some("SALE 50% OFF")),
+            &openrouter_client,
+            &templates,
+        )
+        .await
+        .unwrap()
+        .unwrap();
+        assert_eq!(recreation.description, "A sign reading SALE 50% OFF");
+
+        describe_prepared_image(prepared(None), &openrouter_client, &templates)
+            .await
+            .unwrap();
+
+        let bodies: Vec<String> = server
+            .requests()
+            .iter()
+            .map(|r| String::from_utf8_lossy(&r.body).to_string())
+            .collect();
+        assert_eq!(bodies.len(), 2);
+        assert!(bodies[0].contains("Describe this image."));
+        assert!(bodies[0].contains("SALE 50% OFF"));
+        assert!(bodies[1].contains("Describe this image."));
+        assert!(!bodies[1].contains("OCR detected"));
+    }
+
+    /// Mock instance that answers the in-place `media_attributes` edit with `inplace_status`
+    async fn start_editable_server(inplace_status: u16) -> MockHttpServer {
+        let png = create_test_png();
+        let status = serde_json::to_string(&create_test_toot("https://example.com")).unwrap();
+        MockHttpServer::start(move |request| {
+            let body = String::from_utf8_lossy(&request.body);
+            match (request.method.as_str(), request.path.as_str()) {
+                (_, path) if path.starts_with("/media/") => {
+                    MockResponse::bytes(200, "image/png", png.clone())
+                }
+                ("POST", "/openrouter/chat/completions") => MockResponse::json(
+                    200,
+                    r#"{"choices":[{"message":{"content":"A red square"},"finish_reason":"stop"}]}"#,
+                ),
+                ("GET", "/api/v1/statuses/toot_1/source") => MockResponse::json(
+                    200,
+                    r#"{"id":"toot_1","text":"A picture","spoiler_text":""}"#,
+                ),
+                ("GET", "/api/v1/statuses/toot_1") => MockResponse::json(200, &status),
+                ("PUT", "/api/v1/statuses/toot_1") if body.contains("media_attributes") => {
+                    MockResponse::json(inplace_status, &status)
+                }
+                ("PUT", "/api/v1/statuses/toot_1") => MockResponse::json(200, &status),
+                ("POST", "/api/v2/media") => MockResponse::json(200, r#"{"id":"media_2"}"#),
+                _ => MockResponse::json(404, r#"{"error":"Record not found"}"#),
+            }
+        })
+        .await
+    }
+
+    #[tokio::test]
+    async fn test_batched_image_descriptions_use_one_request() {
+        let png = create_test_png();
+        let mut toot = create_test_toot("https://example.com");
+        let mut second = toot.media_attachments[0].clone();
+        second.id = "media_2".to_string();
+        second.url = "https://example.com/media/second.png".to_string();
+        toot.media_attachments.push(second);
+        let status = serde_json::to_string(&toot).unwrap();
+        let server = MockHttpServer::start(move |request| {
+            match (request.method.as_str(), request.path.as_str()) {
+                (_, path) if path.starts_with("/media/") => {
+                    MockResponse::bytes(200, "image/png", png.clone())
+                }
+                ("POST", "/openrouter/chat/completions") => MockResponse::json(
+                    200,
+                    r#"{"choices":[{"message":{"content":"[\"A red square\", \"A second red square\"]"},"finish_reason":"stop"}]}"#,
+                ),
+                ("GET", "/api/v1/statuses/toot_1/source") => MockResponse::json(
+                    200,
+                    r#"{"id":"toot_1","text":"A picture","spoiler_text":""}"#,
+                ),
+                ("GET", "/api/v1/statuses/toot_1") | ("PUT", "/api/v1/statuses/toot_1") => {
+                    MockResponse::json(200, &status)
+                }
+                _ => MockResponse::json(404, r#"{"error":"Record not found"}"#),
+            }
+        })
+        .await;
+
+        let mut toot = create_test_toot(&server.url());
+        let mut second = toot.media_attachments[0].clone();
+        second.id = "media_2".to_string();
+        second.url = format!("{}/media/second.png", server.url());
+        toot.media_attachments.push(second);
+        let mut config = create_test_config(&server.url());
+        config.openrouter.batch_image_descriptions = Some(true);
+        let config = RuntimeConfig::new(config);
+        process_toot(
+            &toot,
+            &MastodonClient::new(config.config().mastodon.clone()),
+            &OpenRouterClient::new(config.config().openrouter.clone()),
+            &local_media_processor(),
+            &LanguageDetector::new(),
+            &config,
+        )
+        .await
+        .unwrap();
+
+        let requests = server.requests();
+        assert_eq!(
+            requests
+                .iter()
+                .filter(|r| r.path == "/openrouter/chat/completions")
+                .count(),
+            1
+        );
+        let edit = requests
+            .iter()
+            .find(|r| r.method == "PUT")
+            .expect("status should be edited");
+        let body = String::from_utf8_lossy(&edit.body);
+        assert!(body.contains("A+red+square"));
+        assert!(body.contains("A+second+red+square"));
+    }
+
+    #[tokio::test]
+    async fn test_long_transcript_in_reply_mode_posted_as_reply() {
+        let server = MockHttpServer::start(|request| {
+            match (request.method.as_str(), request.path.as_str()) {
+                ("POST", "/openrouter/chat/completions") => MockResponse::json(
+                    200,
+                    r#"{"choices":[{"message":{"content":"Someone reads a long story."},"finish_reason":"stop"}]}"#,
+                ),
+                ("POST", "/api/v1/statuses") => MockResponse::json(200, r#"{"id":"reply_1"}"#),
+                _ => MockResponse::json(404, r#"{"error":"Record not found"}"#),
+            }
+        })
+        .await;
+        let mut config = create_test_config(&server.url());
+        config.whisper = Some(crate::config::WhisperConfig {
+            long_transcript_mode: Some("reply".to_string()),
+            ..Default::default()
+        });
+        let config = RuntimeConfig::new(config);
+        assert!(crate::media::audio::keeps_long_transcripts(
+            config.config().whisper()
+        ));
+
+        let openrouter_client = OpenRouterClient::new(config.config().openrouter.clone());
+        let transcript = vec!["word"; 400].join(" ");
+        let summary = TranscriptSummary {
+            provider: Some(&openrouter_client),
+            prompt: "Summarize this transcript.",
+            max_length: 1500,
+        };
+        let (alt_text, full_transcript) = split_long_transcript(transcript.clone(), &summary).await;
+        assert_eq!(alt_text, "Someone reads a long story.");
+        assert_eq!(full_transcript.as_deref(), Some(transcript.as_str()));
+
+        let mut toot = create_test_toot(&server.url());
+        toot.visibility = "unlisted".to_string();
+        post_transcript_replies(
+            &MastodonClient::new(config.config().mastodon.clone()),
+            &toot,
+            &[transcript],
+        )
+        .await;
+
+        // 2000 characters don't fit one 500 character status, so the replies form a thread
+        let replies: Vec<String> = server
+            .requests()
+            .iter()
+            .filter(|r| r.method == "POST" && r.path == "/api/v1/statuses")
+            .map(|r| String::from_utf8_lossy(&r.body).to_string())
+            .collect();
+        assert_eq!(replies.len(), 5);
+        assert!(replies[0].contains(&format!("in_reply_to_id={}", toot.id)));
+        assert!(replies[0].contains("visibility=unlisted"));
+        assert!(replies[0].contains("%281%2F5%29"));
+        assert!(replies[1].contains("in_reply_to_id=reply_1"));
+
+        // Short transcripts stay in the alt text only
+        let (alt_text, full_transcript) =
+            split_long_transcript("Hello".to_string(), &summary).await;
+        assert_eq!(alt_text, "Hello");
+        assert!(full_transcript.is_none());
+    }
+
+    async fn process_with_server(server: &MockHttpServer) {
+        let config = RuntimeConfig::new(create_test_config(&server.url()));
+
+        process_toot(
+            &create_test_toot(&server.url()),
+            &MastodonClient::new(config.config().mastodon.clone()),
+            &OpenRouterClient::new(config.config().openrouter.clone()),
+            &local_media_processor(),
+            &LanguageDetector::new(),
+            &config,
+        )
+        .await
+        .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_inplace_edit_preferred_over_recreation() {
+        let server = start_editable_server(200).await;
+        process_with_server(&server).await;
+
+        let requests = server.requests();
+        let edit = requests
+            .iter()
+            .find(|r| r.method == "PUT")
+            .expect("status should be edited");
+        let body = String::from_utf8_lossy(&edit.body);
+        assert!(body.contains("media_1"));
+        assert!(body.contains("A+red+square"));
+
+        // The original attachment was kept, nothing was uploaded
+        assert!(!requests.iter().any(|r| r.path == "/api/v2/media"));
+    }
+
+    #[tokio::test]
+    async fn test_toot_text_added_to_image_prompt_when_enabled() {
+        let server = start_editable_server(200).await;
+        let mut toot = create_test_toot(&server.url());
+        toot.content = "<p>Meet <strong>my cat Luna</strong>!</p>".to_string();
+
+        for use_toot_text in [false, true] {
+            let mut config = create_test_config(&server.url());
+            config.openrouter.use_toot_text_as_context = Some(use_toot_text);
+            let config = RuntimeConfig::new(config);
+            process_toot(
+                &toot,
+                &MastodonClient::new(config.config().mastodon.clone()),
+                &OpenRouterClient::new(config.config().openrouter.clone()),
+                &local_media_processor(),
+                &LanguageDetector::new(),
+                &config,
+            )
+            .await
+            .unwrap();
         }
+
+        let prompts: Vec<String> = server
+            .requests()
+            .iter()
+            .filter(|r| r.path == "/openrouter/chat/completions")
+            .map(|r| {
+                let body: serde_json::Value = serde_json::from_slice(&r.body).unwrap();
+                body["messages"][0]["content"][0]["text"]
+                    .as_str()
+                    .unwrap()
+                    .to_string()
+            })
+            .collect();
+        assert_eq!(prompts.len(), 2);
+        assert!(!prompts[0].contains("Luna"));
+        assert!(prompts[1].contains("\"\"\"\nMeet my cat Luna!\n\"\"\""));
     }
 
-    // Fallback to content-based language detection
-    debug!("No toot language attribute found, detecting from content");
-    match language_detector.detect_language(&toot.content) {
-        Ok(lang) => {
-            debug!("Detected language from content: {}", lang);
-            Ok(lang)
+    #[tokio::test]
+    async fn test_toot_outside_process_languages_skipped() {
+        let server = start_editable_server(200).await;
+        let mut config = create_test_config(&server.url());
+        config.language = Some(crate::config::LanguageConfig {
+            process_languages: Some(vec!["en".to_string(), "de".to_string()]),
+            ..Default::default()
+        });
+        let config = RuntimeConfig::new(config);
+
+        let process = |language: &str| {
+            let mut toot = create_test_toot(&server.url());
+            toot.language = Some(language.to_string());
+            let config = &config;
+            async move {
+                process_toot(
+                    &toot,
+                    &MastodonClient::new(config.config().mastodon.clone()),
+                    &OpenRouterClient::new(config.config().openrouter.clone()),
+                    &local_media_processor(),
+                    &LanguageDetector::new(),
+                    config,
+                )
+                .await
+                .unwrap()
+            }
+        };
+        let described = |server: &MockHttpServer| {
+            server
+                .requests()
+                .iter()
+                .filter(|r| r.path == "/openrouter/chat/completions")
+                .count()
+        };
+
+        process("fr").await;
+        assert_eq!(described(&server), 0);
+
+        // Regional variants match their base language
+        process("de-AT").await;
+        assert_eq!(described(&server), 1);
+    }
+
+    #[tokio::test]
+    async fn test_chart_hashtag_selects_chart_prompt() {
+        let server = start_editable_server(200).await;
+        let mut chart = create_test_toot(&server.url());
+        chart.tags = vec![crate::mastodon::Tag {
+            name: "Chart".to_string(),
+            url: format!("{}/tags/chart", server.url()),
+        }];
+        let config = RuntimeConfig::new(create_test_config(&server.url()));
+        assert_eq!(chart_prompt(&chart, &config), Some(DEFAULT_CHART_PROMPT));
+
+        for toot in [create_test_toot(&server.url()), chart] {
+            process_toot(
+                &toot,
+                &MastodonClient::new(config.config().mastodon.clone()),
+                &OpenRouterClient::new(config.config().openrouter.clone()),
+                &local_media_processor(),
+                &LanguageDetector::new(),
+                &config,
+            )
+            .await
+            .unwrap();
         }
-        Err(e) => {
-            warn!("Language detection failed: {}, defaulting to English", e);
-            Ok("en".to_string())
+
+        let prompts: Vec<String> = server
+            .requests()
+            .iter()
+            .filter(|r| r.path == "/openrouter/chat/completions")
+            .map(|r| {
+                let body: serde_json::Value = serde_json::from_slice(&r.body).unwrap();
+                body["messages"][0]["content"][0]["text"]
+                    .as_str()
+                    .unwrap()
+                    .to_string()
+            })
+            .collect();
+        assert_eq!(prompts.len(), 2);
+        assert!(!prompts[0].contains(DEFAULT_CHART_PROMPT));
+        assert!(prompts[1].contains(DEFAULT_CHART_PROMPT));
+
+        // Only the configured hashtags count once the list is set
+        let mut custom = create_test_config(&server.url());
+        custom.openrouter.chart_hashtags = Some(vec!["#plot".to_string()]);
+        let custom = RuntimeConfig::new(custom);
+        let mut toot = create_test_toot(&server.url());
+        toot.tags = vec![crate::mastodon::Tag {
+            name: "chart".to_string(),
+            url: format!("{}/tags/chart", server.url()),
+        }];
+        assert_eq!(chart_prompt(&toot, &custom), None);
+        toot.tags[0].name = "plot".to_string();
+        assert_eq!(chart_prompt(&toot, &custom), Some(DEFAULT_CHART_PROMPT));
+    }
+
+    #[test]
+    fn test_toot_context_text_is_plain_and_bounded() {
+        let mut toot = create_test_toot("http://127.0.0.1:1");
+        toot.content = String::new();
+        assert_eq!(toot_context_text(&toot), None);
+
+        toot.content = format!("<p>{}</p>", "word ".repeat(200));
+        let text = toot_context_text(&toot).unwrap();
+        assert!(text.chars().count() <= MAX_TOOT_CONTEXT_LENGTH + 1);
+        assert!(text.starts_with("word word"));
+        assert!(text.ends_with("word…"));
+    }
+
+    #[tokio::test]
+    async fn test_result_event_emitted_for_processed_toot() {
+        let server = start_editable_server(200).await;
+        let dir = tempfile::tempdir().unwrap();
+        let events_path = dir.path().join("events.jsonl");
+
+        let mut config = create_test_config(&server.url());
+        config.events = Some(crate::config::EventsConfig {
+            output: Some(events_path.to_string_lossy().to_string()),
+        });
+        let config = RuntimeConfig::new(config);
+        process_toot(
+            &create_test_toot(&server.url()),
+            &MastodonClient::new(config.config().mastodon.clone()),
+            &OpenRouterClient::new(config.config().openrouter.clone()),
+            &local_media_processor(),
+            &LanguageDetector::new(),
+            &config,
+        )
+        .await
+        .unwrap();
+
+        let contents = std::fs::read_to_string(&events_path).unwrap();
+        assert_eq!(contents.lines().count(), 1);
+        let event: serde_json::Value = serde_json::from_str(contents.trim()).unwrap();
+        assert_eq!(event["event"], "toot_processed");
+        assert_eq!(event["toot_id"], "toot_1");
+        assert_eq!(event["success"], true);
+        assert_eq!(event["edit"], false);
+        assert_eq!(event["media_ids"], serde_json::json!(["media_1"]));
+        assert_eq!(event["media"][0]["media_id"], "media_1");
+        assert!(event["media"][0]["description"]
+            .as_str()
+            .unwrap()
+            .starts_with("A red square"));
+        assert_eq!(
+            event["media"][0]["model"],
+            config.config().openrouter.vision_model.as_str()
+        );
+        assert_eq!(event["error"], serde_json::Value::Null);
+    }
+
+    #[tokio::test]
+    async fn test_instance_description_limit_truncates_description() {
+        let png = create_test_png();
+        let status = serde_json::to_string(&create_test_toot("https://example.com")).unwrap();
+        let long_description = "A red square on a plain background. ".repeat(10);
+        let server = MockHttpServer::start(move |request| {
+            match (request.method.as_str(), request.path.as_str()) {
+                ("GET", "/api/v1/instance") => MockResponse::json(
+                    200,
+                    r#"{"configuration":{"statuses":{"max_characters":500},"media_attachments":{"description_limit":120}}}"#,
+                ),
+                (_, path) if path.starts_with("/media/") => {
+                    MockResponse::bytes(200, "image/png", png.clone())
+                }
+                ("POST", "/openrouter/chat/completions") => MockResponse::json(
+                    200,
+                    &serde_json::json!({
+                        "choices": [{
+                            "message": {"content": long_description},
+                            "finish_reason": "stop"
+                        }]
+                    })
+                    .to_string(),
+                ),
+                ("GET", "/api/v1/statuses/toot_1/source") => MockResponse::json(
+                    200,
+                    r#"{"id":"toot_1","text":"A picture","spoiler_text":""}"#,
+                ),
+                ("GET", "/api/v1/statuses/toot_1") | ("PUT", "/api/v1/statuses/toot_1") => {
+                    MockResponse::json(200, &status)
+                }
+                _ => MockResponse::json(404, r#"{"error":"Record not found"}"#),
+            }
+        })
+        .await;
+
+        process_with_server(&server).await;
+
+        let edit = server
+            .requests()
+            .into_iter()
+            .find(|r| r.method == "PUT")
+            .expect("status should be edited");
+        let form: Vec<(String, String)> = url::form_urlencoded::parse(&edit.body)
+            .into_owned()
+            .collect();
+        let description = &form
+            .iter()
+            .find(|(key, _)| key == "media_attributes[0][description]")
+            .unwrap()
+            .1;
+        // Truncated to the instance limit rather than the default of 1500, attribution included
+        assert!(description.chars().count() <= 120, "{description}");
+        assert!(description.starts_with("A red square"));
+        assert!(description.contains("made by AI"));
+    }
+
+    #[test]
+    fn test_effective_max_length() {
+        assert_eq!(effective_max_length(Some(5000), None, 1500), 5000);
+        assert_eq!(effective_max_length(Some(5000), Some(800), 800), 800);
+        assert_eq!(effective_max_length(Some(420), Some(800), 800), 420);
+        assert_eq!(effective_max_length(None, Some(800), 800), 800);
+        assert_eq!(effective_max_length(None, None, 1500), 1500);
+    }
+
+    #[tokio::test]
+    async fn test_ai_description_reprocessed_but_human_description_kept() {
+        let png = create_test_png();
+        let mut toot = create_test_toot("https://example.com");
+        toot.media_attachments = vec![
+            MediaAttachment {
+                description: Some(
+                    "A square — this image description was made by AI: old/model".to_string(),
+                ),
+                ..toot.media_attachments[0].clone()
+            },
+            MediaAttachment {
+                id: "media_2".to_string(),
+                url: "https://example.com/media/photo.png".to_string(),
+                description: Some("My cat asleep on the sofa".to_string()),
+                ..toot.media_attachments[0].clone()
+            },
+        ];
+        let status = serde_json::to_string(&toot).unwrap();
+
+        let server = MockHttpServer::start(move |request| {
+            match (request.method.as_str(), request.path.as_str()) {
+                (_, path) if path.starts_with("/media/") => {
+                    MockResponse::bytes(200, "image/png", png.clone())
+                }
+                ("POST", "/openrouter/chat/completions") => MockResponse::json(
+                    200,
+                    r#"{"choices":[{"message":{"content":"A red square"},"finish_reason":"stop"}]}"#,
+                ),
+                ("GET", "/api/v1/statuses/toot_1/source") => MockResponse::json(
+                    200,
+                    r#"{"id":"toot_1","text":"A picture","spoiler_text":""}"#,
+                ),
+                ("GET", "/api/v1/statuses/toot_1") | ("PUT", "/api/v1/statuses/toot_1") => {
+                    MockResponse::json(200, &status)
+                }
+                _ => MockResponse::json(404, r#"{"error":"Record not found"}"#),
+            }
+        })
+        .await;
+
+        let mut config = create_test_config(&server.url());
+        config.media = Some(crate::config::MediaConfig {
+            reprocess_existing_descriptions: Some(true),
+            ..Default::default()
+        });
+        let config = RuntimeConfig::new(config);
+        let media_processor = MediaProcessor::with_unified_transformer(crate::media::MediaConfig {
+            reprocess_existing_descriptions: true,
+            ..local_media_config()
+        });
+        toot.media_attachments
+            .iter_mut()
+            .for_each(|media| media.url = media.url.replace("https://example.com", &server.url()));
+
+        process_toot(
+            &toot,
+            &MastodonClient::new(config.config().mastodon.clone()),
+            &OpenRouterClient::new(config.config().openrouter.clone()),
+            &media_processor,
+            &LanguageDetector::new(),
+            &config,
+        )
+        .await
+        .unwrap();
+
+        let requests = server.requests();
+        // Only the AI-described image was downloaded and described again
+        assert!(requests.iter().any(|r| r.path == "/media/image.png"));
+        assert!(!requests.iter().any(|r| r.path == "/media/photo.png"));
+
+        // The existing AI description isn't mistaken for a race with another instance
+        let edit = requests
+            .iter()
+            .find(|r| r.method == "PUT")
+            .expect("status should be edited");
+        let body = String::from_utf8_lossy(&edit.body);
+        assert!(body.contains("A+red+square"));
+        assert!(!body.contains("old%2Fmodel"));
+
+        // The written description also carries an AI attribution, but it is Alternator's own
+        // and must not be described again when the edit comes back
+        let written = url::form_urlencoded::parse(&edit.body)
+            .find(|(key, value)| key.contains("description") && value.contains("A red square"))
+            .map(|(_, value)| value.into_owned())
+            .expect("description in edit");
+        toot.media_attachments[0].description = Some(written);
+        assert!(media_processor
+            .filter_processable_media_with_audio(&toot.media_attachments, false)
+            .is_empty());
+
+        process_toot(
+            &toot,
+            &MastodonClient::new(config.config().mastodon.clone()),
+            &OpenRouterClient::new(config.config().openrouter.clone()),
+            &media_processor,
+            &LanguageDetector::new(),
+            &config,
+        )
+        .await
+        .unwrap();
+        let completions = server
+            .requests()
+            .iter()
+            .filter(|r| r.path == "/openrouter/chat/completions")
+            .count();
+        assert_eq!(completions, 1);
+    }
+
+    #[tokio::test]
+    async fn test_gifv_routed_to_keyframe_extraction() {
+        assert!(VideoProcessingStrategy.can_handle("gifv"));
+        assert!(!AudioProcessingStrategy.can_handle("gifv"));
+        assert!(!ImageProcessingStrategy.can_handle("gifv"));
+
+        let server = MockHttpServer::start(|request| {
+            if request.path.starts_with("/media/") {
+                MockResponse::bytes(200, "video/mp4", b"not really an mp4".to_vec())
+            } else {
+                MockResponse::json(500, "{}")
+            }
+        })
+        .await;
+        let config = RuntimeConfig::new(create_test_config(&server.url()));
+        assert!(!config.is_audio_enabled());
+
+        let media = MediaAttachment {
+            id: "gif_1".to_string(),
+            media_type: "gifv".to_string(),
+            url: format!("{}/media/clip.mp4", server.url()),
+            preview_url: None,
+            description: None,
+            meta: None,
+        };
+        let detector = LanguageDetector::new();
+        let templates = DescriptionTemplates::for_language(
+            &detector,
+            "en",
+            None,
+            DEFAULT_MAX_DESCRIPTION_LENGTH,
+        )
+        .unwrap();
+
+        let result = VideoProcessingStrategy
+            .process_media(
+                &media,
+                &local_media_processor(),
+                &OpenRouterClient::new(config.config().openrouter.clone()),
+                &templates,
+                &config,
+            )
+            .await
+            .unwrap();
+
+        // Downloaded for frame extraction without Whisper; no keyframe, so nothing to describe
+        assert!(result.is_none());
+        let requests = server.requests();
+        assert!(requests.iter().any(|r| r.path == "/media/clip.mp4"));
+        assert!(!requests.iter().any(|r| r.path.starts_with("/openrouter")));
+    }
+
+    #[tokio::test]
+    async fn test_video_described_from_preview_without_audio_processing() {
+        let server = start_editable_server(200).await;
+        // Whisper is disabled in the test config, just like when FFmpeg is missing
+        let config = RuntimeConfig::new(create_test_config(&server.url()));
+        assert!(!config.is_audio_enabled());
+
+        let mut toot = create_test_toot(&server.url());
+        toot.media_attachments[0] = MediaAttachment {
+            id: "media_1".to_string(),
+            media_type: "video".to_string(),
+            url: format!("{}/media/clip.mp4", server.url()),
+            preview_url: Some(format!("{}/media/clip_small.png", server.url())),
+            description: None,
+            meta: None,
+        };
+        process_toot(
+            &toot,
+            &MastodonClient::new(config.config().mastodon.clone()),
+            &OpenRouterClient::new(config.config().openrouter.clone()),
+            &local_media_processor(),
+            &LanguageDetector::new(),
+            &config,
+        )
+        .await
+        .unwrap();
+
+        let requests = server.requests();
+        assert!(requests.iter().any(|r| r.path == "/media/clip_small.png"));
+        // Described in place, so the video itself was never downloaded
+        assert!(!requests.iter().any(|r| r.path == "/media/clip.mp4"));
+        let edit = requests
+            .iter()
+            .find(|r| r.method == "PUT")
+            .expect("status should be edited");
+        let body = String::from_utf8_lossy(&edit.body);
+        assert!(body.contains("media_attributes"));
+        assert!(body.contains("A+red+square"));
+    }
+
+    #[tokio::test]
+    async fn test_rejected_inplace_edit_falls_back_to_recreation() {
+        let server = start_editable_server(422).await;
+        process_with_server(&server).await;
+
+        let requests = server.requests();
+        assert!(requests
+            .iter()
+            .any(|r| r.method == "POST" && r.path == "/api/v2/media"));
+
+        let edits: Vec<_> = requests
+            .iter()
+            .filter(|r| r.method == "PUT")
+            .map(|r| String::from_utf8_lossy(&r.body).to_string())
+            .collect();
+        assert_eq!(edits.len(), 2);
+        assert!(edits[0].contains("media_attributes"));
+        assert!(edits[1].contains("media_2"));
+    }
+
+    #[tokio::test]
+    async fn test_attachments_described_concurrently_and_batched() {
+        let png = create_test_png();
+        let mut toot = create_test_toot("https://example.com");
+        toot.media_attachments = (1..=4)
+            .map(|n| MediaAttachment {
+                id: format!("media_{n}"),
+                url: format!("https://example.com/media/image_{n}.png"),
+                ..toot.media_attachments[0].clone()
+            })
+            .collect();
+        let status = serde_json::to_string(&toot).unwrap();
+
+        let server = MockHttpServer::start(move |request| {
+            match (request.method.as_str(), request.path.as_str()) {
+                // One attachment can't be downloaded
+                ("GET", "/media/image_4.png") => MockResponse::json(404, "{}"),
+                (_, path) if path.starts_with("/media/") => {
+                    MockResponse::bytes(200, "image/png", png.clone())
+                }
+                ("POST", "/openrouter/chat/completions") => MockResponse::json(
+                    200,
+                    r#"{"choices":[{"message":{"content":"A red square"},"finish_reason":"stop"}]}"#,
+                )
+                .with_delay(std::time::Duration::from_millis(500)),
+                ("GET", "/api/v1/statuses/toot_1/source") => MockResponse::json(
+                    200,
+                    r#"{"id":"toot_1","text":"A picture","spoiler_text":""}"#,
+                ),
+                ("GET", "/api/v1/statuses/toot_1") | ("PUT", "/api/v1/statuses/toot_1") => {
+                    MockResponse::json(200, &status)
+                }
+                _ => MockResponse::json(404, r#"{"error":"Record not found"}"#),
+            }
+        })
+        .await;
+
+        for media in &mut toot.media_attachments {
+            media.url = media.url.replace("https://example.com", &server.url());
+        }
+        let mut config = create_test_config(&server.url());
+        config.openrouter.min_request_interval_ms = Some(0);
+        let config = RuntimeConfig::new(config);
+
+        let started = std::time::Instant::now();
+        process_toot(
+            &toot,
+            &MastodonClient::new(config.config().mastodon.clone()),
+            &OpenRouterClient::new(config.config().openrouter.clone()),
+            &local_media_processor(),
+            &LanguageDetector::new(),
+            &config,
+        )
+        .await
+        .unwrap();
+
+        // Three 500ms describe calls ran side by side rather than one after another
+        assert!(started.elapsed() < std::time::Duration::from_millis(1200));
+
+        let requests = server.requests();
+        let describe_calls = requests
+            .iter()
+            .filter(|r| r.path == "/openrouter/chat/completions")
+            .count();
+        assert_eq!(describe_calls, 3);
+
+        // The failed download didn't abort the others, which were applied in a single edit
+        let edits: Vec<_> = requests
+            .iter()
+            .filter(|r| r.method == "PUT")
+            .map(|r| String::from_utf8_lossy(&r.body).to_string())
+            .collect();
+        assert_eq!(edits.len(), 1);
+        for media_id in ["media_1", "media_2", "media_3"] {
+            assert!(edits[0].contains(media_id));
+        }
+        assert!(!edits[0].contains("media_4"));
+    }
+
+    /// Provider that records how many descriptions are being generated at once
+    struct InflightCountingProvider {
+        current: std::sync::atomic::AtomicUsize,
+        peak: std::sync::atomic::AtomicUsize,
+    }
+
+    #[async_trait::async_trait]
+    impl DescriptionProvider for InflightCountingProvider {
+        async fn describe_image(
+            &self,
+            _image_data: &[u8],
+            _prompt: &str,
+        ) -> Result<crate::openrouter::ImageDescription, crate::error::OpenRouterError> {
+            use std::sync::atomic::Ordering;
+            let inflight = self.current.fetch_add(1, Ordering::SeqCst) + 1;
+            self.peak.fetch_max(inflight, Ordering::SeqCst);
+            tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+            self.current.fetch_sub(1, Ordering::SeqCst);
+            Ok(crate::openrouter::ImageDescription {
+                text: "A red square".to_string(),
+                model: "counting/model".to_string(),
+                usage: None,
+            })
+        }
+
+        async fn process_text(
+            &self,
+            prompt: &str,
+        ) -> Result<String, crate::error::OpenRouterError> {
+            Ok(prompt.to_string())
+        }
+
+        fn max_concurrent_requests(&self) -> usize {
+            8
+        }
+    }
+
+    #[tokio::test]
+    async fn test_inflight_media_processing_capped_by_max_inflight() {
+        let png = create_test_png();
+        let mut toot = create_test_toot("https://example.com");
+        toot.media_attachments = (1..=6)
+            .map(|n| MediaAttachment {
+                id: format!("media_{n}"),
+                url: format!("https://example.com/media/image_{n}.png"),
+                ..toot.media_attachments[0].clone()
+            })
+            .collect();
+        let status = serde_json::to_string(&toot).unwrap();
+
+        let server = MockHttpServer::start(move |request| match request.path.as_str() {
+            path if path.starts_with("/media/") => {
+                MockResponse::bytes(200, "image/png", png.clone())
+            }
+            "/api/v1/statuses/toot_1" => MockResponse::json(200, &status),
+            _ => MockResponse::json(404, r#"{"error":"Record not found"}"#),
+        })
+        .await;
+
+        for media in &mut toot.media_attachments {
+            media.url = media.url.replace("https://example.com", &server.url());
         }
+        let mut config = create_test_config(&server.url());
+        config.mastodon.max_inflight = Some(2);
+        let config = RuntimeConfig::new(config).with_dry_run(true);
+
+        let provider = InflightCountingProvider {
+            current: std::sync::atomic::AtomicUsize::new(0),
+            peak: std::sync::atomic::AtomicUsize::new(0),
+        };
+        process_toot(
+            &toot,
+            &MastodonClient::new(config.config().mastodon.clone()),
+            &provider,
+            &local_media_processor(),
+            &LanguageDetector::new(),
+            &config,
+        )
+        .await
+        .unwrap();
+
+        // The provider would take all six at once, the in-flight cap allows two
+        assert_eq!(provider.peak.load(std::sync::atomic::Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn test_dm_delivery_sends_descriptions_without_editing() {
+        let png = create_test_png();
+        let server = MockHttpServer::start(move |request| {
+            match (request.method.as_str(), request.path.as_str()) {
+                (_, path) if path.starts_with("/media/") => {
+                    MockResponse::bytes(200, "image/png", png.clone())
+                }
+                ("POST", "/openrouter/chat/completions") => MockResponse::json(
+                    200,
+                    r#"{"choices":[{"message":{"content":"A red square"},"finish_reason":"stop"}]}"#,
+                ),
+                ("GET", "/api/v1/accounts/verify_credentials") => MockResponse::json(
+                    200,
+                    r#"{"id":"user_1","username":"testuser","acct":"testuser","display_name":"Test User","url":"https://example.com/@testuser"}"#,
+                ),
+                ("POST", "/api/v1/statuses") => MockResponse::json(200, r#"{"id":"dm_1"}"#),
+                _ => MockResponse::json(404, r#"{"error":"Record not found"}"#),
+            }
+        })
+        .await;
+
+        let mut config = create_test_config(&server.url());
+        config.mastodon.delivery_mode = Some("dm".to_string());
+        let config = RuntimeConfig::new(config);
+        let mut mastodon_client = MastodonClient::new(config.config().mastodon.clone());
+        mastodon_client.verify_credentials().await.unwrap();
+        let toot = create_test_toot(&server.url());
+
+        process_toot(
+            &toot,
+            &mastodon_client,
+            &OpenRouterClient::new(config.config().openrouter.clone()),
+            &local_media_processor(),
+            &LanguageDetector::new(),
+            &config,
+        )
+        .await
+        .unwrap();
+
+        let requests = server.requests();
+        assert!(!requests.iter().any(|r| r.method == "PUT"));
+        assert!(!requests.iter().any(|r| r.path == "/api/v2/media"));
+
+        let dm = requests
+            .iter()
+            .find(|r| r.method == "POST" && r.path == "/api/v1/statuses")
+            .expect("descriptions should be sent as a DM");
+        let body = String::from_utf8_lossy(&dm.body);
+        assert!(body.contains("visibility=direct"));
+        assert!(body.contains("A+red+square"));
+        assert!(body.contains(&toot.uri.replace(':', "%3A").replace('/', "%2F")));
+    }
+
+    #[tokio::test]
+    async fn test_dm_delivery_describes_toots_with_polls() {
+        let png = create_test_png();
+        let server = MockHttpServer::start(move |request| {
+            match (request.method.as_str(), request.path.as_str()) {
+                (_, path) if path.starts_with("/media/") => {
+                    MockResponse::bytes(200, "image/png", png.clone())
+                }
+                ("POST", "/openrouter/chat/completions") => MockResponse::json(
+                    200,
+                    r#"{"choices":[{"message":{"content":"A red square"},"finish_reason":"stop"}]}"#,
+                ),
+                ("GET", "/api/v1/accounts/verify_credentials") => MockResponse::json(
+                    200,
+                    r#"{"id":"user_1","username":"testuser","acct":"testuser","display_name":"Test User","url":"https://example.com/@testuser"}"#,
+                ),
+                ("POST", "/api/v1/statuses") => MockResponse::json(200, r#"{"id":"dm_1"}"#),
+                _ => MockResponse::json(404, r#"{"error":"Record not found"}"#),
+            }
+        })
+        .await;
+
+        let mut config = create_test_config(&server.url());
+        config.mastodon.delivery_mode = Some("dm".to_string());
+        config.mastodon.poll_handling = Some("skip".to_string());
+        let config = RuntimeConfig::new(config);
+        let mut mastodon_client = MastodonClient::new(config.config().mastodon.clone());
+        mastodon_client.verify_credentials().await.unwrap();
+
+        // Neither poll_handling = "skip" nor an ended poll matter, the status isn't edited
+        let mut toot = create_test_toot(&server.url());
+        toot.poll = Some(create_test_poll(chrono::Duration::hours(-1), true));
+
+        process_toot(
+            &toot,
+            &mastodon_client,
+            &OpenRouterClient::new(config.config().openrouter.clone()),
+            &local_media_processor(),
+            &LanguageDetector::new(),
+            &config,
+        )
+        .await
+        .unwrap();
+
+        let requests = server.requests();
+        assert!(!requests.iter().any(|r| r.method == "PUT"));
+        let dm = requests
+            .iter()
+            .find(|r| r.method == "POST" && r.path == "/api/v1/statuses")
+            .expect("descriptions should be sent as a DM");
+        assert!(String::from_utf8_lossy(&dm.body).contains("A+red+square"));
+    }
+
+    #[tokio::test]
+    async fn test_dm_delivery_splits_descriptions_over_status_limit() {
+        let png = create_test_png();
+        let description = "A red square on a plain white background. ".repeat(4);
+        let completion = serde_json::json!({
+            "choices": [{"message": {"content": description.trim()}, "finish_reason": "stop"}]
+        })
+        .to_string();
+        let server = MockHttpServer::start(move |request| {
+            match (request.method.as_str(), request.path.as_str()) {
+                (_, path) if path.starts_with("/media/") => {
+                    MockResponse::bytes(200, "image/png", png.clone())
+                }
+                ("POST", "/openrouter/chat/completions") => MockResponse::json(200, &completion),
+                ("GET", "/api/v1/accounts/verify_credentials") => MockResponse::json(
+                    200,
+                    r#"{"id":"user_1","username":"testuser","acct":"testuser","display_name":"Test User","url":"https://example.com/@testuser"}"#,
+                ),
+                ("GET", "/api/v1/instance") => MockResponse::json(
+                    200,
+                    r#"{"configuration":{"statuses":{"max_characters":100}}}"#,
+                ),
+                ("POST", "/api/v1/statuses") => MockResponse::json(200, r#"{"id":"dm_1"}"#),
+                _ => MockResponse::json(404, r#"{"error":"Record not found"}"#),
+            }
+        })
+        .await;
+
+        let mut config = create_test_config(&server.url());
+        config.mastodon.delivery_mode = Some("dm".to_string());
+        let config = RuntimeConfig::new(config);
+        let mut mastodon_client = MastodonClient::new(config.config().mastodon.clone());
+        mastodon_client.verify_credentials().await.unwrap();
+        let toot = create_test_toot(&server.url());
+
+        process_toot(
+            &toot,
+            &mastodon_client,
+            &OpenRouterClient::new(config.config().openrouter.clone()),
+            &local_media_processor(),
+            &LanguageDetector::new(),
+            &config,
+        )
+        .await
+        .unwrap();
+
+        let dms: Vec<String> = server
+            .requests()
+            .iter()
+            .filter(|r| r.method == "POST" && r.path == "/api/v1/statuses")
+            .map(|r| {
+                url::form_urlencoded::parse(&r.body)
+                    .find(|(key, _)| key == "status")
+                    .map(|(_, value)| value.into_owned())
+                    .unwrap()
+            })
+            .collect();
+        assert!(dms.len() > 1, "expected a split DM, got {dms:?}");
+        assert!(dms.iter().all(|dm| dm.chars().count() <= 100), "{dms:?}");
+        assert!(dms[0].starts_with("Alt text for "));
+    }
+
+    async fn process_with_skip_flags(toot: &TootEvent, server: &MockHttpServer) {
+        let mut config = create_test_config(&server.url());
+        config.mastodon.skip_sensitive = Some(true);
+        config.mastodon.skip_spoiler = Some(true);
+        config.mastodon.skip_replies = Some(true);
+        config.mastodon.respect_filters = Some(true);
+        let config = RuntimeConfig::new(config);
+
+        process_toot(
+            toot,
+            &MastodonClient::new(config.config().mastodon.clone()),
+            &OpenRouterClient::new(config.config().openrouter.clone()),
+            &local_media_processor(),
+            &LanguageDetector::new(),
+            &config,
+        )
+        .await
+        .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_sensitive_toot_skipped_when_enabled() {
+        let server =
+            MockHttpServer::start(|_| MockResponse::json(404, r#"{"error":"Record not found"}"#))
+                .await;
+
+        let mut toot = create_test_toot(&server.url());
+        toot.sensitive = true;
+        process_with_skip_flags(&toot, &server).await;
+
+        // Neither the media download nor the description request happened
+        assert!(server.requests().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_spoiler_toot_skipped_when_enabled() {
+        let server =
+            MockHttpServer::start(|_| MockResponse::json(404, r#"{"error":"Record not found"}"#))
+                .await;
+
+        let mut toot = create_test_toot(&server.url());
+        toot.spoiler_text = "food".to_string();
+        process_with_skip_flags(&toot, &server).await;
+
+        assert!(server.requests().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_reply_skipped_when_enabled() {
+        let server =
+            MockHttpServer::start(|_| MockResponse::json(404, r#"{"error":"Record not found"}"#))
+                .await;
+
+        let mut toot = create_test_toot(&server.url());
+        toot.in_reply_to_id = Some("parent_toot".to_string());
+        process_with_skip_flags(&toot, &server).await;
+
+        assert!(server.requests().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_filtered_toot_skipped_when_enabled() {
+        let server =
+            MockHttpServer::start(|_| MockResponse::json(404, r#"{"error":"Record not found"}"#))
+                .await;
+
+        let mut toot: TootEvent = serde_json::from_value({
+            let mut status = serde_json::to_value(create_test_toot(&server.url())).unwrap();
+            status["filtered"] = serde_json::json!([{
+                "filter": {
+                    "id": "1",
+                    "title": "Spoilers",
+                    "context": ["home", "public"],
+                    "filter_action": "warn"
+                },
+                "keyword_matches": ["finale"],
+                "status_matches": null
+            }]);
+            status
+        })
+        .unwrap();
+        assert_eq!(toot.hiding_filter().unwrap().title, "Spoilers");
+        process_with_skip_flags(&toot, &server).await;
+        assert!(server.requests().is_empty());
+
+        // Blur filters only hide media previews, so the toot is still processed
+        toot.filtered[0].filter.filter_action = "blur".to_string();
+        assert!(toot.hiding_filter().is_none());
+        assert!(skip_reason(
+            &toot,
+            &RuntimeConfig::new({
+                let mut config = create_test_config(&server.url());
+                config.mastodon.respect_filters = Some(true);
+                config
+            })
+        )
+        .is_none());
+    }
+
+    #[tokio::test]
+    async fn test_image_description_gets_attribution_for_model() {
+        let server = MockHttpServer::start(|_| {
+            MockResponse::json(
+                200,
+                r#"{"choices":[{"message":{"content":"A red square"},"finish_reason":"stop"}]}"#,
+            )
+        })
+        .await;
+
+        let config = create_test_config(&server.url());
+        let openrouter_client = OpenRouterClient::new(config.openrouter.clone());
+        let templates = DescriptionTemplates {
+            prompt: "Describe this image.",
+            audio_prompt: "Summarize this transcript.",
+            video_prompt: "Describe this clip.",
+            animation_note: "This is an animation.",
+            attribution: Some(LanguageDetector::new().get_attribution_template("en")),
+            max_length: openrouter_client.max_description_length(),
+            toot_text: None,
+            chart_prompt: None,
+        };
+        let prepared = PreparedImage {
+            media: create_test_toot(&server.url()).media_attachments[0].clone(),
+            original_data: create_test_png(),
+            processed_data: create_test_png(),
+            passthrough_url: None,
+            ocr_text: None,
+            animated: false,
+        };
+
+        let recreation = describe_prepared_image(prepared, &openrouter_client, &templates)
+            .await
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(
+            recreation.description,
+            format!(
+                "A red square — this image description was made by AI: {}",
+                config.openrouter.vision_model
+            )
+        );
+
+        // The prompt no longer asks the model for the attribution
+        let body = String::from_utf8_lossy(&server.requests()[0].body).to_string();
+        assert!(!body.contains("made by AI"));
+    }
+
+    #[tokio::test]
+    async fn test_image_url_passthrough_sends_remote_url() {
+        let server = MockHttpServer::start(|_| {
+            MockResponse::json(
+                200,
+                r#"{"choices":[{"message":{"content":"A red square"},"finish_reason":"stop"}]}"#,
+            )
+        })
+        .await;
+
+        let mut config = create_test_config(&server.url());
+        config.openrouter.image_url_passthrough = Some(true);
+        let openrouter_client = OpenRouterClient::new(config.openrouter.clone());
+        let templates = DescriptionTemplates {
+            prompt: "Describe this image.",
+            audio_prompt: "Summarize this transcript.",
+            video_prompt: "Describe this clip.",
+            animation_note: "This is an animation.",
+            attribution: None,
+            max_length: openrouter_client.max_description_length(),
+            toot_text: None,
+            chart_prompt: None,
+        };
+        let mut media = create_test_toot(&server.url()).media_attachments[0].clone();
+        media.url = "https://files.example/media/a.png".to_string();
+
+        let recreation = ImageProcessingStrategy
+            .process_media(
+                &media,
+                &local_media_processor(),
+                &openrouter_client,
+                &templates,
+                &RuntimeConfig::new(config),
+            )
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(recreation.description, "A red square");
+        assert!(recreation.data.is_empty());
+
+        // Only the OpenRouter request was made; the image itself was never downloaded
+        let requests = server.requests();
+        assert_eq!(requests.len(), 1);
+        let body: serde_json::Value = serde_json::from_slice(&requests[0].body).unwrap();
+        assert_eq!(
+            body["messages"][0]["content"][1]["image_url"]["url"],
+            "https://files.example/media/a.png"
+        );
+
+        // Plain http URLs are never passed through
+        media.url = "http://files.example/media/a.png".to_string();
+        assert!(passthrough_url(&media).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_image_url_passthrough_respects_blocked_hosts() {
+        let server = MockHttpServer::start(|_| {
+            MockResponse::json(
+                200,
+                r#"{"choices":[{"message":{"content":"A red square"},"finish_reason":"stop"}]}"#,
+            )
+        })
+        .await;
+
+        let mut config = create_test_config(&server.url());
+        config.openrouter.image_url_passthrough = Some(true);
+        let openrouter_client = OpenRouterClient::new(config.openrouter.clone());
+        let templates = DescriptionTemplates {
+            prompt: "Describe this image.",
+            audio_prompt: "Summarize this transcript.",
+            video_prompt: "Describe this clip.",
+            animation_note: "This is an animation.",
+            attribution: None,
+            max_length: openrouter_client.max_description_length(),
+            toot_text: None,
+            chart_prompt: None,
+        };
+        let mut media = create_test_toot(&server.url()).media_attachments[0].clone();
+        media.url = "https://cdn.files.example/media/a.png".to_string();
+        let mut media_config = local_media_config();
+        media_config.host_policy.blocked_hosts = vec!["files.example".to_string()];
+        let media_processor = MediaProcessor::with_unified_transformer(media_config);
+
+        let result = ImageProcessingStrategy
+            .process_media(
+                &media,
+                &media_processor,
+                &openrouter_client,
+                &templates,
+                &RuntimeConfig::new(config),
+            )
+            .await;
+        assert!(matches!(
+            result,
+            Err(AlternatorError::Media(
+                crate::error::MediaError::HostNotAllowed { .. }
+            ))
+        ));
+        // The blocked URL never reached the provider
+        assert!(server.requests().is_empty());
+    }
+
+    #[test]
+    fn test_apply_attribution() {
+        assert_eq!(
+            apply_attribution(
+                "A cat on a sofa. ",
+                "vision/model",
+                Some("this image description was made by AI: {model}"),
+                DEFAULT_MAX_DESCRIPTION_LENGTH,
+            ),
+            "A cat on a sofa. — this image description was made by AI: vision/model"
+        );
+
+        assert_eq!(
+            apply_attribution(
+                "A cat on a sofa.",
+                "vision/model",
+                Some("alt text by {model}"),
+                DEFAULT_MAX_DESCRIPTION_LENGTH,
+            ),
+            "A cat on a sofa. — alt text by vision/model"
+        );
+
+        assert_eq!(
+            apply_attribution(
+                "A cat on a sofa.\n",
+                "vision/model",
+                None,
+                DEFAULT_MAX_DESCRIPTION_LENGTH
+            ),
+            "A cat on a sofa."
+        );
+    }
+
+    #[test]
+    fn test_apply_attribution_respects_length_limit() {
+        let long_description = "word ".repeat(400);
+        let attributed = apply_attribution(
+            &long_description,
+            "vision/model",
+            Some("this image description was made by AI: {model}"),
+            DEFAULT_MAX_DESCRIPTION_LENGTH,
+        );
+
+        assert!(attributed.chars().count() <= DEFAULT_MAX_DESCRIPTION_LENGTH);
+        assert!(attributed.ends_with("... — this image description was made by AI: vision/model"));
+
+        // Without attribution the description is left to the model's own limit
+        let unattributed = apply_attribution(
+            &long_description,
+            "vision/model",
+            None,
+            DEFAULT_MAX_DESCRIPTION_LENGTH,
+        );
+        assert_eq!(unattributed, long_description.trim());
+
+        // A custom limit shortens the description further
+        let attributed = apply_attribution(
+            &long_description,
+            "vision/model",
+            Some("alt text by {model}"),
+            100,
+        );
+        assert!(attributed.chars().count() <= 100);
+        assert!(attributed.ends_with("... — alt text by vision/model"));
+    }
+
+    #[test]
+    fn test_combine_video_description() {
+        let visual = Some("A cat jumps onto a table".to_string());
+
+        assert_eq!(
+            combine_video_description(
+                visual.clone(),
+                Some("Hello there".to_string()),
+                DEFAULT_MAX_DESCRIPTION_LENGTH
+            ),
+            "A cat jumps onto a table\n\nHello there"
+        );
+        // Silent videos only keep the visual description
+        assert_eq!(
+            combine_video_description(
+                visual.clone(),
+                Some(crate::media::NO_SPEECH_DESCRIPTION.to_string()),
+                DEFAULT_MAX_DESCRIPTION_LENGTH
+            ),
+            "A cat jumps onto a table"
+        );
+        assert_eq!(
+            combine_video_description(visual, None, DEFAULT_MAX_DESCRIPTION_LENGTH),
+            "A cat jumps onto a table"
+        );
+        assert_eq!(
+            combine_video_description(
+                None,
+                Some("Hello there".to_string()),
+                DEFAULT_MAX_DESCRIPTION_LENGTH
+            ),
+            "Hello there"
+        );
+        assert_eq!(
+            combine_video_description(None, None, DEFAULT_MAX_DESCRIPTION_LENGTH),
+            crate::media::NO_SPEECH_DESCRIPTION
+        );
+    }
+
+    #[test]
+    fn test_combine_video_description_respects_length_limit() {
+        let combined = combine_video_description(
+            Some("v".repeat(1000)),
+            Some("t".repeat(1000)),
+            DEFAULT_MAX_DESCRIPTION_LENGTH,
+        );
+
+        assert_eq!(combined.chars().count(), DEFAULT_MAX_DESCRIPTION_LENGTH);
+        assert!(combined.ends_with("..."));
+
+        let combined = combine_video_description(Some("v".repeat(1000)), None, 500);
+        assert_eq!(combined.chars().count(), 500);
+    }
+
+    #[test]
+    fn test_description_templates_select_prompt_by_media_kind() {
+        let mut language_detector = LanguageDetector::new();
+        language_detector.add_media_prompt_template(
+            "de".to_string(),
+            MediaKind::Audio,
+            "Fasse das Transkript zusammen.".to_string(),
+        );
+
+        let templates =
+            DescriptionTemplates::for_language(&language_detector, "de-DE", None, 1500).unwrap();
+
+        assert_eq!(
+            templates.prompt_for(MediaKind::Image),
+            language_detector.get_prompt_template("de").unwrap()
+        );
+        assert_eq!(
+            templates.prompt_for(MediaKind::Audio),
+            "Fasse das Transkript zusammen."
+        );
+        assert_eq!(
+            templates.prompt_for(MediaKind::Video),
+            language_detector
+                .get_media_prompt_template("de", MediaKind::Video)
+                .unwrap()
+        );
+        assert_ne!(
+            templates.prompt_for(MediaKind::Video),
+            templates.prompt_for(MediaKind::Image)
+        );
+    }
+
+    #[test]
+    fn test_skip_reason_defaults_to_processing() {
+        let config = RuntimeConfig::new(create_test_config("http://127.0.0.1:1"));
+        let mut toot = create_test_toot("http://127.0.0.1:1");
+        toot.sensitive = true;
+        toot.spoiler_text = "food".to_string();
+
+        assert!(skip_reason(&toot, &config).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_direct_toot_skipped_when_visibility_excluded() {
+        let server =
+            MockHttpServer::start(|_| MockResponse::json(404, r#"{"error":"Record not found"}"#))
+                .await;
+
+        let mut config = create_test_config(&server.url());
+        config.mastodon.process_visibilities =
+            Some(vec!["public".to_string(), "unlisted".to_string()]);
+        let config = RuntimeConfig::new(config);
+
+        let mut toot = create_test_toot(&server.url());
+        assert!(skip_reason(&toot, &config).is_none());
+
+        toot.visibility = "direct".to_string();
+        assert_eq!(
+            skip_reason(&toot, &config),
+            Some("its visibility is not in process_visibilities")
+        );
+
+        process_toot(
+            &toot,
+            &MastodonClient::new(config.config().mastodon.clone()),
+            &OpenRouterClient::new(config.config().openrouter.clone()),
+            &local_media_processor(),
+            &LanguageDetector::new(),
+            &config,
+        )
+        .await
+        .unwrap();
+        assert!(server.requests().is_empty());
+    }
+
+    fn create_test_poll(expires_in: chrono::Duration, expired: bool) -> crate::mastodon::Poll {
+        crate::mastodon::Poll {
+            id: "poll_1".to_string(),
+            expires_at: Some(Utc::now() + expires_in),
+            expired,
+            multiple: false,
+            votes_count: 3,
+            voters_count: Some(3),
+            voted: None,
+            own_votes: None,
+            options: vec![
+                crate::mastodon::PollOption {
+                    title: "Cats".to_string(),
+                    votes_count: Some(2),
+                },
+                crate::mastodon::PollOption {
+                    title: "Dogs".to_string(),
+                    votes_count: Some(1),
+                },
+            ],
+            emojis: vec![],
+        }
+    }
+
+    #[test]
+    fn test_skip_reason_for_polls() {
+        let mut toot = create_test_toot("http://127.0.0.1:1");
+        toot.poll = Some(create_test_poll(chrono::Duration::hours(1), false));
+
+        // Open polls are re-applied by default
+        let config = RuntimeConfig::new(create_test_config("http://127.0.0.1:1"));
+        assert!(skip_reason(&toot, &config).is_none());
+
+        let mut skip_config = create_test_config("http://127.0.0.1:1");
+        skip_config.mastodon.poll_handling = Some("skip".to_string());
+        let skip_config = RuntimeConfig::new(skip_config);
+        assert_eq!(
+            skip_reason(&toot, &skip_config),
+            Some("has a poll (poll_handling = \"skip\")")
+        );
+
+        // Ended polls can't be re-applied without reopening them
+        toot.poll = Some(create_test_poll(chrono::Duration::hours(-1), true));
+        assert!(skip_reason(&toot, &config)
+            .unwrap()
+            .contains("can't be preserved"));
+    }
+
+    #[test]
+    fn test_skip_reason_for_media_only_toots() {
+        let mut toot = create_test_toot("http://127.0.0.1:1");
+        toot.content = String::new();
+
+        // Media-only toots are edited with a zero-width space by default
+        let config = RuntimeConfig::new(create_test_config("http://127.0.0.1:1"));
+        assert!(skip_reason(&toot, &config).is_none());
+
+        let mut no_empty_edits = create_test_config("http://127.0.0.1:1");
+        no_empty_edits.mastodon.allow_empty_text_edit = Some(false);
+        assert_eq!(
+            skip_reason(&toot, &RuntimeConfig::new(no_empty_edits.clone())),
+            Some("has no text (allow_empty_text_edit disabled)")
+        );
+
+        // Toots with text and descriptions sent by DM are unaffected
+        toot.content = "<p>A picture</p>".to_string();
+        assert!(skip_reason(&toot, &RuntimeConfig::new(no_empty_edits.clone())).is_none());
+        toot.content = String::new();
+        no_empty_edits.mastodon.delivery_mode = Some("dm".to_string());
+        assert!(skip_reason(&toot, &RuntimeConfig::new(no_empty_edits)).is_none());
     }
 }
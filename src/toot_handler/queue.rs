@@ -0,0 +1,183 @@
+//! Bounded queue between the streaming listener and the toot processor
+
+use crate::config::RuntimeConfig;
+use crate::mastodon::TootEvent;
+use std::sync::Arc;
+use tokio::sync::mpsc::error::TrySendError;
+use tokio::sync::{mpsc, Mutex};
+use tracing::warn;
+
+/// Default number of stream toots waiting for the processor
+pub const DEFAULT_QUEUE_CAPACITY: usize = 100;
+
+/// What the listener does with a new toot when the queue is full
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QueueOverflow {
+    /// Wait for the processor to free a slot; the stream isn't read in the meantime
+    Block,
+    /// Discard the oldest queued toot to make room and keep reading the stream
+    DropOldest,
+}
+
+impl QueueOverflow {
+    /// Overflow behavior from `mastodon.queue_overflow` (default: block)
+    pub fn from_config(config: &RuntimeConfig) -> Self {
+        match config.config().mastodon.queue_overflow.as_deref() {
+            Some("drop_oldest") => Self::DropOldest,
+            _ => Self::Block,
+        }
+    }
+}
+
+/// Create a queue holding up to `capacity` toots
+pub fn toot_queue(capacity: usize, overflow: QueueOverflow) -> (TootSender, TootReceiver) {
+    let capacity = capacity.max(1);
+    let (sender, receiver) = mpsc::channel(capacity);
+    let receiver = Arc::new(Mutex::new(receiver));
+
+    (
+        TootSender {
+            sender,
+            receiver: Arc::clone(&receiver),
+            overflow,
+            capacity,
+        },
+        TootReceiver { receiver },
+    )
+}
+
+/// Listener side of the queue; dropping it lets the processor drain and stop
+pub struct TootSender {
+    sender: mpsc::Sender<TootEvent>,
+    /// Shared with the processor so the oldest entry can be discarded on overflow
+    receiver: Arc<Mutex<mpsc::Receiver<TootEvent>>>,
+    overflow: QueueOverflow,
+    capacity: usize,
+}
+
+impl TootSender {
+    /// Queue a toot, applying the overflow behavior when the queue is full
+    pub async fn push(&self, toot: TootEvent) {
+        let mut toot = toot;
+        loop {
+            toot = match self.sender.try_send(toot) {
+                // A closed queue means the processor stopped; nothing left to do
+                Ok(()) | Err(TrySendError::Closed(_)) => return,
+                Err(TrySendError::Full(toot)) => toot,
+            };
+
+            match self.overflow {
+                QueueOverflow::Block => {
+                    warn!(
+                        "Toot queue full ({} toots), waiting for the processor before reading toot {}",
+                        self.capacity, toot.id
+                    );
+                    let _ = self.sender.send(toot).await;
+                    return;
+                }
+                QueueOverflow::DropOldest => {
+                    // The processor only holds the lock while the queue is empty or being
+                    // drained, so this doesn't wait long
+                    if let Ok(dropped) = self.receiver.lock().await.try_recv() {
+                        warn!(
+                            "Toot queue full ({} toots), dropping oldest queued toot {}",
+                            self.capacity, dropped.id
+                        );
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Processor side of the queue
+pub struct TootReceiver {
+    receiver: Arc<Mutex<mpsc::Receiver<TootEvent>>>,
+}
+
+impl TootReceiver {
+    /// Next queued toot, `None` once the listener has stopped and the queue is drained
+    pub async fn recv(&mut self) -> Option<TootEvent> {
+        self.receiver.lock().await.recv().await
+    }
+
+    /// Take all toots still waiting, e.g. to keep them across a shutdown
+    pub async fn drain(&mut self) -> Vec<TootEvent> {
+        let mut receiver = self.receiver.lock().await;
+        let mut toots = Vec::with_capacity(receiver.len());
+        while let Ok(toot) = receiver.try_recv() {
+            toots.push(toot);
+        }
+        toots
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mastodon::Account;
+    use chrono::Utc;
+
+    fn toot(id: &str) -> TootEvent {
+        serde_json::from_value(serde_json::json!({
+            "id": id,
+            "uri": format!("https://mastodon.example/statuses/{id}"),
+            "account": Account {
+                id: "user_1".to_string(),
+                username: "testuser".to_string(),
+                acct: "testuser".to_string(),
+                display_name: "Test User".to_string(),
+                url: "https://mastodon.example/@testuser".to_string(),
+            },
+            "content": "",
+            "media_attachments": [],
+            "created_at": Utc::now(),
+            "visibility": "public",
+            "sensitive": false,
+            "spoiler_text": "",
+            "mentions": [],
+            "tags": [],
+            "emojis": [],
+        }))
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_drop_oldest_keeps_newest_toots() {
+        let (sender, mut receiver) = toot_queue(2, QueueOverflow::DropOldest);
+        for id in ["1", "2", "3"] {
+            sender.push(toot(id)).await;
+        }
+        drop(sender);
+
+        assert_eq!(receiver.recv().await.unwrap().id, "2");
+        assert_eq!(receiver.recv().await.unwrap().id, "3");
+        assert!(receiver.recv().await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_drain_takes_waiting_toots_in_order() {
+        let (sender, mut receiver) = toot_queue(5, QueueOverflow::Block);
+        for id in ["1", "2", "3"] {
+            sender.push(toot(id)).await;
+        }
+
+        let drained: Vec<String> = receiver.drain().await.into_iter().map(|t| t.id).collect();
+        assert_eq!(drained, ["1", "2", "3"]);
+        assert!(receiver.drain().await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_block_waits_for_free_slot() {
+        let (sender, mut receiver) = toot_queue(1, QueueOverflow::Block);
+        sender.push(toot("1")).await;
+
+        let blocked = tokio::spawn(async move { sender.push(toot("2")).await });
+        tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+        assert!(!blocked.is_finished());
+
+        assert_eq!(receiver.recv().await.unwrap().id, "1");
+        blocked.await.unwrap();
+        assert_eq!(receiver.recv().await.unwrap().id, "2");
+    }
+}
@@ -1,13 +1,22 @@
 use crate::error::{AlternatorError, MastodonError};
-use crate::mastodon::MastodonStream;
+use crate::mastodon::{MastodonStream, MediaAttachment};
 use tracing::{debug, warn};
 
+/// Whether media got a description other than the one processing started from
+pub(crate) fn has_new_description(current: Option<&str>, replaced: Option<&str>) -> bool {
+    let current = current
+        .map(str::trim)
+        .filter(|description| !description.is_empty());
+    current.is_some() && current != replaced.map(str::trim)
+}
+
 /// Check for race conditions by retrieving current toot state
 pub async fn check_race_condition(
     mastodon_client: &impl MastodonStream,
     toot_id: &str,
-    media_id: &str,
+    media: &MediaAttachment,
 ) -> Result<(), AlternatorError> {
+    let media_id = &media.id;
     debug!(
         "Checking for race conditions on toot {} media {}",
         toot_id, media_id
@@ -21,14 +30,10 @@ pub async fn check_race_condition(
                 .iter()
                 .find(|m| m.id == *media_id)
             {
-                if current_media.description.is_some()
-                    && !current_media
-                        .description
-                        .as_ref()
-                        .unwrap()
-                        .trim()
-                        .is_empty()
-                {
+                if has_new_description(
+                    current_media.description.as_deref(),
+                    media.description.as_deref(),
+                ) {
                     debug!(
                         "Media {} already has description, race condition detected",
                         media_id
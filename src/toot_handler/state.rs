@@ -0,0 +1,294 @@
+use std::fs::{File, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+use tracing::{debug, info, warn};
+
+/// Flush pending entries once this many have accumulated
+const FLUSH_BATCH_SIZE: usize = 32;
+
+/// Flush pending entries once the oldest one is this old
+pub const FLUSH_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Prefix for processed toot entries in the state file
+const TOOT_PREFIX: &str = "toot:";
+
+/// Prefix for processed edit entries in the state file
+const EDIT_PREFIX: &str = "edit:";
+
+/// Prefix for toot IDs handled by the startup backfill
+const BACKFILL_PREFIX: &str = "backfill:";
+
+/// Entries loaded from a state file
+#[derive(Debug, Default)]
+pub struct ProcessedEntries {
+    /// Processed toot IDs, oldest first
+    pub toots: Vec<String>,
+    /// Processed edit keys, oldest first
+    pub edits: Vec<String>,
+    /// Toot IDs handled by the backfill, oldest first
+    pub backfill: Vec<String>,
+}
+
+/// Append-only, newline-delimited store of processed toot IDs and edit keys
+///
+/// Entries are buffered and written in batches so that processing a toot does not
+/// cost an fsync. The owner calls `flush_if_due` periodically so a quiet stream doesn't
+/// leave entries buffered; anything still buffered is written when the store is dropped.
+pub struct ProcessedStore {
+    path: PathBuf,
+    pending: Vec<String>,
+    oldest_pending: Option<Instant>,
+    flush_interval: Duration,
+}
+
+impl ProcessedStore {
+    /// Open the store at `path`, loading existing entries
+    ///
+    /// The file is compacted to the most recent `capacity` entries of each kind
+    /// when it has grown well beyond what the in-memory caches can hold.
+    pub fn open(
+        path: impl AsRef<Path>,
+        capacity: usize,
+    ) -> Result<(Self, ProcessedEntries), std::io::Error> {
+        let path = path.as_ref().to_path_buf();
+        let mut entries = ProcessedEntries::default();
+        let mut line_count = 0;
+
+        if path.exists() {
+            let reader = BufReader::new(File::open(&path)?);
+            for line in reader.lines() {
+                let line = line?;
+                line_count += 1;
+                if let Some(id) = line.strip_prefix(TOOT_PREFIX) {
+                    entries.toots.push(id.to_string());
+                } else if let Some(key) = line.strip_prefix(EDIT_PREFIX) {
+                    entries.edits.push(key.to_string());
+                } else if let Some(id) = line.strip_prefix(BACKFILL_PREFIX) {
+                    entries.backfill.push(id.to_string());
+                } else if !line.trim().is_empty() {
+                    warn!("Ignoring malformed state file entry: {}", line);
+                }
+            }
+        } else if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            std::fs::create_dir_all(parent)?;
+        }
+
+        Self::keep_latest(&mut entries.toots, capacity);
+        Self::keep_latest(&mut entries.edits, capacity);
+        Self::keep_latest(&mut entries.backfill, capacity);
+
+        let store = Self {
+            path,
+            pending: Vec::new(),
+            oldest_pending: None,
+            flush_interval: FLUSH_INTERVAL,
+        };
+
+        if line_count > 2 * capacity {
+            debug!(
+                "Compacting state file {} ({} entries)",
+                store.path.display(),
+                line_count
+            );
+            store.rewrite(&entries)?;
+        }
+
+        info!(
+            "Loaded {} processed toots and {} processed edits from {}",
+            entries.toots.len(),
+            entries.edits.len(),
+            store.path.display()
+        );
+
+        Ok((store, entries))
+    }
+
+    /// Record a processed toot ID
+    pub fn record_toot(&mut self, toot_id: &str) {
+        self.record(format!("{TOOT_PREFIX}{toot_id}"));
+    }
+
+    /// Record a processed edit key
+    pub fn record_edit(&mut self, edit_key: &str) {
+        self.record(format!("{EDIT_PREFIX}{edit_key}"));
+    }
+
+    /// Record a toot ID handled by the backfill, written immediately so that an
+    /// interrupted backfill resumes after it
+    pub fn record_backfill(&mut self, toot_id: &str) {
+        self.record(format!("{BACKFILL_PREFIX}{toot_id}"));
+        if let Err(e) = self.flush() {
+            warn!("Failed to write state file {}: {}", self.path.display(), e);
+        }
+    }
+
+    fn record(&mut self, line: String) {
+        self.pending.push(line);
+        let oldest = *self.oldest_pending.get_or_insert_with(Instant::now);
+
+        if self.pending.len() >= FLUSH_BATCH_SIZE || oldest.elapsed() >= self.flush_interval {
+            if let Err(e) = self.flush() {
+                warn!("Failed to write state file {}: {}", self.path.display(), e);
+            }
+        }
+    }
+
+    /// Write buffered entries once the oldest has waited the flush interval, even if no
+    /// further entry arrives
+    pub fn flush_if_due(&mut self) {
+        if self
+            .oldest_pending
+            .is_some_and(|oldest| oldest.elapsed() >= self.flush_interval)
+        {
+            if let Err(e) = self.flush() {
+                warn!("Failed to write state file {}: {}", self.path.display(), e);
+            }
+        }
+    }
+
+    /// Append all buffered entries to the state file
+    pub fn flush(&mut self) -> Result<(), std::io::Error> {
+        if self.pending.is_empty() {
+            return Ok(());
+        }
+
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?;
+        let mut buffer = self.pending.join("\n");
+        buffer.push('\n');
+        file.write_all(buffer.as_bytes())?;
+        file.sync_data()?;
+
+        debug!(
+            "Wrote {} entries to state file {}",
+            self.pending.len(),
+            self.path.display()
+        );
+        self.pending.clear();
+        self.oldest_pending = None;
+        Ok(())
+    }
+
+    /// Replace the state file contents with the given entries
+    fn rewrite(&self, entries: &ProcessedEntries) -> Result<(), std::io::Error> {
+        let tmp_path = self.path.with_extension("tmp");
+        {
+            let mut file = File::create(&tmp_path)?;
+            for id in &entries.toots {
+                writeln!(file, "{TOOT_PREFIX}{id}")?;
+            }
+            for key in &entries.edits {
+                writeln!(file, "{EDIT_PREFIX}{key}")?;
+            }
+            for id in &entries.backfill {
+                writeln!(file, "{BACKFILL_PREFIX}{id}")?;
+            }
+            file.sync_data()?;
+        }
+        std::fs::rename(tmp_path, &self.path)
+    }
+
+    fn keep_latest(entries: &mut Vec<String>, capacity: usize) {
+        if entries.len() > capacity {
+            entries.drain(..entries.len() - capacity);
+        }
+    }
+}
+
+impl Drop for ProcessedStore {
+    fn drop(&mut self) {
+        if let Err(e) = self.flush() {
+            warn!("Failed to write state file {}: {}", self.path.display(), e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_store_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("state.txt");
+
+        {
+            let (mut store, entries) = ProcessedStore::open(&path, 100).unwrap();
+            assert!(entries.toots.is_empty());
+            store.record_toot("1");
+            store.record_edit("2:a,b");
+            // Buffered until flushed or dropped
+            assert!(!path.exists());
+        }
+
+        let (_store, entries) = ProcessedStore::open(&path, 100).unwrap();
+        assert_eq!(entries.toots, vec!["1"]);
+        assert_eq!(entries.edits, vec!["2:a,b"]);
+    }
+
+    #[test]
+    fn test_store_flushes_in_batches() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("state.txt");
+        let (mut store, _) = ProcessedStore::open(&path, 100).unwrap();
+
+        for i in 0..FLUSH_BATCH_SIZE {
+            store.record_toot(&i.to_string());
+        }
+
+        let content = std::fs::read_to_string(&path).unwrap();
+        assert_eq!(content.lines().count(), FLUSH_BATCH_SIZE);
+        assert!(store.pending.is_empty());
+    }
+
+    #[test]
+    fn test_store_flushes_due_entries_without_new_records() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("state.txt");
+        let (mut store, _) = ProcessedStore::open(&path, 100).unwrap();
+        store.flush_interval = Duration::from_millis(20);
+
+        store.record_toot("1");
+        store.flush_if_due();
+        assert!(!path.exists());
+
+        std::thread::sleep(Duration::from_millis(30));
+        store.flush_if_due();
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "toot:1\n");
+        assert!(store.pending.is_empty());
+    }
+
+    #[test]
+    fn test_store_compacts_large_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("state.txt");
+        let lines: Vec<String> = (0..50).map(|i| format!("toot:{i}")).collect();
+        std::fs::write(&path, lines.join("\n")).unwrap();
+
+        let (_store, entries) = ProcessedStore::open(&path, 10).unwrap();
+        assert_eq!(entries.toots.len(), 10);
+        assert_eq!(entries.toots.first().map(String::as_str), Some("40"));
+
+        let content = std::fs::read_to_string(&path).unwrap();
+        assert_eq!(content.lines().count(), 10);
+    }
+
+    #[test]
+    fn test_store_writes_backfill_entries_immediately() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("state.txt");
+        let (mut store, _) = ProcessedStore::open(&path, 100).unwrap();
+
+        store.record_backfill("7");
+        let content = std::fs::read_to_string(&path).unwrap();
+        assert_eq!(content, "backfill:7\n");
+        drop(store);
+
+        let (_store, entries) = ProcessedStore::open(&path, 100).unwrap();
+        assert_eq!(entries.backfill, vec!["7"]);
+        assert!(entries.toots.is_empty());
+    }
+}
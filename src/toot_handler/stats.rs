@@ -1,6 +1,50 @@
+use crate::openrouter::Usage;
+
 /// Statistics about toot processing
-#[allow(dead_code)] // Stats struct for API completeness
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
 pub struct ProcessingStats {
     pub processed_toots_count: usize,
+    /// Media attachments a description was generated for
+    pub media_described_count: usize,
+    /// OpenRouter requests that reported token usage
+    pub requests_count: usize,
+    pub prompt_tokens: u64,
+    pub completion_tokens: u64,
+    pub total_tokens: u64,
+    /// Estimated cost in USD of requests whose model pricing is known
+    pub estimated_cost: f64,
+}
+
+impl ProcessingStats {
+    /// Add the token usage of one OpenRouter request, with its estimated cost if known
+    pub fn record_usage(&mut self, usage: &Usage, cost: Option<f64>) {
+        self.requests_count += 1;
+        self.prompt_tokens += u64::from(usage.prompt_tokens.unwrap_or(0));
+        self.completion_tokens += u64::from(usage.completion_tokens.unwrap_or(0));
+        self.total_tokens +=
+            u64::from(usage.total_tokens.unwrap_or(
+                usage.prompt_tokens.unwrap_or(0) + usage.completion_tokens.unwrap_or(0),
+            ));
+        self.estimated_cost += cost.unwrap_or(0.0);
+    }
+
+    /// Count a toot that got descriptions for `media_count` attachments
+    pub fn record_toot(&mut self, media_count: usize) {
+        self.processed_toots_count += 1;
+        self.media_described_count += media_count;
+    }
+
+    /// One-line summary for the shutdown log
+    pub fn summary(&self) -> String {
+        format!(
+            "{} toots processed, {} media described, {} OpenRouter requests, {} tokens ({} prompt / {} completion), estimated cost ${:.4}",
+            self.processed_toots_count,
+            self.media_described_count,
+            self.requests_count,
+            self.total_tokens,
+            self.prompt_tokens,
+            self.completion_tokens,
+            self.estimated_cost
+        )
+    }
 }
@@ -435,6 +435,9 @@ mod tests {
             model_dir: None,
             language: Some("auto".to_string()),
             max_duration_minutes: Some(10),
+            min_audio_duration_secs: None,
+            chunk_minutes: None,
+            long_transcript_mode: None,
             python_executable: Some("python3".to_string()),
             device: None,
             backend: None,
@@ -455,6 +458,9 @@ mod tests {
             model_dir: None,
             language: Some("auto".to_string()),
             max_duration_minutes: Some(10),
+            min_audio_duration_secs: None,
+            chunk_minutes: None,
+            long_transcript_mode: None,
             python_executable: Some("python3".to_string()),
             device: None,
             backend: None,
@@ -29,19 +29,69 @@ fn create_test_config() -> Config {
         mastodon: MastodonConfig {
             instance_url: "https://mastodon.social".to_string(),
             access_token: "test_token".to_string(),
+            access_token_file: None,
             user_stream: Some(true),
             backfill_count: Some(25),
             backfill_pause: Some(60),
+            stream: None,
+            stream_tag: None,
+            stream_list: None,
+            own_toots_only: None,
+            state_file: None,
+            failed_toots_file: None,
+            processed_cache_size: None,
+            skip_sensitive: None,
+            skip_spoiler: None,
+            skip_replies: None,
+            respect_filters: None,
+            prefer_inplace_edit: None,
+            reconnect_catchup_count: None,
+            poll_handling: None,
+            processing_timeout_secs: None,
+            streaming_auth: None,
+            process_visibilities: None,
+            shutdown_grace_secs: None,
+            inter_toot_delay_ms: None,
+            new_toot_delay_secs: None,
+            max_inflight: None,
+            queue_capacity: None,
+            queue_overflow: None,
+            workers: None,
+            backfill_since: None,
+            backfill_until: None,
+            user_agent_contact: None,
+            delivery_mode: None,
+            allow_empty_text_edit: None,
         },
         openrouter: OpenRouterConfig {
             api_key: "test_api_key".to_string(),
+            api_key_file: None,
             model: "anthropic/claude-3-haiku".to_string(),
             vision_model: "anthropic/claude-3-haiku".to_string(),
             vision_fallback_model: "google/gemma-3-27b-it:free".to_string(),
             text_model: "anthropic/claude-3-haiku".to_string(),
             text_fallback_model: "moonshotai/kimi-k2:free".to_string(),
             base_url: Some("https://test.openrouter.ai/api/v1".to_string()),
+            provider: None,
             max_tokens: Some(150),
+            daily_spend_cap: None,
+            max_concurrent_requests: None,
+            min_request_interval_ms: None,
+            description_cache_size: None,
+            description_cache_ttl_secs: None,
+            max_description_length: None,
+            min_description_length: None,
+            refusal_patterns: None,
+            circuit_breaker_threshold: None,
+            circuit_breaker_cooldown_secs: None,
+            retry_base_delay_ms: None,
+            retry_backoff_multiplier: None,
+            retry_max_delay_ms: None,
+            image_url_passthrough: None,
+            use_toot_text_as_context: None,
+            chart_hashtags: None,
+            chart_prompt: None,
+            batch_image_descriptions: None,
         },
         media: Some(MediaConfig {
             max_size_mb: Some(10),
@@ -54,6 +104,17 @@ fn create_test_config() -> Config {
                 "image/webp".to_string(),
             ]),
             resize_max_dimension: Some(2048),
+            max_download_size_mb: Some(100),
+            download_retries: Some(3),
+            proxy_url: None,
+            allowed_media_hosts: None,
+            blocked_media_hosts: None,
+            allow_private_media_hosts: None,
+            ffmpeg_path: None,
+            analysis_budget_mb: None,
+            analysis_output_format: None,
+            min_image_dimension: None,
+            reprocess_existing_descriptions: None,
         }),
         balance: Some(BalanceConfig {
             enabled: Some(false), // Disable for tests
@@ -62,6 +123,8 @@ fn create_test_config() -> Config {
         }),
         logging: Some(LoggingConfig {
             level: Some("debug".to_string()),
+            format: None,
+            redact_urls: None,
         }),
         whisper: Some(WhisperConfig {
             enabled: Some(false),
@@ -69,11 +132,23 @@ fn create_test_config() -> Config {
             model_dir: None,
             language: None,
             max_duration_minutes: Some(10),
+            min_audio_duration_secs: None,
+            chunk_minutes: None,
+            long_transcript_mode: None,
             python_executable: Some("python3".to_string()),
             device: None,
             backend: None,
             preload: Some(true),
         }),
+        ocr: None,
+        attribution: None,
+        health: None,
+        notifications: None,
+        systemd: None,
+        tracing: None,
+        language: None,
+        prompts: None,
+        events: None,
     }
 }
 
@@ -486,13 +561,33 @@ async fn test_balance_monitoring_configuration() {
 
     let openrouter_client = alternator::openrouter::OpenRouterClient::new(OpenRouterConfig {
         api_key: "test_key".to_string(),
+        api_key_file: None,
         model: "test_model".to_string(),
         vision_model: "test_vision_model".to_string(),
         vision_fallback_model: "test_vision_fallback_model".to_string(),
         text_model: "test_text_model".to_string(),
         text_fallback_model: "test_text_fallback_model".to_string(),
         base_url: None,
+        provider: None,
         max_tokens: Some(150),
+        daily_spend_cap: None,
+        max_concurrent_requests: None,
+        min_request_interval_ms: None,
+        description_cache_size: None,
+        description_cache_ttl_secs: None,
+        max_description_length: None,
+        min_description_length: None,
+        refusal_patterns: None,
+        circuit_breaker_threshold: None,
+        circuit_breaker_cooldown_secs: None,
+        retry_base_delay_ms: None,
+        retry_backoff_multiplier: None,
+        retry_max_delay_ms: None,
+        image_url_passthrough: None,
+        use_toot_text_as_context: None,
+        chart_hashtags: None,
+        chart_prompt: None,
+        batch_image_descriptions: None,
     });
 
     let monitor = alternator::balance::BalanceMonitor::new(enabled_config, openrouter_client);
@@ -512,13 +607,33 @@ async fn test_balance_monitoring_configuration() {
 
     let openrouter_client2 = alternator::openrouter::OpenRouterClient::new(OpenRouterConfig {
         api_key: "test_key".to_string(),
+        api_key_file: None,
         model: "test_model".to_string(),
         vision_model: "test_vision_model".to_string(),
         vision_fallback_model: "test_vision_fallback_model".to_string(),
         text_model: "test_text_model".to_string(),
         text_fallback_model: "test_text_fallback_model".to_string(),
         base_url: None,
+        provider: None,
         max_tokens: Some(150),
+        daily_spend_cap: None,
+        max_concurrent_requests: None,
+        min_request_interval_ms: None,
+        description_cache_size: None,
+        description_cache_ttl_secs: None,
+        max_description_length: None,
+        min_description_length: None,
+        refusal_patterns: None,
+        circuit_breaker_threshold: None,
+        circuit_breaker_cooldown_secs: None,
+        retry_base_delay_ms: None,
+        retry_backoff_multiplier: None,
+        retry_max_delay_ms: None,
+        image_url_passthrough: None,
+        use_toot_text_as_context: None,
+        chart_hashtags: None,
+        chart_prompt: None,
+        batch_image_descriptions: None,
     });
 
     let monitor2 = alternator::balance::BalanceMonitor::new(disabled_config, openrouter_client2);
@@ -550,6 +665,15 @@ async fn test_end_to_end_toot_processing_workflow() {
             ]
             .into_iter()
             .collect(),
+            max_download_size_mb: 100,
+            download_retries: 3,
+            proxy_url: None,
+            host_policy: Default::default(),
+            analysis_budget_mb: alternator::media::image::DEFAULT_ANALYSIS_BUDGET_MB,
+            analysis_output_format: alternator::media::image::ImageFormat::Jpeg,
+            min_image_dimension: 0,
+            reprocess_existing_descriptions: false,
+            attribution_template: None,
         });
     let language_detector = alternator::language::LanguageDetector::new();
 
@@ -609,6 +733,8 @@ async fn test_toot_processing_race_condition_handling() {
         tags: Vec::new(),
         emojis: Vec::new(),
         poll: None,
+        filtered: Vec::new(),
+        edited_at: None,
         is_edit: false,
     };
 
@@ -618,6 +744,15 @@ async fn test_toot_processing_race_condition_handling() {
             max_size_mb: 10.0,
             max_dimension: 2048,
             supported_formats: vec!["image/jpeg".to_string()].into_iter().collect(),
+            max_download_size_mb: 100,
+            download_retries: 3,
+            proxy_url: None,
+            host_policy: Default::default(),
+            analysis_budget_mb: alternator::media::image::DEFAULT_ANALYSIS_BUDGET_MB,
+            analysis_output_format: alternator::media::image::ImageFormat::Jpeg,
+            min_image_dimension: 0,
+            reprocess_existing_descriptions: false,
+            attribution_template: None,
         });
 
     let processable_media = media_processor.filter_processable_media(&test_toot.media_attachments);
@@ -652,6 +787,15 @@ async fn test_toot_processing_duplicate_prevention() {
             max_size_mb: 10.0,
             max_dimension: 2048,
             supported_formats: vec!["image/jpeg".to_string()].into_iter().collect(),
+            max_download_size_mb: 100,
+            download_retries: 3,
+            proxy_url: None,
+            host_policy: Default::default(),
+            analysis_budget_mb: alternator::media::image::DEFAULT_ANALYSIS_BUDGET_MB,
+            analysis_output_format: alternator::media::image::ImageFormat::Jpeg,
+            min_image_dimension: 0,
+            reprocess_existing_descriptions: false,
+            attribution_template: None,
         });
     let language_detector = alternator::language::LanguageDetector::new();
 
@@ -721,6 +865,8 @@ async fn test_empty_message_with_media_behavior() {
         tags: Vec::new(),
         emojis: Vec::new(),
         poll: None,
+        filtered: Vec::new(),
+        edited_at: None,
         is_edit: false,
     };
 
@@ -756,6 +902,8 @@ async fn test_empty_message_with_media_behavior() {
         tags: Vec::new(),
         emojis: Vec::new(),
         poll: None,
+        filtered: Vec::new(),
+        edited_at: None,
         is_edit: false,
     };
 
@@ -996,6 +1144,8 @@ async fn test_audio_format_support() {
         tags: Vec::new(),
         emojis: Vec::new(),
         poll: None,
+        filtered: Vec::new(),
+        edited_at: None,
         is_edit: false,
     };
 
@@ -1022,6 +1172,15 @@ async fn test_audio_format_support() {
             max_size_mb: 10.0,
             max_dimension: 2048,
             supported_formats,
+            max_download_size_mb: 100,
+            download_retries: 3,
+            proxy_url: None,
+            host_policy: Default::default(),
+            analysis_budget_mb: alternator::media::image::DEFAULT_ANALYSIS_BUDGET_MB,
+            analysis_output_format: alternator::media::image::ImageFormat::Jpeg,
+            min_image_dimension: 0,
+            reprocess_existing_descriptions: false,
+            attribution_template: None,
         });
 
     let processable_explicit =
@@ -1065,6 +1224,8 @@ async fn test_audio_format_support() {
         tags: Vec::new(),
         emojis: Vec::new(),
         poll: None,
+        filtered: Vec::new(),
+        edited_at: None,
         is_edit: false,
     };
 
@@ -1093,7 +1254,7 @@ async fn test_openrouter_mocking_in_integration_tests() {
 
     // Test account balance
     let balance = mock_client.get_account_balance().await.unwrap();
-    assert_eq!(balance, 50.0);
+    assert_eq!(balance, Some(50.0));
 
     // Test image description
     let test_image = vec![0u8; 2048];
@@ -1133,7 +1294,11 @@ async fn test_balance_monitoring_with_mock() {
 
     let mock_client = MockOpenRouterClient::new().with_balance(15.0);
     let balance = mock_client.get_account_balance().await.unwrap();
-    assert_eq!(balance, 15.0);
+    assert_eq!(balance, Some(15.0));
+
+    // Accounts without a credit limit report no remaining balance
+    let unlimited_client = MockOpenRouterClient::new().with_unlimited_balance();
+    assert_eq!(unlimited_client.get_account_balance().await.unwrap(), None);
 
     // Test error handling
     let error_client = MockOpenRouterClient::with_error(OpenRouterError::AuthenticationFailed);
@@ -1235,3 +1400,141 @@ async fn test_model_availability_with_mock() {
 
     println!("Model availability mock test passed");
 }
+
+/// Start a minimal Mastodon API mock serving credentials and a fixed set of statuses
+///
+/// Returns the instance URL and a log of requested paths.
+async fn start_mock_mastodon(
+    statuses: Vec<TootEvent>,
+) -> (String, std::sync::Arc<Mutex<Vec<String>>>) {
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let requests = std::sync::Arc::new(Mutex::new(Vec::new()));
+    let recorded = requests.clone();
+
+    tokio::spawn(async move {
+        while let Ok((mut socket, _)) = listener.accept().await {
+            let mut buf = vec![0u8; 8192];
+            let n = socket.read(&mut buf).await.unwrap_or(0);
+            let request = String::from_utf8_lossy(&buf[..n]).to_string();
+            let path = request
+                .split_whitespace()
+                .nth(1)
+                .unwrap_or_default()
+                .to_string();
+            recorded.lock().unwrap().push(path.clone());
+
+            let (status, body) = if path == "/api/v1/accounts/verify_credentials" {
+                (
+                    "200 OK",
+                    r#"{"id":"user_123","username":"testuser","acct":"testuser","display_name":"Test User","url":"https://example.com/@testuser"}"#.to_string(),
+                )
+            } else if let Some(toot) = path
+                .strip_prefix("/api/v1/statuses/")
+                .and_then(|id| statuses.iter().find(|toot| toot.id == id))
+            {
+                ("200 OK", serde_json::to_string(toot).unwrap())
+            } else {
+                (
+                    "404 Not Found",
+                    r#"{"error":"Record not found"}"#.to_string(),
+                )
+            };
+
+            let response = format!(
+                "HTTP/1.1 {status}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                body.len()
+            );
+            let _ = socket.write_all(response.as_bytes()).await;
+        }
+    });
+
+    (format!("http://{addr}"), requests)
+}
+
+/// Create a toot without media owned by the given account
+fn create_status(id: &str, account_id: &str) -> TootEvent {
+    TootEvent {
+        id: id.to_string(),
+        uri: format!("https://example.com/users/testuser/statuses/{id}"),
+        account: Account {
+            id: account_id.to_string(),
+            username: "testuser".to_string(),
+            acct: "testuser".to_string(),
+            display_name: "Test User".to_string(),
+            url: "https://example.com/@testuser".to_string(),
+        },
+        content: "Status processed in once mode".to_string(),
+        language: Some("en".to_string()),
+        media_attachments: Vec::new(),
+        created_at: Utc::now(),
+        url: None,
+        visibility: "public".to_string(),
+        sensitive: false,
+        spoiler_text: String::new(),
+        in_reply_to_id: None,
+        in_reply_to_account_id: None,
+        mentions: Vec::new(),
+        tags: Vec::new(),
+        emojis: Vec::new(),
+        poll: None,
+        filtered: Vec::new(),
+        edited_at: None,
+        is_edit: false,
+    }
+}
+
+#[tokio::test]
+async fn test_process_single_toot_with_mock_mastodon() {
+    use alternator::error::MastodonError;
+
+    let (instance_url, requests) = start_mock_mastodon(vec![
+        create_status("100", "user_123"),
+        create_status("200", "someone_else"),
+    ])
+    .await;
+
+    let mut config = create_test_config();
+    config.mastodon.instance_url = instance_url;
+
+    let mut toot_handler = TootStreamHandler::new(
+        alternator::mastodon::MastodonClient::new(config.mastodon.clone()),
+        alternator::openrouter::OpenRouterClient::new(config.openrouter.clone()),
+        alternator::media::MediaProcessor::with_default_config(),
+        alternator::language::LanguageDetector::new(),
+        RuntimeConfig::new(config),
+    );
+
+    // Own toot is fetched, verified and marked as processed
+    toot_handler.process_single("100").await.unwrap();
+    assert_eq!(toot_handler.get_processing_stats().processed_toots_count, 1);
+
+    // Processing it again is deduplicated without another fetch
+    let fetches_before = requests.lock().unwrap().len();
+    toot_handler.process_single("100").await.unwrap();
+    assert_eq!(requests.lock().unwrap().len(), fetches_before);
+
+    // Toots from other accounts are rejected
+    let result = toot_handler.process_single("200").await;
+    assert!(matches!(
+        result,
+        Err(AlternatorError::Mastodon(MastodonError::InvalidTootData(_)))
+    ));
+
+    // Missing toots surface as an error for a non-zero exit
+    let result = toot_handler.process_single("404").await;
+    assert!(matches!(
+        result,
+        Err(AlternatorError::Mastodon(
+            MastodonError::TootNotFound { .. }
+        ))
+    ));
+
+    assert_eq!(toot_handler.get_processing_stats().processed_toots_count, 1);
+    assert!(requests
+        .lock()
+        .unwrap()
+        .contains(&"/api/v1/accounts/verify_credentials".to_string()));
+}